@@ -14,12 +14,15 @@ use holochain_conductor_api::AdminRequest;
 use holochain_conductor_api::AdminResponse;
 use holochain_conductor_api::AppStatusFilter;
 use holochain_conductor_api::InterfaceDriver;
+use holochain_conductor_api::SignedAppBackup;
 use holochain_conductor_api::{AdminInterfaceConfig, InstalledAppInfo};
 use holochain_p2p::kitsune_p2p::agent_store::AgentInfoSigned;
 use holochain_types::prelude::DnaHash;
 use holochain_types::prelude::InstallAppDnaPayload;
 use holochain_types::prelude::InstallAppPayload;
 use holochain_types::prelude::RegisterDnaPayload;
+use holochain_types::prelude::SerializedBytes;
+use holochain_types::prelude::UnsafeBytes;
 use holochain_types::prelude::YamlProperties;
 use holochain_types::prelude::{AgentPubKey, AppBundleSource};
 use holochain_types::prelude::{CellId, InstallAppBundlePayload};
@@ -72,6 +75,9 @@ pub enum AdminRequestCli {
     ListApps(ListApps),
     EnableApp(EnableApp),
     DisableApp(DisableApp),
+    UninstallApp(UninstallApp),
+    BackupApp(BackupApp),
+    RestoreApp(RestoreApp),
     DumpState(DumpState),
     /// Calls AdminRequest::AddAgentInfo.
     /// [Unimplemented].
@@ -94,6 +100,10 @@ pub struct AddAppWs {
     /// Optional port number.
     /// Defaults to assigned by OS.
     pub port: Option<u16>,
+    #[structopt(short, long)]
+    /// Optional app to attach the interface for,
+    /// which can then make crypto requests over it.
+    pub app_id: Option<String>,
 }
 
 #[derive(Debug, StructOpt, Clone)]
@@ -177,6 +187,36 @@ pub struct DisableApp {
     pub app_id: String,
 }
 
+#[derive(Debug, StructOpt, Clone)]
+/// Calls AdminRequest::UninstallApp
+/// and removes the installed app along with its data.
+pub struct UninstallApp {
+    /// The InstalledAppId to uninstall.
+    pub app_id: String,
+}
+
+#[derive(Debug, StructOpt, Clone)]
+/// Calls AdminRequest::BackupApp
+/// and writes a signed backup of the installed app's source chains to a file.
+pub struct BackupApp {
+    /// The InstalledAppId to back up.
+    pub app_id: String,
+    /// The file to write the backup to.
+    pub path: PathBuf,
+}
+
+#[derive(Debug, StructOpt, Clone)]
+/// Calls AdminRequest::RestoreApp
+/// and installs the app from a backup file, continuing its source chains.
+///
+/// The conductor's keystore must hold the app's agent key,
+/// and the app's dnas must already be registered.
+/// The restored app is enabled.
+pub struct RestoreApp {
+    /// The backup file written by `backup-app`.
+    pub path: PathBuf,
+}
+
 #[derive(Debug, StructOpt, Clone)]
 /// Calls AdminRequest::DumpState
 /// and dumps the current cell's state.
@@ -320,6 +360,20 @@ async fn call_inner(cmd: &mut CmdRunner, call: AdminRequestCli) -> anyhow::Resul
             disable_app(cmd, args).await?;
             msg!("Deactivated app: {:?}", app_id);
         }
+        AdminRequestCli::UninstallApp(args) => {
+            let app_id = args.app_id.clone();
+            uninstall_app(cmd, args).await?;
+            msg!("Uninstalled app: {:?}", app_id);
+        }
+        AdminRequestCli::BackupApp(args) => {
+            let path = args.path.clone();
+            backup_app(cmd, args).await?;
+            msg!("Backed up app to: {}", path.display());
+        }
+        AdminRequestCli::RestoreApp(args) => {
+            let app = restore_app(cmd, args).await?;
+            msg!("Restored app: {:?}", app.installed_app_id);
+        }
         AdminRequestCli::DumpState(args) => {
             let state = dump_state(cmd, args).await?;
             msg!("DUMP STATE \n{}", state);
@@ -584,10 +638,63 @@ pub async fn disable_app(cmd: &mut CmdRunner, args: DisableApp) -> anyhow::Resul
     Ok(())
 }
 
+/// Calls [`AdminRequest::UninstallApp`] and uninstalls the installed app.
+pub async fn uninstall_app(cmd: &mut CmdRunner, args: UninstallApp) -> anyhow::Result<()> {
+    let resp = cmd
+        .command(AdminRequest::UninstallApp {
+            installed_app_id: args.app_id,
+        })
+        .await?;
+    ensure!(
+        matches!(resp, AdminResponse::AppUninstalled),
+        "Failed to uninstall app, got: {:?}",
+        resp
+    );
+    Ok(())
+}
+
+/// Calls [`AdminRequest::BackupApp`] and writes the signed backup to a file.
+pub async fn backup_app(cmd: &mut CmdRunner, args: BackupApp) -> anyhow::Result<()> {
+    let resp = cmd
+        .command(AdminRequest::BackupApp {
+            installed_app_id: args.app_id,
+        })
+        .await?;
+    let backup = expect_match!(resp => AdminResponse::AppBackedUp, "Failed to back up app");
+    let backup = SerializedBytes::try_from(*backup)?;
+    std::fs::write(&args.path, backup.bytes())?;
+    Ok(())
+}
+
+/// Calls [`AdminRequest::RestoreApp`] with the backup in a file
+/// and enables the restored app.
+pub async fn restore_app(
+    cmd: &mut CmdRunner,
+    args: RestoreApp,
+) -> anyhow::Result<InstalledAppInfo> {
+    let backup = SerializedBytes::from(UnsafeBytes::from(std::fs::read(&args.path)?));
+    let backup = SignedAppBackup::try_from(backup)?;
+    let resp = cmd
+        .command(AdminRequest::RestoreApp(Box::new(backup)))
+        .await?;
+    let app = expect_match!(resp => AdminResponse::AppRestored, "Failed to restore app");
+    enable_app(
+        cmd,
+        EnableApp {
+            app_id: app.installed_app_id.clone(),
+        },
+    )
+    .await?;
+    Ok(app)
+}
+
 /// Calls [`AdminRequest::AttachAppInterface`] and adds another app interface.
 pub async fn attach_app_interface(cmd: &mut CmdRunner, args: AddAppWs) -> anyhow::Result<u16> {
     let resp = cmd
-        .command(AdminRequest::AttachAppInterface { port: args.port })
+        .command(AdminRequest::AttachAppInterface {
+            port: args.port,
+            installed_app_id: args.app_id,
+        })
         .await?;
     tracing::debug!(?resp);
     match resp {
@@ -39,6 +39,7 @@ pub async fn run(
             &mut cmd,
             AddAppWs {
                 port: Some(app_port),
+                app_id: None,
             },
         )
         .await?;
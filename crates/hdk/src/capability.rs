@@ -35,6 +35,21 @@ pub fn create_cap_claim(cap_claim_entry: CapClaimEntry) -> ExternResult<HeaderHa
     ))
 }
 
+/// List the capability claims on the local source chain that match the query.
+///
+/// Claims that have been deleted are not returned. Claims created earlier in the
+/// current zome call are included.
+///
+/// ```ignore
+/// let claims = capability_claims(CapClaimQuery::new().grantor(grantor).tag("friend".into()))?;
+/// ```
+///
+/// Claims don't record which functions their grant covers, so use a tag that identifies
+/// the granted functions if you need to look claims up by function.
+pub fn capability_claims(query: CapClaimQuery) -> ExternResult<Vec<CapClaim>> {
+    HDK.with(|h| h.borrow().capability_claims(query))
+}
+
 /// Create a capability grant.
 ///
 /// Wraps the [ `create` ] HDK function with system type parameters set.
@@ -23,6 +23,8 @@ thread_local!(pub static HDK: RefCell<Box<dyn HdkT>> = RefCell::new(Box::new(Hos
 /// ```
 #[cfg_attr(feature = "mock", automock)]
 pub trait HdkT: Send + Sync {
+    // Capability
+    fn capability_claims(&self, query: CapClaimQuery) -> ExternResult<Vec<CapClaim>>;
     // Chain
     fn get_agent_activity(
         &self,
@@ -77,7 +79,7 @@ pub trait HdkT: Send + Sync {
     fn random_bytes(&self, number_of_bytes: u32) -> ExternResult<Bytes>;
     // Time
     fn sys_time(&self, sys_time_input: ()) -> ExternResult<Timestamp>;
-    fn schedule(&self, execute_after: std::time::Duration) -> ExternResult<()>;
+    fn schedule(&self, schedule_input: ScheduleInput) -> ExternResult<()>;
     fn sleep(&self, wake_after: std::time::Duration) -> ExternResult<()>;
     // Trace
     fn trace(&self, trace_msg: TraceMsg) -> ExternResult<()>;
@@ -116,6 +118,9 @@ impl HdkT for ErrHdk {
     fn get_agent_activity(&self, _: GetAgentActivityInput) -> ExternResult<AgentActivity> {
         Self::err()
     }
+    fn capability_claims(&self, _: CapClaimQuery) -> ExternResult<Vec<CapClaim>> {
+        Self::err()
+    }
     fn query(&self, _: ChainQueryFilter) -> ExternResult<Vec<Element>> {
         Self::err()
     }
@@ -211,7 +216,7 @@ impl HdkT for ErrHdk {
     fn sys_time(&self, _: ()) -> ExternResult<Timestamp> {
         Self::err()
     }
-    fn schedule(&self, _: std::time::Duration) -> ExternResult<()> {
+    fn schedule(&self, _: ScheduleInput) -> ExternResult<()> {
         Self::err()
     }
     fn sleep(&self, _: std::time::Duration) -> ExternResult<()> {
@@ -269,6 +274,9 @@ impl HdkT for HostHdk {
             get_agent_activity_input,
         )
     }
+    fn capability_claims(&self, query: CapClaimQuery) -> ExternResult<Vec<CapClaim>> {
+        host_call::<CapClaimQuery, Vec<CapClaim>>(__capability_claims, query)
+    }
     fn query(&self, filter: ChainQueryFilter) -> ExternResult<Vec<Element>> {
         host_call::<ChainQueryFilter, Vec<Element>>(__query, filter)
     }
@@ -378,8 +386,8 @@ impl HdkT for HostHdk {
     fn sys_time(&self, _: ()) -> ExternResult<Timestamp> {
         host_call::<(), Timestamp>(__sys_time, ())
     }
-    fn schedule(&self, execute_after: std::time::Duration) -> ExternResult<()> {
-        host_call::<std::time::Duration, ()>(__schedule, execute_after)
+    fn schedule(&self, schedule_input: ScheduleInput) -> ExternResult<()> {
+        host_call::<ScheduleInput, ()>(__schedule, schedule_input)
     }
     fn sleep(&self, wake_after: std::time::Duration) -> ExternResult<()> {
         host_call::<std::time::Duration, ()>(__sleep, wake_after)
@@ -472,9 +472,7 @@ pub mod trace;
 
 /// Everything related to inspecting or responding to time.
 ///
-/// Currently only fetching the host's opinion of the local time is supported.
-///
-/// @todo implement scheduled execution and sleeping
+/// Fetch the host's opinion of the local time, sleep, or schedule functions to run later.
 pub mod time;
 
 /// Generate cryptographic strength random data
@@ -91,11 +91,6 @@ where
 ///
 /// Only clients who have subscribed to signals from this Cell with the proper
 /// filters will receive it.
-///
-/// TODO: we could consider adding a (optional?) "type" parameter, so that
-/// statically typed languages can more easily get a hint of what type to
-/// deserialize to. This of course requires a corresponding change to the
-/// Signal type.
 pub fn emit_signal<I>(input: I) -> ExternResult<()>
 where
     I: serde::Serialize + std::fmt::Debug,
@@ -106,6 +101,21 @@ where
     })
 }
 
+/// Emit an app-defined Signal with a tag.
+///
+/// Clients can subscribe to signals by their tag, and statically typed
+/// clients can use it as a hint of what type to deserialize the signal to.
+pub fn emit_tagged_signal<T, I>(tag: T, input: I) -> ExternResult<()>
+where
+    T: Into<String>,
+    I: serde::Serialize + std::fmt::Debug,
+{
+    HDK.with(|h| {
+        h.borrow()
+            .emit_signal(AppSignal::tagged(tag.into(), ExternIO::encode(input)?))
+    })
+}
+
 /// ## Remote Signal
 /// Send a signal to a list of other agents.
 /// This will send the data as an [ `AppSignal` ] to
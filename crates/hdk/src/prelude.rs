@@ -1,4 +1,5 @@
 pub use crate::app_entry;
+pub use crate::capability::capability_claims;
 pub use crate::capability::create_cap_claim;
 pub use crate::capability::create_cap_grant;
 pub use crate::capability::delete_cap_grant;
@@ -50,6 +51,7 @@ pub use crate::map_extern::ExternResult;
 pub use crate::p2p::call;
 pub use crate::p2p::call_remote;
 pub use crate::p2p::emit_signal;
+pub use crate::p2p::emit_tagged_signal;
 pub use crate::p2p::remote_signal;
 pub use crate::random::*;
 pub use crate::register_entry;
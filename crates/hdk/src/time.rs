@@ -65,12 +65,48 @@ pub fn sys_time() -> ExternResult<Timestamp> {
     HDK.with(|h| h.borrow().sys_time(()))
 }
 
-/// @todo Not implemented
-pub fn schedule(execute_after: std::time::Duration) -> ExternResult<()> {
-    HDK.with(|h| h.borrow().schedule(execute_after))
+/// Schedule a function in the current zome to run after a delay.
+///
+/// The scheduled function must be an extern that takes no input and returns
+/// a [`ScheduledCallbackResult`], which decides whether it runs again.
+///
+/// ```ignore
+/// #[hdk_extern]
+/// fn expire_invites(_: ()) -> ExternResult<ScheduledCallbackResult> {
+///     // ... delete any invites that have expired ...
+///     Ok(ScheduledCallbackResult::Reschedule(std::time::Duration::from_secs(60 * 60)))
+/// }
+///
+/// schedule("expire_invites", std::time::Duration::from_secs(60 * 60))?;
+/// ```
+///
+/// Schedules are persisted alongside the source chain so they survive a restart of the
+/// conductor. They are only committed if the zome call that created them succeeds.
+/// Scheduling a function that is already scheduled replaces the old schedule.
+///
+/// Scheduled functions run without a caller so there is no capability check, and they
+/// run with the same host access as a zome call, so they can also emit signals.
+/// If the function returns an error it is not scheduled again.
+pub fn schedule(
+    scheduled_fn: impl Into<FunctionName>,
+    execute_after: std::time::Duration,
+) -> ExternResult<()> {
+    HDK.with(|h| {
+        h.borrow()
+            .schedule(ScheduleInput::new(scheduled_fn.into(), execute_after))
+    })
 }
 
-/// @todo Not implemented
+/// Pause the current zome call for at least the given duration.
+///
+/// The host blocks the thread running the zome call while it sleeps, and the zome call
+/// keeps any locks it holds, e.g. on the source chain, so prefer [`schedule`] for long delays.
+///
+/// Sleeping is only allowed where non-deterministic host functions are, so it will
+/// error in validation and other deterministic callbacks. It will also error without
+/// sleeping if waking up would take the zome call past the host's timeout. The timeout
+/// counts from the start of the outermost zome call, so it includes the time spent in
+/// any zome calls that lead to this one.
 pub fn sleep(wake_after: std::time::Duration) -> ExternResult<()> {
     HDK.with(|h| h.borrow().sleep(wake_after))
 }
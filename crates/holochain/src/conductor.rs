@@ -14,6 +14,7 @@
 // TODO: clean up allows once parent is fully documented
 
 pub mod api;
+pub mod app_backup;
 mod cell;
 #[allow(clippy::module_inception)]
 #[allow(missing_docs)]
@@ -22,6 +23,7 @@ pub mod conductor;
 pub mod config;
 #[allow(missing_docs)]
 pub mod dna_store;
+pub mod dpki;
 pub mod entry_def_store;
 #[allow(missing_docs)]
 pub mod error;
@@ -29,6 +31,7 @@ pub mod handle;
 pub mod interactive;
 pub mod interface;
 pub mod manager;
+pub mod metrics;
 pub mod p2p_agent_store;
 pub mod p2p_metrics;
 pub mod paths;
@@ -36,7 +39,9 @@ pub mod state;
 
 pub use cell::error::CellError;
 pub use cell::Cell;
+pub use conductor::full_integration_dump;
 pub use conductor::integration_dump;
+pub use conductor::published_ops_dump;
 pub use conductor::Conductor;
 pub use conductor::ConductorBuilder;
 pub use handle::ConductorHandle;
@@ -5,6 +5,7 @@ use std::sync::Arc;
 use super::error::ConductorApiError;
 use super::error::ConductorApiResult;
 use crate::conductor::interface::SignalBroadcaster;
+use crate::conductor::metrics::ConductorMetrics;
 use crate::conductor::ConductorHandle;
 use crate::core::workflow::ZomeCallResult;
 use async_trait::async_trait;
@@ -13,7 +14,6 @@ use holochain_conductor_api::ZomeCall;
 use holochain_keystore::KeystoreSender;
 use holochain_state::host_fn_workspace::HostFnWorkspace;
 use holochain_types::prelude::*;
-use tracing::*;
 
 /// The concrete implementation of [CellConductorApiT], which is used to give
 /// Cells an API for calling back to their [Conductor].
@@ -62,15 +62,25 @@ impl CellConductorApiT for CellConductorApi {
         }
     }
 
-    async fn dpki_request(&self, _method: String, _args: String) -> ConductorApiResult<String> {
-        warn!("Using placeholder dpki");
-        Ok("TODO".to_string())
+    async fn key_state(&self, key: AgentPubKey, at: Timestamp) -> ConductorApiResult<KeyState> {
+        match self.conductor_handle.key_registry() {
+            Some(key_registry) => Ok(key_registry.key_state(key, at).await?),
+            None => Ok(KeyState::NotFound),
+        }
     }
 
     fn keystore(&self) -> &KeystoreSender {
         self.conductor_handle.keystore()
     }
 
+    fn metrics(&self) -> &ConductorMetrics {
+        self.conductor_handle.metrics()
+    }
+
+    fn zome_call_timeout(&self) -> std::time::Duration {
+        self.conductor_handle.zome_call_timeout()
+    }
+
     async fn signal_broadcaster(&self) -> SignalBroadcaster {
         self.conductor_handle.signal_broadcaster().await
     }
@@ -100,6 +110,12 @@ impl CellConductorApiT for CellConductorApi {
         self.conductor_handle.get_entry_def(key).await
     }
 
+    async fn block_agent(&self, agent: AgentPubKey) -> ConductorApiResult<()> {
+        self.conductor_handle
+            .block_agent(self.cell_id.dna_hash(), agent)
+            .await
+    }
+
     fn into_call_zome_handle(self) -> CellConductorReadHandle {
         Arc::new(self)
     }
@@ -119,13 +135,19 @@ pub trait CellConductorApiT: Clone + Send + Sync + Sized {
         call: ZomeCall,
     ) -> ConductorApiResult<ZomeCallResult>;
 
-    /// Make a request to the DPKI service running for this Conductor.
-    /// TODO: decide on actual signature
-    async fn dpki_request(&self, method: String, args: String) -> ConductorApiResult<String>;
+    /// Ask the conductor's DPKI key registry for the state of a key
+    /// at a point in time. Without a registry every key is [`KeyState::NotFound`].
+    async fn key_state(&self, key: AgentPubKey, at: Timestamp) -> ConductorApiResult<KeyState>;
 
     /// Request access to this conductor's keystore
     fn keystore(&self) -> &KeystoreSender;
 
+    /// The metrics this conductor records as zome calls and validation happen
+    fn metrics(&self) -> &ConductorMetrics;
+
+    /// The longest a zome call may wait on host functions like `sleep`
+    fn zome_call_timeout(&self) -> std::time::Duration;
+
     /// Access the broadcast Sender which will send a Signal across every
     /// attached app interface
     async fn signal_broadcaster(&self) -> SignalBroadcaster;
@@ -142,6 +164,10 @@ pub trait CellConductorApiT: Clone + Send + Sync + Sized {
     /// Get a [EntryDef] from the [EntryDefBuf]
     async fn get_entry_def(&self, key: &EntryDefBufferKey) -> Option<EntryDef>;
 
+    /// Block an agent from this cell's space once they are proven
+    /// to have broken the rules of the network.
+    async fn block_agent(&self, agent: AgentPubKey) -> ConductorApiResult<()>;
+
     /// Turn this into a call zome handle
     fn into_call_zome_handle(self) -> CellConductorReadHandle;
 }
@@ -5,9 +5,11 @@ use crate::conductor::api::error::ConductorApiError;
 use crate::conductor::api::error::ConductorApiResult;
 use crate::conductor::api::error::SerializationError;
 use crate::conductor::conductor::CellStatus;
+use crate::conductor::dpki::DpkiError;
 use crate::conductor::error::ConductorError;
 use crate::conductor::interface::error::InterfaceError;
 use crate::conductor::interface::error::InterfaceResult;
+use crate::conductor::interface::AdminEventSubscription;
 use crate::conductor::ConductorHandle;
 use holochain_keystore::KeystoreSenderExt;
 use holochain_serialized_bytes::prelude::*;
@@ -31,6 +33,13 @@ pub trait AdminInterfaceApi: 'static + Send + Sync + Clone {
         request: AdminRequest,
     ) -> ConductorApiResult<AdminResponse>;
 
+    /// Make a copy of this api for a new connection,
+    /// which is not subscribed to events yet
+    fn for_connection(&self) -> Self;
+
+    /// Whether events were subscribed to through this api
+    fn event_subscription(&self) -> &AdminEventSubscription;
+
     // -- provided -- //
 
     /// Deal with error cases produced by `handle_admin_request_inner`
@@ -53,11 +62,15 @@ pub trait AdminInterfaceApi: 'static + Send + Sync + Clone {
 pub struct RealAdminInterfaceApi {
     /// Mutable access to the Conductor
     conductor_handle: ConductorHandle,
+    event_subscription: AdminEventSubscription,
 }
 
 impl RealAdminInterfaceApi {
     pub(crate) fn new(conductor_handle: ConductorHandle) -> Self {
-        RealAdminInterfaceApi { conductor_handle }
+        RealAdminInterfaceApi {
+            conductor_handle,
+            event_subscription: AdminEventSubscription::default(),
+        }
     }
 }
 
@@ -131,13 +144,34 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 Ok(AdminResponse::DnaRegistered(hash))
             }
             CreateCloneCell(payload) => {
-                let cell_id = payload.cell_id();
-                self.conductor_handle
+                let cell_id = self
+                    .conductor_handle
                     .clone()
                     .create_clone_cell(*payload)
                     .await?;
                 Ok(AdminResponse::CloneCellCreated(cell_id))
             }
+            ArchiveCloneCell(payload) => {
+                self.conductor_handle
+                    .clone()
+                    .archive_clone_cell(*payload)
+                    .await?;
+                Ok(AdminResponse::CloneCellArchived)
+            }
+            RestoreArchivedCloneCell(payload) => {
+                self.conductor_handle
+                    .clone()
+                    .restore_archived_clone_cell(*payload)
+                    .await?;
+                Ok(AdminResponse::ArchivedCloneCellRestored)
+            }
+            DeleteArchivedCloneCell(payload) => {
+                self.conductor_handle
+                    .clone()
+                    .destroy_clone_cell(*payload)
+                    .await?;
+                Ok(AdminResponse::ArchivedCloneCellDeleted)
+            }
             InstallApp(payload) => {
                 trace!(?payload.dnas);
                 let InstallAppPayload {
@@ -211,6 +245,13 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .clone()
                     .generate_sign_keypair_from_pure_entropy()
                     .await?;
+                if let Some(key_registry) = self.conductor_handle.key_registry() {
+                    // The DPKI app itself needs a key before it can be installed,
+                    // so failing to register shouldn't stop keys being generated.
+                    if let Err(e) = key_registry.register_key(agent_pub_key.clone()).await {
+                        warn!(?e, "Failed to register new agent key with DPKI");
+                    }
+                }
                 Ok(AdminResponse::AgentPubKeyGenerated(agent_pub_key))
             }
             ListCellIds => {
@@ -267,6 +308,13 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::AppDisabled)
             }
+            UninstallApp { installed_app_id } => {
+                self.conductor_handle
+                    .clone()
+                    .uninstall_app(&installed_app_id)
+                    .await?;
+                Ok(AdminResponse::AppUninstalled)
+            }
             StartApp { installed_app_id } => {
                 // TODO: check to see if app was actually started
                 let app = self
@@ -276,12 +324,15 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::AppStarted(app.status().is_running()))
             }
-            AttachAppInterface { port } => {
+            AttachAppInterface {
+                port,
+                installed_app_id,
+            } => {
                 let port = port.unwrap_or(0);
                 let port = self
                     .conductor_handle
                     .clone()
-                    .add_app_interface(port)
+                    .add_app_interface(port, installed_app_id)
                     .await?;
                 Ok(AdminResponse::AppInterfaceAttached { port })
             }
@@ -293,6 +344,16 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 let state = self.conductor_handle.dump_cell_state(&cell_id).await?;
                 Ok(AdminResponse::StateDumped(state))
             }
+            DumpFullState {
+                cell_id,
+                dht_ops_filter,
+            } => {
+                let state = self
+                    .conductor_handle
+                    .dump_full_cell_state(&cell_id, dht_ops_filter)
+                    .await?;
+                Ok(AdminResponse::FullStateDumped(Box::new(state)))
+            }
             AddAgentInfo { agent_infos } => {
                 self.conductor_handle.add_agent_infos(agent_infos).await?;
                 Ok(AdminResponse::AgentInfoAdded)
@@ -301,6 +362,80 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 let r = self.conductor_handle.get_agent_infos(cell_id).await?;
                 Ok(AdminResponse::AgentInfoRequested(r))
             }
+            BlockAgent { dna_hash, agent } => {
+                self.conductor_handle.block_agent(&dna_hash, agent).await?;
+                Ok(AdminResponse::AgentBlocked)
+            }
+            UnblockAgent { dna_hash, agent } => {
+                self.conductor_handle
+                    .unblock_agent(&dna_hash, agent)
+                    .await?;
+                Ok(AdminResponse::AgentUnblocked)
+            }
+            ListBlockedAgents { dna_hash } => {
+                let agents = self.conductor_handle.list_blocked_agents(&dna_hash).await?;
+                Ok(AdminResponse::BlockedAgentsListed(agents))
+            }
+            NetworkMetrics { dna_hash } => {
+                let metrics = self.conductor_handle.network_metrics(dna_hash).await?;
+                Ok(AdminResponse::NetworkMetrics(metrics))
+            }
+            RevokeAgentKey { agent_key } => {
+                self.conductor_handle
+                    .key_registry()
+                    .ok_or(DpkiError::RegistryMissing)?
+                    .revoke_key(agent_key)
+                    .await?;
+                Ok(AdminResponse::AgentKeyRevoked)
+            }
+            RotateAppAgentKey { installed_app_id } => {
+                let agent_key = self
+                    .conductor_handle
+                    .clone()
+                    .rotate_app_agent_key(&installed_app_id)
+                    .await?;
+                Ok(AdminResponse::AppAgentKeyRotated(agent_key))
+            }
+            UpgradeApp(payload) => {
+                let installed_app_id = payload.installed_app_id.clone();
+                self.conductor_handle.clone().upgrade_app(*payload).await?;
+                let app_info = self
+                    .conductor_handle
+                    .get_app_info(&installed_app_id)
+                    .await?
+                    .ok_or(ConductorError::AppNotInstalled(installed_app_id))?;
+                Ok(AdminResponse::AppUpgraded(app_info))
+            }
+            ConfirmAppUpgrade { installed_app_id } => {
+                self.conductor_handle
+                    .clone()
+                    .confirm_app_upgrade(&installed_app_id)
+                    .await?;
+                Ok(AdminResponse::AppUpgradeConfirmed)
+            }
+            SubscribeEvents => {
+                self.event_subscription
+                    .subscribe(self.conductor_handle.subscribe_admin_events());
+                Ok(AdminResponse::EventsSubscribed)
+            }
+            ListTaskRestarts => Ok(AdminResponse::TaskRestartsListed(
+                self.conductor_handle.list_task_restarts().await?,
+            )),
+            BackupApp { installed_app_id } => {
+                let backup = self.conductor_handle.backup_app(&installed_app_id).await?;
+                Ok(AdminResponse::AppBackedUp(Box::new(backup)))
+            }
+            RestoreApp(backup) => {
+                let app: InstalledApp = self
+                    .conductor_handle
+                    .clone()
+                    .restore_app(*backup)
+                    .await?
+                    .into();
+                Ok(AdminResponse::AppRestored(
+                    InstalledAppInfo::from_installed_app(&app),
+                ))
+            }
 
             // deprecated aliases
             ListActiveApps => {
@@ -319,6 +454,14 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
             }
         }
     }
+
+    fn for_connection(&self) -> Self {
+        Self::new(self.conductor_handle.clone())
+    }
+
+    fn event_subscription(&self) -> &AdminEventSubscription {
+        &self.event_subscription
+    }
 }
 
 /// Return the proper phenotype for a Dna, given a manifest and some optional
@@ -2,14 +2,19 @@ use super::InterfaceApi;
 use crate::conductor::api::error::ConductorApiResult;
 use crate::conductor::api::error::ExternalApiWireError;
 use crate::conductor::api::error::SerializationError;
+use crate::conductor::error::ConductorError;
 use crate::conductor::interface::error::InterfaceError;
 use crate::conductor::interface::error::InterfaceResult;
+use crate::conductor::interface::SignalSubscriptions;
 use crate::conductor::state::AppInterfaceId;
 use crate::conductor::ConductorHandle;
 
 use holochain_serialized_bytes::prelude::*;
 
 use holochain_types::prelude::*;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::broadcast;
 
 pub use holochain_conductor_api::*;
 
@@ -22,6 +27,14 @@ pub trait AppInterfaceApi: 'static + Send + Sync + Clone {
         request: AppRequest,
     ) -> ConductorApiResult<AppResponse>;
 
+    /// Make a copy of this api for a new connection,
+    /// with no signal subscriptions of its own yet
+    fn for_connection(&self) -> Self;
+
+    /// Whether a signal should be sent to the connection this api is for,
+    /// according to the signal subscriptions made through it
+    async fn allows_signal(&self, signal: &Signal) -> bool;
+
     // -- provided -- //
 
     /// Deal with error cases produced by `handle_app_request_inner`
@@ -43,6 +56,10 @@ pub trait AppInterfaceApi: 'static + Send + Sync + Clone {
 pub struct RealAppInterfaceApi {
     conductor_handle: ConductorHandle,
     interface_id: AppInterfaceId,
+    signal_subscriptions: SignalSubscriptions,
+    /// The conductor's events, which tell a connection when the cells of the
+    /// apps it subscribed to may have changed. Only set for a connection.
+    app_events: Option<Arc<parking_lot::Mutex<broadcast::Receiver<AdminEvent>>>>,
 }
 
 impl RealAppInterfaceApi {
@@ -51,6 +68,46 @@ impl RealAppInterfaceApi {
         Self {
             conductor_handle,
             interface_id,
+            signal_subscriptions: SignalSubscriptions::default(),
+            app_events: None,
+        }
+    }
+
+    /// Forget the cached cells of the subscribed apps if any app or cell has
+    /// changed since they were looked up.
+    /// Returns false if there are no events to keep the cache current with.
+    fn refresh_cached_cells(&self) -> bool {
+        use broadcast::error::TryRecvError;
+        let mut app_events = match &self.app_events {
+            Some(app_events) => app_events.lock(),
+            None => return false,
+        };
+        let mut changed = false;
+        loop {
+            match app_events.try_recv() {
+                // Any event may mean an app's cells changed, and missed
+                // events may have too.
+                Ok(_) | Err(TryRecvError::Lagged(_)) => changed = true,
+                Err(TryRecvError::Empty) | Err(TryRecvError::Closed) => break,
+            }
+        }
+        if changed {
+            self.signal_subscriptions.forget_cells();
+        }
+        true
+    }
+
+    /// Look up the cells an app currently uses
+    async fn app_cells(&self, installed_app_id: &InstalledAppId) -> HashSet<CellId> {
+        match self.conductor_handle.get_app_info(installed_app_id).await {
+            Ok(Some(info)) => info
+                .cell_data
+                .iter()
+                .chain(&info.clone_cells)
+                .chain(&info.upgraded_cells)
+                .map(|cell| cell.as_id().clone())
+                .collect(),
+            _ => HashSet::new(),
         }
     }
 }
@@ -105,9 +162,70 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                     Err(e) => Ok(AppResponse::Error(e.into())),
                 }
             }
-            AppRequest::SignalSubscription(_) => Ok(AppResponse::Unimplemented(request)),
-            AppRequest::Crypto(_) => Ok(AppResponse::Unimplemented(request)),
+            AppRequest::SignalSubscription(subscription) => {
+                let installed_app_id = subscription.installed_app_id().clone();
+                self.conductor_handle
+                    .get_app_info(&installed_app_id)
+                    .await?
+                    .ok_or_else(|| ConductorError::AppNotInstalled(installed_app_id.clone()))?;
+                self.signal_subscriptions
+                    .subscribe(installed_app_id, subscription.filters().clone());
+                Ok(AppResponse::SignalSubscriptionUpdated)
+            }
+            AppRequest::Crypto(request) => Ok(AppResponse::Crypto(Box::new(
+                self.conductor_handle
+                    .app_crypto(&self.interface_id, *request)
+                    .await?,
+            ))),
+        }
+    }
+
+    fn for_connection(&self) -> Self {
+        Self {
+            app_events: Some(Arc::new(parking_lot::Mutex::new(
+                self.conductor_handle.subscribe_admin_events(),
+            ))),
+            ..Self::new(self.conductor_handle.clone(), self.interface_id.clone())
+        }
+    }
+
+    /// An app signal is sent if any subscription to an app using its cell
+    /// lets it through. System signals are not tied to an app and are always sent.
+    async fn allows_signal(&self, signal: &Signal) -> bool {
+        let (cell_id, zome_name) = match signal {
+            Signal::App(cell_id, zome_name, _) => (cell_id, zome_name),
+            Signal::System(_) => return true,
+        };
+        let cache_cells = self.refresh_cached_cells();
+        let subscriptions = self.signal_subscriptions.get();
+        if subscriptions.is_empty() {
+            return true;
+        }
+        for (installed_app_id, subscription) in subscriptions {
+            if !subscription
+                .filters
+                .allows(cell_id, zome_name, signal.tag())
+            {
+                continue;
+            }
+            // Clones may have come and gone since the subscription was made,
+            // so the app's cells are looked up again whenever they may have changed.
+            let cells = match subscription.cells {
+                Some(cells) => cells,
+                None => {
+                    let cells = self.app_cells(&installed_app_id).await;
+                    if cache_cells {
+                        self.signal_subscriptions
+                            .set_cells(&installed_app_id, cells.clone());
+                    }
+                    cells
+                }
+            };
+            if cells.contains(cell_id) {
+                return true;
+            }
         }
+        false
     }
 }
 
@@ -1,5 +1,6 @@
 //! Errors occurring during a [CellConductorApi] or [InterfaceApi] call
 
+use crate::conductor::dpki::DpkiError;
 use crate::conductor::error::ConductorError;
 use crate::conductor::interface::error::InterfaceError;
 use crate::conductor::CellError;
@@ -107,6 +108,9 @@ pub enum ConductorApiError {
     #[error(transparent)]
     RusqliteError(#[from] rusqlite::Error),
 
+    #[error(transparent)]
+    DpkiError(#[from] DpkiError),
+
     /// Other
     #[error("Other: {0}")]
     Other(Box<dyn std::error::Error + Send + Sync>),
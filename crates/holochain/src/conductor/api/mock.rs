@@ -4,6 +4,7 @@ use super::CellConductorApiT;
 use super::ZomeCall;
 use crate::conductor::api::error::ConductorApiResult;
 use crate::conductor::interface::SignalBroadcaster;
+use crate::conductor::metrics::ConductorMetrics;
 use crate::core::workflow::ZomeCallResult;
 use async_trait::async_trait;
 use holo_hash::DnaHash;
@@ -26,14 +27,17 @@ mock! {
             call: ZomeCall,
         ) -> ConductorApiResult<ZomeCallResult>;
 
-        pub fn sync_dpki_request(&self, method: String, args: String) -> ConductorApiResult<String>;
+        pub fn sync_key_state(&self, key: AgentPubKey, at: Timestamp) -> ConductorApiResult<KeyState>;
 
         pub fn mock_keystore(&self) -> &KeystoreSender;
+        pub fn mock_metrics(&self) -> &ConductorMetrics;
+        pub fn mock_zome_call_timeout(&self) -> std::time::Duration;
         pub fn mock_signal_broadcaster(&self) -> SignalBroadcaster;
         pub fn sync_get_dna(&self, dna_hash: &DnaHash) -> Option<DnaFile>;
         pub fn sync_get_this_dna(&self) -> ConductorApiResult<DnaFile>;
         pub fn sync_get_zome(&self, dna_hash: &DnaHash, zome_name: &ZomeName) -> ConductorApiResult<Zome>;
         pub fn sync_get_entry_def(&self, key: &EntryDefBufferKey) -> Option<EntryDef>;
+        pub fn sync_block_agent(&self, agent: AgentPubKey) -> ConductorApiResult<()>;
         pub fn into_call_zome_handle(self) -> super::CellConductorReadHandle;
     }
 
@@ -56,14 +60,22 @@ impl CellConductorApiT for MockCellConductorApi {
         self.sync_call_zome(cell_id, call)
     }
 
-    async fn dpki_request(&self, method: String, args: String) -> ConductorApiResult<String> {
-        self.sync_dpki_request(method, args)
+    async fn key_state(&self, key: AgentPubKey, at: Timestamp) -> ConductorApiResult<KeyState> {
+        self.sync_key_state(key, at)
     }
 
     fn keystore(&self) -> &KeystoreSender {
         self.mock_keystore()
     }
 
+    fn metrics(&self) -> &ConductorMetrics {
+        self.mock_metrics()
+    }
+
+    fn zome_call_timeout(&self) -> std::time::Duration {
+        self.mock_zome_call_timeout()
+    }
+
     async fn signal_broadcaster(&self) -> SignalBroadcaster {
         self.mock_signal_broadcaster()
     }
@@ -84,6 +96,10 @@ impl CellConductorApiT for MockCellConductorApi {
         self.sync_get_entry_def(key)
     }
 
+    async fn block_agent(&self, agent: AgentPubKey) -> ConductorApiResult<()> {
+        self.sync_block_agent(agent)
+    }
+
     fn into_call_zome_handle(self) -> super::CellConductorReadHandle {
         self.into_call_zome_handle()
     }
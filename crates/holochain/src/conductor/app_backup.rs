@@ -0,0 +1,195 @@
+//! Backing up the source chains an app authored, and restoring them
+//! into a conductor holding the same agent key.
+
+use super::error::ConductorError;
+use super::error::ConductorResult;
+use holochain_conductor_api::AppBackup;
+use holochain_conductor_api::CellChainBackup;
+use holochain_conductor_api::InstalledAppInfo;
+use holochain_conductor_api::SignedAppBackup;
+use holochain_keystore::AgentPubKeyExt;
+use holochain_keystore::KeystoreSender;
+use holochain_state::source_chain;
+use holochain_state::source_chain::SourceChain;
+use holochain_state::source_chain::SourceChainError;
+use holochain_types::prelude::*;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+/// Read the chain a cell authored, with its entries
+pub async fn backup_chain(env: EnvWrite, cell_id: CellId) -> ConductorResult<CellChainBackup> {
+    let chain = SourceChain::new(env, cell_id.agent_pubkey().clone()).await?;
+    let mut elements = chain
+        .query(QueryFilter::new().include_entries(true))
+        .await?;
+    elements.sort_by_key(|element| element.header().header_seq());
+
+    let mut headers = Vec::with_capacity(elements.len());
+    let mut entries = Vec::new();
+    for element in elements {
+        let (shh, entry) = element.into_inner();
+        entries.extend(entry.into_option());
+        let (header, signature) = shh.into_header_and_signature();
+        headers.push(SignedHeader(header.into_content(), signature));
+    }
+    Ok(CellChainBackup {
+        cell_id,
+        headers,
+        entries,
+    })
+}
+
+/// Write a backed up chain into the empty database of its cell.
+/// The ops of the chain are authored again, so they are validated
+/// and published as they were the first time.
+pub async fn restore_chain(env: EnvWrite, chain: CellChainBackup) -> ConductorResult<()> {
+    let CellChainBackup {
+        cell_id,
+        headers,
+        entries,
+    } = chain;
+    match SourceChain::new(env.clone(), cell_id.agent_pubkey().clone()).await {
+        Err(SourceChainError::ChainEmpty) => (),
+        Ok(_) => {
+            return Err(ConductorError::InvalidAppBackup(format!(
+                "cell {:?} already has a source chain",
+                cell_id
+            )))
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    let entries: HashMap<EntryHash, Entry> = entries
+        .into_iter()
+        .map(|entry| {
+            let (entry, hash) = EntryHashed::from_content_sync(entry).into_inner();
+            (hash, entry)
+        })
+        .collect();
+    let mut elements = Vec::with_capacity(headers.len());
+    for SignedHeader(header, signature) in headers {
+        if header.author() != cell_id.agent_pubkey() {
+            return Err(ConductorError::InvalidAppBackup(format!(
+                "the chain of cell {:?} has a header by another author",
+                cell_id
+            )));
+        }
+        let entry = match header.entry_hash() {
+            // Several headers may share an entry, so it stays in the map.
+            Some(entry_hash) => Some(entries.get(entry_hash).cloned().ok_or_else(|| {
+                ConductorError::InvalidAppBackup(format!(
+                    "the chain of cell {:?} is missing entry {}",
+                    cell_id, entry_hash
+                ))
+            })?),
+            None => None,
+        };
+        let shh =
+            SignedHeaderHashed::with_presigned(HeaderHashed::from_content_sync(header), signature);
+        let element = Element::new(shh, entry);
+        let ops =
+            produce_op_lights_from_elements(vec![&element]).map_err(SourceChainError::from)?;
+        let (shh, entry) = element.into_inner();
+        elements.push((shh, ops, entry.into_option()));
+    }
+
+    env.async_commit(move |txn| {
+        for (shh, ops, entry) in elements {
+            source_chain::put_raw(txn, shh, ops, entry)?;
+        }
+        ConductorResult::Ok(())
+    })
+    .await
+}
+
+/// Serialize a backup and sign it with the app's agent key
+pub async fn sign_backup(
+    keystore: &KeystoreSender,
+    backup: AppBackup,
+) -> ConductorResult<SignedAppBackup> {
+    let agent_key = backup.agent_key.clone();
+    let backup = SerializedBytes::try_from(backup)?;
+    let signature = agent_key
+        .sign_raw(keystore, &SignedAppBackup::signed_data(&backup))
+        .await?;
+    Ok(SignedAppBackup { backup, signature })
+}
+
+/// Check the signature of a backup, and that the keystore holds its agent
+/// key so the restored chains can be continued.
+pub async fn open_backup(
+    keystore: &KeystoreSender,
+    signed: SignedAppBackup,
+) -> ConductorResult<AppBackup> {
+    let SignedAppBackup { backup, signature } = signed;
+    let data = SignedAppBackup::signed_data(&backup);
+    let backup = AppBackup::try_from(backup)?;
+    let agent_key = backup.agent_key.clone();
+    if !agent_key.verify_signature_raw(&signature, &data).await? {
+        return Err(ConductorError::InvalidAppBackup(
+            "the signature does not match the backup".to_string(),
+        ));
+    }
+    // The keystore has no lookup, so sign a short message which can't be
+    // mistaken for anything else to find out whether it holds the key.
+    if agent_key
+        .sign_raw(keystore, KEY_CHECK_MESSAGE)
+        .await
+        .is_err()
+    {
+        return Err(ConductorError::InvalidAppBackup(format!(
+            "the keystore does not hold the agent key {}",
+            agent_key
+        )));
+    }
+    Ok(backup)
+}
+
+/// Signed when restoring a backup to check that the keystore holds its key
+const KEY_CHECK_MESSAGE: &[u8] = b"holochain app backup key check";
+
+/// Rebuild an app from the info about it in a backup.
+/// The info doesn't record clone limits, so each slot allows as many
+/// clones as it had, archived ones included.
+/// Observers aren't recorded either, but they are the only provisioned
+/// cells without a chain in the backup.
+pub fn app_from_info(
+    agent_key: AgentPubKey,
+    info: InstalledAppInfo,
+    chain_cells: &HashSet<CellId>,
+) -> ConductorResult<InstalledAppCommon> {
+    let InstalledAppInfo {
+        installed_app_id,
+        cell_data,
+        clone_cells,
+        archived_clone_cells,
+        upgraded_cells,
+        status: _,
+    } = info;
+    if !upgraded_cells.is_empty() {
+        return Err(AppError::UpgradePending(installed_app_id).into());
+    }
+    let slots = cell_data.into_iter().map(|cell| {
+        let (cell_id, nick) = cell.into_inner();
+        if !chain_cells.contains(&cell_id) {
+            return (nick, AppSlot::observer(cell_id));
+        }
+        let clone_limit = clone_cells
+            .iter()
+            .chain(archived_clone_cells.iter())
+            .filter(|clone| *clone.as_nick() == nick)
+            .count();
+        (nick, AppSlot::new(cell_id, true, clone_limit as u32))
+    });
+    let mut app = InstalledAppCommon::new(installed_app_id, agent_key, slots.collect::<Vec<_>>());
+    for cell in clone_cells {
+        let (cell_id, nick) = cell.into_inner();
+        app.add_clone(&nick, cell_id)?;
+    }
+    for cell in archived_clone_cells {
+        let (cell_id, nick) = cell.into_inner();
+        app.add_clone(&nick, cell_id.clone())?;
+        app.archive_clone(&nick, &cell_id)?;
+    }
+    Ok(app)
+}
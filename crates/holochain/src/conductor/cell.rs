@@ -22,6 +22,7 @@ use crate::core::workflow::call_zome_workflow;
 use crate::core::workflow::countersigning_workflow::countersigning_success;
 use crate::core::workflow::countersigning_workflow::incoming_countersigning;
 use crate::core::workflow::countersigning_workflow::CountersigningWorkspace;
+use crate::core::workflow::error::WorkflowError;
 use crate::core::workflow::genesis_workflow::genesis_workflow;
 use crate::core::workflow::incoming_dht_ops_workflow::incoming_dht_ops_workflow;
 use crate::core::workflow::initialize_zomes_workflow;
@@ -30,6 +31,8 @@ use crate::core::workflow::GenesisWorkflowArgs;
 use crate::core::workflow::GenesisWorkspace;
 use crate::core::workflow::InitializeZomesWorkflowArgs;
 use crate::core::workflow::ZomeCallResult;
+use crate::core::SysValidationError;
+use crate::core::ValidationOutcome;
 use crate::{conductor::api::error::ConductorApiError, core::ribosome::RibosomeT};
 use error::CellError;
 use futures::future::FutureExt;
@@ -109,6 +112,32 @@ where
     init_mutex: tokio::sync::Mutex<()>,
     /// Countersigning workspace that is shared across this cell.
     countersigning_workspace: CountersigningWorkspace,
+    /// Observers read from the DHT but never author a source chain
+    is_observer: bool,
+    /// Stops the queue consumers of this cell alone
+    stop: sync::broadcast::Sender<()>,
+}
+
+/// How long a cell being removed waits for its queue consumers to finish
+const QUEUE_CONSUMERS_STOP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// A stop signal for the queue consumers of a single cell,
+/// which is also sent when the conductor stops all of its tasks.
+fn cell_stop_broadcaster(
+    conductor_stop: &sync::broadcast::Sender<()>,
+) -> sync::broadcast::Sender<()> {
+    let (stop, mut cell_stopped) = sync::broadcast::channel(1);
+    let mut conductor_stopped = conductor_stop.subscribe();
+    let forward = stop.clone();
+    tokio::spawn(async move {
+        tokio::select! {
+            _ = conductor_stopped.recv() => {
+                forward.send(()).ok();
+            }
+            _ = cell_stopped.recv() => (),
+        }
+    });
+    stop
 }
 
 impl Cell {
@@ -120,6 +149,9 @@ impl Cell {
     /// skipped.
     ///
     /// No Cell will be created if the SourceChain is not ready to be used.
+    ///
+    /// An observer has no SourceChain, so genesis is never checked for it.
+    #[allow(clippy::too_many_arguments)]
     pub async fn create(
         id: CellId,
         conductor_handle: ConductorHandle,
@@ -128,17 +160,19 @@ impl Cell {
         holochain_p2p_cell: holochain_p2p::HolochainP2pCell,
         managed_task_add_sender: sync::mpsc::Sender<ManagedTaskAdd>,
         managed_task_stop_broadcaster: sync::broadcast::Sender<()>,
+        is_observer: bool,
     ) -> CellResult<(Self, InitialQueueTriggers)> {
         let conductor_api = CellConductorApi::new(conductor_handle.clone(), id.clone());
 
         // check if genesis has been run
-        let has_genesis = {
+        let has_genesis = is_observer || {
             // check if genesis ran.
             GenesisWorkspace::new(env.clone())?.has_genesis(id.agent_pubkey())?
         };
 
         if has_genesis {
             let countersigning_workspace = CountersigningWorkspace::new();
+            let stop = cell_stop_broadcaster(&managed_task_stop_broadcaster);
             let (queue_triggers, initial_queue_triggers) = spawn_queue_consumer_tasks(
                 env.clone(),
                 cache.clone(),
@@ -146,8 +180,9 @@ impl Cell {
                 conductor_handle.clone(),
                 conductor_api.clone(),
                 managed_task_add_sender,
-                managed_task_stop_broadcaster,
+                stop.clone(),
                 countersigning_workspace.clone(),
+                is_observer,
             )
             .await;
 
@@ -161,6 +196,8 @@ impl Cell {
                     queue_triggers,
                     init_mutex: Default::default(),
                     countersigning_workspace,
+                    is_observer,
+                    stop,
                 },
                 initial_queue_triggers,
             ))
@@ -248,6 +285,8 @@ impl Cell {
             | QueryOpHashes { .. }
             | QueryAgentInfoSignedNearBasis { .. }
             | QueryPeerDensity { .. }
+            | IsBlocked { .. }
+            | Block { .. }
             | PutMetricDatum { .. }
             | QueryMetrics { .. } => {
                 // These events are aggregated over a set of cells, so need to be handled at the conductor level.
@@ -286,7 +325,12 @@ impl Cell {
                     let res = self
                         .handle_publish(request_validation_receipt, countersigning_session, ops)
                         .await
-                        .map_err(holochain_p2p::HolochainP2pError::other);
+                        .map_err(|e| match e {
+                            CellError::HolochainP2pError(
+                                e @ holochain_p2p::HolochainP2pError::Counterfeit(_),
+                            ) => e,
+                            e => holochain_p2p::HolochainP2pError::other(e),
+                        });
                     respond.respond(Ok(async move { res }.boxed().into()));
                 }
                 .instrument(debug_span!("cell_handle_publish"))
@@ -410,6 +454,23 @@ impl Cell {
                 .instrument(debug_span!("cell_handle_fetch_op_data"))
                 .await;
             }
+
+            FetchOpSizes {
+                span_context: _,
+                respond,
+                op_hashes,
+                ..
+            } => {
+                async {
+                    let res = self
+                        .handle_fetch_op_sizes(op_hashes)
+                        .await
+                        .map_err(holochain_p2p::HolochainP2pError::other);
+                    respond.respond(Ok(async move { res }.boxed().into()));
+                }
+                .instrument(debug_span!("cell_handle_fetch_op_sizes"))
+                .await;
+            }
             SignNetworkData {
                 span_context: _,
                 respond,
@@ -472,9 +533,15 @@ impl Cell {
                 request_validation_receipt,
             )
             .await
-            .map_err(Box::new)
-            .map_err(ConductorApiError::from)
-            .map_err(Box::new)?;
+            .map_err(|e| -> CellError {
+                match e {
+                    // Let the network know so it can block the node that sent the ops.
+                    WorkflowError::SysValidationError(SysValidationError::ValidationOutcome(
+                        outcome @ ValidationOutcome::Counterfeit(..),
+                    )) => holochain_p2p::HolochainP2pError::counterfeit(outcome.to_string()).into(),
+                    e => Box::new(ConductorApiError::from(Box::new(e))).into(),
+                }
+            })?;
         }
         Ok(())
     }
@@ -591,14 +658,17 @@ impl Cell {
             .map_err(Into::into)
     }
 
-    #[instrument(skip(self, _dht_hash, _options))]
+    #[instrument(skip(self, options))]
     /// a remote node is asking us for metadata
     async fn handle_get_meta(
         &self,
-        _dht_hash: holo_hash::AnyDhtHash,
-        _options: holochain_p2p::event::GetMetaOptions,
+        dht_hash: holo_hash::AnyDhtHash,
+        options: holochain_p2p::event::GetMetaOptions,
     ) -> CellResult<MetadataSet> {
-        unimplemented!()
+        let env = self.env.clone();
+        authority::handle_get_meta(env.into(), dht_hash, options)
+            .await
+            .map_err(Into::into)
     }
 
     #[instrument(skip(self, options))]
@@ -755,6 +825,13 @@ impl Cell {
                             )?
                             .collect::<rusqlite::Result<Vec<_>>>()?,
                         ArcInterval::Bounded(start_loc, end_loc) => {
+                            // Arc sets can't hold u32::MAX, so an interval
+                            // reaching just short of it holds it too.
+                            let end_loc = if end_loc == u32::MAX - 1 {
+                                u32::MAX
+                            } else {
+                                end_loc
+                            };
                             let sql = if start_loc <= end_loc {
                                 continuous
                             } else {
@@ -841,6 +918,54 @@ impl Cell {
         Ok(results)
     }
 
+    #[instrument(skip(self, op_hashes))]
+    /// The network module is requesting the size of dht ops.
+    /// An op's size is its header's size, plus its entry's size when
+    /// it carries a public entry, so every authority agrees on it
+    /// without loading the op.
+    async fn handle_fetch_op_sizes(
+        &self,
+        op_hashes: Vec<holo_hash::DhtOpHash>,
+    ) -> CellResult<Vec<(holo_hash::DhtOpHash, u32)>> {
+        let results = self
+            .env()
+            .async_reader(move |txn| {
+                let mut positions = "?,".repeat(op_hashes.len());
+                positions.pop();
+                let sql = format!(
+                    "
+                SELECT DhtOp.hash,
+                LENGTH(Header.blob) + CASE
+                    WHEN DhtOp.type IN (
+                        'StoreElement', 'StoreEntry',
+                        'RegisterUpdatedContent', 'RegisterUpdatedElement'
+                    )
+                    AND Header.private_entry = 0
+                    THEN IFNULL(LENGTH(Entry.blob), 0)
+                    ELSE 0
+                END AS size
+                FROM DHtOp
+                JOIN Header ON DhtOp.header_hash = Header.hash
+                LEFT JOIN Entry ON Header.entry_hash = Entry.hash
+                WHERE
+                DhtOp.when_integrated IS NOT NULL
+                AND
+                DhtOp.hash in ({})
+                ",
+                    positions
+                );
+                let mut stmt = txn.prepare(&sql)?;
+                let r = stmt
+                    .query_map(rusqlite::params_from_iter(op_hashes.into_iter()), |row| {
+                        Ok((row.get("hash")?, row.get("size")?))
+                    })?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+                StateQueryResult::Ok(r)
+            })
+            .await?;
+        Ok(results)
+    }
+
     /// the network module would like this cell/agent to sign some data
     #[tracing::instrument(skip(self))]
     async fn handle_sign_network_data(&self) -> CellResult<Signature> {
@@ -878,29 +1003,44 @@ impl Cell {
         call: ZomeCall,
         workspace_lock: Option<HostFnWorkspace>,
     ) -> CellResult<ZomeCallResult> {
-        // Check if init has run if not run it
-        self.check_or_run_zome_init().await?;
+        // Check if init has run if not run it.
+        // Observers can't run init, as it may write to the chain.
+        if !self.is_observer {
+            self.check_or_run_zome_init().await?;
+        }
 
         let arc = self.env();
         let keystore = arc.keystore().clone();
 
         // If there is no existing zome call then this is the root zome call
         let is_root_zome_call = workspace_lock.is_none();
+        // The root zome call sets the deadline, which the calls it makes share.
+        let deadline = std::time::Instant::now() + self.conductor_api.zome_call_timeout();
         let workspace_lock = match workspace_lock {
             Some(l) => l,
-            None => {
-                HostFnWorkspace::new(
-                    self.env().clone(),
-                    self.cache().clone(),
-                    self.id.agent_pubkey().clone(),
-                )
-                .await?
-            }
+            None if self.is_observer => HostFnWorkspace::observer(
+                self.env().clone(),
+                self.cache().clone(),
+                self.id.agent_pubkey().clone(),
+            )
+            .with_deadline(deadline),
+            None => HostFnWorkspace::new(
+                self.env().clone(),
+                self.cache().clone(),
+                self.id.agent_pubkey().clone(),
+            )
+            .await?
+            .with_deadline(deadline),
         };
 
         let conductor_api = self.conductor_api.clone();
         let signal_tx = self.signal_broadcaster().await;
         let ribosome = self.get_ribosome().await?;
+        let zome_fn = if ribosome.zome_fn_exists(&call.zome_name, &call.fn_name) {
+            Some((call.zome_name.clone(), call.fn_name.clone()))
+        } else {
+            None
+        };
         let invocation = ZomeCallInvocation::from_interface_call(conductor_api.clone(), call).await;
 
         let args = CallZomeWorkflowArgs {
@@ -910,7 +1050,8 @@ impl Cell {
             conductor_api,
             is_root_zome_call,
         };
-        Ok(call_zome_workflow(
+        let started = std::time::Instant::now();
+        let result = call_zome_workflow(
             workspace_lock,
             self.holochain_p2p_cell.clone(),
             keystore,
@@ -918,8 +1059,21 @@ impl Cell {
             self.queue_triggers.publish_dht_ops.clone(),
             self.queue_triggers.integrate_dht_ops.clone(),
         )
-        .await
-        .map_err(Box::new)?)
+        .await;
+        let outcome = match &result {
+            Ok(Ok(ZomeCallResponse::Ok(_))) => "ok",
+            _ => "error",
+        };
+        self.conductor_api
+            .metrics()
+            .record_zome_call(zome_fn, outcome, started.elapsed());
+        let result = result.map_err(Box::new)?;
+        // The call may have scheduled a function that is due
+        // before the scheduler would otherwise wake up.
+        if is_root_zome_call {
+            self.queue_triggers.scheduler.clone().trigger();
+        }
+        Ok(result)
     }
 
     /// Check if each Zome's init callback has been run, and if not, run it.
@@ -944,7 +1098,8 @@ impl Cell {
             self.cache().clone(),
             id.agent_pubkey().clone(),
         )
-        .await?;
+        .await?
+        .with_deadline(std::time::Instant::now() + conductor_api.zome_call_timeout());
 
         // Check if initialization has run
         if workspace.source_chain().has_initialized()? {
@@ -980,24 +1135,34 @@ impl Cell {
         Ok(())
     }
 
-    /// Clean up long-running managed tasks.
-    //
-    // FIXME: this should ensure that the long-running managed tasks,
-    //        i.e. the queue consumers, are stopped. Currently, they
-    //        will continue running because we have no way to target a specific
-    //        Cell's tasks for shutdown.
-    //
-    //        Consider using a separate TaskManager for each Cell, so that all
-    //        of a Cell's tasks can be shut down at once. Perhaps the Conductor
-    //        TaskManager can have these Cell TaskManagers as children.
-    //        [ B-04176 ]
+    /// Stop the queue consumers of this cell and wait for them to finish,
+    /// so nothing is left using its database, then leave the network.
     pub async fn cleanup(&self) -> CellResult<()> {
         use holochain_p2p::HolochainP2pCellT;
+        self.stop_queue_consumers().await;
         self.holochain_p2p_cell().leave().await?;
-        tracing::info!("Cell removed, but cleanup is not yet fully implemented.");
         Ok(())
     }
 
+    async fn stop_queue_consumers(&self) {
+        // Nothing is listening once every consumer has finished.
+        if self.stop.send(()).is_err() {
+            return;
+        }
+        let stopped = tokio::time::timeout(QUEUE_CONSUMERS_STOP_TIMEOUT, async {
+            while self.stop.receiver_count() > 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        })
+        .await;
+        if stopped.is_err() {
+            tracing::error!(
+                cell_id = ?self.id,
+                "The queue consumers of a removed cell did not stop in time"
+            );
+        }
+    }
+
     /// Delete all data associated with this Cell by DELETING the associated
     /// LMDB environment. Completely reverses Cell creation.
     /// NB: This is NOT meant to be a Drop impl! This destroys all data
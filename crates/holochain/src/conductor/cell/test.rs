@@ -1,4 +1,5 @@
 use crate::conductor::manager::spawn_task_manager;
+use crate::conductor::manager::Supervisor;
 use crate::core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckResult;
 use crate::core::ribosome::MockRibosomeT;
 use crate::core::workflow::incoming_dht_ops_workflow::op_exists;
@@ -31,6 +32,7 @@ async fn test_cell_handle_publish() {
     mock_handle
         .expect_get_dna()
         .returning(|_| Some(fixt!(DnaFile)));
+    mock_handle.expect_key_registry().returning(|| None);
 
     let mock_handle: crate::conductor::handle::ConductorHandle = Arc::new(mock_handle);
     let mut mock_ribosome = MockRibosomeT::new();
@@ -48,8 +50,11 @@ async fn test_cell_handle_publish() {
     .await
     .unwrap();
 
-    let (add_task_sender, shutdown) = spawn_task_manager(mock_handle.clone());
     let (stop_tx, _) = sync::broadcast::channel(1);
+    let (add_task_sender, shutdown) = spawn_task_manager(
+        mock_handle.clone(),
+        Supervisor::new(Default::default(), stop_tx.clone()),
+    );
 
     let (cell, _) = super::Cell::create(
         cell_id,
@@ -59,6 +64,7 @@ async fn test_cell_handle_publish() {
         holochain_p2p_cell,
         add_task_sender,
         stop_tx.clone(),
+        false,
     )
     .await
     .unwrap();
@@ -11,6 +11,8 @@
 use super::api::RealAppInterfaceApi;
 use super::config::AdminInterfaceConfig;
 use super::config::InterfaceDriver;
+use super::config::MetricsConfig;
+use super::config::TaskSupervisionConfig;
 use super::dna_store::RealDnaStore;
 use super::entry_def_store::get_entry_defs;
 use super::error::ConductorError;
@@ -19,12 +21,16 @@ use super::interface::error::InterfaceResult;
 use super::interface::websocket::spawn_admin_interface_task;
 use super::interface::websocket::spawn_app_interface_task;
 use super::interface::websocket::spawn_websocket_listener;
+use super::interface::websocket::ADMIN_EVENT_BUFFER_SIZE;
 use super::interface::websocket::SIGNAL_BUFFER_SIZE;
 use super::interface::SignalBroadcaster;
 use super::manager::keep_alive_task;
+use super::manager::InterfaceKind;
 use super::manager::ManagedTaskAdd;
 use super::manager::ManagedTaskHandle;
+use super::manager::Respawn;
 use super::manager::TaskManagerRunHandle;
+use super::metrics::spawn_metrics_endpoint;
 use super::paths::EnvironmentRootPath;
 use super::state::AppInterfaceId;
 use super::state::ConductorState;
@@ -37,19 +43,27 @@ use crate::conductor::cell::Cell;
 use crate::conductor::config::ConductorConfig;
 use crate::conductor::error::ConductorResult;
 use crate::conductor::handle::ConductorHandle;
+use crate::conductor::handle::ConductorHandleT;
 use crate::core::queue_consumer::InitialQueueTriggers;
 use crate::{
     conductor::api::error::ConductorApiResult, core::ribosome::real_ribosome::RealRibosome,
 };
 pub use builder::*;
 use futures::future;
-use futures::future::TryFutureExt;
+use futures::future::FutureExt;
 use futures::stream::StreamExt;
 use holo_hash::DnaHash;
 use holochain_conductor_api::conductor::PassphraseServiceConfig;
+use holochain_conductor_api::AdminEvent;
 use holochain_conductor_api::AppStatusFilter;
+use holochain_conductor_api::DhtOpDump;
+use holochain_conductor_api::DhtOpDumpFilter;
+use holochain_conductor_api::FullIntegrationStateDump;
 use holochain_conductor_api::InstalledAppInfo;
 use holochain_conductor_api::IntegrationStateDump;
+use holochain_conductor_api::PublishedOpDump;
+use holochain_conductor_api::ValidationLimboOpDump;
+use holochain_conductor_api::ValidationLimboStage;
 use holochain_keystore::lair_keystore::spawn_lair_keystore;
 use holochain_keystore::test_keystore::spawn_test_keystore;
 use holochain_keystore::KeystoreSender;
@@ -59,11 +73,13 @@ use holochain_sqlite::prelude::*;
 use holochain_state::mutations;
 use holochain_state::prelude::from_blob;
 use holochain_state::prelude::StateMutationResult;
+use holochain_state::validation_receipts::list_receipts;
 use holochain_types::prelude::*;
 use rusqlite::OptionalExtension;
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
+use tokio::sync::broadcast;
 use tokio::sync::RwLock;
 use tracing::*;
 
@@ -157,6 +173,12 @@ where
 
     /// Handle to the network actor.
     holochain_p2p: holochain_p2p::HolochainP2pRef,
+
+    /// The channel for sending events to subscribed admin connections
+    admin_event_broadcaster: broadcast::Sender<AdminEvent>,
+
+    /// How the task manager reacts to the failure of managed tasks
+    pub(super) task_supervision: TaskSupervisionConfig,
 }
 
 impl Conductor {
@@ -332,8 +354,11 @@ where
             // Now that tasks are spawned, register them with the TaskManager
             for (port, handle) in handles {
                 ports.push(port);
-                self.manage_task(ManagedTaskAdd::ignore(
+                let respawn = respawn_admin_interface(port, admin_api.clone(), stop_tx.clone());
+                self.manage_task(ManagedTaskAdd::interface(
                     handle,
+                    InterfaceKind::Admin,
+                    respawn,
                     &format!("admin interface, port {}", port),
                 ))
                 .await?
@@ -345,9 +370,31 @@ where
         Ok(())
     }
 
+    pub(super) async fn add_metrics_endpoint_via_handle(
+        &mut self,
+        config: MetricsConfig,
+        handle: ConductorHandle,
+    ) -> ConductorResult<u16> {
+        let stop_rx = self
+            .task_manager
+            .as_ref()
+            .expect("Task manager not initialized")
+            .task_stop_broadcaster()
+            .subscribe();
+        let (port, task) = spawn_metrics_endpoint(handle, &config, stop_rx).await?;
+        self.manage_task(ManagedTaskAdd::ignore(
+            task,
+            &format!("metrics endpoint, port {}", port),
+        ))
+        .await?;
+        tracing::info!("Serving metrics on port {}", port);
+        Ok(port)
+    }
+
     pub(super) async fn add_app_interface_via_handle(
         &mut self,
         port: either::Either<u16, AppInterfaceId>,
+        installed_app_id: Option<InstalledAppId>,
         handle: ConductorHandle,
     ) -> ConductorResult<u16> {
         let interface_id = match port {
@@ -360,18 +407,26 @@ where
         // This receiver is thrown away because we can produce infinite new
         // receivers from the Sender
         let (signal_tx, _r) = tokio::sync::broadcast::channel(SIGNAL_BUFFER_SIZE);
-        let stop_rx = self
+        let stop_tx = self
             .task_manager
             .as_ref()
             .expect("Task manager not initialized")
             .task_stop_broadcaster()
-            .subscribe();
-        let (port, task) = spawn_app_interface_task(port, app_api, signal_tx.clone(), stop_rx)
-            .await
-            .map_err(Box::new)?;
-        // TODO: RELIABILITY: Handle this task by restarting it if it fails and log the error
-        self.manage_task(ManagedTaskAdd::ignore(
+            .clone();
+        let (port, task) = spawn_app_interface_task(
+            port,
+            app_api.clone(),
+            signal_tx.clone(),
+            stop_tx.subscribe(),
+        )
+        .await
+        .map_err(Box::new)?;
+        // If the task fails, it is restarted on the port it was bound to
+        let respawn = respawn_app_interface(port, app_api, signal_tx.clone(), stop_tx);
+        self.manage_task(ManagedTaskAdd::interface(
             task,
+            InterfaceKind::App,
+            respawn,
             &format!("app interface, port {}", port),
         ))
         .await?;
@@ -382,7 +437,7 @@ where
         }
 
         self.app_interfaces.insert(interface_id.clone(), interface);
-        let config = AppInterfaceConfig::websocket(port);
+        let config = AppInterfaceConfig::websocket(port, installed_app_id);
         self.update_state(|mut state| {
             state.app_interfaces.insert(interface_id, config);
             Ok(state)
@@ -440,10 +495,14 @@ where
         &mut self,
         handle: ConductorHandle,
     ) -> ConductorResult<()> {
-        for id in self.get_state().await?.app_interfaces.keys().cloned() {
+        for (id, config) in self.get_state().await?.app_interfaces {
             tracing::debug!("Starting up app interface: {:?}", id);
             let _ = self
-                .add_app_interface_via_handle(either::Right(id), handle.clone())
+                .add_app_interface_via_handle(
+                    either::Right(id),
+                    config.installed_app_id,
+                    handle.clone(),
+                )
                 .await?;
         }
         Ok(())
@@ -459,6 +518,12 @@ where
         )
     }
 
+    /// Send an event to every admin connection which subscribed to events
+    pub(super) fn emit_admin_event(&self, event: AdminEvent) {
+        // There may be no subscribers, which is fine.
+        let _ = self.admin_event_broadcaster.send(event);
+    }
+
     /// Instantiate a Ribosome for use with a DNA
     pub(crate) fn get_ribosome(&self, dna_hash: &DnaHash) -> ConductorResult<RealRibosome> {
         match self.dna_store().get(dna_hash) {
@@ -503,8 +568,9 @@ where
 
         let app_ids: Option<HashSet<InstalledAppId>> = app_ids.map(S::into);
         let running_cells: HashSet<CellId> = self.running_cells().map(first).cloned().collect();
-        let (_, delta) = self
+        let (_, (delta, events)) = self
             .update_state_prime(move |mut state| {
+                let mut events = Vec::new();
                 let apps = state.installed_apps_mut().iter_mut().filter(|(id, _)| {
                     app_ids
                         .as_ref()
@@ -513,8 +579,9 @@ where
                 });
                 let delta = apps
                     .into_iter()
-                    .map(|(_app_id, app)| {
-                        match app.status().clone() {
+                    .map(|(app_id, app)| {
+                        let before = app.status().clone();
+                        let delta = match before {
                             Running => {
                                 // If not all required cells are running, pause the app
                                 let missing: Vec<_> = app
@@ -543,38 +610,37 @@ where
                                 // Disabled status should never automatically change.
                                 AppStatusFx::NoChange
                             }
-                        }
+                        };
+                        events.extend(app_stopped_event(app_id, &before, app.status()));
+                        delta
                     })
                     .fold(AppStatusFx::default(), AppStatusFx::combine);
-                Ok((state, delta))
+                Ok((state, (delta, events)))
             })
             .await?;
+        for event in events {
+            self.emit_admin_event(event);
+        }
         Ok(delta)
     }
 
     /// Remove all Cells which are not referenced by any Enabled app.
     /// (Cells belonging to Paused apps are not considered "dangling" and will not be removed)
-    pub(super) async fn remove_dangling_cells(&mut self) -> ConductorResult<()> {
+    /// The removed cells must be stopped with [`stop_cells`] once the
+    /// conductor lock is released, since their queue consumers may need it.
+    pub(super) async fn remove_dangling_cells(&mut self) -> ConductorResult<Vec<Arc<Cell>>> {
         let state = self.get_state().await?;
         let keepers: HashSet<CellId> = state
             .enabled_apps()
             .flat_map(|(_, app)| app.all_cells().cloned().collect::<HashSet<_>>())
             .collect();
-
-        // Clean up all cells that will be dropped (leave network, etc.)
-        let tasks = self
+        let dangling: Vec<CellId> = self
             .cells
-            .iter()
-            .filter(|(id, _)| !keepers.contains(id))
-            .map(|(_, cell)| cell.cell.cleanup());
-        futures::future::join_all(tasks)
-            .await
-            .into_iter()
-            .collect::<CellResult<Vec<()>>>()?;
-
-        // drop all but the keepers
-        self.cells.retain(|id, _| keepers.contains(id));
-        Ok(())
+            .keys()
+            .filter(|id| !keepers.contains(id))
+            .cloned()
+            .collect();
+        Ok(self.remove_cells(dangling))
     }
 
     /// Attempt to create all necessary Cells which have not already been created
@@ -606,6 +672,14 @@ where
             .flat_map(|(_id, app)| app.all_cells().collect::<Vec<&CellId>>())
             .collect();
 
+        // Observer cells are created without checking for genesis
+        let observer_cells: HashSet<&CellId> = state
+            .installed_apps()
+            .iter()
+            .flat_map(|(_id, app)| app.observer_cells())
+            .collect();
+        let observer_cells = &observer_cells;
+
         // calculate the existing cells so we can filter those out, only creating
         // cells for CellIds that don't have cells
         let on_cells: HashSet<&CellId> = self.cells.iter().map(first).collect();
@@ -640,6 +714,7 @@ where
                     holochain_p2p_cell,
                     task_manager.task_add_sender().clone(),
                     task_manager.task_stop_broadcaster().clone(),
+                    observer_cells.contains(cell_id),
                 )
                 .await
                 .map_err(|err| (cell_id.clone(), err))
@@ -673,14 +748,20 @@ where
         app_id: &InstalledAppId,
         transition: AppStatusTransition,
     ) -> ConductorResult<(InstalledApp, AppStatusFx)> {
-        Ok(self
+        let (app, delta, event) = self
             .update_state_prime(move |mut state| {
+                let before = state.get_app(&app_id)?.status().clone();
                 let (app, delta) = state.transition_app_status(&app_id, transition)?.clone();
                 let app = app.clone();
-                Ok((state, (app, delta)))
+                let event = app_stopped_event(&app_id, &before, app.status());
+                Ok((state, (app, delta, event)))
             })
             .await?
-            .1)
+            .1;
+        if let Some(event) = event {
+            self.emit_admin_event(event);
+        }
+        Ok((app, delta))
     }
 
     /// Entirely remove an app from the database, returning the removed app.
@@ -700,6 +781,55 @@ where
         Ok(app)
     }
 
+    /// Record that an app created an X25519 keypair, so that it can use it.
+    pub(super) async fn add_app_x25519_key(
+        &self,
+        app_id: InstalledAppId,
+        key: X25519PubKey,
+    ) -> ConductorResult<()> {
+        self.update_state(move |mut state| {
+            state.add_app_x25519_key(app_id, key);
+            Ok(state)
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Remove an app from the database.
+    /// Returns its cells which no other app uses, archived clones included,
+    /// and the DNAs which no remaining cell uses.
+    pub(super) async fn uninstall_app(
+        &mut self,
+        app_id: &InstalledAppId,
+    ) -> ConductorResult<(Vec<CellId>, HashSet<DnaHash>)> {
+        let app = self.remove_app_from_db(app_id).await?;
+        tracing::debug!(msg = "Removed app from db.", app = ?app);
+
+        let in_use = cells_in_use(&self.get_state().await?);
+        let removed: Vec<CellId> = app
+            .all_cells()
+            .chain(app.archived_cells_by_slot().map(second))
+            .filter(|cell_id| !in_use.contains(cell_id))
+            .cloned()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let unused_dnas: HashSet<DnaHash> = removed
+            .iter()
+            .map(|cell_id| cell_id.dna_hash().clone())
+            .filter(|dna_hash| !in_use.iter().any(|c| c.dna_hash() == dna_hash))
+            .collect();
+        // The caches of unused DNAs are about to be removed,
+        // so the conductor mustn't keep them open.
+        let mut caches = self.caches.lock();
+        for dna_hash in &unused_dnas {
+            caches.remove(dna_hash);
+        }
+        drop(caches);
+
+        Ok((removed, unused_dnas))
+    }
+
     /// Add fully constructed cells to the cell map in the Conductor
     pub(super) fn add_and_initialize_cells(&mut self, cells: Vec<(Cell, InitialQueueTriggers)>) {
         let (cells, triggers): (Vec<_>, Vec<_>) = cells.into_iter().unzip();
@@ -772,6 +902,147 @@ where
         Ok(cell_id)
     }
 
+    /// Archive a clone cell of an app. It will stop running once dangling
+    /// cells are removed, but its data is kept so it can be restored.
+    pub(super) async fn archive_clone_cell(
+        &mut self,
+        payload: &CloneCellPayload,
+    ) -> ConductorResult<()> {
+        self.update_clone_cell(payload, |app, slot_id, cell_id| {
+            app.archive_clone(slot_id, cell_id)
+        })
+        .await
+    }
+
+    /// Restore an archived clone cell to its app, subject to the clone limit.
+    pub(super) async fn restore_clone_cell(
+        &mut self,
+        payload: &CloneCellPayload,
+    ) -> ConductorResult<()> {
+        self.update_clone_cell(payload, |app, slot_id, cell_id| {
+            app.restore_clone(slot_id, cell_id)
+        })
+        .await
+    }
+
+    /// Move an app onto a new agent key in the conductor state.
+    /// This does not touch the data of the app's cells.
+    pub(super) async fn rotate_app_agent_key(
+        &mut self,
+        app_id: &InstalledAppId,
+        new_key: AgentPubKey,
+    ) -> ConductorResult<()> {
+        self.update_state_prime(move |mut state| {
+            state
+                .installed_apps_mut()
+                .get_mut(app_id)
+                .ok_or_else(|| ConductorError::AppNotInstalled(app_id.clone()))?
+                .rotate_agent_key(new_key);
+            Ok((state, ()))
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Put the slots of an upgrade in place in the conductor state.
+    /// The cells they replace are kept until the upgrade is confirmed.
+    pub(super) async fn upgrade_app(
+        &mut self,
+        app_id: &InstalledAppId,
+        slots: Vec<(SlotId, AppSlot)>,
+    ) -> ConductorResult<()> {
+        self.update_state_prime(move |mut state| {
+            state
+                .installed_apps_mut()
+                .get_mut(app_id)
+                .ok_or_else(|| ConductorError::AppNotInstalled(app_id.clone()))?
+                .upgrade_slots(slots)?;
+            Ok((state, ()))
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Confirm an app's pending upgrade in the conductor state, returning the
+    /// cells it replaced. This does not touch the data of those cells.
+    pub(super) async fn confirm_app_upgrade(
+        &mut self,
+        app_id: &InstalledAppId,
+    ) -> ConductorResult<Vec<CellId>> {
+        let (_, upgraded_cells) = self
+            .update_state_prime(move |mut state| {
+                let upgraded_cells = state
+                    .installed_apps_mut()
+                    .get_mut(app_id)
+                    .ok_or_else(|| ConductorError::AppNotInstalled(app_id.clone()))?
+                    .confirm_upgrade();
+                Ok((state, upgraded_cells))
+            })
+            .await?;
+        Ok(upgraded_cells)
+    }
+
+    /// Forget a clone cell entirely. This does not touch the cell's data.
+    pub(super) async fn remove_clone_cell(
+        &mut self,
+        payload: &CloneCellPayload,
+    ) -> ConductorResult<()> {
+        self.update_clone_cell(payload, |app, slot_id, cell_id| {
+            app.remove_clone(slot_id, cell_id)
+        })
+        .await
+    }
+
+    /// Forget an archived clone cell entirely. This does not touch the cell's data.
+    /// Returns whether any remaining cell still uses the clone's DNA.
+    pub(super) async fn remove_archived_clone_cell(
+        &mut self,
+        payload: &CloneCellPayload,
+    ) -> ConductorResult<bool> {
+        self.update_clone_cell(payload, |app, slot_id, cell_id| {
+            app.remove_archived_clone(slot_id, cell_id)
+        })
+        .await?;
+        let dna_hash = payload.cell_id.dna_hash();
+        Ok(cells_in_use(&self.get_state().await?)
+            .iter()
+            .any(|cell_id| cell_id.dna_hash() == dna_hash))
+    }
+
+    /// Apply a change to a clone cell of an app, which returns false if the
+    /// clone could not be found.
+    async fn update_clone_cell<F>(
+        &mut self,
+        payload: &CloneCellPayload,
+        f: F,
+    ) -> ConductorResult<()>
+    where
+        F: FnOnce(&mut InstalledApp, &SlotId, &CellId) -> AppResult<bool> + Send,
+    {
+        let CloneCellPayload {
+            installed_app_id,
+            slot_id,
+            cell_id,
+        } = payload;
+        self.update_state_prime(move |mut state| {
+            let app = state
+                .installed_apps_mut()
+                .get_mut(installed_app_id)
+                .ok_or_else(|| ConductorError::AppNotInstalled(installed_app_id.clone()))?;
+            if f(app, slot_id, cell_id)? {
+                Ok((state, ()))
+            } else {
+                Err(ConductorError::CloneCellNotFound(
+                    installed_app_id.clone(),
+                    cell_id.clone(),
+                    slot_id.clone(),
+                ))
+            }
+        })
+        .await?;
+        Ok(())
+    }
+
     pub(super) async fn load_wasms_into_dna_files(
         &self,
     ) -> ConductorResult<(
@@ -848,18 +1119,20 @@ where
         &self.keystore
     }
 
-    /// Remove cells from the cell map in the Conductor
-    pub(super) async fn remove_cells(&mut self, cell_ids: Vec<CellId>) {
+    /// Remove cells from the cell map in the Conductor.
+    /// The removed cells must be stopped with [`stop_cells`] once the
+    /// conductor lock is released.
+    pub(super) fn remove_cells(&mut self, cell_ids: Vec<CellId>) -> Vec<Arc<Cell>> {
+        let mut removed = Vec::with_capacity(cell_ids.len());
         for cell_id in cell_ids {
             if let Some(item) = self.cells.remove(&cell_id) {
-                // TODO: make this function async, or ensure that cleanup is fast,
-                //       so we don't hold the conductor lock unduly long while doing cleanup
-                //       (currently cleanup is a noop, so it's not urgent)
-                if let Err(err) = item.cell.cleanup().await {
-                    tracing::error!("Error cleaning up Cell: {:?}\nCellId: {}", err, cell_id);
+                if item.is_running() {
+                    self.emit_admin_event(AdminEvent::CellLeftNetwork { cell_id });
                 }
+                removed.push(item.cell);
             }
         }
+        removed
     }
 
     /// Restart every paused app
@@ -1042,21 +1315,28 @@ pub(super) async fn genesis_cells(
             let keystore = keystore.clone();
             let conductor_handle = conductor_handle.clone();
             let cell_id_inner = cell_id.clone();
-            let ribosome = conductor_handle
-                .get_ribosome(cell_id.dna_hash())
+            let result = async {
+                let ribosome = conductor_handle
+                    .get_ribosome(cell_id.dna_hash())
+                    .await
+                    .map_err(Box::new)?;
+                tokio::spawn(async move {
+                    let env = EnvWrite::open(
+                        &root_env_dir,
+                        DbKind::Cell(cell_id_inner.clone()),
+                        keystore.clone(),
+                    )?;
+                    Cell::genesis(cell_id_inner, conductor_handle, env, ribosome, proof).await
+                })
                 .await
-                .map_err(Box::new)?;
-            tokio::spawn(async move {
-                let env = EnvWrite::open(
-                    &root_env_dir,
-                    DbKind::Cell(cell_id_inner.clone()),
-                    keystore.clone(),
-                )?;
-                Cell::genesis(cell_id_inner, conductor_handle, env, ribosome, proof).await
-            })
-            .map_err(CellError::from)
-            .and_then(|result| async move { result.map(|_| cell_id) })
-            .await
+                .map_err(CellError::from)?
+            }
+            .await;
+            // Keep the cell id of failures so they can be reported
+            match result {
+                Ok(()) => Ok(cell_id),
+                Err(e) => Err((cell_id, e)),
+            }
         });
     let (success, errors): (Vec<_>, Vec<_>) = futures::future::join_all(cells_tasks)
         .await
@@ -1068,16 +1348,24 @@ pub(super) async fn genesis_cells(
 
     // If there were errors, cleanup and return the errors
     if !errors.is_empty() {
-        for cell_id in success {
-            let db = DbWrite::open(&root_env_dir, DbKind::Cell(cell_id))?;
-            db.remove().await?;
-        }
+        // Remove every cell, since a failed genesis may have written part of its chain.
+        let failed = errors.iter().filter_map(|e| match e {
+            Err((cell_id, _)) => Some(cell_id.clone()),
+            Ok(_) => None,
+        });
+        remove_cell_envs(&root_env_dir, success.chain(failed).collect::<Vec<_>>()).await;
 
         // match needed to avoid Debug requirement on unwrap_err
         let errors = errors
             .into_iter()
             .map(|e| match e {
-                Err(e) => e,
+                Err((cell_id, e)) => {
+                    conductor_handle.emit_admin_event(AdminEvent::CellGenesisFailed {
+                        cell_id,
+                        error: e.to_string(),
+                    });
+                    e
+                }
                 Ok(_) => unreachable!("Safe because of the partition"),
             })
             .collect();
@@ -1089,6 +1377,117 @@ pub(super) async fn genesis_cells(
     }
 }
 
+/// Stop cells which were removed from the conductor, waiting for their
+/// queue consumers to finish. Every cell is stopped even if some fail.
+pub(super) async fn stop_cells(cells: Vec<Arc<Cell>>) -> CellResult<()> {
+    futures::future::join_all(cells.iter().map(|cell| cell.cleanup()))
+        .await
+        .into_iter()
+        .collect()
+}
+
+/// Remove the databases of cells which never ran, such as the cells created
+/// for a migration which failed. Failures are logged so the rest are still removed.
+pub(super) async fn remove_cell_envs(
+    root_env_dir: &std::path::Path,
+    cell_ids: impl IntoIterator<Item = CellId>,
+) {
+    for cell_id in cell_ids {
+        let removed = async {
+            DbWrite::open(root_env_dir, DbKind::Cell(cell_id.clone()))?
+                .remove()
+                .await
+        }
+        .await;
+        if let Err(error) = removed {
+            error!(?cell_id, ?error, "Failed to remove the database of a cell");
+        }
+    }
+}
+
+/// Spawn an admin interface again on the same port, after it failed
+fn respawn_admin_interface(
+    port: u16,
+    admin_api: RealAdminInterfaceApi,
+    stop_tx: StopBroadcaster,
+) -> Respawn {
+    Arc::new(move || {
+        let admin_api = admin_api.clone();
+        let stop_rx = stop_tx.subscribe();
+        async move {
+            let (listener_handle, listener) = spawn_websocket_listener(port)
+                .await
+                .map_err(Box::new)
+                .map_err(ConductorError::from)?;
+            let handle = spawn_admin_interface_task(listener_handle, listener, admin_api, stop_rx)
+                .map_err(Box::new)
+                .map_err(ConductorError::from)?;
+            Ok(handle)
+        }
+        .boxed()
+    })
+}
+
+/// Spawn an app interface again on the same port, after it failed
+fn respawn_app_interface(
+    port: u16,
+    app_api: RealAppInterfaceApi,
+    signal_tx: broadcast::Sender<Signal>,
+    stop_tx: StopBroadcaster,
+) -> Respawn {
+    Arc::new(move || {
+        let app_api = app_api.clone();
+        let signal_tx = signal_tx.clone();
+        let stop_rx = stop_tx.subscribe();
+        async move {
+            let (_, handle) = spawn_app_interface_task(port, app_api, signal_tx, stop_rx)
+                .await
+                .map_err(Box::new)
+                .map_err(ConductorError::from)?;
+            Ok(handle)
+        }
+        .boxed()
+    })
+}
+
+/// The event telling admin clients that an app was stopped,
+/// if a status transition paused or disabled it.
+fn app_stopped_event(
+    installed_app_id: &InstalledAppId,
+    before: &AppStatus,
+    after: &AppStatus,
+) -> Option<AdminEvent> {
+    if before == after {
+        return None;
+    }
+    let installed_app_id = installed_app_id.clone();
+    match after.clone() {
+        AppStatus::Running => None,
+        AppStatus::Paused(reason) => Some(AdminEvent::AppPaused {
+            installed_app_id,
+            reason,
+        }),
+        AppStatus::Disabled(reason) => Some(AdminEvent::AppDisabled {
+            installed_app_id,
+            reason,
+        }),
+    }
+}
+
+/// Every cell, archived clones included, used by an installed app.
+fn cells_in_use(state: &ConductorState) -> HashSet<CellId> {
+    state
+        .installed_apps()
+        .values()
+        .flat_map(|app| {
+            app.all_cells()
+                .chain(app.archived_cells_by_slot().map(second))
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
 /// Dump the integration json state.
 pub async fn integration_dump(vault: &EnvRead) -> ConductorApiResult<IntegrationStateDump> {
     vault
@@ -1125,6 +1524,171 @@ pub async fn integration_dump(vault: &EnvRead) -> ConductorApiResult<Integration
         .await
 }
 
+/// The columns of a [`DhtOpDump`] and the op's progress,
+/// selected from the `DhtOp` table joined with its `Header`.
+const DHT_OP_DUMP_SELECT: &str = "
+    SELECT
+    DhtOp.hash, DhtOp.type, DhtOp.header_hash, Header.author,
+    DhtOp.authored_timestamp_ms, DhtOp.validation_stage,
+    DhtOp.num_validation_attempts, DhtOp.last_validation_attempt,
+    DhtOp.last_publish_time, DhtOp.receipts_complete
+    FROM DhtOp
+    JOIN Header ON DhtOp.header_hash = Header.hash
+";
+
+fn dht_op_dump(row: &rusqlite::Row) -> rusqlite::Result<DhtOpDump> {
+    let ms: i64 = row.get("authored_timestamp_ms")?;
+    Ok(DhtOpDump {
+        hash: row.get("hash")?,
+        op_type: row.get("type")?,
+        header_hash: row.get("header_hash")?,
+        author: row.get("author")?,
+        authored_timestamp: Timestamp(
+            ms.div_euclid(1000),
+            (ms.rem_euclid(1000) * 1_000_000) as u32,
+        ),
+    })
+}
+
+/// The conditions on the ops selected by [`DHT_OP_DUMP_SELECT`] which keep
+/// only those passing the filter, to be joined to a `WHERE` clause with `AND`,
+/// along with their parameters.
+fn dht_op_dump_filter_sql(filter: &DhtOpDumpFilter) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+    // Ops are authored at whole milliseconds, so a bound between two
+    // milliseconds selects the same ops as the next millisecond up.
+    let ceil_ms = |t: Timestamp| t.to_sql_ms_lossy() + (t.1 % 1_000_000 != 0) as i64;
+    let mut sql = String::new();
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+    if let Some(op_types) = &filter.op_types {
+        if op_types.is_empty() {
+            sql.push_str(" AND 0");
+        } else {
+            let placeholders = vec!["?"; op_types.len()].join(", ");
+            sql.push_str(&format!(" AND DhtOp.type IN ({})", placeholders));
+            params.extend(
+                op_types
+                    .iter()
+                    .map(|t| Box::new(*t) as Box<dyn rusqlite::ToSql>),
+            );
+        }
+    }
+    if let Some(author) = &filter.author {
+        sql.push_str(" AND Header.author = ?");
+        params.push(Box::new(author.clone()));
+    }
+    if let Some(after) = filter.authored_after {
+        sql.push_str(" AND DhtOp.authored_timestamp_ms >= ?");
+        params.push(Box::new(ceil_ms(after)));
+    }
+    if let Some(before) = filter.authored_before {
+        sql.push_str(" AND DhtOp.authored_timestamp_ms < ?");
+        params.push(Box::new(ceil_ms(before)));
+    }
+    (sql, params)
+}
+
+/// A typed dump of the ops in a cell's vault, by how far they
+/// have got through validation and integration.
+pub async fn full_integration_dump(
+    vault: &EnvRead,
+    filter: DhtOpDumpFilter,
+) -> ConductorApiResult<FullIntegrationStateDump> {
+    vault
+        .async_reader(move |txn| {
+            let (filter_sql, params) = dht_op_dump_filter_sql(&filter);
+            let filter_params = || rusqlite::params_from_iter(params.iter());
+            let validation_limbo = txn
+                .prepare(&format!(
+                    "{}
+                    WHERE DhtOp.when_integrated IS NULL
+                    AND (
+                        (DhtOp.is_authored = 1 AND DhtOp.validation_stage IS NOT NULL AND DhtOp.validation_stage < 3)
+                        OR
+                        (DhtOp.is_authored = 0 AND (DhtOp.validation_stage IS NULL OR DhtOp.validation_stage < 3))
+                    )
+                    {}
+                    ",
+                    DHT_OP_DUMP_SELECT, filter_sql
+                ))?
+                .query_and_then(filter_params(), |row| {
+                    Ok(ValidationLimboOpDump {
+                        op: dht_op_dump(row)?,
+                        status: ValidationLimboStage::from_db(row.get("validation_stage")?),
+                        num_validation_attempts: row
+                            .get::<_, Option<u32>>("num_validation_attempts")?
+                            .unwrap_or(0),
+                        last_validation_attempt: row
+                            .get::<_, Option<i64>>("last_validation_attempt")?
+                            .map(|secs| Timestamp(secs, 0)),
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            let integration_limbo = txn
+                .prepare(&format!(
+                    "{} WHERE DhtOp.when_integrated IS NULL AND DhtOp.validation_stage = 3 {}",
+                    DHT_OP_DUMP_SELECT, filter_sql
+                ))?
+                .query_and_then(filter_params(), dht_op_dump)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            let integrated = txn.query_row(
+                &format!(
+                    "
+                    SELECT COUNT(*) FROM DhtOp
+                    JOIN Header ON DhtOp.header_hash = Header.hash
+                    WHERE DhtOp.when_integrated IS NOT NULL {}
+                    ",
+                    filter_sql
+                ),
+                filter_params(),
+                |row| row.get::<_, u32>(0),
+            )? as usize;
+            ConductorApiResult::Ok(FullIntegrationStateDump {
+                validation_limbo,
+                integration_limbo,
+                integrated,
+            })
+        })
+        .await
+}
+
+/// A typed dump of the ops a cell has authored and published,
+/// with the validation receipts collected for each.
+pub async fn published_ops_dump(
+    vault: &EnvRead,
+    filter: DhtOpDumpFilter,
+) -> ConductorApiResult<Vec<PublishedOpDump>> {
+    vault
+        .async_reader(move |txn| {
+            let (filter_sql, filter_params) = dht_op_dump_filter_sql(&filter);
+            let published = txn
+                .prepare(&format!(
+                    "{} WHERE DhtOp.is_authored = 1 AND DhtOp.last_publish_time IS NOT NULL {}",
+                    DHT_OP_DUMP_SELECT, filter_sql
+                ))?
+                .query_and_then(rusqlite::params_from_iter(filter_params.iter()), |row| {
+                    Ok((
+                        dht_op_dump(row)?,
+                        row.get::<_, Option<i64>>("last_publish_time")?,
+                        row.get::<_, Option<bool>>("receipts_complete")?,
+                    ))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            published
+                .into_iter()
+                .map(|(op, last_publish_time, receipts_complete)| {
+                    let receipts = list_receipts(&txn, &op.hash)?;
+                    Ok(PublishedOpDump {
+                        op,
+                        last_publish_time: last_publish_time.map(|secs| Timestamp(secs, 0)),
+                        receipts_complete: receipts_complete.unwrap_or(false),
+                        receipts,
+                    })
+                })
+                .collect::<ConductorApiResult<Vec<_>>>()
+        })
+        .await
+}
+
 //-----------------------------------------------------------------------------
 // Private methods
 //-----------------------------------------------------------------------------
@@ -1141,6 +1705,7 @@ where
         keystore: KeystoreSender,
         root_env_dir: EnvironmentRootPath,
         holochain_p2p: holochain_p2p::HolochainP2pRef,
+        task_supervision: TaskSupervisionConfig,
     ) -> ConductorResult<Self> {
         Ok(Self {
             conductor_env: env,
@@ -1155,6 +1720,8 @@ where
             keystore,
             root_env_dir,
             holochain_p2p,
+            admin_event_broadcaster: broadcast::channel(ADMIN_EVENT_BUFFER_SIZE).0,
+            task_supervision,
         })
     }
 
@@ -1230,6 +1797,8 @@ where
 mod builder {
     use super::*;
     use crate::conductor::dna_store::RealDnaStore;
+    use crate::conductor::dpki::DnaKeyRegistry;
+    use crate::conductor::dpki::KeyRegistryRef;
     use crate::conductor::ConductorHandle;
     use holochain_sqlite::db::DbKind;
     #[cfg(any(test, feature = "test_utils"))]
@@ -1244,6 +1813,8 @@ mod builder {
         pub dna_store: DS,
         /// Optional keystore override
         pub keystore: Option<KeystoreSender>,
+        /// Optional key registry override
+        pub key_registry: Option<KeyRegistryRef>,
         #[cfg(any(test, feature = "test_utils"))]
         /// Optional state override (for testing)
         pub state: Option<ConductorState>,
@@ -1333,8 +1904,12 @@ mod builder {
             let state = self.state;
 
             let Self {
-                dna_store, config, ..
+                dna_store,
+                config,
+                key_registry,
+                ..
             } = self;
+            let (key_registry, dna_key_registry) = Self::key_registry(key_registry, &config);
 
             let network_config = match &config.network {
                 None => holochain_p2p::kitsune_p2p::KitsuneP2pConfig::default(),
@@ -1358,6 +1933,7 @@ mod builder {
                 keystore,
                 env_path,
                 holochain_p2p,
+                config.task_supervision.clone().unwrap_or_default(),
             )
             .await?;
 
@@ -1367,6 +1943,7 @@ mod builder {
             // Get data before handle
             let keystore = conductor.keystore.clone();
             let holochain_p2p = conductor.holochain_p2p.clone();
+            let admin_event_broadcaster = conductor.admin_event_broadcaster.clone();
 
             // Create handle
             let handle: ConductorHandle = Arc::new(ConductorHandleImpl {
@@ -1374,12 +1951,22 @@ mod builder {
                 conductor: RwLock::new(conductor),
                 keystore,
                 holochain_p2p,
+                key_registry,
+                admin_event_broadcaster,
 
                 #[cfg(any(test, feature = "test_utils"))]
                 skip_publish: std::sync::atomic::AtomicBool::new(false),
+                #[cfg(any(test, feature = "test_utils"))]
+                fail_app_upgrade: std::sync::atomic::AtomicBool::new(false),
                 p2p_env: Arc::new(parking_lot::Mutex::new(HashMap::new())),
                 p2p_metrics_env: Arc::new(parking_lot::Mutex::new(HashMap::new())),
+                p2p_block_lists: Arc::new(parking_lot::Mutex::new(HashMap::new())),
+                metrics: Default::default(),
+                zome_call_timeout: config.zome_call_timeout(),
             });
+            if let Some(dna_key_registry) = dna_key_registry {
+                dna_key_registry.attach(&handle);
+            }
 
             Self::finish(handle, config, p2p_evt).await
         }
@@ -1390,11 +1977,17 @@ mod builder {
             p2p_evt: holochain_p2p::event::HolochainP2pEventReceiver,
         ) -> ConductorResult<ConductorHandle> {
             tokio::task::spawn(p2p_event_task(p2p_evt, handle.clone()));
+            tokio::task::spawn(p2p_metrics_prune_task(Arc::downgrade(&handle)));
 
             let configs = conductor_config.admin_interfaces.unwrap_or_default();
             let cell_startup_errors = handle.clone().initialize_conductor(configs).await?;
 
-            // TODO: This should probably be emitted over the admin interface
+            if let Some(metrics) = conductor_config.metrics {
+                handle.clone().add_metrics_endpoint(metrics).await?;
+            }
+
+            // These were also emitted as admin events, but no admin client
+            // could have subscribed to them yet
             if !cell_startup_errors.is_empty() {
                 error!(
                     msg = "Failed to create the following active apps",
@@ -1407,6 +2000,29 @@ mod builder {
             Ok(handle)
         }
 
+        /// Use this key registry instead of the one from the DPKI config
+        pub fn with_key_registry(mut self, key_registry: KeyRegistryRef) -> Self {
+            self.key_registry = Some(key_registry);
+            self
+        }
+
+        /// The key registry to give the handle, along with the DNA backed
+        /// registry which needs attaching to the handle once it exists.
+        fn key_registry(
+            key_registry: Option<KeyRegistryRef>,
+            config: &ConductorConfig,
+        ) -> (Option<KeyRegistryRef>, Option<Arc<DnaKeyRegistry>>) {
+            match (key_registry, &config.dpki) {
+                (Some(key_registry), _) => (Some(key_registry), None),
+                (None, Some(dpki)) => {
+                    let registry = Arc::new(DnaKeyRegistry::new(dpki.instance_id.clone()));
+                    let key_registry: KeyRegistryRef = registry.clone();
+                    (Some(key_registry), Some(registry))
+                }
+                (None, None) => (None, None),
+            }
+        }
+
         /// Pass a test keystore in, to ensure that generated test agents
         /// are actually available for signing (especially for tryorama compat)
         pub fn with_keystore(mut self, keystore: KeystoreSender) -> Self {
@@ -1462,14 +2078,18 @@ mod builder {
                 keystore,
                 self.config.environment_path.clone(),
                 holochain_p2p,
+                self.config.task_supervision.clone().unwrap_or_default(),
             )
             .await?;
 
             let conductor = Self::update_fake_state(self.state, conductor).await?;
+            let (key_registry, dna_key_registry) =
+                Self::key_registry(self.key_registry, &self.config);
 
             // Get data before handle
             let keystore = conductor.keystore.clone();
             let holochain_p2p = conductor.holochain_p2p.clone();
+            let admin_event_broadcaster = conductor.admin_event_broadcaster.clone();
 
             // Create handle
             let handle: ConductorHandle = Arc::new(ConductorHandleImpl {
@@ -1477,11 +2097,21 @@ mod builder {
                 conductor: RwLock::new(conductor),
                 keystore,
                 holochain_p2p,
+                key_registry,
+                admin_event_broadcaster,
                 p2p_env: envs.p2p(),
                 p2p_metrics_env: envs.p2p_metrics(),
+                p2p_block_lists: Arc::new(parking_lot::Mutex::new(HashMap::new())),
+                metrics: Default::default(),
+                zome_call_timeout: self.config.zome_call_timeout(),
                 #[cfg(any(test, feature = "test_utils"))]
                 skip_publish: std::sync::atomic::AtomicBool::new(false),
+                #[cfg(any(test, feature = "test_utils"))]
+                fail_app_upgrade: std::sync::atomic::AtomicBool::new(false),
             });
+            if let Some(dna_key_registry) = dna_key_registry {
+                dna_key_registry.attach(&handle);
+            }
 
             // Install extra DNAs, in particular:
             // the ones with InlineZomes will not be registered in the Wasm DB
@@ -1524,5 +2154,27 @@ async fn p2p_event_task(
     tracing::warn!("p2p_event_task has ended");
 }
 
+/// Prune the expired p2p metric data on an interval,
+/// for as long as the conductor is running.
+async fn p2p_metrics_prune_task(handle: std::sync::Weak<dyn ConductorHandleT>) {
+    let mut interval = tokio::time::interval(super::p2p_metrics::METRIC_PRUNE_INTERVAL);
+    loop {
+        interval.tick().await;
+        let handle = match handle.upgrade() {
+            Some(handle) => handle,
+            None => break,
+        };
+        if handle.check_running().await.is_err() {
+            break;
+        }
+        if let Err(e) = handle.prune_p2p_metrics().await {
+            tracing::error!(
+                message = "error pruning p2p metrics",
+                error = ?e,
+            );
+        }
+    }
+}
+
 #[cfg(test)]
 pub mod tests;
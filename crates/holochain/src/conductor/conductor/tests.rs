@@ -12,9 +12,11 @@ use crate::{
     assert_eq_retry_10s, core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckResult,
 };
 use ::fixt::prelude::*;
+use holochain_conductor_api::AdminEvent;
 use holochain_conductor_api::InstalledAppInfoStatus;
 use holochain_conductor_api::{AdminRequest, AdminResponse, AppRequest, AppResponse, ZomeCall};
 use holochain_keystore::crude_mock_keystore::spawn_crude_mock_keystore;
+use holochain_p2p::DnaHashExt;
 use holochain_state::prelude::*;
 use holochain_types::test_utils::fake_cell_id;
 use holochain_wasm_test_utils::TestWasm;
@@ -401,6 +403,28 @@ async fn test_uninstall_app() {
         },
         (1, 0)
     );
+    let cell_ids = conductor.list_cell_ids(None).await.unwrap();
+    assert_eq!(cell_ids.len(), 2);
+
+    // Record a metric about each agent, which goes with the agent's cell
+    let metrics_env = |cell_id: &CellId| {
+        EnvWrite::open(
+            conductor.envs().path(),
+            DbKind::P2pMetrics(cell_id.dna_hash().to_kitsune()),
+            conductor.keystore(),
+        )
+        .unwrap()
+    };
+    for cell_id in &cell_ids {
+        crate::conductor::p2p_metrics::put_metric_datum(
+            metrics_env(cell_id),
+            cell_id.agent_pubkey().clone(),
+            kitsune_p2p::event::MetricKind::QuickGossip,
+            std::time::SystemTime::now(),
+        )
+        .await
+        .unwrap();
+    }
 
     conductor
         .inner_handle()
@@ -416,6 +440,24 @@ async fn test_uninstall_app() {
         },
         (0, 0)
     );
+
+    // - Ensure that its cells are removed, along with their data
+    assert!(conductor.list_cell_ids(None).await.unwrap().is_empty());
+    for cell_id in cell_ids {
+        let infos = conductor
+            .get_agent_infos(Some(cell_id.clone()))
+            .await
+            .unwrap();
+        assert!(infos.is_empty());
+        let metrics = crate::conductor::p2p_metrics::summarize_metrics(metrics_env(&cell_id))
+            .await
+            .unwrap();
+        assert!(metrics.is_empty());
+    }
+    let cell_dbs = std::fs::read_dir(conductor.envs().path().join("cell"))
+        .map(|dir| dir.count())
+        .unwrap_or(0);
+    assert_eq!(cell_dbs, 0);
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -543,7 +585,11 @@ async fn test_signing_error_during_genesis_doesnt_bork_interfaces() {
     let (cell1,) = app1.into_tuple();
     let (cell2,) = app2.into_tuple();
 
-    let app_port = conductor.inner_handle().add_app_interface(0).await.unwrap();
+    let app_port = conductor
+        .inner_handle()
+        .add_app_interface(0, None)
+        .await
+        .unwrap();
     let (mut app_client, _) = websocket_client_by_port(app_port).await.unwrap();
     let (mut admin_client, _) = conductor.admin_ws_client().await;
 
@@ -757,6 +803,37 @@ async fn test_installation_fails_if_genesis_self_check_is_invalid() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_admin_event_for_genesis_failure() {
+    observability::test_run().ok();
+    let bad_zome = InlineZome::new_unique(Vec::new()).callback(
+        "genesis_self_check",
+        |_api, _data: GenesisSelfCheckData| {
+            Ok(GenesisSelfCheckResult::Invalid(
+                "intentional invalid result for testing".into(),
+            ))
+        },
+    );
+
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let mut events = conductor.subscribe_admin_events();
+    common_genesis_test_app(&mut conductor, bad_zome)
+        .await
+        .unwrap_err();
+
+    // - Only the cell of the bad zome failed genesis
+    let mut failed = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        if let AdminEvent::CellGenesisFailed { cell_id, error } = event {
+            failed.push((cell_id, error));
+        }
+    }
+    assert_eq!(failed.len(), 1);
+    assert!(failed[0]
+        .1
+        .contains("intentional invalid result for testing"));
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_bad_entry_validation_after_genesis_returns_zome_call_error() {
     observability::test_run().ok();
@@ -919,6 +996,56 @@ async fn test_app_status_states() {
     assert_matches!(get_status().await, InstalledAppInfoStatus::Running);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_admin_events_for_app_status() {
+    observability::test_run().ok();
+    let zome = simple_create_entry_zome();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    common_genesis_test_app(&mut conductor, zome).await.unwrap();
+    let mut events = conductor.subscribe_admin_events();
+    let app_id = "app".to_string();
+
+    conductor
+        .pause_app(&app_id, PausedAppReason::Error("because".into()))
+        .await
+        .unwrap();
+    conductor.start_app(&app_id).await.unwrap();
+    conductor
+        .disable_app(&app_id, DisabledAppReason::User)
+        .await
+        .unwrap();
+    // Disabling an app which is already disabled doesn't emit anything
+    conductor
+        .disable_app(&app_id, DisabledAppReason::User)
+        .await
+        .unwrap();
+
+    let mut app_events = Vec::new();
+    let mut cells_left = 0;
+    while let Ok(event) = events.try_recv() {
+        match event {
+            AdminEvent::CellLeftNetwork { .. } => cells_left += 1,
+            AdminEvent::CellJoinedNetwork { .. } => (),
+            event => app_events.push(event),
+        }
+    }
+    assert_eq!(
+        app_events,
+        vec![
+            AdminEvent::AppPaused {
+                installed_app_id: app_id.clone(),
+                reason: PausedAppReason::Error("because".into()),
+            },
+            AdminEvent::AppDisabled {
+                installed_app_id: app_id,
+                reason: DisabledAppReason::User,
+            },
+        ]
+    );
+    // Both cells of the disabled app left the network
+    assert_eq!(cells_left, 2);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 #[ignore = "we don't have the ability to share cells across apps yet, but will need a test for that once we do"]
 async fn test_app_status_states_multi_app() {
@@ -1114,3 +1241,490 @@ async fn test_init_concurrency() {
     assert_eq!(num_calls_clone.fetch_add(0, Ordering::SeqCst), 100);
     assert_eq!(num_inits_clone.fetch_add(0, Ordering::SeqCst), 1);
 }
+
+/// Attach an app interface, for an app or for none, and return its id
+async fn attach_app_crypto_interface(
+    conductor: &SweetConductor,
+    installed_app_id: Option<&str>,
+) -> AppInterfaceId {
+    conductor
+        .inner_handle()
+        .add_app_interface(0, installed_app_id.map(str::to_string))
+        .await
+        .unwrap();
+    conductor
+        .get_state_from_handle()
+        .await
+        .unwrap()
+        .app_interfaces
+        .into_iter()
+        .find(|(_, config)| config.installed_app_id.as_deref() == installed_app_id)
+        .map(|(id, _)| id)
+        .unwrap()
+}
+
+/// Create an X25519 keypair for an app over an interface attached for it
+async fn create_app_x25519_key(
+    conductor: &SweetConductor,
+    interface_id: &AppInterfaceId,
+    installed_app_id: &str,
+) -> X25519PubKey {
+    use holochain_conductor_api::{CryptoRequest, CryptoResponse};
+    match conductor
+        .app_crypto(
+            interface_id,
+            CryptoRequest::CreateX25519Keypair {
+                installed_app_id: installed_app_id.to_string(),
+            },
+        )
+        .await
+        .unwrap()
+    {
+        CryptoResponse::X25519PubKey(key) => key,
+        other => panic!("unexpected response {:?}", other),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_app_crypto() {
+    use holochain_conductor_api::{CryptoRequest, CryptoResponse};
+    use holochain_keystore::AgentPubKeyExt;
+
+    observability::test_run().ok();
+    let (dna, _) = mk_dna("zome", InlineZome::new_unique(Vec::new()))
+        .await
+        .unwrap();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let alice = SweetAgents::one(conductor.keystore()).await;
+    conductor
+        .setup_app_for_agent("app", alice.clone(), &[dna])
+        .await
+        .unwrap();
+    let installed_app_id = "app".to_string();
+    let interface_id = attach_app_crypto_interface(&conductor, Some("app")).await;
+
+    // - Data is signed with the app's agent key
+    let data = vec![1, 2, 3];
+    let response = conductor
+        .app_crypto(
+            &interface_id,
+            CryptoRequest::Sign {
+                installed_app_id: installed_app_id.clone(),
+                data: Bytes::from(data.clone()),
+            },
+        )
+        .await
+        .unwrap();
+    let signature = match response {
+        CryptoResponse::Signature(signature) => signature,
+        other => panic!("unexpected response {:?}", other),
+    };
+    assert!(alice
+        .verify_signature_raw(&signature, &CryptoRequest::signed_data(&data))
+        .await
+        .unwrap());
+    // - The signature is only good for the tagged data,
+    //   so it can't pass for a signature over a header
+    assert!(!alice.verify_signature_raw(&signature, &data).await.unwrap());
+
+    // - Data encrypted for one of the app's keys can be decrypted with it
+    let sender = create_app_x25519_key(&conductor, &interface_id, "app").await;
+    let recipient = create_app_x25519_key(&conductor, &interface_id, "app").await;
+    let plaintext = XSalsa20Poly1305Data::from(data);
+    let encrypted = match conductor
+        .app_crypto(
+            &interface_id,
+            CryptoRequest::Encrypt {
+                installed_app_id: installed_app_id.clone(),
+                payload: X25519XSalsa20Poly1305Encrypt::new(sender, recipient, plaintext.clone()),
+            },
+        )
+        .await
+        .unwrap()
+    {
+        CryptoResponse::Encrypted(encrypted) => encrypted,
+        other => panic!("unexpected response {:?}", other),
+    };
+    assert_eq!(
+        conductor
+            .app_crypto(
+                &interface_id,
+                CryptoRequest::Decrypt {
+                    installed_app_id: installed_app_id.clone(),
+                    payload: X25519XSalsa20Poly1305Decrypt::new(recipient, sender, encrypted),
+                },
+            )
+            .await
+            .unwrap(),
+        CryptoResponse::Decrypted(Some(plaintext))
+    );
+
+    // - A disabled app can't use the keystore
+    conductor
+        .disable_app(&installed_app_id, DisabledAppReason::User)
+        .await
+        .unwrap();
+    assert_matches!(
+        conductor
+            .app_crypto(
+                &interface_id,
+                CryptoRequest::Sign {
+                    installed_app_id: installed_app_id.clone(),
+                    data: Bytes::from(vec![1, 2, 3]),
+                },
+            )
+            .await,
+        Err(ConductorError::AppNotRunning(id)) if id == installed_app_id
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_app_crypto_other_apps_keys() {
+    use holochain_conductor_api::CryptoRequest;
+    use holochain_keystore::KeystoreSenderExt;
+
+    observability::test_run().ok();
+    let (dna, _) = mk_dna("zome", InlineZome::new_unique(Vec::new()))
+        .await
+        .unwrap();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    conductor.setup_app("app_a", &[dna.clone()]).await.unwrap();
+    conductor.setup_app("app_b", &[dna]).await.unwrap();
+    let interface_a = attach_app_crypto_interface(&conductor, Some("app_a")).await;
+    let interface_b = attach_app_crypto_interface(&conductor, Some("app_b")).await;
+    let unattached = attach_app_crypto_interface(&conductor, None).await;
+    let key_a = create_app_x25519_key(&conductor, &interface_a, "app_a").await;
+    let key_b = create_app_x25519_key(&conductor, &interface_b, "app_b").await;
+    let sign_for_a = || CryptoRequest::Sign {
+        installed_app_id: "app_a".to_string(),
+        data: Bytes::from(vec![1, 2, 3]),
+    };
+
+    // - App B's interface can't sign with app A's agent key
+    assert_matches!(
+        conductor.app_crypto(&interface_b, sign_for_a()).await,
+        Err(ConductorError::AppCryptoNotAllowed(id)) if id == "app_a"
+    );
+
+    // - An interface attached for no app can't sign for any app
+    assert_matches!(
+        conductor.app_crypto(&unattached, sign_for_a()).await,
+        Err(ConductorError::AppCryptoNotAllowed(id)) if id == "app_a"
+    );
+
+    // - App B can't encrypt from or decrypt to app A's X25519 key
+    let data = XSalsa20Poly1305Data::from(vec![1, 2, 3]);
+    assert_matches!(
+        conductor
+            .app_crypto(
+                &interface_b,
+                CryptoRequest::Encrypt {
+                    installed_app_id: "app_b".to_string(),
+                    payload: X25519XSalsa20Poly1305Encrypt::new(key_a, key_b, data.clone()),
+                },
+            )
+            .await,
+        Err(ConductorError::AppCryptoNotAllowed(id)) if id == "app_b"
+    );
+    let encrypted = conductor
+        .keystore()
+        .x_25519_x_salsa20_poly1305_encrypt(X25519XSalsa20Poly1305Encrypt::new(key_b, key_a, data))
+        .await
+        .unwrap();
+    assert_matches!(
+        conductor
+            .app_crypto(
+                &interface_b,
+                CryptoRequest::Decrypt {
+                    installed_app_id: "app_b".to_string(),
+                    payload: X25519XSalsa20Poly1305Decrypt::new(key_a, key_b, encrypted),
+                },
+            )
+            .await,
+        Err(ConductorError::AppCryptoNotAllowed(id)) if id == "app_b"
+    );
+
+    // - Keys created in the keystore by anything else are not the app's
+    let other_key = conductor.keystore().create_x25519_keypair().await.unwrap();
+    assert_matches!(
+        conductor
+            .app_crypto(
+                &interface_b,
+                CryptoRequest::Encrypt {
+                    installed_app_id: "app_b".to_string(),
+                    payload: X25519XSalsa20Poly1305Encrypt::new(
+                        other_key,
+                        key_b,
+                        XSalsa20Poly1305Data::from(vec![1]),
+                    ),
+                },
+            )
+            .await,
+        Err(ConductorError::AppCryptoNotAllowed(id)) if id == "app_b"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_dump_full_cell_state() {
+    use holochain_conductor_api::DhtOpDumpFilter;
+
+    observability::test_run().ok();
+    let (dna, _) = mk_dna("zome", simple_create_entry_zome()).await.unwrap();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", &[dna]).await.unwrap();
+    let (cell,) = app.into_tuple();
+    let _: HeaderHash = conductor.call(&cell.zome("zome"), "create", ()).await;
+    crate::test_utils::consistency_10s(&[&cell]).await;
+
+    // - The cell's own ops are integrated, and the cell is one of its peers
+    let dump = conductor
+        .dump_full_cell_state(cell.cell_id(), None)
+        .await
+        .unwrap();
+    assert!(dump.integration_dump.integrated > 0);
+    assert!(dump
+        .peers
+        .iter()
+        .any(|peer| peer.agent == *cell.agent_pubkey()));
+    let all = dump;
+
+    // - Only ops of the filtered type are dumped
+    let filter = DhtOpDumpFilter {
+        op_types: Some(vec![DhtOpType::StoreEntry]),
+        ..Default::default()
+    };
+    let dump = conductor
+        .dump_full_cell_state(cell.cell_id(), Some(filter))
+        .await
+        .unwrap();
+    assert!(dump.integration_dump.integrated > 0);
+    assert!(dump.integration_dump.integrated < all.integration_dump.integrated);
+    assert!(dump
+        .published_ops
+        .iter()
+        .all(|published| published.op.op_type == DhtOpType::StoreEntry));
+
+    // - The cell's own ops all pass a filter on its own key
+    let filter = DhtOpDumpFilter {
+        author: Some(cell.agent_pubkey().clone()),
+        authored_before: Some(timestamp::now()),
+        ..Default::default()
+    };
+    let dump = conductor
+        .dump_full_cell_state(cell.cell_id(), Some(filter))
+        .await
+        .unwrap();
+    assert_eq!(
+        dump.integration_dump.integrated,
+        all.integration_dump.integrated
+    );
+    assert_eq!(dump.published_ops.len(), all.published_ops.len());
+
+    // - No ops were authored after now
+    let filter = DhtOpDumpFilter {
+        authored_after: Some(timestamp::now()),
+        ..Default::default()
+    };
+    let dump = conductor
+        .dump_full_cell_state(cell.cell_id(), Some(filter))
+        .await
+        .unwrap();
+    assert_eq!(dump.integration_dump.integrated, 0);
+    assert!(dump.published_ops.is_empty());
+
+    // - Ops by another author are filtered out
+    let filter = DhtOpDumpFilter {
+        author: Some(fixt!(AgentPubKey)),
+        ..Default::default()
+    };
+    let dump = conductor
+        .dump_full_cell_state(cell.cell_id(), Some(filter))
+        .await
+        .unwrap();
+    assert_eq!(dump.integration_dump.integrated, 0);
+    assert!(dump.integration_dump.validation_limbo.is_empty());
+    assert!(dump.integration_dump.integration_limbo.is_empty());
+    assert!(dump.published_ops.is_empty());
+}
+
+#[test]
+fn dht_op_dump_filter_sql_selects_matching_ops() {
+    use holochain_conductor_api::DhtOpDumpFilter;
+
+    let db = rusqlite::Connection::open_in_memory().unwrap();
+    db.execute_batch(
+        "
+        CREATE TABLE Header (hash INTEGER PRIMARY KEY, author BLOB);
+        CREATE TABLE DhtOp (header_hash INTEGER, type TEXT, authored_timestamp_ms INTEGER);
+        ",
+    )
+    .unwrap();
+    let alice = AgentPubKey::from_raw_32(vec![1; 32]);
+    let bobbo = AgentPubKey::from_raw_32(vec![2; 32]);
+    // (header, author, op type, authored at ms)
+    let ops = vec![
+        (1, &alice, DhtOpType::StoreEntry, 10),
+        (2, &bobbo, DhtOpType::RegisterAddLink, 11),
+        (3, &alice, DhtOpType::StoreElement, 20),
+    ];
+    for (header, author, op_type, ms) in &ops {
+        db.execute(
+            "INSERT INTO Header (hash, author) VALUES (?, ?)",
+            rusqlite::params![header, author],
+        )
+        .unwrap();
+        db.execute(
+            "INSERT INTO DhtOp (header_hash, type, authored_timestamp_ms) VALUES (?, ?, ?)",
+            rusqlite::params![header, op_type, ms],
+        )
+        .unwrap();
+    }
+    let select = |filter: DhtOpDumpFilter| -> Vec<i64> {
+        let (filter_sql, params) = dht_op_dump_filter_sql(&filter);
+        db.prepare(&format!(
+            "SELECT DhtOp.header_hash FROM DhtOp
+            JOIN Header ON DhtOp.header_hash = Header.hash
+            WHERE 1 {} ORDER BY DhtOp.header_hash",
+            filter_sql
+        ))
+        .unwrap()
+        .query_map(rusqlite::params_from_iter(params.iter()), |row| row.get(0))
+        .unwrap()
+        .collect::<rusqlite::Result<_>>()
+        .unwrap()
+    };
+    let ms =
+        |ms: i64, extra_ns: u32| Timestamp(ms / 1000, (ms % 1000) as u32 * 1_000_000 + extra_ns);
+
+    assert_eq!(select(DhtOpDumpFilter::default()), vec![1, 2, 3]);
+
+    // - By type, and no type at all selects nothing
+    let filter = DhtOpDumpFilter {
+        op_types: Some(vec![DhtOpType::StoreEntry, DhtOpType::StoreElement]),
+        ..Default::default()
+    };
+    assert_eq!(select(filter), vec![1, 3]);
+    let filter = DhtOpDumpFilter {
+        op_types: Some(vec![]),
+        ..Default::default()
+    };
+    assert!(select(filter).is_empty());
+
+    // - By author
+    let filter = DhtOpDumpFilter {
+        author: Some(bobbo.clone()),
+        ..Default::default()
+    };
+    assert_eq!(select(filter), vec![2]);
+
+    // - After is inclusive and before is exclusive
+    let filter = DhtOpDumpFilter {
+        authored_after: Some(ms(11, 0)),
+        authored_before: Some(ms(20, 0)),
+        ..Default::default()
+    };
+    assert_eq!(select(filter), vec![2]);
+
+    // - Bounds between two milliseconds round up,
+    //   as no op is authored between them
+    let filter = DhtOpDumpFilter {
+        authored_after: Some(ms(10, 1)),
+        ..Default::default()
+    };
+    assert_eq!(select(filter), vec![2, 3]);
+    let filter = DhtOpDumpFilter {
+        authored_before: Some(ms(11, 1)),
+        ..Default::default()
+    };
+    assert_eq!(select(filter), vec![1, 2]);
+
+    // - Every condition must hold
+    let filter = DhtOpDumpFilter {
+        op_types: Some(vec![DhtOpType::StoreEntry]),
+        author: Some(bobbo),
+        ..Default::default()
+    };
+    assert!(select(filter).is_empty());
+}
+
+/// Wait until a cell has no ops left to validate or integrate,
+/// and integrated the expected number of them if one is given.
+/// Returns the number of ops it integrated.
+async fn wait_for_integrated_ops(
+    conductor: &SweetConductor,
+    cell_id: &CellId,
+    expected: Option<usize>,
+) -> usize {
+    let mut dump = None;
+    for _ in 0..100 {
+        let current = conductor
+            .dump_full_cell_state(cell_id, None)
+            .await
+            .unwrap()
+            .integration_dump;
+        let settled = current.validation_limbo.is_empty()
+            && current.integration_limbo.is_empty()
+            && current.integrated > 0;
+        if settled && expected.map_or(true, |expected| current.integrated == expected) {
+            return current.integrated;
+        }
+        dump = Some(current);
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    panic!(
+        "The ops of cell {:?} were not integrated, expected {:?} but found {:?}",
+        cell_id, expected, dump
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_backup_and_restore_app() {
+    observability::test_run().ok();
+    let (dna, _) = mk_dna("zome", simple_create_entry_zome()).await.unwrap();
+    let app_id = "app".to_string();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app(&app_id, &[dna.clone()]).await.unwrap();
+    let (cell,) = app.into_tuple();
+    let _: HeaderHash = conductor.call(&cell.zome("zome"), "create", ()).await;
+    let integrated = wait_for_integrated_ops(&conductor, cell.cell_id(), None).await;
+    let head = SourceChain::new(cell.env().clone(), cell.agent_pubkey().clone())
+        .await
+        .unwrap()
+        .chain_head()
+        .unwrap();
+
+    let backup = conductor.backup_app(&app_id).await.unwrap();
+
+    // - A fresh conductor holding the same agent key restores the app
+    let envs = TestEnvs::with_keystore(
+        tempdir::TempDir::new("restored-conductor").unwrap(),
+        conductor.keystore(),
+    );
+    let config = standard_config();
+    let handle = SweetConductor::handle_from_existing(&envs, &config, &[dna]).await;
+    let restored = SweetConductor::new(handle, envs, config).await;
+    restored
+        .inner_handle()
+        .restore_app(backup.clone())
+        .await
+        .unwrap();
+    restored.enable_app(&app_id).await.unwrap();
+
+    // - The chain is continued rather than created again
+    let env = restored.get_cell_env(cell.cell_id()).await.unwrap();
+    let restored_head = SourceChain::new(env, cell.agent_pubkey().clone())
+        .await
+        .unwrap()
+        .chain_head()
+        .unwrap();
+    assert_eq!(restored_head, head);
+
+    // - The restored ops are validated and integrated again
+    wait_for_integrated_ops(&restored, cell.cell_id(), Some(integrated)).await;
+
+    // - The app can only be restored once
+    assert_matches!(
+        restored.inner_handle().restore_app(backup).await,
+        Err(ConductorError::AppAlreadyInstalled(id)) if id == app_id
+    );
+}
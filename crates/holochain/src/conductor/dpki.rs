@@ -0,0 +1,332 @@
+//! The DPKI key registry keeps track of agent keys as they are generated,
+//! replaced and revoked, so that ops signed with a key which was no longer
+//! valid at the time can be rejected during validation.
+//!
+//! A conductor configured with a [`DpkiConfig`] uses a [`DnaKeyRegistry`],
+//! which defers to an installed DPKI app. The [`MemKeyRegistry`] keeps its
+//! records in memory and is meant for tests.
+//!
+//! [`DpkiConfig`]: holochain_conductor_api::config::conductor::DpkiConfig
+
+use super::api::error::ConductorApiError;
+use super::handle::ConductorHandleT;
+use super::ConductorHandle;
+use crate::core::ribosome::error::RibosomeError;
+use holochain_conductor_api::ZomeCall;
+use holochain_types::prelude::*;
+use holochain_types::timestamp;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Weak;
+
+/// The zome of the DPKI app which the [`DnaKeyRegistry`] calls into.
+pub const DPKI_ZOME_NAME: &str = "dpki";
+
+/// How long the [`DnaKeyRegistry`] trusts a key state which could still change.
+const KEY_STATE_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(60);
+
+#[allow(missing_docs)]
+#[derive(thiserror::Error, Debug)]
+pub enum DpkiError {
+    #[error("The DPKI app {0} is not installed or has no cells")]
+    AppMissing(InstalledAppId),
+
+    #[error("The DPKI key registry is not attached to a running conductor")]
+    ConductorMissing,
+
+    #[error("This conductor has no DPKI key registry configured")]
+    RegistryMissing,
+
+    #[error("The key {0} can't be changed as it is no longer valid: {1:?}")]
+    KeyNotValid(AgentPubKey, KeyState),
+
+    #[error("The DPKI zome function {0} did not succeed: {1:?}")]
+    UnexpectedResponse(FunctionName, ZomeCallResponse),
+
+    #[error(transparent)]
+    ConductorApiError(#[from] Box<ConductorApiError>),
+
+    #[error(transparent)]
+    RibosomeError(#[from] Box<RibosomeError>),
+
+    #[error(transparent)]
+    SerializedBytesError(#[from] SerializedBytesError),
+}
+
+/// Result type for key registry calls
+pub type DpkiResult<T> = Result<T, DpkiError>;
+
+/// A shared reference to the conductor's key registry
+pub type KeyRegistryRef = Arc<dyn KeyRegistry>;
+
+/// A service which records the lifecycle of agent keys.
+#[async_trait::async_trait]
+pub trait KeyRegistry: Send + Sync {
+    /// Get the state of a key as of the given time.
+    async fn key_state(&self, key: AgentPubKey, at: Timestamp) -> DpkiResult<KeyState>;
+
+    /// Record that a new key was generated.
+    async fn register_key(&self, key: AgentPubKey) -> DpkiResult<()>;
+
+    /// Record that a key was replaced by a new key.
+    async fn rotate_key(&self, old_key: AgentPubKey, new_key: AgentPubKey) -> DpkiResult<()>;
+
+    /// Record that a key was revoked and must not be trusted from now on.
+    async fn revoke_key(&self, key: AgentPubKey) -> DpkiResult<()>;
+}
+
+/// A key registry which keeps the latest state of each key in memory.
+#[derive(Default)]
+pub struct MemKeyRegistry(parking_lot::Mutex<HashMap<AgentPubKey, KeyState>>);
+
+impl MemKeyRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn change(&self, key: AgentPubKey, state: KeyState) -> DpkiResult<()> {
+        let mut keys = self.0.lock();
+        let current = keys.entry(key.clone()).or_insert(KeyState::Valid);
+        if !current.is_valid() {
+            return Err(DpkiError::KeyNotValid(key, current.clone()));
+        }
+        *current = state;
+        Ok(())
+    }
+}
+
+/// The state of a key at a point in time, given its latest state.
+fn key_state_at(latest: &KeyState, at: Timestamp) -> KeyState {
+    match latest {
+        // The key was still valid at the time asked about.
+        KeyState::Updated { timestamp, .. } | KeyState::Invalidated { timestamp }
+            if *timestamp > at =>
+        {
+            KeyState::Valid
+        }
+        state => state.clone(),
+    }
+}
+
+#[async_trait::async_trait]
+impl KeyRegistry for MemKeyRegistry {
+    async fn key_state(&self, key: AgentPubKey, at: Timestamp) -> DpkiResult<KeyState> {
+        Ok(match self.0.lock().get(&key) {
+            None => KeyState::NotFound,
+            Some(latest) => key_state_at(latest, at),
+        })
+    }
+
+    async fn register_key(&self, key: AgentPubKey) -> DpkiResult<()> {
+        self.0.lock().entry(key).or_insert(KeyState::Valid);
+        Ok(())
+    }
+
+    async fn rotate_key(&self, old_key: AgentPubKey, new_key: AgentPubKey) -> DpkiResult<()> {
+        self.change(
+            old_key,
+            KeyState::Updated {
+                new_key: new_key.clone(),
+                timestamp: timestamp::now(),
+            },
+        )?;
+        self.register_key(new_key).await
+    }
+
+    async fn revoke_key(&self, key: AgentPubKey) -> DpkiResult<()> {
+        self.change(
+            key,
+            KeyState::Invalidated {
+                timestamp: timestamp::now(),
+            },
+        )
+    }
+}
+
+/// A key registry backed by a DPKI app installed on this conductor.
+///
+/// The app's first cell is expected to have a [`DPKI_ZOME_NAME`] zome with
+/// the functions `key_state`, `register_key`, `rotate_key` and `revoke_key`,
+/// taking the same arguments as the [`KeyRegistry`] methods as a tuple.
+///
+/// Every op is checked against the registry during sys validation, so the
+/// latest state of each key is cached rather than calling into the DPKI cell
+/// each time. Replaced and revoked keys can't change again so they are cached
+/// for good, other states for a minute.
+pub struct DnaKeyRegistry {
+    installed_app_id: InstalledAppId,
+    // Weak, as the conductor holds on to the registry.
+    conductor_handle: once_cell::sync::OnceCell<Weak<dyn ConductorHandleT>>,
+    latest_key_states: parking_lot::Mutex<HashMap<AgentPubKey, (KeyState, std::time::Instant)>>,
+}
+
+impl DnaKeyRegistry {
+    /// Create a registry which defers to the given app.
+    /// It can't be used until it is [attached](Self::attach) to a conductor.
+    pub fn new(installed_app_id: InstalledAppId) -> Self {
+        Self {
+            installed_app_id,
+            conductor_handle: Default::default(),
+            latest_key_states: Default::default(),
+        }
+    }
+
+    /// Attach this registry to the conductor running the DPKI app.
+    pub fn attach(&self, conductor_handle: &ConductorHandle) {
+        let _ = self.conductor_handle.set(Arc::downgrade(conductor_handle));
+    }
+
+    /// The cell to call, if the DPKI app is installed yet.
+    async fn dpki_cell(&self) -> DpkiResult<(ConductorHandle, Option<CellId>)> {
+        let handle = self
+            .conductor_handle
+            .get()
+            .and_then(Weak::upgrade)
+            .ok_or(DpkiError::ConductorMissing)?;
+        let cell_id = handle
+            .get_app_info(&self.installed_app_id)
+            .await
+            .map_err(|e| Box::new(ConductorApiError::from(e)))?
+            .and_then(|info| info.cell_data.into_iter().next())
+            .map(InstalledCell::into_id);
+        Ok((handle, cell_id))
+    }
+
+    async fn call<I, O>(&self, fn_name: &str, payload: I) -> DpkiResult<O>
+    where
+        I: serde::Serialize + std::fmt::Debug,
+        O: serde::de::DeserializeOwned + std::fmt::Debug,
+    {
+        let (handle, cell_id) = self.dpki_cell().await?;
+        let cell_id =
+            cell_id.ok_or_else(|| DpkiError::AppMissing(self.installed_app_id.clone()))?;
+        let call = ZomeCall {
+            provenance: cell_id.agent_pubkey().clone(),
+            cell_id,
+            zome_name: DPKI_ZOME_NAME.into(),
+            fn_name: fn_name.into(),
+            payload: ExternIO::encode(payload)?,
+            cap: None,
+        };
+        match handle
+            .call_zome(call)
+            .await
+            .map_err(Box::new)?
+            .map_err(Box::new)?
+        {
+            ZomeCallResponse::Ok(output) => Ok(output.decode()?),
+            other => Err(DpkiError::UnexpectedResponse(fn_name.into(), other)),
+        }
+    }
+
+    fn cached_key_state(&self, key: &AgentPubKey) -> Option<KeyState> {
+        let mut latest_key_states = self.latest_key_states.lock();
+        match latest_key_states.get(key) {
+            Some((state, fetched_at))
+                if !state.is_valid() || fetched_at.elapsed() < KEY_STATE_CACHE_TTL =>
+            {
+                Some(state.clone())
+            }
+            Some(_) => {
+                latest_key_states.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn forget_key_states(&self, keys: &[&AgentPubKey]) {
+        let mut latest_key_states = self.latest_key_states.lock();
+        for key in keys {
+            latest_key_states.remove(*key);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl KeyRegistry for DnaKeyRegistry {
+    async fn key_state(&self, key: AgentPubKey, at: Timestamp) -> DpkiResult<KeyState> {
+        // Until the DPKI app is installed nothing can have been recorded,
+        // and the DPKI cell itself has to make it through genesis.
+        if let Some(latest) = self.cached_key_state(&key) {
+            return Ok(key_state_at(&latest, at));
+        }
+        if self.dpki_cell().await?.1.is_none() {
+            return Ok(KeyState::NotFound);
+        }
+        let latest: KeyState = self
+            .call("key_state", (key.clone(), timestamp::now()))
+            .await?;
+        let state = key_state_at(&latest, at);
+        self.latest_key_states
+            .lock()
+            .insert(key, (latest, std::time::Instant::now()));
+        Ok(state)
+    }
+
+    async fn register_key(&self, key: AgentPubKey) -> DpkiResult<()> {
+        self.call("register_key", key.clone()).await?;
+        self.forget_key_states(&[&key]);
+        Ok(())
+    }
+
+    async fn rotate_key(&self, old_key: AgentPubKey, new_key: AgentPubKey) -> DpkiResult<()> {
+        self.call("rotate_key", (old_key.clone(), new_key.clone()))
+            .await?;
+        self.forget_key_states(&[&old_key, &new_key]);
+        Ok(())
+    }
+
+    async fn revoke_key(&self, key: AgentPubKey) -> DpkiResult<()> {
+        self.call("revoke_key", key.clone()).await?;
+        self.forget_key_states(&[&key]);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fixt::prelude::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn mem_key_registry_tracks_key_lifecycle() {
+        let registry = MemKeyRegistry::new();
+        let old_key = fixt!(AgentPubKey);
+        let new_key = fixt!(AgentPubKey);
+        let before = timestamp::now();
+
+        assert_eq!(
+            registry.key_state(old_key.clone(), before).await.unwrap(),
+            KeyState::NotFound
+        );
+
+        registry.register_key(old_key.clone()).await.unwrap();
+        registry
+            .rotate_key(old_key.clone(), new_key.clone())
+            .await
+            .unwrap();
+        let after = timestamp::now();
+
+        // Signatures made before the rotation are still good.
+        assert_eq!(
+            registry.key_state(old_key.clone(), before).await.unwrap(),
+            KeyState::Valid
+        );
+        assert!(matches!(
+            registry.key_state(old_key.clone(), after).await.unwrap(),
+            KeyState::Updated { new_key: k, .. } if k == new_key
+        ));
+        // A replaced key can't be rotated again.
+        assert!(registry
+            .rotate_key(old_key, fixt!(AgentPubKey))
+            .await
+            .is_err());
+
+        registry.revoke_key(new_key.clone()).await.unwrap();
+        let state = registry.key_state(new_key, timestamp::now()).await.unwrap();
+        assert!(matches!(state, KeyState::Invalidated { .. }));
+        assert!(!state.is_valid());
+    }
+}
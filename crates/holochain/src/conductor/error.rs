@@ -76,6 +76,12 @@ pub enum ConductorError {
     #[error("Failed to run genesis on the following cells in the app: {errors:?}")]
     GenesisFailed { errors: Vec<CellError> },
 
+    #[error("App '{app_id}' was uninstalled, but some of its data was not removed: {errors:?}")]
+    UninstallIncomplete {
+        app_id: InstalledAppId,
+        errors: Vec<ConductorError>,
+    },
+
     #[error(transparent)]
     SerializedBytesError(#[from] holochain_serialized_bytes::SerializedBytesError),
 
@@ -91,6 +97,21 @@ pub enum ConductorError {
     #[error("Tried to perform an operation on an app that was not running: {0}")]
     AppNotRunning(InstalledAppId),
 
+    #[error("App '{0}' can't use this interface or key for crypto")]
+    AppCryptoNotAllowed(InstalledAppId),
+
+    #[error("App '{0}' has no clone cell {1:?} in slot '{2}'")]
+    CloneCellNotFound(InstalledAppId, CellId, SlotId),
+
+    #[error("The migrate_agent callback in zome '{1}' refused to migrate app '{0}': {2}")]
+    MigrateAgentFailed(InstalledAppId, ZomeName, String),
+
+    #[error("The app backup can't be restored: {0}")]
+    InvalidAppBackup(String),
+
+    #[error(transparent)]
+    DpkiError(#[from] super::dpki::DpkiError),
+
     #[error(transparent)]
     HolochainP2pError(#[from] holochain_p2p::HolochainP2pError),
 
@@ -112,6 +133,9 @@ pub enum ConductorError {
     #[error(transparent)]
     StateMutationError(#[from] holochain_state::mutations::StateMutationError),
 
+    #[error(transparent)]
+    SourceChainError(#[from] holochain_state::source_chain::SourceChainError),
+
     #[error(transparent)]
     RusqliteError(#[from] rusqlite::Error),
 }
@@ -41,50 +41,83 @@
 
 use super::api::error::ConductorApiResult;
 use super::api::ZomeCall;
+use super::app_backup;
 use super::conductor::CellStatus;
 use super::config::AdminInterfaceConfig;
+use super::config::MetricsConfig;
+use super::dpki::KeyRegistryRef;
+use super::error::ConductorError;
 use super::error::ConductorResult;
+use super::full_integration_dump;
 use super::integration_dump;
 use super::interface::SignalBroadcaster;
 use super::manager::spawn_task_manager;
+use super::manager::Supervisor;
 use super::manager::TaskManagerClient;
 use super::manager::TaskManagerRunHandle;
+use super::metrics::ConductorMetrics;
 use super::p2p_agent_store;
 use super::p2p_agent_store::all_agent_infos;
 use super::p2p_agent_store::get_agent_info_signed;
 use super::p2p_agent_store::inject_agent_infos;
 use super::p2p_agent_store::list_all_agent_info;
 use super::p2p_agent_store::list_all_agent_info_signed_near_basis;
+use super::published_ops_dump;
 use super::Cell;
 use super::CellError;
 use super::Conductor;
 use crate::conductor::p2p_agent_store::get_single_agent_info;
 use crate::conductor::p2p_agent_store::query_peer_density;
+use crate::conductor::p2p_metrics::prune_metrics;
 use crate::conductor::p2p_metrics::put_metric_datum;
 use crate::conductor::p2p_metrics::query_metrics;
+use crate::conductor::p2p_metrics::remove_agent_metrics;
+use crate::conductor::p2p_metrics::summarize_metrics;
 use crate::core::ribosome::real_ribosome::RealRibosome;
+use crate::core::workflow::migrate_agent_workflow::migrate_agent_workflow;
+use crate::core::workflow::migrate_agent_workflow::reopen_chains;
+use crate::core::workflow::migrate_agent_workflow::ClosedChain;
+use crate::core::workflow::migrate_agent_workflow::MigrateAgentOutcome;
+use crate::core::workflow::migrate_agent_workflow::MigrateChain;
+use crate::core::workflow::migrate_agent_workflow::MigratingChain;
 use crate::core::workflow::ZomeCallResult;
 use derive_more::From;
 use futures::future::FutureExt;
 use futures::StreamExt;
 use holochain_conductor_api::conductor::EnvironmentRootPath;
+use holochain_conductor_api::AdminEvent;
+use holochain_conductor_api::AgentNetworkMetrics;
+use holochain_conductor_api::AppBackup;
 use holochain_conductor_api::AppStatusFilter;
+use holochain_conductor_api::CryptoRequest;
+use holochain_conductor_api::CryptoResponse;
+use holochain_conductor_api::DhtOpDumpFilter;
+use holochain_conductor_api::FullStateDump;
 use holochain_conductor_api::InstalledAppInfo;
 use holochain_conductor_api::JsonDump;
+use holochain_conductor_api::NetworkMetrics;
+use holochain_conductor_api::SignedAppBackup;
+use holochain_conductor_api::TaskRestarts;
+use holochain_keystore::KeystoreSenderExt;
 use holochain_p2p::event::HolochainP2pEvent;
 use holochain_p2p::event::HolochainP2pEvent::*;
+use holochain_p2p::AgentPubKeyExt;
 use holochain_p2p::DnaHashExt;
 use holochain_p2p::HolochainP2pCell;
 use holochain_p2p::HolochainP2pCellT;
+use holochain_sqlite::db::p2p_remove_agent;
 use holochain_sqlite::db::DbKind;
+use holochain_sqlite::db::DbWrite;
 use holochain_state::host_fn_workspace::HostFnWorkspace;
 use holochain_state::source_chain;
 use holochain_types::prelude::*;
 use kitsune_p2p::agent_store::AgentInfoSigned;
+use kitsune_p2p::event::BlockTarget;
 use kitsune_p2p::KitsuneSpace;
 use kitsune_p2p_types::config::JOIN_NETWORK_TIMEOUT;
 use std::collections::HashMap;
 use std::{collections::HashSet, sync::Arc};
+use tokio::sync::broadcast;
 use tokio::sync::RwLock;
 use tracing::*;
 
@@ -125,8 +158,15 @@ pub trait ConductorHandleT: Send + Sync {
         configs: Vec<AdminInterfaceConfig>,
     ) -> ConductorResult<()>;
 
-    /// Add an app interface
-    async fn add_app_interface(self: Arc<Self>, port: u16) -> ConductorResult<u16>;
+    /// Add an app interface, optionally attached for a single app
+    async fn add_app_interface(
+        self: Arc<Self>,
+        port: u16,
+        installed_app_id: Option<InstalledAppId>,
+    ) -> ConductorResult<u16>;
+
+    /// Start serving the conductor's metrics, returning the port they are served on
+    async fn add_metrics_endpoint(self: Arc<Self>, config: MetricsConfig) -> ConductorResult<u16>;
 
     /// List the app interfaces currently install.
     async fn list_app_interfaces(&self) -> ConductorResult<Vec<u16>>;
@@ -182,17 +222,55 @@ pub trait ConductorHandleT: Send + Sync {
     /// Request access to this conductor's keystore
     fn keystore(&self) -> &KeystoreSender;
 
+    /// The metrics this conductor records as zome calls and validation happen
+    fn metrics(&self) -> &ConductorMetrics;
+
+    /// The longest a zome call may wait on host functions like `sleep`
+    fn zome_call_timeout(&self) -> std::time::Duration;
+
     /// Request access to this conductor's networking handle
     fn holochain_p2p(&self) -> &holochain_p2p::HolochainP2pRef;
 
+    /// The DPKI key registry, if this conductor is configured to use one
+    fn key_registry(&self) -> Option<KeyRegistryRef>;
+
     /// Create a new Cell in an existing App based on an existing DNA
     async fn create_clone_cell(
         self: Arc<Self>,
         payload: CreateCloneCellPayload,
     ) -> ConductorResult<CellId>;
 
-    /// Destroy a cloned Cell
-    async fn destroy_clone_cell(self: Arc<Self>, cell_id: CellId) -> ConductorResult<()>;
+    /// Archive a cloned Cell. The Cell stops running but its data is kept.
+    async fn archive_clone_cell(self: Arc<Self>, payload: CloneCellPayload) -> ConductorResult<()>;
+
+    /// Restore an archived clone Cell to its App and start it if the App is running
+    async fn restore_archived_clone_cell(
+        self: Arc<Self>,
+        payload: CloneCellPayload,
+    ) -> ConductorResult<()>;
+
+    /// Destroy an archived clone Cell, deleting all of its data
+    async fn destroy_clone_cell(self: Arc<Self>, payload: CloneCellPayload) -> ConductorResult<()>;
+
+    /// Move an App onto a newly generated agent key, returning the new key.
+    /// Each of the App's source chains is closed and continued on a new chain
+    /// for the new key, as long as the App's `migrate_agent` callbacks allow it.
+    async fn rotate_app_agent_key(
+        self: Arc<Self>,
+        installed_app_id: &InstalledAppId,
+    ) -> ConductorResult<AgentPubKey>;
+
+    /// Upgrade an App to a new version from an AppBundle.
+    /// Cells whose DNA changed are migrated to new Cells. The old Cells are
+    /// kept running, so their data can still be read, until the upgrade is
+    /// confirmed.
+    async fn upgrade_app(self: Arc<Self>, payload: UpgradeAppPayload) -> ConductorResult<()>;
+
+    /// Confirm an App's pending upgrade, dropping the Cells it replaced.
+    async fn confirm_app_upgrade(
+        self: Arc<Self>,
+        installed_app_id: &InstalledAppId,
+    ) -> ConductorResult<()>;
 
     /// Install Cells into ConductorState based on installation info, and run
     /// genesis on all new source chains
@@ -208,9 +286,20 @@ pub trait ConductorHandleT: Send + Sync {
         payload: InstallAppBundlePayload,
     ) -> ConductorResult<StoppedApp>;
 
-    /// Uninstall an app from the state DB and remove all running Cells
+    /// Uninstall an app from the state DB, and remove the Cells no other app
+    /// uses along with their databases and agent info
     async fn uninstall_app(self: Arc<Self>, app: &InstalledAppId) -> ConductorResult<()>;
 
+    /// Back up the chains authored by an app's cells, signed by the app's agent
+    async fn backup_app(
+        &self,
+        installed_app_id: &InstalledAppId,
+    ) -> ConductorResult<SignedAppBackup>;
+
+    /// Install an app again from a backup, continuing its chains.
+    /// The app is left disabled.
+    async fn restore_app(self: Arc<Self>, backup: SignedAppBackup) -> ConductorResult<StoppedApp>;
+
     /// Adjust app statuses (via state transitions) to match the current
     /// reality of which Cells are present in the conductor.
     async fn reconcile_app_status_with_cell_status(
@@ -271,16 +360,41 @@ pub trait ConductorHandleT: Send + Sync {
     /// Dump the cells state
     async fn dump_cell_state(&self, cell_id: &CellId) -> ConductorApiResult<String>;
 
+    /// Dump the state of a Cell as a typed [`FullStateDump`],
+    /// including only the ops which pass the filter.
+    async fn dump_full_cell_state(
+        &self,
+        cell_id: &CellId,
+        dht_ops_filter: Option<DhtOpDumpFilter>,
+    ) -> ConductorApiResult<FullStateDump>;
+
     /// Access the broadcast Sender which will send a Signal across every
     /// attached app interface
     async fn signal_broadcaster(&self) -> SignalBroadcaster;
 
+    /// Send an event to every admin connection which subscribed to events
+    fn emit_admin_event(&self, event: AdminEvent);
+
+    /// Receive the events sent to subscribed admin connections
+    fn subscribe_admin_events(&self) -> broadcast::Receiver<AdminEvent>;
+
+    /// List the managed tasks which were restarted after they failed
+    async fn list_task_restarts(&self) -> ConductorResult<Vec<TaskRestarts>>;
+
     /// Get info about an installed App, whether active or inactive
     async fn get_app_info(
         &self,
         installed_app_id: &InstalledAppId,
     ) -> ConductorResult<Option<InstalledAppInfo>>;
 
+    /// Sign, encrypt or decrypt data for a running App, using the keystore.
+    /// The request must come over an interface attached for the App.
+    async fn app_crypto(
+        &self,
+        interface_id: &super::state::AppInterfaceId,
+        request: CryptoRequest,
+    ) -> ConductorResult<CryptoResponse>;
+
     /// Add signed agent info to the conductor
     async fn add_agent_infos(&self, agent_infos: Vec<AgentInfoSigned>) -> ConductorApiResult<()>;
 
@@ -290,6 +404,29 @@ pub trait ConductorHandleT: Send + Sync {
         cell_id: Option<CellId>,
     ) -> ConductorApiResult<Vec<AgentInfoSigned>>;
 
+    /// Block an agent from a space. Their agent info is dropped and
+    /// this conductor will no longer gossip with or serve them.
+    async fn block_agent(&self, dna_hash: &DnaHash, agent: AgentPubKey) -> ConductorApiResult<()>;
+
+    /// Remove an agent from a space's block list.
+    async fn unblock_agent(&self, dna_hash: &DnaHash, agent: AgentPubKey)
+        -> ConductorApiResult<()>;
+
+    /// List the agents blocked from a space.
+    async fn list_blocked_agents(&self, dna_hash: &DnaHash)
+        -> ConductorApiResult<Vec<AgentPubKey>>;
+
+    /// Get the network metrics of a DNA's space, or of the spaces of
+    /// every DNA with cells in this conductor if `None`.
+    async fn network_metrics(
+        &self,
+        dna_hash: Option<DnaHash>,
+    ) -> ConductorApiResult<Vec<NetworkMetrics>>;
+
+    /// Remove the expired p2p metric data of every space this conductor
+    /// has recorded metrics for.
+    async fn prune_p2p_metrics(&self) -> ConductorResult<()>;
+
     /// Print the current setup in a machine readable way.
     async fn print_setup(&self);
 
@@ -333,6 +470,10 @@ pub trait ConductorHandleT: Send + Sync {
     /// For testing we can choose to skip publish.
     fn set_skip_publish(&self, skip_publish: bool);
 
+    #[cfg(any(test, feature = "test_utils"))]
+    /// For testing we can make the next app upgrade fail to write its state.
+    fn fail_next_app_upgrade(&self);
+
     /// Manually coerce cells to a given CellStatus. FOR TESTING ONLY.
     #[cfg(any(test, feature = "test_utils"))]
     async fn update_cell_status(&self, cell_ids: &[CellId], status: CellStatus);
@@ -384,20 +525,41 @@ pub struct ConductorHandleImpl<DS: DnaStore + 'static> {
     pub(super) keystore: KeystoreSender,
     pub(super) holochain_p2p: holochain_p2p::HolochainP2pRef,
 
+    /// The registry used to check agent keys are still valid
+    pub(super) key_registry: Option<KeyRegistryRef>,
+
     /// The root environment directory where all environments are created
     pub(super) root_env_dir: EnvironmentRootPath,
 
+    /// The channel for sending events to subscribed admin connections
+    pub(super) admin_event_broadcaster: broadcast::Sender<AdminEvent>,
+
     /// The database for storing AgentInfoSigned
     pub(super) p2p_env: Arc<parking_lot::Mutex<HashMap<Arc<KitsuneSpace>, EnvWrite>>>,
 
     /// The database for storing p2p MetricDatum(s)
     pub(super) p2p_metrics_env: Arc<parking_lot::Mutex<HashMap<Arc<KitsuneSpace>, EnvWrite>>>,
 
+    /// The block list of each space, loaded from its p2p_env on first use
+    /// so checking every incoming message doesn't hit the database.
+    pub(super) p2p_block_lists:
+        Arc<parking_lot::Mutex<HashMap<Arc<KitsuneSpace>, HashSet<BlockTarget>>>>,
+
+    /// The metrics recorded by this conductor's cells
+    pub(super) metrics: ConductorMetrics,
+
+    /// The longest a zome call may wait on host functions like `sleep`
+    pub(super) zome_call_timeout: std::time::Duration,
+
     // Testing:
     #[cfg(any(test, feature = "test_utils"))]
     /// All conductors should skip publishing.
     /// This is useful for testing gossip.
     pub skip_publish: std::sync::atomic::AtomicBool,
+    #[cfg(any(test, feature = "test_utils"))]
+    /// The next app upgrade fails when writing to the conductor state.
+    /// This is useful for testing that a failed upgrade is rolled back.
+    pub fail_app_upgrade: std::sync::atomic::AtomicBool,
 }
 
 #[async_trait::async_trait]
@@ -429,11 +591,17 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
             if conductor.task_manager.is_some() {
                 panic!("Cannot start task manager twice");
             }
-            let (task_add_sender, run_handle) = spawn_task_manager(self.clone());
             let (task_stop_broadcaster, _) = tokio::sync::broadcast::channel::<()>(1);
+            let supervisor = Supervisor::new(
+                conductor.task_supervision.clone(),
+                task_stop_broadcaster.clone(),
+            );
+            let task_restarts = supervisor.restarts();
+            let (task_add_sender, run_handle) = spawn_task_manager(self.clone(), supervisor);
             conductor.task_manager = Some(TaskManagerClient::new(
                 task_add_sender,
                 task_stop_broadcaster,
+                task_restarts,
                 run_handle,
             ));
 
@@ -453,9 +621,19 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         self.process_app_status_fx(AppStatusFx::SpinUp, None).await
     }
 
-    async fn add_app_interface(self: Arc<Self>, port: u16) -> ConductorResult<u16> {
+    async fn add_app_interface(
+        self: Arc<Self>,
+        port: u16,
+        installed_app_id: Option<InstalledAppId>,
+    ) -> ConductorResult<u16> {
         let mut lock = self.conductor.write().await;
-        lock.add_app_interface_via_handle(either::Left(port), self.clone())
+        lock.add_app_interface_via_handle(either::Left(port), installed_app_id, self.clone())
+            .await
+    }
+
+    async fn add_metrics_endpoint(self: Arc<Self>, config: MetricsConfig) -> ConductorResult<u16> {
+        let mut lock = self.conductor.write().await;
+        lock.add_metrics_endpoint_via_handle(config, self.clone())
             .await
     }
 
@@ -589,6 +767,24 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
                     .map_err(holochain_p2p::HolochainP2pError::other);
                 respond.respond(Ok(async move { res }.boxed().into()));
             }
+            IsBlocked {
+                target, respond, ..
+            } => {
+                let res = self
+                    .is_blocked(space, &target)
+                    .map_err(holochain_p2p::HolochainP2pError::other);
+                respond.respond(Ok(async move { res }.boxed().into()));
+            }
+            Block {
+                target, respond, ..
+            } => {
+                let env = { self.p2p_env(space.clone()) };
+                let res = p2p_agent_store::block(env, target)
+                    .await
+                    .map_err(holochain_p2p::HolochainP2pError::other);
+                self.p2p_block_lists.lock().remove(&space);
+                respond.respond(Ok(async move { res }.boxed().into()));
+            }
             PutMetricDatum {
                 respond,
                 agent,
@@ -627,7 +823,8 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
             | GetLinks { .. }
             | GetAgentActivity { .. }
             | ValidationReceiptReceived { .. }
-            | FetchOpData { .. } => {
+            | FetchOpData { .. }
+            | FetchOpSizes { .. } => {
                 let cell_id = CellId::new(event.dna_hash().clone(), event.target_agents().clone());
                 let cell = self.cell_by_id(&cell_id).await?;
                 cell.handle_holochain_p2p_event(event).await?;
@@ -737,23 +934,43 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         &self.keystore
     }
 
+    fn metrics(&self) -> &ConductorMetrics {
+        &self.metrics
+    }
+
+    fn zome_call_timeout(&self) -> std::time::Duration {
+        self.zome_call_timeout
+    }
+
     fn holochain_p2p(&self) -> &holochain_p2p::HolochainP2pRef {
         &self.holochain_p2p
     }
 
+    fn key_registry(&self) -> Option<KeyRegistryRef> {
+        self.key_registry.clone()
+    }
+
     async fn create_clone_cell(
         self: Arc<Self>,
         payload: CreateCloneCellPayload,
     ) -> ConductorResult<CellId> {
         let CreateCloneCellPayload {
             properties,
-            dna_hash,
             installed_app_id,
-            agent_key,
             slot_id,
             membrane_proof,
+            ..
         } = payload;
-        let cell_id = CellId::new(dna_hash, agent_key);
+
+        // Register the cloned DNA and add the clone to the app first,
+        // since the clone's CellId isn't known until the DNA is modified.
+        let cell_id = {
+            let mut conductor = self.conductor.write().await;
+            let properties = properties.unwrap_or_else(|| ().into());
+            conductor
+                .add_clone_cell_to_app(&installed_app_id, &slot_id, properties)
+                .await?
+        };
         let cells = vec![(cell_id.clone(), membrane_proof)];
 
         // Gather the directory and keystore to avoid holding the conductor read lock.
@@ -763,22 +980,240 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
             let keystore = lock.keystore().clone();
             (root_env_dir, keystore)
         };
-        // Run genesis on cells.
-        crate::conductor::conductor::genesis_cells(root_env_dir, keystore, cells, self.clone())
+        // Run genesis on the clone.
+        if let Err(err) =
+            crate::conductor::conductor::genesis_cells(root_env_dir, keystore, cells, self.clone())
+                .await
+        {
+            // Forget the clone so it doesn't count towards the clone limit.
+            self.conductor
+                .write()
+                .await
+                .remove_clone_cell(&CloneCellPayload {
+                    installed_app_id,
+                    slot_id,
+                    cell_id,
+                })
+                .await?;
+            return Err(err);
+        }
+
+        self.clone()
+            .process_app_status_fx(
+                AppStatusFx::SpinUp,
+                Some(vec![installed_app_id].into_iter().collect()),
+            )
             .await?;
+        Ok(cell_id)
+    }
 
-        {
+    async fn archive_clone_cell(self: Arc<Self>, payload: CloneCellPayload) -> ConductorResult<()> {
+        self.conductor
+            .write()
+            .await
+            .archive_clone_cell(&payload)
+            .await?;
+        // The archived cell is now dangling so it will leave the network and be removed.
+        self.process_app_status_fx(AppStatusFx::SpinDown, None)
+            .await?;
+        Ok(())
+    }
+
+    async fn restore_archived_clone_cell(
+        self: Arc<Self>,
+        payload: CloneCellPayload,
+    ) -> ConductorResult<()> {
+        self.conductor
+            .write()
+            .await
+            .restore_clone_cell(&payload)
+            .await?;
+        self.process_app_status_fx(
+            AppStatusFx::SpinUp,
+            Some(vec![payload.installed_app_id].into_iter().collect()),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn destroy_clone_cell(self: Arc<Self>, payload: CloneCellPayload) -> ConductorResult<()> {
+        // Only archived clones can be destroyed, so the cell is already
+        // out of the conductor and off the network.
+        let (root_env_dir, dna_in_use) = {
             let mut conductor = self.conductor.write().await;
-            let properties = properties.unwrap_or_else(|| ().into());
-            let cell_id = conductor
-                .add_clone_cell_to_app(&installed_app_id, &slot_id, properties)
+            let dna_in_use = conductor.remove_archived_clone_cell(&payload).await?;
+            (
+                std::path::PathBuf::from(conductor.root_env_dir().clone()),
+                dna_in_use,
+            )
+        };
+        let cell_id = payload.cell_id;
+
+        DbWrite::open(&root_env_dir, DbKind::Cell(cell_id.clone()))?
+            .remove()
+            .await?;
+        // The cache is shared by every cell of the DNA.
+        if !dna_in_use {
+            DbWrite::open(&root_env_dir, DbKind::Cache(cell_id.dna_hash().clone()))?
+                .remove()
                 .await?;
-            Ok(cell_id)
         }
+
+        let space = cell_id.dna_hash().to_kitsune();
+        let agent = cell_id.agent_pubkey().to_kitsune();
+        p2p_remove_agent(&self.p2p_env(space), &agent).await?;
+        Ok(())
     }
 
-    async fn destroy_clone_cell(self: Arc<Self>, _cell_id: CellId) -> ConductorResult<()> {
-        todo!()
+    async fn rotate_app_agent_key(
+        self: Arc<Self>,
+        installed_app_id: &InstalledAppId,
+    ) -> ConductorResult<AgentPubKey> {
+        let app = self
+            .conductor
+            .read()
+            .await
+            .get_state()
+            .await?
+            .get_app(installed_app_id)?
+            .clone();
+        // The cells replaced by an upgrade can't be migrated again.
+        if app.upgraded_cells().next().is_some() {
+            return Err(AppError::UpgradePending(installed_app_id.clone()).into());
+        }
+        let old_key = app._agent_key().clone();
+        let new_key = self
+            .keystore
+            .clone()
+            .generate_sign_keypair_from_pure_entropy()
+            .await?;
+        let old_cells: Vec<CellId> = app
+            .all_cells()
+            .chain(app.archived_cells_by_slot().map(|(_, cell_id)| cell_id))
+            .filter(|cell_id| *cell_id.agent_pubkey() == old_key)
+            .cloned()
+            .collect();
+
+        // The cells can't keep running while their chains are being closed.
+        let was_enabled = app.status().is_enabled();
+        if was_enabled {
+            self.clone()
+                .disable_app(installed_app_id, DisabledAppReason::User)
+                .await?;
+        }
+        let mut migrated = self
+            .clone()
+            .migrate_app_cells(installed_app_id, old_key, new_key.clone(), old_cells)
+            .await;
+        if was_enabled {
+            if let Err(error) = self.clone().enable_app(installed_app_id).await {
+                // The reason the migration failed matters more.
+                match migrated {
+                    Ok(()) => migrated = Err(error),
+                    Err(_) => error!(?error, "Failed to enable the app after a failed migration"),
+                }
+            }
+        }
+        migrated?;
+        Ok(new_key)
+    }
+
+    async fn upgrade_app(self: Arc<Self>, payload: UpgradeAppPayload) -> ConductorResult<()> {
+        let UpgradeAppPayload {
+            source,
+            installed_app_id,
+            membrane_proofs,
+            uid,
+        } = payload;
+        let bundle = Self::resolve_app_bundle(source, uid).await?;
+        let app = self
+            .conductor
+            .read()
+            .await
+            .get_state()
+            .await?
+            .get_app(&installed_app_id)?
+            .clone();
+        if app.upgraded_cells().next().is_some() {
+            return Err(AppError::UpgradePending(installed_app_id).into());
+        }
+        let AppUpgradeResolution {
+            resolution,
+            migrations,
+        } = bundle.resolve_upgrade(&app, membrane_proofs).await?;
+
+        let cells_to_create = resolution.cells_to_create();
+        for (dna, _) in resolution.dnas_to_register {
+            self.clone().register_dna(dna).await?;
+        }
+        let root_env_dir = std::path::PathBuf::from(self.root_env_dir.clone());
+        let was_enabled = app.status().is_enabled();
+        let mut disabled = false;
+        let mut upgraded = async {
+            crate::conductor::conductor::genesis_cells(
+                root_env_dir.clone(),
+                self.keystore.clone(),
+                cells_to_create.clone(),
+                self.clone(),
+            )
+            .await?;
+
+            // The old cells can't keep running while their chains are being closed.
+            if was_enabled {
+                self.clone()
+                    .disable_app(&installed_app_id, DisabledAppReason::User)
+                    .await?;
+                disabled = true;
+            }
+            let closed = self
+                .migrate_chains(&installed_app_id, &root_env_dir, &migrations)
+                .await?;
+            let written = self
+                .write_app_upgrade(&installed_app_id, resolution.slots)
+                .await;
+            if written.is_err() {
+                // The app still uses the old cells, so their chains carry on.
+                reopen_chains(closed).await;
+            }
+            written
+        }
+        .await;
+        if upgraded.is_err() {
+            crate::conductor::conductor::remove_cell_envs(
+                &root_env_dir,
+                cells_to_create.into_iter().map(|(cell_id, _)| cell_id),
+            )
+            .await;
+        }
+        if disabled {
+            if let Err(error) = self.clone().enable_app(&installed_app_id).await {
+                // The reason the upgrade failed matters more.
+                match upgraded {
+                    Ok(()) => upgraded = Err(error),
+                    Err(_) => error!(?error, "Failed to enable the app after a failed upgrade"),
+                }
+            }
+        }
+        upgraded
+    }
+
+    async fn confirm_app_upgrade(
+        self: Arc<Self>,
+        installed_app_id: &InstalledAppId,
+    ) -> ConductorResult<()> {
+        let upgraded_cells = self
+            .conductor
+            .write()
+            .await
+            .confirm_app_upgrade(installed_app_id)
+            .await?;
+        if !upgraded_cells.is_empty() {
+            // The replaced cells are now dangling, so they will leave the
+            // network and be removed.
+            self.process_app_status_fx(AppStatusFx::SpinDown, None)
+                .await?;
+        }
+        Ok(())
     }
 
     async fn install_app(
@@ -831,16 +1266,7 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
             uid,
         } = payload;
 
-        let bundle: AppBundle = {
-            let original_bundle = source.resolve().await?;
-            if let Some(uid) = uid {
-                let mut manifest = original_bundle.manifest().to_owned();
-                manifest.set_uid(uid);
-                AppBundle::from(original_bundle.into_inner().update_manifest(manifest)?)
-            } else {
-                original_bundle
-            }
-        };
+        let bundle = Self::resolve_app_bundle(source, uid).await?;
 
         let installed_app_id =
             installed_app_id.unwrap_or_else(|| bundle.manifest().app_name().to_owned());
@@ -900,7 +1326,8 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
     async fn reconcile_cell_status_with_app_status(
         self: Arc<Self>,
     ) -> ConductorResult<CellStartupErrors> {
-        self.conductor.write().await.remove_dangling_cells().await?;
+        let dangling = self.conductor.write().await.remove_dangling_cells().await?;
+        crate::conductor::conductor::stop_cells(dangling).await?;
 
         let results = self
             .create_and_add_initialized_cells_for_running_apps(self.clone())
@@ -979,18 +1406,193 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         installed_app_id: &InstalledAppId,
     ) -> ConductorResult<()> {
         let self_clone = self.clone();
-        {
+        let (removed_cells, unused_dnas, cells) = {
             // Ensure that the conductor lock is dropped before the self_clone
             // is used, or else deadlock will ensue
             let mut conductor = self.conductor.write().await;
-            let app = conductor.remove_app_from_db(installed_app_id).await?;
-            tracing::debug!(msg = "Removed app from db.", app = ?app);
+            let (removed_cells, unused_dnas) = conductor.uninstall_app(installed_app_id).await?;
+            let cells = conductor.remove_cells(removed_cells.clone());
+            (removed_cells, unused_dnas, cells)
+        };
+
+        // The app is gone from the state, so every step from here on is
+        // tried even if an earlier one fails.
+        let mut errors = Vec::new();
+        // The queue consumers must be finished with the databases before
+        // they are removed.
+        if let Err(e) = crate::conductor::conductor::stop_cells(cells).await {
+            errors.push(e.into());
         }
         // Remove cells which may now be dangling due to the removed app
-        self_clone
+        if let Err(e) = self_clone
             .process_app_status_fx(AppStatusFx::SpinDown, None)
-            .await?;
-        Ok(())
+            .await
+        {
+            errors.push(e);
+        }
+
+        // The DNAs and their wasm stay registered, so they can be installed again.
+        let root_env_dir = std::path::PathBuf::from(self.root_env_dir.clone());
+        for cell_id in removed_cells {
+            let removed = async {
+                DbWrite::open(&root_env_dir, DbKind::Cell(cell_id.clone()))?
+                    .remove()
+                    .await?;
+                let space = cell_id.dna_hash().to_kitsune();
+                let agent = cell_id.agent_pubkey().to_kitsune();
+                p2p_remove_agent(&self.p2p_env(space.clone()), &agent).await?;
+                remove_agent_metrics(self.p2p_metrics_env(space), cell_id.agent_pubkey().clone())
+                    .await
+            }
+            .await;
+            if let Err(e) = removed {
+                errors.push(e);
+            }
+        }
+        for dna_hash in unused_dnas {
+            let removed = async {
+                DbWrite::open(&root_env_dir, DbKind::Cache(dna_hash))?
+                    .remove()
+                    .await
+            }
+            .await;
+            if let Err(e) = removed {
+                errors.push(e.into());
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ConductorError::UninstallIncomplete {
+                app_id: installed_app_id.clone(),
+                errors,
+            })
+        }
+    }
+
+    async fn backup_app(
+        &self,
+        installed_app_id: &InstalledAppId,
+    ) -> ConductorResult<SignedAppBackup> {
+        let app = self
+            .conductor
+            .read()
+            .await
+            .get_state()
+            .await?
+            .get_app(installed_app_id)?
+            .clone();
+        // The restored app couldn't confirm or cancel the upgrade.
+        if app.upgraded_cells().next().is_some() {
+            return Err(AppError::UpgradePending(installed_app_id.clone()).into());
+        }
+
+        let root_env_dir = std::path::PathBuf::from(self.root_env_dir.clone());
+        let observer_cells: HashSet<&CellId> = app.observer_cells().collect();
+        let mut chains = Vec::new();
+        // Observers have no chain to back up
+        for cell_id in app
+            .all_cells()
+            .chain(app.archived_cells_by_slot().map(second))
+            .filter(|cell_id| !observer_cells.contains(cell_id))
+        {
+            let env = EnvWrite::open(
+                &root_env_dir,
+                DbKind::Cell(cell_id.clone()),
+                self.keystore.clone(),
+            )?;
+            chains.push(app_backup::backup_chain(env, cell_id.clone()).await?);
+        }
+        let backup = AppBackup {
+            agent_key: app._agent_key().clone(),
+            app: InstalledAppInfo::from_installed_app(&app),
+            chains,
+        };
+        app_backup::sign_backup(&self.keystore, backup).await
+    }
+
+    async fn restore_app(self: Arc<Self>, backup: SignedAppBackup) -> ConductorResult<StoppedApp> {
+        let AppBackup {
+            agent_key,
+            app,
+            chains,
+        } = app_backup::open_backup(&self.keystore, backup).await?;
+        let chain_cells: HashSet<CellId> =
+            chains.iter().map(|chain| chain.cell_id.clone()).collect();
+        let app = app_backup::app_from_info(agent_key, app, &chain_cells)?;
+        let installed_app_id = app.id().clone();
+        if self
+            .conductor
+            .read()
+            .await
+            .get_state()
+            .await?
+            .installed_apps()
+            .contains_key(&installed_app_id)
+        {
+            return Err(ConductorError::AppAlreadyInstalled(installed_app_id));
+        }
+
+        let observer_cells: HashSet<&CellId> = app.observer_cells().collect();
+        let app_cells: HashSet<CellId> = app
+            .all_cells()
+            .chain(app.archived_cells_by_slot().map(second))
+            .filter(|cell_id| !observer_cells.contains(cell_id))
+            .cloned()
+            .collect();
+        if app_cells != chain_cells {
+            return Err(ConductorError::InvalidAppBackup(
+                "the chains don't match the cells of the app".to_string(),
+            ));
+        }
+        let dnas = self.list_dnas().await?;
+        if let Some(missing) = chains
+            .iter()
+            .map(|chain| chain.cell_id.dna_hash())
+            .find(|dna_hash| !dnas.contains(dna_hash))
+        {
+            return Err(DnaError::DnaMissing(missing.clone()).into());
+        }
+
+        let root_env_dir = std::path::PathBuf::from(self.root_env_dir.clone());
+        let mut restored = Vec::with_capacity(chains.len());
+        let result = async {
+            for chain in chains {
+                let cell_id = chain.cell_id.clone();
+                let env = EnvWrite::open(
+                    &root_env_dir,
+                    DbKind::Cell(cell_id.clone()),
+                    self.keystore.clone(),
+                )?;
+                app_backup::restore_chain(env, chain).await?;
+                restored.push(cell_id);
+            }
+            self.conductor
+                .write()
+                .await
+                .add_disabled_app_to_db(app)
+                .await
+        }
+        .await;
+        if result.is_err() {
+            // Don't leave chains behind which no app uses.
+            // A failure here is logged so the error that stopped the
+            // restore is the one returned.
+            for cell_id in restored {
+                let removed = match DbWrite::open(&root_env_dir, DbKind::Cell(cell_id.clone())) {
+                    Ok(env) => env.remove().await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = removed {
+                    error!(
+                        ?cell_id,
+                        ?e,
+                        "Failed to remove a chain after a failed restore"
+                    );
+                }
+            }
+        }
+        result
     }
 
     async fn list_cell_ids(&self, filter: Option<CellStatus>) -> ConductorResult<Vec<CellId>> {
@@ -1048,10 +1650,53 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         Ok(serde_json::to_string_pretty(&out)?)
     }
 
+    async fn dump_full_cell_state(
+        &self,
+        cell_id: &CellId,
+        dht_ops_filter: Option<DhtOpDumpFilter>,
+    ) -> ConductorApiResult<FullStateDump> {
+        let env: EnvRead = self
+            .conductor
+            .read()
+            .await
+            .cell_by_id(cell_id)?
+            .env()
+            .clone()
+            .into();
+        let filter = dht_ops_filter.unwrap_or_default();
+        let space = cell_id.dna_hash().to_kitsune();
+        let peers = p2p_agent_store::dump_peers(self.p2p_env(space).into())?;
+        Ok(FullStateDump {
+            integration_dump: full_integration_dump(&env, filter.clone()).await?,
+            published_ops: published_ops_dump(&env, filter).await?,
+            peers,
+        })
+    }
+
     async fn signal_broadcaster(&self) -> SignalBroadcaster {
         self.conductor.read().await.signal_broadcaster()
     }
 
+    fn emit_admin_event(&self, event: AdminEvent) {
+        // There may be no subscribers, which is fine.
+        let _ = self.admin_event_broadcaster.send(event);
+    }
+
+    fn subscribe_admin_events(&self) -> broadcast::Receiver<AdminEvent> {
+        self.admin_event_broadcaster.subscribe()
+    }
+
+    async fn list_task_restarts(&self) -> ConductorResult<Vec<TaskRestarts>> {
+        Ok(self
+            .conductor
+            .read()
+            .await
+            .task_manager
+            .as_ref()
+            .map(|manager| manager.task_restarts().list())
+            .unwrap_or_default())
+    }
+
     async fn get_app_info(
         &self,
         installed_app_id: &InstalledAppId,
@@ -1065,6 +1710,64 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
             .get_app_info(installed_app_id))
     }
 
+    async fn app_crypto(
+        &self,
+        interface_id: &super::state::AppInterfaceId,
+        request: CryptoRequest,
+    ) -> ConductorResult<CryptoResponse> {
+        let installed_app_id = request.installed_app_id().clone();
+        let state = self.conductor.read().await.get_state().await?;
+        let attached_app_id = state
+            .interface_by_id(interface_id)
+            .and_then(|config| config.installed_app_id);
+        if attached_app_id.as_ref() != Some(&installed_app_id) {
+            return Err(ConductorError::AppCryptoNotAllowed(installed_app_id));
+        }
+        let app = state.get_app(&installed_app_id)?;
+        if !app.status().is_running() {
+            return Err(ConductorError::AppNotRunning(installed_app_id));
+        }
+        Ok(match request {
+            CryptoRequest::Sign { data, .. } => CryptoResponse::Signature(
+                self.keystore
+                    .sign(Sign::new_raw(
+                        app._agent_key().clone(),
+                        CryptoRequest::signed_data(&data),
+                    ))
+                    .await?,
+            ),
+            CryptoRequest::CreateX25519Keypair { .. } => {
+                let key = self.keystore.create_x25519_keypair().await?;
+                self.conductor
+                    .read()
+                    .await
+                    .add_app_x25519_key(installed_app_id, key)
+                    .await?;
+                CryptoResponse::X25519PubKey(key)
+            }
+            CryptoRequest::Encrypt { payload, .. } => {
+                if !state.app_owns_x25519_key(&installed_app_id, payload.as_sender_ref()) {
+                    return Err(ConductorError::AppCryptoNotAllowed(installed_app_id));
+                }
+                CryptoResponse::Encrypted(
+                    self.keystore
+                        .x_25519_x_salsa20_poly1305_encrypt(payload)
+                        .await?,
+                )
+            }
+            CryptoRequest::Decrypt { payload, .. } => {
+                if !state.app_owns_x25519_key(&installed_app_id, payload.as_recipient_ref()) {
+                    return Err(ConductorError::AppCryptoNotAllowed(installed_app_id));
+                }
+                CryptoResponse::Decrypted(
+                    self.keystore
+                        .x_25519_x_salsa20_poly1305_decrypt(payload)
+                        .await?,
+                )
+            }
+        })
+    }
+
     async fn add_agent_infos(&self, agent_infos: Vec<AgentInfoSigned>) -> ConductorApiResult<()> {
         let mut space_map = HashMap::new();
         for agent_info_signed in agent_infos {
@@ -1106,6 +1809,92 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         }
     }
 
+    async fn block_agent(&self, dna_hash: &DnaHash, agent: AgentPubKey) -> ConductorApiResult<()> {
+        let space = dna_hash.to_kitsune();
+        let env = self.p2p_env(space.clone());
+        let res = p2p_agent_store::block_agent(env, agent).await;
+        self.p2p_block_lists.lock().remove(&space);
+        Ok(res?)
+    }
+
+    async fn unblock_agent(
+        &self,
+        dna_hash: &DnaHash,
+        agent: AgentPubKey,
+    ) -> ConductorApiResult<()> {
+        let space = dna_hash.to_kitsune();
+        let env = self.p2p_env(space.clone());
+        let res = p2p_agent_store::unblock_agent(env, agent).await;
+        self.p2p_block_lists.lock().remove(&space);
+        Ok(res?)
+    }
+
+    async fn list_blocked_agents(
+        &self,
+        dna_hash: &DnaHash,
+    ) -> ConductorApiResult<Vec<AgentPubKey>> {
+        let env = self.p2p_env(dna_hash.to_kitsune());
+        Ok(p2p_agent_store::list_blocked_agents(env.into())?)
+    }
+
+    async fn prune_p2p_metrics(&self) -> ConductorResult<()> {
+        let envs: Vec<EnvWrite> = self.p2p_metrics_env.lock().values().cloned().collect();
+        for env in envs {
+            prune_metrics(env).await?;
+        }
+        Ok(())
+    }
+
+    async fn network_metrics(
+        &self,
+        dna_hash: Option<DnaHash>,
+    ) -> ConductorApiResult<Vec<NetworkMetrics>> {
+        let dna_hashes: Vec<DnaHash> = match dna_hash {
+            Some(dna_hash) => vec![dna_hash],
+            None => self
+                .list_cell_ids(None)
+                .await?
+                .into_iter()
+                .map(|cell_id| cell_id.dna_hash().clone())
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect(),
+        };
+        let mut all_metrics = Vec::with_capacity(dna_hashes.len());
+        for dna_hash in dna_hashes {
+            let space = dna_hash.to_kitsune();
+            let mut agents: HashMap<AgentPubKey, AgentNetworkMetrics> = HashMap::new();
+            for summary in summarize_metrics(self.p2p_metrics_env(space.clone())).await? {
+                let agent = AgentPubKey::from_kitsune(&summary.agent);
+                agents.insert(
+                    agent.clone(),
+                    AgentNetworkMetrics {
+                        mean_round_trip_micros: (summary.requests > 0)
+                            .then(|| summary.total_round_trip_micros / summary.requests),
+                        requests: summary.requests,
+                        bytes_sent: summary.bytes_sent,
+                        bytes_received: summary.bytes_received,
+                        gossip_rounds_complete: summary.gossip_rounds_complete,
+                        gossip_rounds_failed: summary.gossip_rounds_failed,
+                        ..AgentNetworkMetrics::empty(agent)
+                    },
+                );
+            }
+            for info in all_agent_infos(self.p2p_env(space).into())? {
+                let agent = AgentPubKey::from_kitsune(&info.agent);
+                agents
+                    .entry(agent.clone())
+                    .or_insert_with(|| AgentNetworkMetrics::empty(agent))
+                    .storage_arc = Some(info.storage_arc);
+            }
+            all_metrics.push(NetworkMetrics {
+                dna_hash,
+                agents: agents.into_iter().map(|(_, metrics)| metrics).collect(),
+            });
+        }
+        Ok(all_metrics)
+    }
+
     async fn print_setup(&self) {
         self.conductor.read().await.print_setup()
     }
@@ -1116,8 +1905,10 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
     }
 
     async fn remove_cells(&self, cell_ids: &[CellId]) {
-        let mut lock = self.conductor.write().await;
-        lock.remove_cells(cell_ids.to_vec()).await
+        let cells = self.conductor.write().await.remove_cells(cell_ids.to_vec());
+        if let Err(err) = crate::conductor::conductor::stop_cells(cells).await {
+            tracing::error!("Error cleaning up Cell: {:?}", err);
+        }
     }
 
     #[cfg(any(test, feature = "test_utils"))]
@@ -1169,6 +1960,11 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
             .store(skip_publish, std::sync::atomic::Ordering::Relaxed);
     }
 
+    fn fail_next_app_upgrade(&self) {
+        self.fail_app_upgrade
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+
     #[cfg(any(test, feature = "test_utils"))]
     async fn update_cell_status(&self, cell_ids: &[CellId], status: CellStatus) {
         let mut lock = self.conductor.write().await;
@@ -1187,6 +1983,218 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
 }
 
 impl<DS: DnaStore + 'static> ConductorHandleImpl<DS> {
+    /// Get the bundle from its source, with the UID overridden if given.
+    async fn resolve_app_bundle(
+        source: AppBundleSource,
+        uid: Option<Uid>,
+    ) -> ConductorResult<AppBundle> {
+        let original_bundle = source.resolve().await?;
+        Ok(if let Some(uid) = uid {
+            let mut manifest = original_bundle.manifest().to_owned();
+            manifest.set_uid(uid);
+            AppBundle::from(original_bundle.into_inner().update_manifest(manifest)?)
+        } else {
+            original_bundle
+        })
+    }
+
+    /// Continue each of an app's cells on a new chain for the new key,
+    /// then move the app itself onto the new key.
+    /// Nothing is changed for good until every step which can fail has
+    /// succeeded, so a failure leaves the app on its old chains.
+    async fn migrate_app_cells(
+        self: Arc<Self>,
+        installed_app_id: &InstalledAppId,
+        old_key: AgentPubKey,
+        new_key: AgentPubKey,
+        old_cells: Vec<CellId>,
+    ) -> ConductorResult<()> {
+        let root_env_dir = std::path::PathBuf::from(self.root_env_dir.clone());
+        // The new chains are let in by the membrane proofs of the old ones.
+        let mut new_cells = Vec::with_capacity(old_cells.len());
+        for cell_id in &old_cells {
+            let vault = EnvWrite::open(
+                &root_env_dir,
+                DbKind::Cell(cell_id.clone()),
+                self.keystore.clone(),
+            )?;
+            let membrane_proof =
+                source_chain::SourceChain::new(vault, cell_id.agent_pubkey().clone())
+                    .await?
+                    .membrane_proof()?;
+            new_cells.push((
+                CellId::new(cell_id.dna_hash().clone(), new_key.clone()),
+                membrane_proof,
+            ));
+        }
+        crate::conductor::conductor::genesis_cells(
+            root_env_dir.clone(),
+            self.keystore.clone(),
+            new_cells.clone(),
+            self.clone(),
+        )
+        .await?;
+
+        let migrations: Vec<(CellId, CellId)> = old_cells
+            .iter()
+            .cloned()
+            .zip(new_cells.iter().map(|(cell_id, _)| cell_id.clone()))
+            .collect();
+        let migrated = async {
+            let closed = self
+                .migrate_chains(installed_app_id, &root_env_dir, &migrations)
+                .await?;
+            let moved = self
+                .move_app_to_key(installed_app_id, &old_key, &new_key)
+                .await;
+            if moved.is_err() {
+                // The app is still on the old key, so its chains carry on.
+                reopen_chains(closed).await;
+            }
+            moved
+        }
+        .await;
+        if let Err(e) = migrated {
+            crate::conductor::conductor::remove_cell_envs(
+                &root_env_dir,
+                new_cells.into_iter().map(|(cell_id, _)| cell_id),
+            )
+            .await;
+            return Err(e);
+        }
+
+        // The old agent won't be back, so forget its agent info.
+        // The migration holds without this, so failures are only logged.
+        for cell_id in &old_cells {
+            let space = cell_id.dna_hash().to_kitsune();
+            let agent = cell_id.agent_pubkey().to_kitsune();
+            if let Err(error) = p2p_remove_agent(&self.p2p_env(space), &agent).await {
+                error!(
+                    ?cell_id,
+                    ?error,
+                    "Failed to remove the agent info of a migrated cell"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Move an app onto a new key in the conductor state and then in the key
+    /// registry, which must come after the old chains were closed using the
+    /// old key. If the key registry fails the app is moved back.
+    async fn move_app_to_key(
+        &self,
+        installed_app_id: &InstalledAppId,
+        old_key: &AgentPubKey,
+        new_key: &AgentPubKey,
+    ) -> ConductorResult<()> {
+        self.conductor
+            .write()
+            .await
+            .rotate_app_agent_key(installed_app_id, new_key.clone())
+            .await?;
+        if let Some(key_registry) = &self.key_registry {
+            if let Err(e) = key_registry
+                .rotate_key(old_key.clone(), new_key.clone())
+                .await
+            {
+                let moved_back = self
+                    .conductor
+                    .write()
+                    .await
+                    .rotate_app_agent_key(installed_app_id, old_key.clone())
+                    .await;
+                if let Err(error) = moved_back {
+                    error!(?error, "Failed to move an app back onto its old key");
+                }
+                return Err(e.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Record an app's upgraded slots in the conductor state.
+    async fn write_app_upgrade(
+        &self,
+        installed_app_id: &InstalledAppId,
+        slots: Vec<(SlotId, AppSlot)>,
+    ) -> ConductorResult<()> {
+        #[cfg(any(test, feature = "test_utils"))]
+        if self
+            .fail_app_upgrade
+            .swap(false, std::sync::atomic::Ordering::Relaxed)
+        {
+            return Err(ConductorError::Todo(
+                "Failing the app upgrade for a test".into(),
+            ));
+        }
+        self.conductor
+            .write()
+            .await
+            .upgrade_app(installed_app_id, slots)
+            .await
+    }
+
+    /// Close each old chain and open the matching new chain,
+    /// if the `migrate_agent` callbacks allow it.
+    /// The new chains must have been through genesis already.
+    /// Returns the closed chains, which can be reopened if a later step fails.
+    /// If the migration fails the old chains are left open,
+    /// and the caller must remove the new ones.
+    async fn migrate_chains(
+        &self,
+        installed_app_id: &InstalledAppId,
+        root_env_dir: &std::path::Path,
+        migrations: &[(CellId, CellId)],
+    ) -> ConductorResult<Vec<ClosedChain>> {
+        let mut chains = Vec::with_capacity(migrations.len());
+        for (old_cell, new_cell) in migrations {
+            chains.push(MigrateChain {
+                old_chain: self.migrating_chain(root_env_dir, old_cell).await?,
+                new_chain: self.migrating_chain(root_env_dir, new_cell).await?,
+            });
+        }
+        match migrate_agent_workflow(chains).await? {
+            MigrateAgentOutcome::Migrated(closed) => Ok(closed),
+            MigrateAgentOutcome::Refused(zome_name, reason) => Err(
+                ConductorError::MigrateAgentFailed(installed_app_id.clone(), zome_name, reason),
+            ),
+        }
+    }
+
+    async fn migrating_chain(
+        &self,
+        root_env_dir: &std::path::Path,
+        cell_id: &CellId,
+    ) -> ConductorResult<MigratingChain<RealRibosome>> {
+        let dna_hash = cell_id.dna_hash();
+        let dna_file = self
+            .get_dna(dna_hash)
+            .await
+            .ok_or_else(|| DnaError::DnaMissing(dna_hash.clone()))?;
+        let vault = EnvWrite::open(
+            root_env_dir,
+            DbKind::Cell(cell_id.clone()),
+            self.keystore.clone(),
+        )?;
+        let workspace = HostFnWorkspace::new(
+            vault.clone(),
+            EnvWrite::open(
+                root_env_dir,
+                DbKind::Cache(dna_hash.clone()),
+                self.keystore.clone(),
+            )?,
+            cell_id.agent_pubkey().clone(),
+        )
+        .await?;
+        Ok(MigratingChain {
+            ribosome: self.get_ribosome(dna_hash).await?,
+            dna_file,
+            workspace,
+            vault,
+        })
+    }
+
     async fn cell_by_id(&self, cell_id: &CellId) -> ConductorApiResult<Arc<Cell>> {
         let lock = self.conductor.read().await;
         Ok(lock.cell_by_id(cell_id)?)
@@ -1219,9 +2227,9 @@ impl<DS: DnaStore + 'static> ConductorHandleImpl<DS> {
                     // Reconcile cell status so that dangling cells can leave the network and be removed
                     let errors = self.clone().reconcile_cell_status_with_app_status().await?;
 
-                    // TODO: This should probably be emitted over the admin interface
                     if !errors.is_empty() {
                         error!(msg = "Errors when trying to stop app(s)", ?errors);
+                        self.emit_cell_startup_errors(&errors);
                     }
 
                     (NoChange, errors)
@@ -1236,9 +2244,9 @@ impl<DS: DnaStore + 'static> ConductorHandleImpl<DS> {
                         .reconcile_app_status_with_cell_status(app_ids.clone())
                         .await?;
 
-                    // TODO: This should probably be emitted over the admin interface
                     if !errors.is_empty() {
                         error!(msg = "Errors when trying to start app(s)", ?errors);
+                        self.emit_cell_startup_errors(&errors);
                     }
 
                     (delta, errors)
@@ -1291,6 +2299,16 @@ impl<DS: DnaStore + 'static> ConductorHandleImpl<DS> {
         Ok(errors)
     }
 
+    /// Tell admin clients about the cells which failed to start
+    fn emit_cell_startup_errors(&self, errors: &CellStartupErrors) {
+        for (cell_id, err) in errors {
+            self.emit_admin_event(AdminEvent::CellStartupFailed {
+                cell_id: cell_id.clone(),
+                error: err.to_string(),
+            });
+        }
+    }
+
     /// Attempt to join all PendingJoin cells to the kitsune network.
     /// Returns the cells which were joined during this call.
     ///
@@ -1338,6 +2356,12 @@ impl<DS: DnaStore + 'static> ConductorHandleImpl<DS> {
             .await
             .update_cell_status(cell_ids.as_slice(), CellStatus::Joined);
 
+        for cell_id in &cell_ids {
+            self.emit_admin_event(AdminEvent::CellJoinedNetwork {
+                cell_id: cell_id.clone(),
+            });
+        }
+
         cell_ids
     }
 
@@ -1366,4 +2390,17 @@ impl<DS: DnaStore + 'static> ConductorHandleImpl<DS> {
             })
             .clone()
     }
+
+    /// Check the in-memory block list of a space,
+    /// loading it from the database if this is the first check.
+    fn is_blocked(&self, space: Arc<KitsuneSpace>, target: &BlockTarget) -> ConductorResult<bool> {
+        let mut p2p_block_lists = self.p2p_block_lists.lock();
+        let block_list = match p2p_block_lists.entry(space.clone()) {
+            std::collections::hash_map::Entry::Occupied(e) => e.into_mut(),
+            std::collections::hash_map::Entry::Vacant(e) => {
+                e.insert(p2p_agent_store::block_targets(self.p2p_env(space))?)
+            }
+        };
+        Ok(block_list.contains(target))
+    }
 }
@@ -8,9 +8,16 @@
 use crate::conductor::api::*;
 use error::InterfaceError;
 use error::InterfaceResult;
+use holochain_conductor_api::signal_subscription::SignalFilterSet;
+use holochain_conductor_api::AdminEvent;
+use holochain_types::prelude::*;
 use holochain_types::signal::Signal;
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::convert::TryInto;
+use std::sync::Arc;
 use tokio::sync::broadcast;
+use tokio::sync::oneshot;
 
 #[allow(missing_docs)]
 pub mod error;
@@ -98,4 +105,91 @@ impl SignalBroadcaster {
     }
 }
 
+/// The signal subscription made to an app on a single app interface connection
+#[derive(Clone, Debug)]
+pub struct AppSignalSubscription {
+    /// The filters signals from the app must pass
+    pub filters: SignalFilterSet,
+    /// The app's cells when they were last looked up, if they still may be current
+    pub cells: Option<HashSet<CellId>>,
+}
+
+/// The signal subscriptions made on a single app interface connection,
+/// by app. A connection without any subscriptions receives every signal.
+#[derive(Clone, Debug, Default)]
+pub struct SignalSubscriptions(
+    Arc<parking_lot::RwLock<HashMap<InstalledAppId, AppSignalSubscription>>>,
+);
+
+impl SignalSubscriptions {
+    /// Replace the subscription for an app
+    pub fn subscribe(&self, installed_app_id: InstalledAppId, filters: SignalFilterSet) {
+        self.0.write().insert(
+            installed_app_id,
+            AppSignalSubscription {
+                filters,
+                cells: None,
+            },
+        );
+    }
+
+    /// The subscription to each app subscribed to
+    pub fn get(&self) -> HashMap<InstalledAppId, AppSignalSubscription> {
+        self.0.read().clone()
+    }
+
+    /// Remember the cells of a subscribed app
+    pub fn set_cells(&self, installed_app_id: &InstalledAppId, cells: HashSet<CellId>) {
+        if let Some(subscription) = self.0.write().get_mut(installed_app_id) {
+            subscription.cells = Some(cells);
+        }
+    }
+
+    /// Forget the cells of every subscribed app, so they are looked up again
+    pub fn forget_cells(&self) {
+        for subscription in self.0.write().values_mut() {
+            subscription.cells = None;
+        }
+    }
+}
+
+/// The subscription of a single admin interface connection to the
+/// conductor's [`AdminEvent`]s. Connections start out unsubscribed,
+/// and only receive the events sent after they subscribe.
+#[derive(Clone, Debug)]
+pub struct AdminEventSubscription {
+    subscribe_tx: Arc<parking_lot::Mutex<Option<oneshot::Sender<AdminEventReceiver>>>>,
+    subscribe_rx: Arc<parking_lot::Mutex<Option<oneshot::Receiver<AdminEventReceiver>>>>,
+}
+
+type AdminEventReceiver = broadcast::Receiver<AdminEvent>;
+
+impl Default for AdminEventSubscription {
+    fn default() -> Self {
+        let (subscribe_tx, subscribe_rx) = oneshot::channel();
+        Self {
+            subscribe_tx: Arc::new(parking_lot::Mutex::new(Some(subscribe_tx))),
+            subscribe_rx: Arc::new(parking_lot::Mutex::new(Some(subscribe_rx))),
+        }
+    }
+}
+
+impl AdminEventSubscription {
+    /// Start sending the events from this receiver to the connection.
+    /// Subscribing again does nothing.
+    pub fn subscribe(&self, events: AdminEventReceiver) {
+        if let Some(subscribe_tx) = self.subscribe_tx.lock().take() {
+            // The connection may have closed already, which is fine.
+            let _ = subscribe_tx.send(events);
+        }
+    }
+
+    /// Wait until the connection subscribes, and take its events.
+    /// Only the first call gets them.
+    pub async fn events(&self) -> Option<AdminEventReceiver> {
+        let subscribe_rx = self.subscribe_rx.lock().take()?;
+        subscribe_rx.await.ok()
+    }
+}
+
 pub use holochain_conductor_api::config::InterfaceDriver;
@@ -30,6 +30,9 @@ use url2::url2;
 /// Number of signals in buffer before applying
 /// back pressure.
 pub(crate) const SIGNAL_BUFFER_SIZE: usize = 50;
+/// Number of admin events in buffer before slow
+/// connections start missing them.
+pub(crate) const ADMIN_EVENT_BUFFER_SIZE: usize = 50;
 const MAX_CONNECTIONS: isize = 400;
 
 /// Create a WebsocketListener to be used in interfaces
@@ -49,9 +52,10 @@ pub async fn spawn_websocket_listener(
     Ok(listener)
 }
 
-/// Create an Admin Interface, which only receives AdminRequest messages
-/// from the external client
-pub fn spawn_admin_interface_task<A: InterfaceApi>(
+/// Create an Admin Interface, which receives AdminRequest messages
+/// from the external client, and sends the conductor's AdminEvents
+/// to the connections which subscribed to them
+pub fn spawn_admin_interface_task<A: InterfaceApi + AdminInterfaceApi>(
     handle: ListenerHandle,
     listener: impl futures::stream::Stream<Item = ListenerItem> + Send + 'static,
     api: A,
@@ -68,15 +72,17 @@ pub fn spawn_admin_interface_task<A: InterfaceApi>(
         // establish a new connection to a client
         while let Some(connection) = listener.next().await {
             match connection {
-                Ok((_, rx_from_iface)) => {
+                Ok((tx_to_iface, rx_from_iface)) => {
                     if num_connections.fetch_add(1, Ordering::Relaxed) > MAX_CONNECTIONS {
                         // Max connections so drop this connection
                         // which will close it.
                         continue;
                     };
+                    // Each connection has its own event subscription.
                     tokio::task::spawn(recv_incoming_admin_msgs(
-                        api.clone(),
+                        api.for_connection(),
                         rx_from_iface,
+                        tx_to_iface,
                         num_connections.clone(),
                     ));
                 }
@@ -91,7 +97,7 @@ pub fn spawn_admin_interface_task<A: InterfaceApi>(
 
 /// Create an App Interface, which includes the ability to receive signals
 /// from Cells via a broadcast channel
-pub async fn spawn_app_interface_task<A: InterfaceApi>(
+pub async fn spawn_app_interface_task<A: InterfaceApi + AppInterfaceApi>(
     port: u16,
     api: A,
     signal_broadcaster: broadcast::Sender<Signal>,
@@ -118,8 +124,9 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
             match connection {
                 Ok((tx_to_iface, rx_from_iface)) => {
                     let rx_from_cell = signal_broadcaster.subscribe();
+                    // Each connection has its own signal subscriptions.
                     spawn_recv_incoming_msgs_and_outgoing_signals(
-                        api.clone(),
+                        api.for_connection(),
                         rx_from_iface,
                         rx_from_cell,
                         tx_to_iface,
@@ -136,15 +143,22 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
     Ok((port, task))
 }
 
-/// Polls for messages coming in from the external client.
+/// Polls for messages coming in from the external client,
+/// while sending it the events it subscribed to.
 /// Used by Admin interface.
-async fn recv_incoming_admin_msgs<A: InterfaceApi>(
+async fn recv_incoming_admin_msgs<A: InterfaceApi + AdminInterfaceApi>(
     api: A,
     rx_from_iface: WebsocketReceiver,
+    tx_to_iface: WebsocketSender,
     num_connections: Arc<AtomicIsize>,
 ) {
     use futures::stream::StreamExt;
 
+    let events_task = tokio::task::spawn(send_admin_events(
+        api.event_subscription().clone(),
+        tx_to_iface,
+    ));
+
     rx_from_iface
         .for_each_concurrent(4096, move |msg| {
             let api = api.clone();
@@ -156,13 +170,46 @@ async fn recv_incoming_admin_msgs<A: InterfaceApi>(
             }
         })
         .await;
+    events_task.abort();
     num_connections.fetch_sub(1, Ordering::SeqCst);
 }
 
+/// Sends the conductor's events to an admin connection as signals,
+/// once the connection has subscribed to them.
+async fn send_admin_events(subscription: AdminEventSubscription, mut tx_to_iface: WebsocketSender) {
+    let mut rx_events = match subscription.events().await {
+        Some(rx_events) => rx_events,
+        None => return,
+    };
+    loop {
+        let event = match rx_events.recv().await {
+            Ok(event) => event,
+            Err(broadcast::error::RecvError::Lagged(missed)) => {
+                warn!(missed, "Admin connection fell behind and missed events");
+                // Let the client know it should catch up on the
+                // conductor's state some other way.
+                AdminEvent::EventsMissed { missed }
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+        trace!(msg = "Sending admin event", ?event);
+        let result = async {
+            let bytes = SerializedBytes::try_from(event)?;
+            tx_to_iface.signal(bytes).await?;
+            InterfaceResult::Ok(())
+        }
+        .await;
+        if let Err(err) = result {
+            debug!(?err, "Stopped sending admin events to a connection");
+            break;
+        }
+    }
+}
+
 /// Polls for messages coming in from the external client while simultaneously
 /// polling for signals being broadcast from the Cells associated with this
 /// App interface.
-fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
+fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi + AppInterfaceApi>(
     api: A,
     rx_from_iface: WebsocketReceiver,
     rx_from_cell: broadcast::Receiver<Signal>,
@@ -180,9 +227,14 @@ fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
         }
     });
 
+    let signal_api = api.clone();
     tokio::task::spawn(rx_from_cell.for_each_concurrent(4096, move |signal| {
         let mut tx_to_iface = tx_to_iface.clone();
+        let api = signal_api.clone();
         async move {
+            if !api.allows_signal(&signal).await {
+                return;
+            }
             trace!(msg = "Sending signal!", ?signal);
             if let Err(err) = async move {
                 let bytes = SerializedBytes::try_from(signal)?;
@@ -625,7 +677,10 @@ pub mod test {
         let (_tmpdir, conductor_handle) = setup_admin().await;
         let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
         let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
-        let msg = AdminRequest::AttachAppInterface { port: None };
+        let msg = AdminRequest::AttachAppInterface {
+            port: None,
+            installed_app_id: None,
+        };
         let msg = msg.try_into().unwrap();
         let respond = |bytes: SerializedBytes| {
             let response: AdminResponse = bytes.try_into().unwrap();
@@ -7,8 +7,11 @@
 
 mod error;
 pub use error::*;
+mod supervision;
+pub use supervision::*;
 
 use futures::stream::FuturesUnordered;
+use holochain_conductor_api::AdminEvent;
 use holochain_types::prelude::*;
 use std::future::Future;
 use std::pin::Pin;
@@ -39,9 +42,14 @@ pub enum TaskKind {
     Ignore,
     /// If the task returns an error, shut down the conductor.
     Unrecoverable,
-    /// If the task returns an error, "freeze" the cell which caused the error,
-    /// but continue running the rest of the conductor and other managed tasks.
+    /// If the task returns an error, handle it with the supervision policy
+    /// for queue consumers, which by default "freezes" the cell which caused
+    /// the error, but continues running the rest of the conductor and other
+    /// managed tasks.
     CellCritical(CellId),
+    /// If the task returns an error, handle it with the supervision policy
+    /// for this kind of interface, which may spawn the task again.
+    Interface(InterfaceKind, Respawn),
     /// A generic callback for handling the result
     // TODO: B-01455: reevaluate whether this should be a callback
     Generic(OnDeath),
@@ -79,6 +87,17 @@ impl ManagedTaskAdd {
         Self::new(handle, TaskKind::CellCritical(cell_id), name)
     }
 
+    /// If this interface task fails, it is supervised according to the
+    /// policy for its kind of interface, and restarted with `respawn`
+    pub fn interface(
+        handle: ManagedTaskHandle,
+        kind: InterfaceKind,
+        respawn: Respawn,
+        name: &str,
+    ) -> Self {
+        Self::new(handle, TaskKind::Interface(kind, respawn), name)
+    }
+
     /// Handle a task's completion with a generic callback
     pub fn generic(handle: ManagedTaskHandle, f: OnDeath) -> Self {
         Self::new(handle, TaskKind::Generic(f), "unnamed")
@@ -120,6 +139,8 @@ pub enum TaskOutcome {
     /// Either pause or disable all apps which contain the problematic Cell,
     /// depending upon the specific error.
     StopApps(CellId, Box<ManagedTaskError>, String),
+    /// Let the [`Supervisor`] decide what to do about the failure.
+    Supervise(Box<ManagedTaskError>, String, SupervisedTask),
 }
 
 struct TaskManager {
@@ -135,9 +156,10 @@ impl TaskManager {
 
 pub(crate) fn spawn_task_manager(
     handle: ConductorHandle,
+    supervisor: Supervisor,
 ) -> (mpsc::Sender<ManagedTaskAdd>, TaskManagerRunHandle) {
     let (send, recv) = mpsc::channel(CHANNEL_SIZE);
-    (send, tokio::spawn(run(handle, recv)))
+    (send, tokio::spawn(run(handle, supervisor, recv)))
 }
 
 /// A super pessimistic task that is just waiting to die
@@ -150,6 +172,7 @@ pub(crate) async fn keep_alive_task(mut die: broadcast::Receiver<()>) -> Managed
 
 async fn run(
     conductor: ConductorHandle,
+    supervisor: Supervisor,
     mut new_task_channel: mpsc::Receiver<ManagedTaskAdd>,
 ) -> TaskManagerResult {
     let mut task_manager = TaskManager::new();
@@ -168,13 +191,24 @@ async fn run(
             }
             result = task_manager.stream.next() => {
                 tracing::info!("Task completed. Total tasks: {}", task_manager.stream.len());
+                let result = match result {
+                    Some(TaskOutcome::Supervise(error, context, task)) => {
+                        Some(supervisor.on_failure(&conductor, error, context, task))
+                    }
+                    result => result,
+                };
                 match result {
                 Some(TaskOutcome::NewTask(new_task)) => task_manager.stream.push(new_task),
                 Some(TaskOutcome::LogInfo(context)) => {
                     info!("Managed task completed: {}", context)
                 }
                 Some(TaskOutcome::MinorError(error, context)) => {
-                    error!("Minor error during managed task: {:?}\nContext: {}", error, context)
+                    error!("Minor error during managed task: {:?}\nContext: {}", error, context);
+                    conductor.emit_admin_event(AdminEvent::TaskFailed {
+                        context,
+                        error: error.to_string(),
+                        cell_id: None,
+                    });
                 }
                 Some(TaskOutcome::ShutdownConductor(error, context)) => {
                     let error = match *error {
@@ -190,10 +224,20 @@ async fn run(
                         error => error,
                     };
                     error!("Shutting down conductor due to unrecoverable error: {:?}\nContext: {}", error, context);
+                    conductor.emit_admin_event(AdminEvent::TaskFailed {
+                        context,
+                        error: error.to_string(),
+                        cell_id: None,
+                    });
                     return Err(TaskManagerError::Unrecoverable(error));
                 },
                 Some(TaskOutcome::StopApps(cell_id, error, context)) => {
                     tracing::error!("About to automatically stop apps");
+                    conductor.emit_admin_event(AdminEvent::TaskFailed {
+                        context: context.clone(),
+                        error: error.to_string(),
+                        cell_id: Some(cell_id.clone()),
+                    });
                     let app_ids = conductor.list_running_apps_for_required_cell_id(&cell_id).await.map_err(TaskManagerError::internal)?;
                     if error.is_recoverable() {
                         conductor.remove_cells(&[cell_id]).await;
@@ -230,6 +274,7 @@ async fn run(
                         tracing::error!("Apps disabled.");
                     }
                 },
+                Some(TaskOutcome::Supervise(..)) => unreachable!("Supervised failures were handled above"),
                 None => return Ok(()),
             }}
         };
@@ -250,7 +295,19 @@ fn handle_completed_task(kind: &TaskKind, result: ManagedTaskResult, name: Strin
         },
         TaskKind::CellCritical(cell_id) => match result {
             Ok(_) => LogInfo(name),
-            Err(err) => StopApps(cell_id.to_owned(), Box::new(err), name),
+            Err(err) => Supervise(
+                Box::new(err),
+                name,
+                SupervisedTask::QueueConsumer(cell_id.to_owned()),
+            ),
+        },
+        TaskKind::Interface(interface, respawn) => match result {
+            Ok(_) => LogInfo(name),
+            Err(err) => Supervise(
+                Box::new(err),
+                name,
+                SupervisedTask::Interface(*interface, respawn.clone()),
+            ),
         },
         TaskKind::Generic(f) => f(result),
     }
@@ -287,6 +344,9 @@ pub struct TaskManagerClient {
     /// telling them to shut down
     task_stop_broadcaster: StopBroadcaster,
 
+    /// The restarts made by the task manager's supervisor
+    task_restarts: TaskRestartLog,
+
     /// The main task join handle to await on.
     /// The conductor is intended to live as long as this task does.
     /// It can be moved out, hence the Option. If this is None, then the
@@ -299,11 +359,13 @@ impl TaskManagerClient {
     pub fn new(
         task_add_sender: mpsc::Sender<ManagedTaskAdd>,
         task_stop_broadcaster: StopBroadcaster,
+        task_restarts: TaskRestartLog,
         run_handle: TaskManagerRunHandle,
     ) -> Self {
         Self {
             task_add_sender,
             task_stop_broadcaster,
+            task_restarts,
             run_handle: Some(run_handle),
         }
     }
@@ -318,6 +380,11 @@ impl TaskManagerClient {
         &self.task_stop_broadcaster
     }
 
+    /// Accessor
+    pub fn task_restarts(&self) -> &TaskRestartLog {
+        &self.task_restarts
+    }
+
     /// Return the handle to be joined.
     /// This will return None if the handle was already taken.
     pub fn take_handle(&mut self) -> Option<TaskManagerRunHandle> {
@@ -331,14 +398,24 @@ mod test {
     use crate::conductor::error::ConductorError;
     use crate::conductor::handle::MockConductorHandleT;
     use anyhow::Result;
+    use futures::FutureExt;
+    use holochain_conductor_api::conductor::SupervisionPolicy;
+    use holochain_conductor_api::conductor::TaskSupervisionConfig;
     use observability;
+    use std::sync::atomic::AtomicU32;
+    use std::sync::atomic::Ordering;
     use std::sync::Arc;
 
+    fn test_supervisor() -> Supervisor {
+        Supervisor::new(Default::default(), broadcast::channel(1).0)
+    }
+
     #[tokio::test]
     async fn spawn_and_handle_dying_task() -> Result<()> {
         observability::test_run().ok();
         let mock_handle = MockConductorHandleT::new();
-        let (send_task_handle, main_task) = spawn_task_manager(Arc::new(mock_handle));
+        let (send_task_handle, main_task) =
+            spawn_task_manager(Arc::new(mock_handle), test_supervisor());
         let handle = tokio::spawn(async {
             Err(ConductorError::Todo("This task gotta die".to_string()).into())
         });
@@ -366,14 +443,167 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn restart_failed_interface_task() -> Result<()> {
+        observability::test_run().ok();
+        let mut mock_handle = MockConductorHandleT::new();
+        mock_handle.expect_emit_admin_event().return_const(());
+        let supervisor = test_supervisor();
+        let restarts = supervisor.restarts();
+        let (send_task_handle, main_task) = spawn_task_manager(Arc::new(mock_handle), supervisor);
+
+        let respawns = Arc::new(AtomicU32::new(0));
+        let respawn: Respawn = {
+            let respawns = respawns.clone();
+            Arc::new(move || {
+                respawns.fetch_add(1, Ordering::SeqCst);
+                async { Ok(tokio::spawn(async { Ok(()) })) }.boxed()
+            })
+        };
+        let handle = tokio::spawn(async {
+            Err(ConductorError::Todo("Interface failed".to_string()).into())
+        });
+        send_task_handle
+            .send(ManagedTaskAdd::interface(
+                handle,
+                InterfaceKind::App,
+                respawn,
+                "test interface",
+            ))
+            .await
+            .unwrap();
+
+        // The task manager finishes once the restarted task has completed
+        drop(send_task_handle);
+        main_task.await??;
+        assert_eq!(respawns.load(Ordering::SeqCst), 1);
+        let restarts = restarts.list();
+        assert_eq!(restarts.len(), 1);
+        assert_eq!(restarts[0].name, "test interface");
+        assert_eq!(restarts[0].restarts, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn restart_failed_queue_consumer() -> Result<()> {
+        observability::test_run().ok();
+        let cell_id = holochain_types::test_utils::fake_cell_id(1);
+        let mut mock_handle = MockConductorHandleT::new();
+        mock_handle.expect_emit_admin_event().return_const(());
+        // - The cell is removed, which stops its queue consumers,
+        //   before it is created again for its app.
+        let mut sequence = mockall::Sequence::new();
+        mock_handle
+            .expect_remove_cells()
+            .withf({
+                let cell_id = cell_id.clone();
+                move |cell_ids| cell_ids.to_vec() == vec![cell_id.clone()]
+            })
+            .times(1)
+            .in_sequence(&mut sequence)
+            .return_const(());
+        mock_handle
+            .expect_reconcile_cell_status_with_app_status()
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|| Ok(vec![]));
+        mock_handle
+            .expect_reconcile_app_status_with_cell_status()
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_| Ok(AppStatusFx::NoChange));
+        let supervisor = Supervisor::new(
+            TaskSupervisionConfig {
+                queue_consumers: SupervisionPolicy::Restart {
+                    max_restarts: 1,
+                    backoff_ms: 1,
+                    max_backoff_ms: 1,
+                    reset_after_ms: 600_000,
+                },
+                ..Default::default()
+            },
+            broadcast::channel(1).0,
+        );
+        let restarts = supervisor.restarts();
+        let (send_task_handle, main_task) = spawn_task_manager(Arc::new(mock_handle), supervisor);
+
+        let handle = tokio::spawn(async {
+            Err(ConductorError::Todo("Queue consumer failed".to_string()).into())
+        });
+        send_task_handle
+            .send(ManagedTaskAdd::cell_critical(
+                handle,
+                cell_id.clone(),
+                "test consumer",
+            ))
+            .await
+            .unwrap();
+
+        // The task manager finishes once the cell was created again
+        drop(send_task_handle);
+        main_task.await??;
+        let restarts = restarts.list();
+        assert_eq!(restarts.len(), 1);
+        assert_eq!(restarts[0].cell_id, Some(cell_id));
+        assert_eq!(restarts[0].restarts, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn failed_cell_task_emits_admin_event() -> Result<()> {
+        observability::test_run().ok();
+        let cell_id = holochain_types::test_utils::fake_cell_id(1);
+        let mut mock_handle = MockConductorHandleT::new();
+        mock_handle
+            .expect_emit_admin_event()
+            .withf({
+                let cell_id = cell_id.clone();
+                move |event| match event {
+                    AdminEvent::TaskFailed {
+                        context,
+                        cell_id: Some(failed),
+                        ..
+                    } => context == "test consumer" && *failed == cell_id,
+                    _ => false,
+                }
+            })
+            .times(1)
+            .return_const(());
+        mock_handle
+            .expect_list_running_apps_for_required_cell_id()
+            .times(1)
+            .returning(|_| Ok(Default::default()));
+        let (send_task_handle, main_task) =
+            spawn_task_manager(Arc::new(mock_handle), test_supervisor());
+
+        let handle = tokio::spawn(async {
+            Err(ConductorError::Todo("Queue consumer failed".to_string()).into())
+        });
+        send_task_handle
+            .send(ManagedTaskAdd::cell_critical(
+                handle,
+                cell_id,
+                "test consumer",
+            ))
+            .await
+            .unwrap();
+
+        // The task manager finishes once the apps using the cell were stopped
+        drop(send_task_handle);
+        main_task.await??;
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     #[should_panic]
     #[ignore = "panics in tokio break other tests"]
     async fn unrecoverable_error() {
         observability::test_run().ok();
         let (_tx, rx) = tokio::sync::broadcast::channel(1);
-        let mock_handle = MockConductorHandleT::new();
-        let (send_task_handle, main_task) = spawn_task_manager(Arc::new(mock_handle));
+        let mut mock_handle = MockConductorHandleT::new();
+        mock_handle.expect_emit_admin_event().return_const(());
+        let (send_task_handle, main_task) =
+            spawn_task_manager(Arc::new(mock_handle), test_supervisor());
         send_task_handle
             .send(ManagedTaskAdd::ignore(
                 tokio::spawn(keep_alive_task(rx)),
@@ -403,7 +633,8 @@ mod test {
         observability::test_run().ok();
         let (_tx, rx) = tokio::sync::broadcast::channel(1);
         let mock_handle = MockConductorHandleT::new();
-        let (send_task_handle, main_task) = spawn_task_manager(Arc::new(mock_handle));
+        let (send_task_handle, main_task) =
+            spawn_task_manager(Arc::new(mock_handle), test_supervisor());
         send_task_handle
             .send(ManagedTaskAdd::ignore(
                 tokio::spawn(keep_alive_task(rx)),
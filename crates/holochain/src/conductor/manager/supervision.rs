@@ -0,0 +1,253 @@
+//! Supervision of the managed tasks whose failures are handled according to
+//! the conductor's [`TaskSupervisionConfig`].
+
+use super::ManagedTaskAdd;
+use super::ManagedTaskError;
+use super::ManagedTaskHandle;
+use super::ManagedTaskResult;
+use super::TaskKind;
+use super::TaskOutcome;
+use crate::conductor::conductor::StopBroadcaster;
+use crate::conductor::ConductorHandle;
+use futures::future::BoxFuture;
+use holochain_conductor_api::conductor::SupervisionPolicy;
+use holochain_conductor_api::conductor::TaskSupervisionConfig;
+use holochain_conductor_api::AdminEvent;
+use holochain_conductor_api::TaskRestarts;
+use holochain_types::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+/// Spawns a task again after it failed, returning the handle of the new task
+pub type Respawn =
+    Arc<dyn Fn() -> BoxFuture<'static, Result<ManagedTaskHandle, ManagedTaskError>> + Send + Sync>;
+
+/// The kinds of interface whose tasks are supervised
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InterfaceKind {
+    /// An admin interface
+    Admin,
+    /// An app interface
+    App,
+}
+
+/// A failed task whose failure is handled by a [`SupervisionPolicy`]
+pub enum SupervisedTask {
+    /// A queue consumer of a cell, which is restarted by creating the cell again
+    QueueConsumer(CellId),
+    /// An interface, which is restarted by spawning it again
+    Interface(InterfaceKind, Respawn),
+}
+
+/// The restarts of every supervised task, shared with the conductor
+/// so they can be listed over the admin interface
+#[derive(Clone, Default)]
+pub struct TaskRestartLog(Arc<parking_lot::Mutex<HashMap<TaskKey, (TaskRestarts, Instant)>>>);
+
+/// Tasks are told apart by their name and cell
+type TaskKey = (String, Option<CellId>);
+
+impl TaskRestartLog {
+    /// How many times a task was restarted since it last ran for
+    /// `reset_after` without failing
+    fn count(&self, name: &str, cell_id: &Option<CellId>, reset_after: Option<Duration>) -> u32 {
+        let mut log = self.0.lock();
+        let key = (name.to_string(), cell_id.clone());
+        match (log.get_mut(&key), reset_after) {
+            (Some((restarts, last_restart)), Some(reset_after))
+                if last_restart.elapsed() >= reset_after =>
+            {
+                restarts.restarts = 0;
+                0
+            }
+            (Some((restarts, _)), _) => restarts.restarts,
+            (None, _) => 0,
+        }
+    }
+
+    fn record(&self, name: String, cell_id: Option<CellId>, error: &ManagedTaskError) {
+        let last_error = error.to_string();
+        let now = Instant::now();
+        self.0
+            .lock()
+            .entry((name.clone(), cell_id.clone()))
+            .and_modify(|(r, last_restart)| {
+                r.restarts += 1;
+                r.last_error = last_error.clone();
+                *last_restart = now;
+            })
+            .or_insert((
+                TaskRestarts {
+                    name,
+                    cell_id,
+                    restarts: 1,
+                    last_error,
+                },
+                now,
+            ));
+    }
+
+    /// Every task which was restarted
+    pub fn list(&self) -> Vec<TaskRestarts> {
+        self.0.lock().values().map(|(r, _)| r.clone()).collect()
+    }
+}
+
+/// Decides what to do about failed supervised tasks
+pub struct Supervisor {
+    config: TaskSupervisionConfig,
+    restarts: TaskRestartLog,
+    stop_tx: StopBroadcaster,
+}
+
+impl Supervisor {
+    /// Constructor. Restarts are cancelled when a stop is sent on `stop_tx`.
+    pub fn new(config: TaskSupervisionConfig, stop_tx: StopBroadcaster) -> Self {
+        Self {
+            config,
+            restarts: TaskRestartLog::default(),
+            stop_tx,
+        }
+    }
+
+    /// The log of restarts made by this supervisor
+    pub fn restarts(&self) -> TaskRestartLog {
+        self.restarts.clone()
+    }
+
+    fn policy(&self, task: &SupervisedTask) -> &SupervisionPolicy {
+        match task {
+            SupervisedTask::QueueConsumer(_) => &self.config.queue_consumers,
+            SupervisedTask::Interface(InterfaceKind::Admin, _) => &self.config.admin_interfaces,
+            SupervisedTask::Interface(InterfaceKind::App, _) => &self.config.app_interfaces,
+        }
+    }
+
+    /// Apply the policy for a failed task, turning its failure into
+    /// the outcome the task manager should act on
+    pub(super) fn on_failure(
+        &self,
+        conductor: &ConductorHandle,
+        error: Box<ManagedTaskError>,
+        name: String,
+        task: SupervisedTask,
+    ) -> TaskOutcome {
+        let policy = self.policy(&task);
+        if let SupervisionPolicy::Shutdown = policy {
+            return TaskOutcome::ShutdownConductor(error, name);
+        }
+        let cell_id = match &task {
+            SupervisedTask::QueueConsumer(cell_id) => Some(cell_id.clone()),
+            SupervisedTask::Interface(..) => None,
+        };
+        let restarts = self.restarts.count(&name, &cell_id, policy.reset_after());
+        let backoff = match policy.restart_backoff(restarts) {
+            Some(backoff) => backoff,
+            // Either the policy is to stop apps, or the task
+            // was restarted too many times already
+            None => {
+                return match cell_id {
+                    Some(cell_id) => TaskOutcome::StopApps(cell_id, error, name),
+                    None => TaskOutcome::MinorError(error, name),
+                }
+            }
+        };
+
+        tracing::warn!(
+            "Restarting managed task in {:?} after it failed: {:?}\nContext: {}",
+            backoff,
+            error,
+            name
+        );
+        self.restarts
+            .record(name.clone(), cell_id.clone(), error.as_ref());
+        conductor.emit_admin_event(AdminEvent::TaskFailed {
+            context: name.clone(),
+            error: error.to_string(),
+            cell_id,
+        });
+
+        let stop_rx = self.stop_tx.subscribe();
+        match task {
+            SupervisedTask::Interface(kind, respawn) => {
+                let handle = tokio::spawn({
+                    let respawn = respawn.clone();
+                    async move {
+                        if !wait_for_restart(backoff, stop_rx).await {
+                            return Ok(());
+                        }
+                        let handle = respawn().await?;
+                        handle.await?
+                    }
+                });
+                TaskOutcome::NewTask(ManagedTaskAdd::new(
+                    handle,
+                    TaskKind::Interface(kind, respawn),
+                    &name,
+                ))
+            }
+            SupervisedTask::QueueConsumer(cell_id) => {
+                let conductor = conductor.clone();
+                let handle = tokio::spawn({
+                    let cell_id = cell_id.clone();
+                    async move {
+                        if !wait_for_restart(backoff, stop_rx).await {
+                            return Ok(());
+                        }
+                        // The old cell's queue consumers are stopped before
+                        // the new cell spawns all of them again.
+                        conductor.remove_cells(&[cell_id]).await;
+                        conductor
+                            .clone()
+                            .reconcile_cell_status_with_app_status()
+                            .await?;
+                        // Pause the apps whose cell could not be created
+                        conductor
+                            .reconcile_app_status_with_cell_status(None)
+                            .await?;
+                        ManagedTaskResult::Ok(())
+                    }
+                });
+                TaskOutcome::NewTask(ManagedTaskAdd::ignore(
+                    handle,
+                    &format!("restart of cell {:?}", cell_id),
+                ))
+            }
+        }
+    }
+}
+
+/// Wait out the backoff before a restart.
+/// Returns false if the conductor is stopping instead.
+async fn wait_for_restart(
+    backoff: Duration,
+    mut stop_rx: tokio::sync::broadcast::Receiver<()>,
+) -> bool {
+    tokio::select! {
+        _ = tokio::time::sleep(backoff) => true,
+        _ = stop_rx.recv() => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conductor::error::ConductorError;
+
+    #[test]
+    fn restarts_are_forgotten_after_a_healthy_period() {
+        let log = TaskRestartLog::default();
+        let error = ManagedTaskError::Conductor(ConductorError::Todo("failed".to_string()));
+        log.record("task".to_string(), None, &error);
+        log.record("task".to_string(), None, &error);
+        assert_eq!(log.count("task", &None, None), 2);
+        assert_eq!(log.count("task", &None, Some(Duration::from_secs(60))), 2);
+
+        // - The task ran long enough since its last restart
+        assert_eq!(log.count("task", &None, Some(Duration::from_millis(0))), 0);
+        log.record("task".to_string(), None, &error);
+        assert_eq!(log.count("task", &None, None), 1);
+    }
+}
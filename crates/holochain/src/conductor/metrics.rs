@@ -0,0 +1,449 @@
+//! Metrics about the running conductor, served in the OpenMetrics text
+//! format for scraping by a monitoring system such as Prometheus.
+//!
+//! Zome calls and validation outcomes are recorded in memory as they happen.
+//! The workflow queue depths, database connection pool usage and gossip
+//! bandwidth are sampled when the metrics are scraped.
+
+use super::conductor::CellStatus;
+use super::config::MetricsConfig;
+use super::error::ConductorResult;
+use super::manager::ManagedTaskHandle;
+use super::ConductorHandle;
+use crate::core::queue_consumer::queue_depths;
+use crate::core::queue_consumer::QueueDepths;
+use holochain_sqlite::db::connection_pool_usage;
+use holochain_sqlite::db::ConnectionPoolUsage;
+use holochain_sqlite::db::DbKind;
+use holochain_zome_types::FunctionName;
+use holochain_zome_types::ZomeName;
+use kitsune_p2p_types::metrics::metric_gossip_bytes_received_total;
+use kitsune_p2p_types::metrics::metric_gossip_bytes_sent_total;
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+use tokio::sync::broadcast;
+
+/// The content type of the OpenMetrics text format.
+const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// The longest request head we will read before giving up on a request.
+const MAX_REQUEST_HEAD_BYTES: usize = 8 * 1024;
+
+/// How long a client has to send its request and read the response.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The upper bounds, in seconds, of the zome call duration histogram buckets.
+const ZOME_CALL_BUCKETS: [f64; 12] = [
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0,
+];
+
+/// The metrics of one conductor which are recorded as they happen.
+/// Clones share the same metrics.
+#[derive(Clone, Default)]
+pub struct ConductorMetrics(Arc<Mutex<RecordedMetrics>>);
+
+#[derive(Default)]
+struct RecordedMetrics {
+    zome_calls: BTreeMap<(Option<(ZomeName, FunctionName)>, &'static str), Histogram>,
+    validation_outcomes: BTreeMap<(&'static str, &'static str), u64>,
+}
+
+/// A histogram of durations in seconds, with cumulative buckets.
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; ZOME_CALL_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(ZOME_CALL_BUCKETS.iter()) {
+            if seconds <= *bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+impl ConductorMetrics {
+    /// Record that a zome function was called, whether the call succeeded,
+    /// and how long it took.
+    /// The zome and function are `None` if the DNA doesn't define them,
+    /// so that callers can't add a time series for every name they make up.
+    pub(crate) fn record_zome_call(
+        &self,
+        zome_fn: Option<(ZomeName, FunctionName)>,
+        outcome: &'static str,
+        elapsed: Duration,
+    ) {
+        self.0
+            .lock()
+            .zome_calls
+            .entry((zome_fn, outcome))
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// Record the outcome of validating an op.
+    /// The workflow is either "sys" or "app", and the outcome one of
+    /// "accepted", "rejected" or "awaiting_deps".
+    pub(crate) fn record_validation_outcome(&self, workflow: &'static str, outcome: &'static str) {
+        *self
+            .0
+            .lock()
+            .validation_outcomes
+            .entry((workflow, outcome))
+            .or_default() += 1;
+    }
+}
+
+/// Spawn a task serving the metrics over HTTP until the conductor shuts down.
+/// Returns the port the endpoint is listening on.
+pub(crate) async fn spawn_metrics_endpoint(
+    handle: ConductorHandle,
+    config: &MetricsConfig,
+    mut stop: broadcast::Receiver<()>,
+) -> ConductorResult<(u16, ManagedTaskHandle)> {
+    let listener = TcpListener::bind(config.socket_addr()).await?;
+    let port = listener.local_addr()?.port();
+    let task = tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = stop.recv() => break,
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => {
+                        let handle = handle.clone();
+                        tokio::spawn(async move {
+                            let served = tokio::time::timeout(
+                                REQUEST_TIMEOUT,
+                                serve_request(handle, stream),
+                            );
+                            if let Ok(Err(e)) = served.await {
+                                tracing::debug!(msg = "Failed to serve metrics", ?e);
+                            }
+                        });
+                    }
+                    Err(e) => tracing::warn!(msg = "Failed to accept metrics connection", ?e),
+                },
+            }
+        }
+        Ok(())
+    });
+    Ok((port, task))
+}
+
+/// Answer a single HTTP request, serving the metrics at `GET /metrics`.
+async fn serve_request(handle: ConductorHandle, mut stream: TcpStream) -> std::io::Result<()> {
+    let mut head = Vec::new();
+    let mut buf = [0; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let read = stream.read(&mut buf).await?;
+        if read == 0 || head.len() > MAX_REQUEST_HEAD_BYTES {
+            return Ok(());
+        }
+        head.extend_from_slice(&buf[..read]);
+    }
+    let request_line = head.split(|b| *b == b'\n').next().unwrap_or_default();
+    let mut request_line = std::str::from_utf8(request_line)
+        .unwrap_or_default()
+        .split_whitespace();
+    let is_metrics_path = |path: &str| path == "/metrics" || path.starts_with("/metrics?");
+    let (status, body) = match (request_line.next(), request_line.next()) {
+        (Some("GET"), Some(path)) if is_metrics_path(path) => ("200 OK", scrape(&handle).await),
+        _ => ("404 Not Found", String::new()),
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        CONTENT_TYPE,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Sample the metrics which aren't recorded as they happen, and encode all
+/// the metrics.
+async fn scrape(handle: &ConductorHandle) -> String {
+    let mut depths = QueueDepths::default();
+    let cell_ids = handle
+        .list_cell_ids(Some(CellStatus::Joined))
+        .await
+        .unwrap_or_default();
+    for cell_id in cell_ids {
+        let cell_depths = match handle.get_cell_env_readonly(&cell_id).await {
+            Ok(env) => queue_depths(&env).await.map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        match cell_depths {
+            Ok(cell_depths) => depths += cell_depths,
+            Err(e) => tracing::warn!(msg = "Failed to count queue depths", ?cell_id, ?e),
+        }
+    }
+    encode_metrics(handle.metrics(), depths, connection_pool_usage())
+}
+
+/// Encode the metrics in the OpenMetrics text format.
+fn encode_metrics(
+    metrics: &ConductorMetrics,
+    depths: QueueDepths,
+    pools: Vec<ConnectionPoolUsage>,
+) -> String {
+    let mut out = String::new();
+    let metrics = metrics.0.lock();
+
+    family(
+        &mut out,
+        "holochain_zome_call_duration_seconds",
+        "histogram",
+        "The time taken by zome calls, by zome, function and outcome.",
+    );
+    for ((zome_fn, outcome), histogram) in metrics.zome_calls.iter() {
+        // Calls to functions the DNA doesn't define have empty labels.
+        let (zome_name, fn_name) = zome_fn
+            .as_ref()
+            .map(|(zome_name, fn_name)| (zome_name.0.as_str(), fn_name.0.as_str()))
+            .unwrap_or_default();
+        let labels = format!(
+            "zome=\"{}\",fn=\"{}\",outcome=\"{}\"",
+            escape(zome_name),
+            escape(fn_name),
+            outcome
+        );
+        let name = "holochain_zome_call_duration_seconds";
+        for (count, bound) in histogram.buckets.iter().zip(ZOME_CALL_BUCKETS.iter()) {
+            writeln!(
+                out,
+                "{}_bucket{{{},le=\"{}\"}} {}",
+                name, labels, bound, count
+            )
+            .ok();
+        }
+        writeln!(
+            out,
+            "{}_bucket{{{},le=\"+Inf\"}} {}",
+            name, labels, histogram.count
+        )
+        .ok();
+        writeln!(out, "{}_count{{{}}} {}", name, labels, histogram.count).ok();
+        writeln!(out, "{}_sum{{{}}} {}", name, labels, histogram.sum).ok();
+    }
+
+    family(
+        &mut out,
+        "holochain_validation_outcomes",
+        "counter",
+        "The outcomes of validating ops, by validation workflow.",
+    );
+    for ((workflow, outcome), count) in metrics.validation_outcomes.iter() {
+        writeln!(
+            out,
+            "holochain_validation_outcomes_total{{workflow=\"{}\",outcome=\"{}\"}} {}",
+            workflow, outcome, count
+        )
+        .ok();
+    }
+    drop(metrics);
+
+    family(
+        &mut out,
+        "holochain_workflow_queue_depth",
+        "gauge",
+        "The number of ops waiting in each workflow queue, across all cells.",
+    );
+    for (queue, depth) in [
+        ("sys_validation", depths.sys_validation),
+        ("app_validation", depths.app_validation),
+        ("integration", depths.integration),
+        ("publish", depths.publish),
+    ]
+    .iter()
+    {
+        writeln!(
+            out,
+            "holochain_workflow_queue_depth{{queue=\"{}\"}} {}",
+            queue, depth
+        )
+        .ok();
+    }
+
+    let mut pool_totals: BTreeMap<&'static str, ConnectionPoolTotals> = BTreeMap::new();
+    for pool in pools {
+        let totals = pool_totals.entry(db_label(&pool.kind)).or_default();
+        totals.active += pool.connections.saturating_sub(pool.idle_connections) as u64;
+        totals.idle += pool.idle_connections as u64;
+        totals.max += pool.max_size as u64;
+    }
+    family(
+        &mut out,
+        "holochain_db_pool_connections",
+        "gauge",
+        "The open database connections, by kind of database and whether in use.",
+    );
+    for (db, totals) in pool_totals.iter() {
+        for (state, count) in [("active", totals.active), ("idle", totals.idle)].iter() {
+            writeln!(
+                out,
+                "holochain_db_pool_connections{{db=\"{}\",state=\"{}\"}} {}",
+                db, state, count
+            )
+            .ok();
+        }
+    }
+    family(
+        &mut out,
+        "holochain_db_pool_max_connections",
+        "gauge",
+        "The most database connections the pools may open, by kind of database.",
+    );
+    for (db, totals) in pool_totals.iter() {
+        writeln!(
+            out,
+            "holochain_db_pool_max_connections{{db=\"{}\"}} {}",
+            db, totals.max
+        )
+        .ok();
+    }
+
+    family(
+        &mut out,
+        "kitsune_gossip_bytes",
+        "counter",
+        "The bytes of gossip sent and received.",
+    );
+    writeln!(out, "# UNIT kitsune_gossip_bytes bytes").ok();
+    writeln!(
+        out,
+        "kitsune_gossip_bytes_total{{direction=\"sent\"}} {}",
+        metric_gossip_bytes_sent_total()
+    )
+    .ok();
+    writeln!(
+        out,
+        "kitsune_gossip_bytes_total{{direction=\"received\"}} {}",
+        metric_gossip_bytes_received_total()
+    )
+    .ok();
+
+    out.push_str("# EOF\n");
+    out
+}
+
+/// The connection pool usage of all the databases of one kind.
+#[derive(Default)]
+struct ConnectionPoolTotals {
+    active: u64,
+    idle: u64,
+    max: u64,
+}
+
+/// Write the metadata of a metric family.
+fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+    writeln!(out, "# TYPE {} {}", name, kind).ok();
+    writeln!(out, "# HELP {} {}", name, help).ok();
+}
+
+/// The label for a kind of database, leaving out which cell or space it
+/// belongs to so as not to produce a time series per database.
+fn db_label(kind: &DbKind) -> &'static str {
+    match kind {
+        DbKind::Cell(_) => "cell",
+        DbKind::Cache(_) => "cache",
+        DbKind::Conductor => "conductor",
+        DbKind::Wasm => "wasm",
+        DbKind::P2pAgentStore(_) => "p2p_agent_store",
+        DbKind::P2pMetrics(_) => "p2p_metrics",
+    }
+}
+
+/// Escape a label value.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_recorded_metrics() {
+        let zome_name: ZomeName = "metrics_zome".into();
+        let fn_name: FunctionName = "say \"hi\"".into();
+        let zome_fn = Some((zome_name, fn_name));
+        let metrics = ConductorMetrics::default();
+        metrics.record_zome_call(zome_fn.clone(), "ok", Duration::from_millis(20));
+        metrics.record_zome_call(zome_fn, "ok", Duration::from_secs(2));
+        metrics.record_zome_call(None, "error", Duration::from_millis(1));
+        metrics.record_validation_outcome("sys", "accepted");
+
+        let depths = QueueDepths {
+            integration: 3,
+            ..Default::default()
+        };
+        let pools = vec![
+            ConnectionPoolUsage {
+                kind: DbKind::Conductor,
+                connections: 3,
+                idle_connections: 1,
+                max_size: 20,
+            },
+            ConnectionPoolUsage {
+                kind: DbKind::Wasm,
+                connections: 0,
+                idle_connections: 0,
+                max_size: 20,
+            },
+        ];
+        let encoded = encode_metrics(&metrics, depths, pools);
+        let lines: Vec<&str> = encoded.lines().collect();
+        let labels = r#"zome="metrics_zome",fn="say \"hi\"",outcome="ok""#;
+
+        let has = |line: String| lines.contains(&line.as_str());
+        assert!(has(format!(
+            "holochain_zome_call_duration_seconds_bucket{{{},le=\"0.01\"}} 0",
+            labels
+        )));
+        assert!(has(format!(
+            "holochain_zome_call_duration_seconds_bucket{{{},le=\"0.025\"}} 1",
+            labels
+        )));
+        assert!(has(format!(
+            "holochain_zome_call_duration_seconds_bucket{{{},le=\"+Inf\"}} 2",
+            labels
+        )));
+        assert!(has(format!(
+            "holochain_zome_call_duration_seconds_count{{{}}} 2",
+            labels
+        )));
+        assert!(has(
+            r#"holochain_zome_call_duration_seconds_count{zome="",fn="",outcome="error"} 1"#.into()
+        ));
+        assert!(lines.iter().any(|l| l.starts_with(
+            "holochain_validation_outcomes_total{workflow=\"sys\",outcome=\"accepted\"}"
+        )));
+        assert!(has(
+            "holochain_workflow_queue_depth{queue=\"integration\"} 3".into()
+        ));
+        assert!(has(
+            "holochain_db_pool_connections{db=\"conductor\",state=\"active\"} 2".into()
+        ));
+        assert!(has(
+            "holochain_db_pool_max_connections{db=\"wasm\"} 20".into()
+        ));
+        assert_eq!(lines.last(), Some(&"# EOF"));
+    }
+}
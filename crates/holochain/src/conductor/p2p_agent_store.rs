@@ -5,10 +5,12 @@ use holo_hash::AgentPubKey;
 use holo_hash::DnaHash;
 use holochain_conductor_api::AgentInfoDump;
 use holochain_conductor_api::P2pAgentsDump;
+use holochain_conductor_api::PeerDump;
 use holochain_p2p::dht_arc::DhtArc;
 use holochain_p2p::dht_arc::DhtArcBucket;
 use holochain_p2p::dht_arc::PeerDensity;
 use holochain_p2p::kitsune_p2p::agent_store::AgentInfoSigned;
+use holochain_p2p::kitsune_p2p::event::BlockTarget;
 use holochain_p2p::AgentPubKeyExt;
 use holochain_sqlite::prelude::*;
 use holochain_state::prelude::StateMutationResult;
@@ -16,6 +18,7 @@ use holochain_state::prelude::StateQueryResult;
 use holochain_types::prelude::*;
 use holochain_zome_types::CellId;
 use kitsune_p2p::KitsuneBinType;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use super::error::ConductorResult;
@@ -125,6 +128,39 @@ pub async fn put_agent_info_signed(
     Ok(p2p_put(&environ, &agent_info_signed).await?)
 }
 
+/// Block an agent from this space, dropping their agent info
+pub async fn block_agent(env: EnvWrite, agent: AgentPubKey) -> ConductorResult<()> {
+    Ok(p2p_block_agent(&env, &agent.into_kitsune_raw()).await?)
+}
+
+/// Remove an agent from this space's block list
+pub async fn unblock_agent(env: EnvWrite, agent: AgentPubKey) -> ConductorResult<()> {
+    Ok(p2p_unblock_agent(&env, &agent.into_kitsune_raw()).await?)
+}
+
+/// List the agents blocked from this space
+pub fn list_blocked_agents(env: EnvRead) -> StateQueryResult<Vec<AgentPubKey>> {
+    let agents = fresh_reader!(env, |r| r.p2p_list_blocked_agents())?;
+    Ok(agents
+        .into_iter()
+        .map(|agent| AgentPubKey::from_kitsune(&Arc::new(agent)))
+        .collect())
+}
+
+/// Block an agent or node from this space.
+/// Blocking an agent also blocks the nodes it was last seen at.
+pub async fn block(env: EnvWrite, target: BlockTarget) -> ConductorResult<()> {
+    match target {
+        BlockTarget::Agent(agent) => Ok(p2p_block_agent(&env, &agent).await?),
+        BlockTarget::Node(cert) => Ok(p2p_block_node(&env, &cert).await?),
+    }
+}
+
+/// Get every agent and node blocked from this space
+pub fn block_targets(env: EnvWrite) -> ConductorResult<HashSet<BlockTarget>> {
+    Ok(env.conn()?.p2p_list_block_targets()?.into_iter().collect())
+}
+
 fn now() -> u64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -196,6 +232,20 @@ pub fn dump_state(env: EnvRead, cell_id: Option<CellId>) -> StateQueryResult<P2p
     })
 }
 
+/// The peers in a space, with their storage arcs.
+pub fn dump_peers(env: EnvRead) -> StateQueryResult<Vec<PeerDump>> {
+    Ok(all_agent_infos(env)?
+        .into_iter()
+        .map(|info| PeerDump {
+            agent: AgentPubKey::from_kitsune(&info.agent),
+            storage_arc: info.storage_arc,
+            url_list: info.url_list.iter().map(|url| url.to_string()).collect(),
+            signed_at_ms: info.signed_at_ms,
+            expires_at_ms: info.expires_at_ms,
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -223,6 +273,26 @@ mod tests {
         assert_eq!(ret, Some(agent_info_signed));
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_remove_agent_info_signed() {
+        observability::test_run().ok();
+
+        let test_env = test_p2p_agent_store_env();
+        let env = test_env.env();
+
+        let agent_infos = AgentInfoSignedFixturator::new(Unpredictable)
+            .take(2)
+            .collect::<Vec<_>>();
+        inject_agent_infos(env.clone(), agent_infos.iter())
+            .await
+            .unwrap();
+
+        p2p_remove_agent(&env, &agent_infos[0].agent).await.unwrap();
+
+        let agents = all_agent_infos(env.clone().into()).unwrap();
+        assert_eq!(agents, vec![agent_infos[1].clone()]);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn add_agent_info_to_peer_env() {
         observability::test_run().ok();
@@ -4,10 +4,17 @@
 
 use super::error::ConductorResult;
 use holochain_p2p::AgentPubKeyExt;
+use holochain_sqlite::db::AgentMetricsSummary;
 use holochain_sqlite::prelude::*;
 use holochain_types::prelude::*;
 use kitsune_p2p::event::{MetricKind, MetricQuery, MetricQueryAnswer};
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
+
+/// How long p2p metric data is kept for
+pub const METRIC_RETENTION: Duration = Duration::from_secs(60 * 60 * 24 * 7);
+
+/// How often the p2p metric data older than [`METRIC_RETENTION`] is pruned
+pub const METRIC_PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
 
 /// Record a p2p metric datum
 pub async fn put_metric_datum(
@@ -23,6 +30,26 @@ pub async fn put_metric_datum(
     Ok(())
 }
 
+/// Remove the p2p metric data older than [`METRIC_RETENTION`]
+pub async fn prune_metrics(env: EnvWrite) -> ConductorResult<()> {
+    let cutoff = SystemTime::now()
+        .checked_sub(METRIC_RETENTION)
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+    env.async_commit(move |txn| holochain_sqlite::db::prune_metrics(txn, cutoff))
+        .await?;
+    Ok(())
+}
+
+/// Remove the p2p metric data recorded about an agent, such as one which
+/// is no longer running on this conductor
+pub async fn remove_agent_metrics(env: EnvWrite, agent: AgentPubKey) -> ConductorResult<()> {
+    env.async_commit(move |txn| {
+        holochain_sqlite::db::remove_agent_metrics(txn, agent.to_kitsune())
+    })
+    .await?;
+    Ok(())
+}
+
 /// Query the p2p_metrics database in a variety of ways
 pub async fn query_metrics(
     env: EnvWrite,
@@ -33,6 +60,13 @@ pub async fn query_metrics(
         .with_reader(move |mut txn| holochain_sqlite::db::query_metrics(&mut txn, query))?)
 }
 
+/// Add up the metrics recorded about each agent in a space
+pub async fn summarize_metrics(env: EnvWrite) -> ConductorResult<Vec<AgentMetricsSummary>> {
+    Ok(env
+        .conn()?
+        .with_reader(move |mut txn| holochain_sqlite::db::summarize_metrics(&mut txn))?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -203,4 +237,96 @@ mod tests {
             MetricQueryAnswer::Oldest(Some(agent1.to_kitsune()))
         );
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_summarize_metrics() {
+        use MetricKind::*;
+
+        let test_env = test_p2p_metrics_env();
+        let env = test_env.env();
+        let agent1 = fixt!(AgentPubKey);
+        let agent2 = fixt!(AgentPubKey);
+        let ms: Vec<SystemTime> = moments().take(4).collect();
+
+        for (micros, m) in [(100, ms[0]), (300, ms[1])].iter() {
+            put_metric_datum(
+                env.clone(),
+                agent1.clone(),
+                RoundTripLatency { micros: *micros },
+                *m,
+            )
+            .await
+            .unwrap();
+            put_metric_datum(env.clone(), agent1.clone(), BytesSent { bytes: 10 }, *m)
+                .await
+                .unwrap();
+            put_metric_datum(env.clone(), agent1.clone(), BytesReceived { bytes: 20 }, *m)
+                .await
+                .unwrap();
+        }
+        put_metric_datum(env.clone(), agent1.clone(), GossipRoundComplete, ms[2])
+            .await
+            .unwrap();
+        put_metric_datum(env.clone(), agent2.clone(), GossipRoundFailed, ms[2])
+            .await
+            .unwrap();
+        put_metric_datum(env.clone(), agent2.clone(), GossipRoundFailed, ms[3])
+            .await
+            .unwrap();
+
+        let mut summaries = summarize_metrics(env.clone()).await.unwrap();
+        assert_eq!(summaries.len(), 2);
+        summaries.sort_by_key(|s| s.agent != agent1.to_kitsune());
+
+        let s1 = &summaries[0];
+        assert_eq!(s1.requests, 2);
+        assert_eq!(s1.total_round_trip_micros, 400);
+        assert_eq!(s1.bytes_sent, 20);
+        assert_eq!(s1.bytes_received, 40);
+        assert_eq!(s1.gossip_rounds_complete, 1);
+        assert_eq!(s1.gossip_rounds_failed, 0);
+
+        let s2 = &summaries[1];
+        assert_eq!(s2.agent, agent2.to_kitsune());
+        assert_eq!(s2.requests, 0);
+        assert_eq!(s2.gossip_rounds_failed, 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_metrics_are_pruned() {
+        use MetricKind::*;
+
+        let test_env = test_p2p_metrics_env();
+        let env = test_env.env();
+        let agent = fixt!(AgentPubKey);
+        let ms: Vec<SystemTime> = moments().take(2).collect();
+        let expired = ms[0] - METRIC_RETENTION - Duration::from_secs(60);
+
+        put_metric_datum(env.clone(), agent.clone(), GossipRoundComplete, expired)
+            .await
+            .unwrap();
+        put_metric_datum(env.clone(), agent.clone(), GossipRoundComplete, ms[0])
+            .await
+            .unwrap();
+
+        // - Data recorded at the same moment is all kept
+        put_metric_datum(env.clone(), agent.clone(), BytesSent { bytes: 10 }, ms[1])
+            .await
+            .unwrap();
+        put_metric_datum(env.clone(), agent.clone(), BytesSent { bytes: 30 }, ms[1])
+            .await
+            .unwrap();
+
+        // - Expired data is kept until it is pruned
+        let summaries = summarize_metrics(env.clone()).await.unwrap();
+        assert_eq!(summaries[0].gossip_rounds_complete, 2);
+        assert_eq!(summaries[0].bytes_sent, 40);
+
+        // - Only the data within the retention period is left
+        prune_metrics(env.clone()).await.unwrap();
+        let summaries = summarize_metrics(env.clone()).await.unwrap();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].gossip_rounds_complete, 1);
+        assert_eq!(summaries[0].bytes_sent, 40);
+    }
 }
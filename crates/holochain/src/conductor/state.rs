@@ -24,6 +24,9 @@ pub struct ConductorState {
     /// List of interfaces any UI can use to access zome functions.
     #[serde(default)]
     pub(crate) app_interfaces: HashMap<AppInterfaceId, AppInterfaceConfig>,
+    /// The X25519 keys each app has created over its app interface
+    #[serde(default)]
+    app_x25519_keys: HashMap<InstalledAppId, Vec<X25519PubKey>>,
 }
 
 /// A unique identifier used to refer to an App Interface internally.
@@ -124,6 +127,7 @@ impl ConductorState {
 
     /// Getter for a single app. Returns error if app missing.
     pub fn remove_app(&mut self, id: &InstalledAppId) -> ConductorResult<InstalledApp> {
+        self.app_x25519_keys.remove(id);
         self.installed_apps
             .remove(id)
             .ok_or_else(|| ConductorError::AppNotInstalled(id.clone()))
@@ -164,6 +168,19 @@ impl ConductorState {
             .map(InstalledAppInfo::from_installed_app)
     }
 
+    /// Record that an app created an X25519 keypair
+    pub fn add_app_x25519_key(&mut self, id: InstalledAppId, key: X25519PubKey) {
+        self.app_x25519_keys.entry(id).or_default().push(key);
+    }
+
+    /// Whether an app created the X25519 keypair with the given public key
+    pub fn app_owns_x25519_key(&self, id: &str, key: &X25519PubKey) -> bool {
+        self.app_x25519_keys
+            .get(id)
+            .map(|keys| keys.contains(key))
+            .unwrap_or(false)
+    }
+
     /// Returns the interface configuration with the given ID if present
     pub fn interface_by_id(&self, id: &AppInterfaceId) -> Option<AppInterfaceConfig> {
         self.app_interfaces.get(id).cloned()
@@ -185,15 +202,21 @@ pub struct AppInterfaceConfig {
     /// The signal subscription settings for each App
     pub signal_subscriptions: HashMap<InstalledAppId, SignalSubscription>,
 
+    /// The app this interface was attached for, if any.
+    /// Crypto requests are only served for this app.
+    #[serde(default)]
+    pub installed_app_id: Option<InstalledAppId>,
+
     /// The driver for the interface, e.g. Websocket
     pub driver: InterfaceDriver,
 }
 
 impl AppInterfaceConfig {
     /// Create config for a websocket interface
-    pub fn websocket(port: u16) -> Self {
+    pub fn websocket(port: u16, installed_app_id: Option<InstalledAppId>) -> Self {
         Self {
             signal_subscriptions: HashMap::new(),
+            installed_app_id,
             driver: InterfaceDriver::Websocket { port },
         }
     }
@@ -20,6 +20,8 @@
 //! | DhtOpIntegr.   | IntegrationLimbo | IntegratedDhtOps | SysVal + VR    |
 //! | ValReceipt.    | IntegratedDhtOps | IntegratedDhtOps | *n/a           |
 //! | Publish        | AuthoredDhtOps   | *n/a*            | *n/a*          |
+//! |                        **scheduled path**                             |
+//! | Scheduler      | ScheduledFns     | Auth'd + IntQ †  | Publish + Int. |
 //!
 //! († Auth'd + IntQ is short for: AuthoredDhtOps + IntegrationLimbo)
 //!
@@ -28,6 +30,7 @@
 
 use derive_more::Display;
 use futures::future::Either;
+use holochain_sqlite::prelude::DatabaseResult;
 use holochain_types::prelude::*;
 use holochain_zome_types::CellId;
 use tokio::sync;
@@ -52,6 +55,9 @@ use publish_dht_ops_consumer::*;
 mod countersigning_consumer;
 use countersigning_consumer::*;
 
+mod scheduler_consumer;
+use scheduler_consumer::*;
+
 use super::workflow::countersigning_workflow::CountersigningWorkspace;
 use super::workflow::error::WorkflowError;
 
@@ -70,6 +76,7 @@ pub async fn spawn_queue_consumer_tasks(
     task_sender: sync::mpsc::Sender<ManagedTaskAdd>,
     stop: sync::broadcast::Sender<()>,
     countersigning_workspace: CountersigningWorkspace,
+    is_observer: bool,
 ) -> (QueueTriggers, InitialQueueTriggers) {
     let cell_id = cell_network.cell_id();
     // Publish
@@ -131,6 +138,7 @@ pub async fn spawn_queue_consumer_tasks(
         tx_integration.clone(),
         conductor_api.clone(),
         cell_network.clone(),
+        is_observer,
     );
     task_sender
         .send(ManagedTaskAdd::cell_critical(
@@ -149,7 +157,7 @@ pub async fn spawn_queue_consumer_tasks(
         stop.subscribe(),
         tx_app.clone(),
         cell_network.clone(),
-        conductor_api,
+        conductor_api.clone(),
     );
     task_sender
         .send(ManagedTaskAdd::cell_critical(
@@ -176,12 +184,33 @@ pub async fn spawn_queue_consumer_tasks(
         .await
         .expect("Failed to manage workflow handle");
 
+    // Scheduler
+    let (tx_scheduler, handle) = spawn_scheduler_consumer(
+        env.clone(),
+        cache.clone(),
+        conductor_handle.clone(),
+        stop.subscribe(),
+        conductor_api,
+        cell_network.clone(),
+        tx_publish.clone(),
+        tx_integration.clone(),
+    );
+    task_sender
+        .send(ManagedTaskAdd::cell_critical(
+            handle,
+            cell_id.clone(),
+            "scheduler_consumer",
+        ))
+        .await
+        .expect("Failed to manage workflow handle");
+
     (
         QueueTriggers {
             sys_validation: tx_sys.clone(),
             publish_dht_ops: tx_publish.clone(),
             countersigning: tx_cs,
             integrate_dht_ops: tx_integration.clone(),
+            scheduler: tx_scheduler,
         },
         InitialQueueTriggers::new(tx_sys, tx_publish, tx_app, tx_integration, tx_receipt),
     )
@@ -199,6 +228,9 @@ pub struct QueueTriggers {
     pub countersigning: TriggerSender,
     /// Notify the IntegrateDhtOps workflow to run, i.e. after InvokeCallZome
     pub integrate_dht_ops: TriggerSender,
+    /// Notify the Scheduler workflow to run, i.e. after InvokeCallZome
+    /// schedules a function that is due soon
+    pub scheduler: TriggerSender,
 }
 
 /// The triggers to run once at the start of a cell
@@ -239,6 +271,60 @@ impl InitialQueueTriggers {
         self.validation_receipt.trigger();
     }
 }
+
+/// The number of ops waiting in each of a cell's work queues.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QueueDepths {
+    /// Ops waiting for sys validation, or for its dependencies.
+    pub sys_validation: u64,
+    /// Ops waiting for app validation, or for its dependencies.
+    pub app_validation: u64,
+    /// Validated ops waiting to be integrated.
+    pub integration: u64,
+    /// Authored ops which are still being published.
+    pub publish: u64,
+}
+
+impl std::ops::AddAssign for QueueDepths {
+    fn add_assign(&mut self, other: Self) {
+        self.sys_validation += other.sys_validation;
+        self.app_validation += other.app_validation;
+        self.integration += other.integration;
+        self.publish += other.publish;
+    }
+}
+
+/// Count the ops waiting in each of the work queues of a cell's database.
+pub async fn queue_depths(env: &EnvRead) -> DatabaseResult<QueueDepths> {
+    env.async_reader(|txn| {
+        let depths = txn.query_row(
+            "
+            SELECT
+            COUNT(CASE WHEN when_integrated IS NULL AND (
+                (is_authored = 0 AND validation_stage IS NULL) OR validation_stage = 0
+            ) THEN 1 END),
+            COUNT(CASE WHEN when_integrated IS NULL AND validation_stage IN (1, 2) THEN 1 END),
+            COUNT(CASE WHEN when_integrated IS NULL AND validation_stage = 3 THEN 1 END),
+            COUNT(CASE WHEN is_authored = 1 AND withhold_publish IS NULL
+                AND receipts_complete IS NULL THEN 1 END)
+            FROM DhtOp
+            ",
+            [],
+            |row| {
+                let count = |i| row.get::<_, i64>(i).map(|c| c as u64);
+                Ok(QueueDepths {
+                    sys_validation: count(0)?,
+                    app_validation: count(1)?,
+                    integration: count(2)?,
+                    publish: count(3)?,
+                })
+            },
+        )?;
+        DatabaseResult::Ok(depths)
+    })
+    .await
+}
+
 /// The means of nudging a queue consumer to tell it to look for more work
 #[derive(Clone)]
 pub struct TriggerSender(mpsc::Sender<()>);
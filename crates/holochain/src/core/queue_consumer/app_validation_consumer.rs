@@ -26,6 +26,7 @@ pub fn spawn_app_validation_consumer(
     trigger_integration: TriggerSender,
     conductor_api: impl CellConductorApiT + 'static,
     network: HolochainP2pCell,
+    is_observer: bool,
 ) -> (TriggerSender, JoinHandle<ManagedTaskResult>) {
     let (tx, mut rx) = TriggerSender::new();
     let mut trigger_self = tx.clone();
@@ -40,7 +41,7 @@ pub fn spawn_app_validation_consumer(
             }
 
             // Run the workflow
-            let workspace = AppValidationWorkspace::new(env.clone(), cache.clone());
+            let workspace = AppValidationWorkspace::new(env.clone(), cache.clone(), is_observer);
             let result = app_validation_workflow(
                 workspace,
                 trigger_integration.clone(),
@@ -0,0 +1,105 @@
+//! The workflow and queue consumer for running scheduled zome functions
+
+use super::*;
+use crate::conductor::manager::ManagedTaskResult;
+use crate::core::ribosome::real_ribosome::RealRibosome;
+use crate::core::workflow::scheduler_workflow::next_scheduled_fn_due;
+use crate::core::workflow::scheduler_workflow::scheduler_workflow;
+use tokio::task::JoinHandle;
+use tracing::*;
+
+/// Spawn the QueueConsumer for the scheduler workflow
+#[instrument(skip(
+    env,
+    cache,
+    conductor_handle,
+    stop,
+    conductor_api,
+    network,
+    trigger_publish,
+    trigger_integration
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_scheduler_consumer(
+    env: EnvWrite,
+    cache: EnvWrite,
+    conductor_handle: ConductorHandle,
+    mut stop: sync::broadcast::Receiver<()>,
+    conductor_api: impl CellConductorApiT + 'static,
+    network: HolochainP2pCell,
+    trigger_publish: TriggerSender,
+    trigger_integration: TriggerSender,
+) -> (TriggerSender, JoinHandle<ManagedTaskResult>) {
+    let (tx, mut rx) = TriggerSender::new();
+    let mut trigger_self = tx.clone();
+    let handle = tokio::spawn(async move {
+        loop {
+            // Scheduled functions come due with the passage of time, so wake
+            // up when the next one is due even if nothing has triggered us.
+            let next_due = match next_scheduled_fn_due(&env).await {
+                Ok(next_due) => next_due,
+                Err(err) => {
+                    handle_workflow_error(
+                        conductor_handle.clone(),
+                        network.cell_id(),
+                        err,
+                        "scheduler failure",
+                    )
+                    .await?;
+                    continue;
+                }
+            };
+
+            // Wait for next job
+            if let Ok(Job::Shutdown) =
+                tokio::time::timeout(next_due, next_job_or_exit(&mut rx, &mut stop)).await
+            {
+                tracing::warn!(
+                    "Cell is shutting down: stopping scheduler_workflow queue consumer."
+                );
+                break;
+            }
+
+            // Run the workflow
+            let ribosome = match conductor_api.get_this_dna().await {
+                Ok(dna_file) => RealRibosome::new(dna_file),
+                Err(err) => {
+                    handle_workflow_error(
+                        conductor_handle.clone(),
+                        network.cell_id(),
+                        Box::new(err).into(),
+                        "scheduler failure",
+                    )
+                    .await?;
+                    continue;
+                }
+            };
+            let result = scheduler_workflow(
+                env.clone(),
+                cache.clone(),
+                network.clone(),
+                env.keystore(),
+                ribosome,
+                conductor_api.clone(),
+                trigger_publish.clone(),
+                trigger_integration.clone(),
+            )
+            .await;
+            match result {
+                Ok(WorkComplete::Incomplete) => trigger_self.trigger(),
+                Err(err) => {
+                    handle_workflow_error(
+                        conductor_handle.clone(),
+                        network.cell_id(),
+                        err,
+                        "scheduler failure",
+                    )
+                    .await?
+                }
+                _ => (),
+            };
+        }
+        Ok(())
+    });
+    (tx, handle)
+}
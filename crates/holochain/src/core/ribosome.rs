@@ -23,6 +23,8 @@ use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitInvocation;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitResult;
+use crate::core::ribosome::guest_callback::scheduled::ScheduledInvocation;
+use crate::core::ribosome::guest_callback::scheduled::ScheduledResult;
 use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateResult;
 use crate::core::ribosome::guest_callback::validate_link::ValidateLinkHostAccess;
@@ -37,6 +39,7 @@ use guest_callback::entry_defs::EntryDefsHostAccess;
 use guest_callback::init::InitHostAccess;
 use guest_callback::migrate_agent::MigrateAgentHostAccess;
 use guest_callback::post_commit::PostCommitHostAccess;
+use guest_callback::scheduled::ScheduledHostAccess;
 use guest_callback::validate::ValidateHostAccess;
 use guest_callback::validation_package::ValidationPackageHostAccess;
 use holo_hash::AgentPubKey;
@@ -83,6 +86,7 @@ pub enum HostContext {
     Init(InitHostAccess),
     MigrateAgent(MigrateAgentHostAccess),
     PostCommit(PostCommitHostAccess), // TODO: add emit_signal access here?
+    Scheduled(ScheduledHostAccess),
     ValidateCreateLink(ValidateLinkHostAccess),
     Validate(ValidateHostAccess),
     ValidationPackage(ValidationPackageHostAccess),
@@ -101,6 +105,7 @@ impl From<&HostContext> for HostFnAccess {
             HostContext::MigrateAgent(access) => access.into(),
             HostContext::ValidationPackage(access) => access.into(),
             HostContext::PostCommit(access) => access.into(),
+            HostContext::Scheduled(access) => access.into(),
         }
     }
 }
@@ -114,6 +119,7 @@ impl HostContext {
             | Self::MigrateAgent(MigrateAgentHostAccess { workspace, .. })
             | Self::ValidationPackage(ValidationPackageHostAccess { workspace, .. })
             | Self::PostCommit(PostCommitHostAccess { workspace, .. })
+            | Self::Scheduled(ScheduledHostAccess { workspace, .. })
             | Self::Validate(ValidateHostAccess { workspace, .. })
             | Self::ValidateCreateLink(ValidateLinkHostAccess { workspace, .. }) => workspace,
             _ => panic!(
@@ -127,7 +133,8 @@ impl HostContext {
         match self {
             Self::ZomeCall(ZomeCallHostAccess { keystore, .. })
             | Self::Init(InitHostAccess { keystore, .. })
-            | Self::PostCommit(PostCommitHostAccess { keystore, .. }) => keystore,
+            | Self::PostCommit(PostCommitHostAccess { keystore, .. })
+            | Self::Scheduled(ScheduledHostAccess { keystore, .. }) => keystore,
             _ => panic!(
                 "Gave access to a host function that uses the keystore without providing a keystore"
             ),
@@ -140,6 +147,7 @@ impl HostContext {
             Self::ZomeCall(ZomeCallHostAccess { network, .. })
             | Self::Init(InitHostAccess { network, .. })
             | Self::PostCommit(PostCommitHostAccess { network, .. })
+            | Self::Scheduled(ScheduledHostAccess { network, .. })
             | Self::ValidationPackage(ValidationPackageHostAccess { network, .. })
             | Self::Validate(ValidateHostAccess { network, .. })
             | Self::ValidateCreateLink(ValidateLinkHostAccess { network, .. }) => network,
@@ -152,7 +160,8 @@ impl HostContext {
     /// Get the signal broadcaster, panics if none was provided
     pub fn signal_tx(&mut self) -> &mut SignalBroadcaster {
         match self {
-            Self::ZomeCall(ZomeCallHostAccess { signal_tx, .. }) => signal_tx,
+            Self::ZomeCall(ZomeCallHostAccess { signal_tx, .. })
+            | Self::Scheduled(ScheduledHostAccess { signal_tx, .. }) => signal_tx,
             _ => panic!(
                 "Gave access to a host function that uses the signal broadcaster without providing one"
             ),
@@ -162,7 +171,8 @@ impl HostContext {
     /// Get the associated CellId, panics if not applicable
     pub fn cell_id(&self) -> &CellId {
         match self {
-            Self::ZomeCall(ZomeCallHostAccess { cell_id, .. }) => cell_id,
+            Self::ZomeCall(ZomeCallHostAccess { cell_id, .. })
+            | Self::Scheduled(ScheduledHostAccess { cell_id, .. }) => cell_id,
             _ => panic!("Gave access to a host function that references a CellId"),
         }
     }
@@ -172,6 +182,9 @@ impl HostContext {
         match self {
             Self::ZomeCall(ZomeCallHostAccess {
                 call_zome_handle, ..
+            })
+            | Self::Scheduled(ScheduledHostAccess {
+                call_zome_handle, ..
             }) => call_zome_handle,
             _ => panic!(
                 "Gave access to a host function that uses the call zome handle without providing a call zome handle"
@@ -487,6 +500,12 @@ pub trait RibosomeT: Sized + std::fmt::Debug {
         invocation: PostCommitInvocation,
     ) -> RibosomeResult<PostCommitResult>;
 
+    fn run_scheduled(
+        &self,
+        access: ScheduledHostAccess,
+        invocation: ScheduledInvocation,
+    ) -> RibosomeResult<ScheduledResult>;
+
     /// Helper function for running a validation callback. Just calls
     /// [`run_callback`][] under the hood.
     /// [`run_callback`]: #method.run_callback
@@ -3,6 +3,7 @@ pub mod genesis_self_check;
 pub mod init;
 pub mod migrate_agent;
 pub mod post_commit;
+pub mod scheduled;
 pub mod validate;
 pub mod validate_link;
 pub mod validation_package;
@@ -0,0 +1,182 @@
+use crate::conductor::api::CellConductorReadHandle;
+use crate::conductor::interface::SignalBroadcaster;
+use crate::core::ribosome::FnComponents;
+use crate::core::ribosome::HostContext;
+use crate::core::ribosome::Invocation;
+use crate::core::ribosome::ZomesToInvoke;
+use derive_more::Constructor;
+use holochain_keystore::KeystoreSender;
+use holochain_p2p::HolochainP2pCell;
+use holochain_serialized_bytes::prelude::*;
+use holochain_state::host_fn_workspace::HostFnWorkspace;
+use holochain_types::prelude::*;
+
+#[derive(Clone)]
+pub struct ScheduledInvocation {
+    zome: Zome,
+    fn_name: FunctionName,
+}
+
+impl ScheduledInvocation {
+    pub fn new(zome: Zome, fn_name: FunctionName) -> Self {
+        Self { zome, fn_name }
+    }
+}
+
+#[derive(Clone, Constructor)]
+pub struct ScheduledHostAccess {
+    pub workspace: HostFnWorkspace,
+    pub keystore: KeystoreSender,
+    pub network: HolochainP2pCell,
+    pub signal_tx: SignalBroadcaster,
+    pub call_zome_handle: CellConductorReadHandle,
+    pub cell_id: CellId,
+}
+
+impl From<ScheduledHostAccess> for HostContext {
+    fn from(scheduled_host_access: ScheduledHostAccess) -> Self {
+        Self::Scheduled(scheduled_host_access)
+    }
+}
+
+impl From<&ScheduledHostAccess> for HostFnAccess {
+    fn from(_: &ScheduledHostAccess) -> Self {
+        Self::all()
+    }
+}
+
+impl Invocation for ScheduledInvocation {
+    fn zomes(&self) -> ZomesToInvoke {
+        ZomesToInvoke::One(self.zome.to_owned())
+    }
+    fn fn_components(&self) -> FnComponents {
+        vec![self.fn_name.to_string()].into()
+    }
+    fn host_input(self) -> Result<ExternIO, SerializedBytesError> {
+        ExternIO::encode(())
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub enum ScheduledResult {
+    /// The function is finished and can be unscheduled.
+    Done,
+    /// The function should run again after the given delay.
+    Reschedule(core::time::Duration),
+    /// The function failed so it will be unscheduled.
+    Fail(String),
+}
+
+impl From<Vec<(ZomeName, ScheduledCallbackResult)>> for ScheduledResult {
+    fn from(a: Vec<(ZomeName, ScheduledCallbackResult)>) -> Self {
+        a.into_iter().map(|(_, v)| v).collect::<Vec<_>>().into()
+    }
+}
+
+impl From<Vec<ScheduledCallbackResult>> for ScheduledResult {
+    fn from(callback_results: Vec<ScheduledCallbackResult>) -> Self {
+        // a function that doesn't exist has nothing left to do
+        callback_results.into_iter().fold(Self::Done, |acc, x| match x {
+            // fail overrides everything
+            ScheduledCallbackResult::Fail(fail_string) => Self::Fail(fail_string),
+            // reschedule overrides done
+            ScheduledCallbackResult::Reschedule(delay) => match acc {
+                Self::Fail(_) => acc,
+                _ => Self::Reschedule(delay),
+            },
+            // done allows acc to continue
+            ScheduledCallbackResult::Done => acc,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ScheduledResult;
+    use crate::core::ribosome::HostContext;
+    use crate::core::ribosome::Invocation;
+    use crate::core::ribosome::ZomesToInvoke;
+    use crate::fixt::ScheduledHostAccessFixturator;
+    use crate::fixt::ScheduledInvocationFixturator;
+    use ::fixt::prelude::*;
+    use holochain_types::prelude::*;
+    use holochain_zome_types::ExternIO;
+
+    #[test]
+    fn scheduled_callback_result_fold() {
+        let mut rng = ::fixt::rng();
+
+        let delay = core::time::Duration::from_secs(1);
+        let result_done = || ScheduledResult::Done;
+        let result_reschedule = || ScheduledResult::Reschedule(delay);
+        let result_fail = || ScheduledResult::Fail("".into());
+
+        let cb_done = || ScheduledCallbackResult::Done;
+        let cb_reschedule = || ScheduledCallbackResult::Reschedule(delay);
+        let cb_fail = || ScheduledCallbackResult::Fail("".into());
+
+        for (mut results, expected) in vec![
+            (vec![], result_done()),
+            (vec![cb_done()], result_done()),
+            (vec![cb_reschedule()], result_reschedule()),
+            (vec![cb_fail()], result_fail()),
+            (vec![cb_done(), cb_reschedule()], result_reschedule()),
+            (vec![cb_fail(), cb_reschedule(), cb_done()], result_fail()),
+        ] {
+            // order of the results should not change the final result
+            results.shuffle(&mut rng);
+
+            assert_eq!(expected, results.into(),);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn scheduled_invocation_access() {
+        let scheduled_host_access = ScheduledHostAccessFixturator::new(::fixt::Unpredictable)
+            .next()
+            .unwrap();
+        assert_eq!(
+            HostFnAccess::from(&scheduled_host_access),
+            HostFnAccess::all()
+        );
+
+        // - Every handle the granted host fns rely on is available.
+        let cell_id = scheduled_host_access.cell_id.clone();
+        let mut host_context = HostContext::from(scheduled_host_access);
+        assert_eq!(host_context.cell_id(), &cell_id);
+        host_context.signal_tx();
+        host_context.call_zome_handle();
+    }
+
+    #[test]
+    fn scheduled_invocation_zomes() {
+        let scheduled_invocation = ScheduledInvocationFixturator::new(::fixt::Unpredictable)
+            .next()
+            .unwrap();
+        let zome = scheduled_invocation.zome.clone();
+        assert_eq!(ZomesToInvoke::One(zome), scheduled_invocation.zomes(),);
+    }
+
+    #[test]
+    fn scheduled_invocation_fn_components() {
+        let scheduled_invocation = ScheduledInvocationFixturator::new(::fixt::Unpredictable)
+            .next()
+            .unwrap();
+
+        let mut expected = vec![scheduled_invocation.fn_name.to_string()];
+        for fn_component in scheduled_invocation.fn_components() {
+            assert_eq!(fn_component, expected.pop().unwrap());
+        }
+    }
+
+    #[test]
+    fn scheduled_invocation_host_input() {
+        let scheduled_invocation = ScheduledInvocationFixturator::new(::fixt::Empty)
+            .next()
+            .unwrap();
+
+        let host_input = scheduled_invocation.clone().host_input().unwrap();
+
+        assert_eq!(host_input, ExternIO::encode(()).unwrap());
+    }
+}
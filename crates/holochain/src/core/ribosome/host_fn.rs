@@ -64,8 +64,8 @@ host_fn_api_impls! {
     // Call a zome on a remote agent.
     fn call_remote (Vec<zt::call_remote::CallRemote>) -> Vec<zt::ZomeCallResponse>;
 
-    // @todo List all the local capability claims.
-    fn capability_claims (()) -> ();
+    // List the local capability claims that match the query.
+    fn capability_claims (zt::capability::CapClaimQuery) -> Vec<zt::capability::CapClaim>;
 
     // @todo List all the local capability grants.
     fn capability_grants (()) -> ();
@@ -151,8 +151,8 @@ host_fn_api_impls! {
     // // @todo
     // fn send (()) -> ();
 
-    // @todo
-    fn schedule (core::time::Duration) -> ();
+    // Schedule a function in this zome to run after a delay.
+    fn schedule (zt::schedule::ScheduleInput) -> ();
 
     // @todo
     fn sleep (core::time::Duration) -> ();
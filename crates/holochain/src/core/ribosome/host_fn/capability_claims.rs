@@ -1,13 +1,99 @@
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
+use holochain_types::prelude::*;
 use std::sync::Arc;
 use holochain_wasmer_host::prelude::WasmError;
+use crate::core::ribosome::HostFnAccess;
 
-/// lists all the local claims filtered by tag
+/// lists all the local claims filtered by tag, grantor and secret
 pub fn capability_claims(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
-    _input: (),
-) -> Result<(), WasmError> {
-    unimplemented!();
+    call_context: Arc<CallContext>,
+    input: CapClaimQuery,
+) -> Result<Vec<CapClaim>, WasmError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess{ read_workspace: Permission::Allow, .. } => {
+            tokio_helper::block_forever_on(async move {
+                call_context
+                    .host_context
+                    .workspace()
+                    .source_chain()
+                    .capability_claims(input)
+                    .await
+                    .map_err(|source_chain_error| WasmError::Host(source_chain_error.to_string()))
+            })
+        },
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use crate::sweettest::SweetAgents;
+    use crate::sweettest::SweetConductor;
+    use crate::sweettest::SweetDnaFile;
+    use hdk::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ribosome_capability_claims() {
+        observability::test_run().ok();
+        let (dna_file, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Capability])
+            .await
+            .unwrap();
+        let mut conductor = SweetConductor::from_standard_config().await;
+        let (alice_pubkey, bob_pubkey) = SweetAgents::two(conductor.keystore()).await;
+        let apps = conductor
+            .setup_app_for_agents("app-", &[alice_pubkey.clone(), bob_pubkey.clone()], &[dna_file])
+            .await
+            .unwrap();
+        let ((alice,), (bobbo,)) = apps.into_tuples();
+        let alice = alice.zome(TestWasm::Capability);
+        let bobbo = bobbo.zome(TestWasm::Capability);
+
+        // Alice sends bob a claim so bob has one claim from alice.
+        let _: () = conductor
+            .call(&alice, "send_assigned_cap_claim", bob_pubkey.clone())
+            .await;
+
+        let claims: Vec<CapClaim> = conductor
+            .call(&bobbo, "capability_claims", CapClaimQuery::new())
+            .await;
+        assert_eq!(claims.len(), 1);
+        assert_eq!(claims[0].grantor(), &alice_pubkey);
+        assert_eq!(claims[0].tag(), "has_cap_claim");
+
+        let claims: Vec<CapClaim> = conductor
+            .call(
+                &bobbo,
+                "capability_claims",
+                CapClaimQuery::new()
+                    .grantor(alice_pubkey.clone())
+                    .tag("has_cap_claim".into())
+                    .secret(*claims[0].secret()),
+            )
+            .await;
+        assert_eq!(claims.len(), 1);
+
+        let claims: Vec<CapClaim> = conductor
+            .call(&bobbo, "capability_claims", CapClaimQuery::new().grantor(bob_pubkey))
+            .await;
+        assert!(claims.is_empty());
+
+        let claims: Vec<CapClaim> = conductor
+            .call(
+                &bobbo,
+                "capability_claims",
+                CapClaimQuery::new().tag("not_a_tag".into()),
+            )
+            .await;
+        assert!(claims.is_empty());
+
+        // Alice has no claims of her own.
+        let claims: Vec<CapClaim> = conductor
+            .call(&alice, "capability_claims", CapClaimQuery::new())
+            .await;
+        assert!(claims.is_empty());
+    }
 }
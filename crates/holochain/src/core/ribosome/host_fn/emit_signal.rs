@@ -14,7 +14,7 @@ pub fn emit_signal(
     match HostFnAccess::from(&call_context.host_context()) {
         HostFnAccess{ write_workspace: Permission::Allow, .. } => {
             let cell_id = call_context.host_context().cell_id().clone();
-            let signal = Signal::App(cell_id, input);
+            let signal = Signal::App(cell_id, call_context.zome.zome_name().clone(), input);
             call_context.host_context().signal_tx().send(signal).map_err(|interface_error| WasmError::Host(interface_error.to_string()))?;
             Ok(())
         },
@@ -43,7 +43,7 @@ pub fn must_get_entry<'a>(
                     .map_err(|cascade_error| WasmError::Host(cascade_error.to_string()))? {
                         Some(entry) => Ok(entry),
                         None => match call_context.host_context {
-                            HostContext::EntryDefs(_) | HostContext::GenesisSelfCheck(_) | HostContext::MigrateAgent(_) | HostContext::PostCommit(_) | HostContext::ZomeCall(_) => Err(WasmError::Host(format!("Failed to get EntryHashed {}", entry_hash))),
+                            HostContext::EntryDefs(_) | HostContext::GenesisSelfCheck(_) | HostContext::MigrateAgent(_) | HostContext::PostCommit(_) | HostContext::Scheduled(_) | HostContext::ZomeCall(_) => Err(WasmError::Host(format!("Failed to get EntryHashed {}", entry_hash))),
                             HostContext::Init(_) => RuntimeError::raise(Box::new(WasmError::HostShortCircuit(
                                 holochain_serialized_bytes::encode(
                                     &ExternIO::encode(InitCallbackResult::UnresolvedDependencies(vec![entry_hash.into()]))?
@@ -44,7 +44,7 @@ pub fn must_get_header<'a>(
                     .map_err(|cascade_error| WasmError::Host(cascade_error.to_string()))? {
                         Some(header) => Ok(header),
                         None => match call_context.host_context {
-                            HostContext::EntryDefs(_) | HostContext::GenesisSelfCheck(_) | HostContext::MigrateAgent(_) | HostContext::PostCommit(_) | HostContext::ZomeCall(_) => Err(WasmError::Host(format!("Failed to get SignedHeaderHashed {}", header_hash))),
+                            HostContext::EntryDefs(_) | HostContext::GenesisSelfCheck(_) | HostContext::MigrateAgent(_) | HostContext::PostCommit(_) | HostContext::Scheduled(_) | HostContext::ZomeCall(_) => Err(WasmError::Host(format!("Failed to get SignedHeaderHashed {}", header_hash))),
                             HostContext::Init(_) => RuntimeError::raise(
                                 Box::new(
                                     WasmError::HostShortCircuit(
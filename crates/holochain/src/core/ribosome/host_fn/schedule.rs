@@ -2,11 +2,54 @@ use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
 use std::sync::Arc;
 use holochain_wasmer_host::prelude::WasmError;
+use crate::core::ribosome::HostFnAccess;
+use holochain_types::prelude::*;
 
 pub fn schedule(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
-    _input: core::time::Duration,
+    call_context: Arc<CallContext>,
+    input: ScheduleInput,
 ) -> Result<(), WasmError> {
-    unimplemented!()
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess{ write_workspace: Permission::Allow, .. } => {
+            let ScheduleInput { scheduled_fn, execute_after } = input;
+            let start_at = (holochain_types::timestamp::now() + execute_after)
+                .map_err(|e| WasmError::Host(e.to_string()))?;
+            let scheduled_fn = ScheduledFn::new(call_context.zome.zome_name().clone(), scheduled_fn);
+            call_context
+                .host_context
+                .workspace()
+                .source_chain()
+                .schedule_fn(scheduled_fn, start_at)
+                .map_err(|source_chain_error| WasmError::Host(source_chain_error.to_string()))?;
+            Ok(())
+        },
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use crate::fixt::ZomeCallHostAccessFixturator;
+    use ::fixt::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ribosome_schedule_test() {
+        let host_access = fixt!(ZomeCallHostAccess, Predictable);
+        let _: () =
+            crate::call_test_ribosome!(host_access, TestWasm::Schedule, "schedule", ()).unwrap();
+
+        // The schedule is staged in the scratch until the zome call is flushed.
+        let mut scratch = host_access.workspace.source_chain().snapshot().unwrap();
+        let scheduled: Vec<_> = scratch.drain_scheduled_fns().map(|(f, _)| f).collect();
+        assert_eq!(
+            scheduled,
+            vec![holochain_zome_types::ScheduledFn::new(
+                TestWasm::Schedule.into(),
+                "scheduled_tick".into()
+            )],
+        );
+    }
 }
@@ -2,11 +2,83 @@ use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
 use std::sync::Arc;
 use holochain_wasmer_host::prelude::WasmError;
+use crate::core::ribosome::HostFnAccess;
+use holochain_types::prelude::*;
 
 pub fn sleep(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
-    _input: core::time::Duration,
+    call_context: Arc<CallContext>,
+    input: core::time::Duration,
 ) -> Result<(), WasmError> {
-    unimplemented!()
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess{ non_determinism: Permission::Allow, .. } => {
+            // The deadline is set by the outermost zome call and shared by the
+            // calls it makes, so nested calls can't sleep past it either.
+            // Refuse up front rather than holding the call open only to time out.
+            let deadline = call_context.host_context().workspace().deadline();
+            let wake_at = std::time::Instant::now()
+                .checked_add(input)
+                .filter(|wake_at| deadline.map_or(true, |deadline| *wake_at <= deadline));
+            match wake_at {
+                // Host functions are synchronous so this blocks the calling
+                // thread, which is why the sleep is bounded by the deadline.
+                Some(wake_at) => {
+                    tokio_helper::block_forever_on(tokio::time::sleep_until(wake_at.into()));
+                    Ok(())
+                },
+                None => Err(WasmError::Host(format!(
+                    "Sleeping for {:?} would exceed the zome call timeout",
+                    input
+                ))),
+            }
+        },
+        // Sleeping would make the outcome of deterministic callbacks
+        // such as validation depend on timing.
+        _ => Err(WasmError::Host(
+            "sleep is not allowed in deterministic callbacks".to_string()
+        )),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use crate::fixt::ZomeCallHostAccessFixturator;
+    use ::fixt::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn invoke_import_sleep_test() {
+        let host_access = fixt!(ZomeCallHostAccess, Predictable);
+        let started_at = std::time::Instant::now();
+        let _: () = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::SysTime,
+            "sleep",
+            core::time::Duration::from_millis(100)
+        )
+        .unwrap();
+        assert!(started_at.elapsed() >= core::time::Duration::from_millis(100));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn sleep_past_zome_call_timeout_test() {
+        let timeout = core::time::Duration::from_secs(10);
+        let mut host_access = fixt!(ZomeCallHostAccess, Predictable);
+        // The deadline of the workspace is the one set by the root zome call,
+        // so it holds however deeply the sleeping call is nested.
+        host_access.workspace = host_access
+            .workspace
+            .with_deadline(std::time::Instant::now() + timeout);
+        let started_at = std::time::Instant::now();
+        let result: Result<(), _> = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::SysTime,
+            "sleep",
+            timeout * 2
+        );
+        assert!(result.is_err());
+        // The call fails immediately instead of sleeping until the timeout.
+        assert!(started_at.elapsed() < timeout);
+    }
 }
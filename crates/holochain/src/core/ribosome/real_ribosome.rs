@@ -21,6 +21,9 @@ use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitInvocation;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitResult;
+use crate::core::ribosome::guest_callback::scheduled::ScheduledHostAccess;
+use crate::core::ribosome::guest_callback::scheduled::ScheduledInvocation;
+use crate::core::ribosome::guest_callback::scheduled::ScheduledResult;
 use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateResult;
 use crate::core::ribosome::guest_callback::validate_link::ValidateLinkHostAccess;
@@ -229,6 +232,20 @@ impl RealRibosome {
         )?)
     }
 
+    /// Whether this DNA has the zome and the zome defines the function.
+    pub fn zome_fn_exists(&self, zome_name: &ZomeName, fn_name: &FunctionName) -> bool {
+        match self.dna_file.dna().get_zome(zome_name) {
+            Ok(zome) => match zome.zome_def() {
+                ZomeDef::Wasm(_) => self
+                    .module(zome_name)
+                    .map(|module| module.info().exports.contains_key(fn_name.as_ref()))
+                    .unwrap_or(false),
+                ZomeDef::Inline(zome) => zome.has_callback(fn_name),
+            },
+            Err(_) => false,
+        }
+    }
+
     pub fn wasm_cache_key(&self, zome_name: &ZomeName) -> Result<[u8; 32], DnaError> {
         // TODO: make this actually the hash of the wasm once we can do that
         // watch out for cache misses in the tests that make things slooow if you change this!
@@ -487,10 +504,7 @@ impl RibosomeT for RealRibosome {
         zome: &Zome,
         to_call: &FunctionName,
     ) -> Result<Option<ExternIO>, RibosomeError> {
-        let call_context = CallContext {
-            zome: zome.clone(),
-            host_context,
-        };
+        let call_context = CallContext::new(zome.clone(), host_context);
 
         match zome.zome_def() {
             ZomeDef::Wasm(_) => {
@@ -631,6 +645,14 @@ impl RibosomeT for RealRibosome {
     ) -> RibosomeResult<PostCommitResult> {
         do_callback!(self, access, invocation, PostCommitCallbackResult)
     }
+
+    fn run_scheduled(
+        &self,
+        access: ScheduledHostAccess,
+        invocation: ScheduledInvocation,
+    ) -> RibosomeResult<ScheduledResult> {
+        do_callback!(self, access, invocation, ScheduledCallbackResult)
+    }
 }
 
 #[cfg(test)]
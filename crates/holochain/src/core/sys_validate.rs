@@ -45,10 +45,29 @@ pub async fn verify_header_signature(sig: &Signature, header: &Header) -> SysVal
 }
 
 /// Verify the author key was valid at the time
-/// of signing with dpki
-/// TODO: This is just a stub until we have dpki.
-pub async fn author_key_is_valid(_author: &AgentPubKey) -> SysValidationResult<()> {
-    Ok(())
+/// of signing according to the conductor's DPKI key registry.
+/// If the registry can't answer, the op can be validated again later.
+pub async fn author_key_is_valid(
+    author: &AgentPubKey,
+    timestamp: Timestamp,
+    conductor_api: &impl CellConductorApiT,
+) -> SysValidationResult<()> {
+    let key_state = match conductor_api.key_state(author.clone(), timestamp).await {
+        Ok(key_state) => key_state,
+        Err(e) => {
+            tracing::warn!(?e, %author, "Failed to get the author key state from the key registry");
+            return Err(SysValidationError::ValidationOutcome(
+                ValidationOutcome::AuthorKeyStateUnknown(author.clone()),
+            ));
+        }
+    };
+    if key_state.is_valid() {
+        Ok(())
+    } else {
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::AuthorKeyInvalid(author.clone(), key_state),
+        ))
+    }
 }
 
 /// Verify the countersigning session contains the specified header.
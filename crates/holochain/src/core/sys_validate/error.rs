@@ -95,8 +95,14 @@ impl<E> TryFrom<OutcomeOrError<ValidationOutcome, E>> for ValidationOutcome {
 /// failed validation.
 #[derive(Error, Debug)]
 pub enum ValidationOutcome {
+    #[error("The author {0} key was not valid at the time of signing: {1:?}")]
+    AuthorKeyInvalid(AgentPubKey, KeyState),
     #[error("The element with signature {0:?} and header {1:?} was found to be counterfeit")]
     Counterfeit(Signature, Header),
+    #[error(
+        "The key registry could not say if the author {0} key was valid at the time of signing"
+    )]
+    AuthorKeyStateUnknown(AgentPubKey),
     #[error("The header {1:?} is not found in the countersigning session data {0:?}")]
     HeaderNotInCounterSigningSession(CounterSigningSessionData, NewEntryHeader),
     #[error(transparent)]
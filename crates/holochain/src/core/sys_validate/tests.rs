@@ -1,6 +1,7 @@
 use super::*;
 use crate::conductor::api::error::ConductorApiError;
 use crate::conductor::api::MockCellConductorApi;
+use crate::conductor::dpki::DpkiError;
 use crate::test_utils::fake_genesis;
 use ::fixt::prelude::*;
 use error::SysValidationError;
@@ -38,6 +39,53 @@ async fn verify_header_signature_test() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn author_key_is_valid_test() {
+    let author = fake_agent_pubkey_1();
+    let revoked_at = Timestamp(100, 0);
+    let mut conductor_api = MockCellConductorApi::new();
+    conductor_api
+        .expect_sync_key_state()
+        .returning(move |_, at| {
+            Ok(if at < revoked_at {
+                KeyState::Valid
+            } else {
+                KeyState::Invalidated {
+                    timestamp: revoked_at,
+                }
+            })
+        });
+
+    let before = Timestamp(99, 0);
+    assert_matches!(
+        author_key_is_valid(&author, before, &conductor_api).await,
+        Ok(())
+    );
+    assert_matches!(
+        author_key_is_valid(&author, revoked_at, &conductor_api).await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::AuthorKeyInvalid(_, KeyState::Invalidated { .. })
+        ))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn author_key_state_unknown_when_registry_fails() {
+    let author = fake_agent_pubkey_1();
+    let mut conductor_api = MockCellConductorApi::new();
+    conductor_api
+        .expect_sync_key_state()
+        .returning(|_, _| Err(ConductorApiError::DpkiError(DpkiError::ConductorMissing)));
+
+    // A registry error is an outcome to retry, not an error that stops the workflow
+    assert_matches!(
+        author_key_is_valid(&author, Timestamp(1, 0), &conductor_api).await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::AuthorKeyStateUnknown(_)
+        ))
+    );
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn check_previous_header() {
     let mut header = fixt!(CreateLink);
@@ -30,7 +30,9 @@ pub mod genesis_workflow;
 pub mod incoming_dht_ops_workflow;
 pub mod initialize_zomes_workflow;
 pub mod integrate_dht_ops_workflow;
+pub mod migrate_agent_workflow;
 pub mod publish_dht_ops_workflow;
+pub mod scheduler_workflow;
 pub mod sys_validation_workflow;
 pub mod validation_receipt_workflow;
 
@@ -92,6 +92,14 @@ async fn app_validation_workflow_inner(
             );
         }
 
+        conductor_api.metrics().record_validation_outcome(
+            "app",
+            match outcome {
+                Outcome::Accepted => "accepted",
+                Outcome::AwaitingDeps(_) => "awaiting_deps",
+                Outcome::Rejected(_) => "rejected",
+            },
+        );
         match outcome {
             Outcome::Accepted => {
                 workspace
@@ -815,11 +823,17 @@ pub fn run_link_validation_callback<I: Invocation + 'static>(
 pub struct AppValidationWorkspace {
     vault: EnvWrite,
     cache: EnvWrite,
+    /// Observer cells validate the ops they hold without a chain of their own
+    is_observer: bool,
 }
 
 impl AppValidationWorkspace {
-    pub fn new(vault: EnvWrite, cache: EnvWrite) -> Self {
-        Self { vault, cache }
+    pub fn new(vault: EnvWrite, cache: EnvWrite, is_observer: bool) -> Self {
+        Self {
+            vault,
+            cache,
+            is_observer,
+        }
     }
 
     pub async fn put_validation_limbo(
@@ -855,7 +869,15 @@ impl AppValidationWorkspace {
         &self,
         author: AgentPubKey,
     ) -> AppValidationResult<HostFnWorkspace> {
-        Ok(HostFnWorkspace::new(self.vault.clone(), self.cache.clone(), author).await?)
+        if self.is_observer {
+            Ok(HostFnWorkspace::observer(
+                self.vault.clone(),
+                self.cache.clone(),
+                author,
+            ))
+        } else {
+            Ok(HostFnWorkspace::new(self.vault.clone(), self.cache.clone(), author).await?)
+        }
     }
 
     pub fn full_cascade<Network: HolochainP2pCellT + Clone + 'static + Send>(
@@ -876,6 +898,7 @@ impl From<&HostFnWorkspace> for AppValidationWorkspace {
         Self {
             vault: vault.into(),
             cache,
+            is_observer: h.source_chain().is_observer(),
         }
     }
 }
@@ -18,6 +18,7 @@ use holochain_sqlite::prelude::*;
 use holochain_state::source_chain;
 use holochain_state::workspace::WorkspaceResult;
 use holochain_types::prelude::*;
+use holochain_types::timestamp;
 use rusqlite::named_params;
 use tracing::*;
 
@@ -81,14 +82,21 @@ where
         return Err(WorkflowError::GenesisFailure(reason));
     }
 
-    // TODO: this is a placeholder for a real DPKI request to show intent
-    if api
-        .dpki_request("is_agent_pubkey_valid".into(), agent_pubkey.to_string())
-        .await
-        .expect("TODO: actually implement this")
-        == "INVALID"
-    {
-        return Err(WorkflowError::AgentInvalid(agent_pubkey.clone()));
+    // Don't create a source chain for a key which has been replaced or revoked.
+    // If the key registry can't answer yet, the key is checked again
+    // when the authorities validate our ops.
+    match api.key_state(agent_pubkey.clone(), timestamp::now()).await {
+        Ok(key_state) if !key_state.is_valid() => {
+            return Err(WorkflowError::AgentInvalid(agent_pubkey.clone()));
+        }
+        Ok(_) => (),
+        Err(e) => {
+            tracing::warn!(
+                ?e,
+                agent = %agent_pubkey,
+                "Failed to get the agent key state from the key registry, running genesis anyway"
+            );
+        }
     }
 
     source_chain::genesis(
@@ -162,8 +170,8 @@ pub mod tests {
         {
             let workspace = GenesisWorkspace::new(vault.clone().into()).unwrap();
             let mut api = MockCellConductorApi::new();
-            api.expect_sync_dpki_request()
-                .returning(|_, _| Ok("mocked dpki request response".to_string()));
+            api.expect_sync_key_state()
+                .returning(|_, _| Ok(KeyState::NotFound));
             let mut ribosome = MockRibosomeT::new();
             ribosome
                 .expect_run_genesis_self_check()
@@ -231,7 +239,7 @@ Data X (data & structure) from Store Y:
 
 Functions / Workflows:
 
-- check that agent key is valid [MOCKED dpki] (via real dpki [LEAPFROG])
+- check that agent key is valid (via the DPKI key registry)
 
 - retrieve DNA from file path [in the future from HCHC]
 
@@ -0,0 +1,182 @@
+//! Migrate Agent Workflow: move an agent's source chains onto new chains.
+//! Each old chain is closed with a [`CloseChain`] header and its replacement,
+//! which must already have been through genesis, is opened with an
+//! [`OpenChain`] header.
+//!
+//! [`CloseChain`]: holochain_zome_types::header::CloseChain
+//! [`OpenChain`]: holochain_zome_types::header::OpenChain
+
+use super::error::WorkflowResult;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentHostAccess;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
+use crate::core::ribosome::RibosomeT;
+use holochain_state::host_fn_workspace::HostFnWorkspace;
+use holochain_state::mutations::delete_authored_header;
+use holochain_types::prelude::*;
+use holochain_zome_types::header::builder;
+use tracing::*;
+
+/// A source chain taking part in a migration, along with its DNA
+pub struct MigratingChain<Ribosome>
+where
+    Ribosome: RibosomeT + Send + 'static,
+{
+    /// The DNA of the chain
+    pub dna_file: DnaFile,
+    /// The ribosome to run the chain's `migrate_agent` callbacks with
+    pub ribosome: Ribosome,
+    /// The chain itself
+    pub workspace: HostFnWorkspace,
+    /// The database holding the chain
+    pub vault: EnvWrite,
+}
+
+/// A source chain to migrate, along with the chain replacing it
+pub struct MigrateChain<Ribosome>
+where
+    Ribosome: RibosomeT + Send + 'static,
+{
+    /// The chain being closed
+    pub old_chain: MigratingChain<Ribosome>,
+    /// The chain being opened
+    pub new_chain: MigratingChain<Ribosome>,
+}
+
+/// A chain closed by a migration, which can be reopened
+/// if the rest of the migration fails.
+pub struct ClosedChain {
+    vault: EnvWrite,
+    close_hash: HeaderHash,
+}
+
+impl ClosedChain {
+    /// Remove the [`CloseChain`] header so the chain carries on as before.
+    /// Only valid while the chain's cell is not running,
+    /// as the header must not have been published.
+    ///
+    /// [`CloseChain`]: holochain_zome_types::header::CloseChain
+    pub async fn reopen(self) -> WorkflowResult<()> {
+        let close_hash = self.close_hash;
+        self.vault
+            .async_commit(move |txn| delete_authored_header(txn, &close_hash))
+            .await?;
+        Ok(())
+    }
+}
+
+/// How a migration went
+pub enum MigrateAgentOutcome {
+    /// Every chain was migrated.
+    /// The old chains are closed but can still be reopened.
+    Migrated(Vec<ClosedChain>),
+    /// A `migrate_agent` callback refused the migration,
+    /// so none of the chains were touched.
+    /// Holds the first zome that refused and its reason.
+    Refused(ZomeName, String),
+}
+
+#[instrument(skip(chains))]
+pub async fn migrate_agent_workflow<Ribosome>(
+    chains: Vec<MigrateChain<Ribosome>>,
+) -> WorkflowResult<MigrateAgentOutcome>
+where
+    Ribosome: RibosomeT + Send + 'static,
+{
+    // The callbacks can only read their chains, so run all of them before
+    // writing anything. That way a failure leaves every chain untouched.
+    let (chains, result) = tokio::task::spawn_blocking(move || {
+        let result = run_migrate_agent_callbacks(&chains);
+        (chains, result)
+    })
+    .await?;
+    if let MigrateAgentResult::Fail(zome_name, reason) = result? {
+        return Ok(MigrateAgentOutcome::Refused(zome_name, reason));
+    }
+
+    let (old_chains, new_chains): (Vec<_>, Vec<_>) = chains
+        .into_iter()
+        .map(|chain| (chain.old_chain, chain.new_chain))
+        .unzip();
+
+    // Each chain points at the DNA and agent of the other.
+    let mut close_hashes = Vec::with_capacity(old_chains.len());
+    for (old_chain, new_chain) in old_chains.iter().zip(&new_chains) {
+        let old_source_chain = old_chain.workspace.source_chain();
+        let new_source_chain = new_chain.workspace.source_chain();
+        close_hashes.push(
+            old_source_chain
+                .put(
+                    builder::CloseChain::new(
+                        new_chain.dna_file.dna_hash().clone(),
+                        new_source_chain.agent_pubkey().clone(),
+                    ),
+                    None,
+                    ChainTopOrdering::Strict,
+                )
+                .await?,
+        );
+        new_source_chain
+            .put(
+                builder::OpenChain::new(
+                    old_chain.dna_file.dna_hash().clone(),
+                    old_source_chain.agent_pubkey().clone(),
+                ),
+                None,
+                ChainTopOrdering::Strict,
+            )
+            .await?;
+    }
+
+    // The caller drops the new chains if the migration fails,
+    // so they are safe to write first.
+    for new_chain in new_chains {
+        new_chain.workspace.flush().await?;
+    }
+    let mut closed = Vec::with_capacity(old_chains.len());
+    for (old_chain, close_hash) in old_chains.into_iter().zip(close_hashes) {
+        if let Err(e) = old_chain.workspace.clone().flush().await {
+            // Reopen the chains which were already closed.
+            reopen_chains(closed).await;
+            return Err(e.into());
+        }
+        closed.push(ClosedChain {
+            vault: old_chain.vault,
+            close_hash,
+        });
+    }
+    Ok(MigrateAgentOutcome::Migrated(closed))
+}
+
+/// Reopen chains closed by a migration which failed later on,
+/// logging any which can't be reopened.
+pub async fn reopen_chains(closed: Vec<ClosedChain>) {
+    for closed_chain in closed {
+        if let Err(error) = closed_chain.reopen().await {
+            error!(?error, "Failed to reopen a chain after a failed migration");
+        }
+    }
+}
+
+fn run_migrate_agent_callbacks<Ribosome>(
+    chains: &[MigrateChain<Ribosome>],
+) -> WorkflowResult<MigrateAgentResult>
+where
+    Ribosome: RibosomeT + Send + 'static,
+{
+    for chain in chains {
+        for (side, migrate_agent) in vec![
+            (&chain.old_chain, MigrateAgent::Close),
+            (&chain.new_chain, MigrateAgent::Open),
+        ] {
+            let result = side.ribosome.run_migrate_agent(
+                MigrateAgentHostAccess::new(side.workspace.clone()),
+                MigrateAgentInvocation::new(side.dna_file.dna_def().clone(), migrate_agent),
+            )?;
+            if let MigrateAgentResult::Fail(..) = result {
+                return Ok(result);
+            }
+        }
+    }
+    Ok(MigrateAgentResult::Pass)
+}
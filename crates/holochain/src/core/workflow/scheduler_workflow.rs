@@ -0,0 +1,226 @@
+//! Runs the zome functions scheduled with the `schedule` host fn once they come due.
+
+use super::error::WorkflowResult;
+use super::inline_validation;
+use crate::conductor::api::CellConductorApiT;
+use crate::core::queue_consumer::TriggerSender;
+use crate::core::queue_consumer::WorkComplete;
+use crate::core::ribosome::error::RibosomeError;
+use crate::core::ribosome::guest_callback::scheduled::ScheduledHostAccess;
+use crate::core::ribosome::guest_callback::scheduled::ScheduledInvocation;
+use crate::core::ribosome::guest_callback::scheduled::ScheduledResult;
+use crate::core::ribosome::RibosomeT;
+use holochain_keystore::KeystoreSender;
+use holochain_p2p::HolochainP2pCell;
+use holochain_state::host_fn_workspace::HostFnWorkspace;
+use holochain_state::prelude::*;
+use holochain_state::schedule::due_scheduled_fns;
+use holochain_state::schedule::next_scheduled_fn_start_ms;
+use holochain_types::prelude::*;
+use holochain_types::timestamp;
+use std::time::Duration;
+use tracing::*;
+
+/// The longest the scheduler will sleep before checking for due functions.
+pub const SCHEDULER_MAX_INTERVAL: Duration = Duration::from_secs(60);
+
+#[instrument(skip(
+    env,
+    cache,
+    network,
+    keystore,
+    ribosome,
+    conductor_api,
+    trigger_publish_dht_ops,
+    trigger_integrate_dht_ops
+))]
+#[allow(clippy::too_many_arguments)]
+pub async fn scheduler_workflow<Ribosome, C>(
+    env: EnvWrite,
+    cache: EnvWrite,
+    network: HolochainP2pCell,
+    keystore: KeystoreSender,
+    ribosome: Ribosome,
+    conductor_api: C,
+    mut trigger_publish_dht_ops: TriggerSender,
+    mut trigger_integrate_dht_ops: TriggerSender,
+) -> WorkflowResult<WorkComplete>
+where
+    Ribosome: RibosomeT + Clone + Send + 'static,
+    C: CellConductorApiT,
+{
+    let now = timestamp::now();
+    let due = env
+        .async_reader(move |txn| due_scheduled_fns(&txn, now))
+        .await?;
+
+    let mut wrote_headers = false;
+    for scheduled_fn in due {
+        let author = conductor_api.cell_id().agent_pubkey().clone();
+        let workspace = HostFnWorkspace::new(env.clone(), cache.clone(), author)
+            .await?
+            .with_deadline(std::time::Instant::now() + conductor_api.zome_call_timeout());
+        let result = run_scheduled_fn(
+            workspace.clone(),
+            network.clone(),
+            keystore.clone(),
+            ribosome.clone(),
+            conductor_api.clone(),
+            scheduled_fn.clone(),
+        )
+        .await;
+
+        match result {
+            Ok(ScheduledResult::Reschedule(delay)) => match timestamp::now() + delay {
+                Ok(start_at) => workspace
+                    .source_chain()
+                    .schedule_fn(scheduled_fn.clone(), start_at)?,
+                Err(error) => {
+                    error!(?scheduled_fn, ?error, "Could not reschedule function");
+                    unschedule(&env, scheduled_fn.clone()).await?;
+                }
+            },
+            // Remove the old schedule before flushing in case
+            // the function scheduled itself again.
+            Ok(ScheduledResult::Done) => unschedule(&env, scheduled_fn.clone()).await?,
+            // A function that fails or errors is never run again, otherwise a
+            // broken function would run forever. Nothing it wrote is committed.
+            Ok(ScheduledResult::Fail(reason)) => {
+                warn!(?scheduled_fn, %reason, "Scheduled function failed");
+                unschedule(&env, scheduled_fn).await?;
+                continue;
+            }
+            Err(error) => {
+                error!(?scheduled_fn, ?error, "Scheduled function errored");
+                unschedule(&env, scheduled_fn).await?;
+                continue;
+            }
+        }
+
+        let is_empty = workspace.source_chain().is_empty()?;
+        if let Err(error) = workspace.flush().await {
+            error!(?scheduled_fn, ?error, "Failed to commit scheduled function");
+            unschedule(&env, scheduled_fn).await?;
+            continue;
+        }
+        wrote_headers |= !is_empty;
+    }
+
+    // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
+
+    if wrote_headers {
+        trigger_publish_dht_ops.trigger();
+        trigger_integrate_dht_ops.trigger();
+    }
+    Ok(WorkComplete::Complete)
+}
+
+/// Run a single scheduled function and validate anything it wrote.
+async fn run_scheduled_fn<Ribosome, C>(
+    workspace: HostFnWorkspace,
+    network: HolochainP2pCell,
+    keystore: KeystoreSender,
+    ribosome: Ribosome,
+    conductor_api: C,
+    scheduled_fn: ScheduledFn,
+) -> WorkflowResult<ScheduledResult>
+where
+    Ribosome: RibosomeT + Send + 'static,
+    C: CellConductorApiT,
+{
+    let zome = ribosome
+        .dna_def()
+        .get_zome(scheduled_fn.zome_name())
+        .map_err(|_| RibosomeError::ZomeNotExists(scheduled_fn.zome_name().clone()))?;
+    let signal_tx = conductor_api.signal_broadcaster().await;
+    let call_zome_handle = conductor_api.clone().into_call_zome_handle();
+    let cell_id = conductor_api.cell_id().clone();
+    let (ribosome, result) = tokio::task::spawn_blocking({
+        let workspace = workspace.clone();
+        let network = network.clone();
+        let zome = zome.clone();
+        move || {
+            let host_access = ScheduledHostAccess::new(
+                workspace,
+                keystore,
+                network,
+                signal_tx,
+                call_zome_handle,
+                cell_id,
+            );
+            let invocation = ScheduledInvocation::new(zome, scheduled_fn.fn_name().clone());
+            let result = ribosome.run_scheduled(host_access, invocation);
+            (ribosome, result)
+        }
+    })
+    .await?;
+    let result = result?;
+
+    inline_validation(workspace, network, conductor_api, Some(zome), ribosome).await?;
+    Ok(result)
+}
+
+async fn unschedule(env: &EnvWrite, scheduled_fn: ScheduledFn) -> WorkflowResult<()> {
+    env.async_commit(move |txn| unschedule_fn(txn, &scheduled_fn))
+        .await?;
+    Ok(())
+}
+
+/// How long until the next scheduled function is due,
+/// capped at [`SCHEDULER_MAX_INTERVAL`].
+pub async fn next_scheduled_fn_due(env: &EnvWrite) -> WorkflowResult<Duration> {
+    let next_start_ms = env
+        .async_reader(|txn| next_scheduled_fn_start_ms(&txn))
+        .await?;
+    let now_ms = timestamp::now().to_sql_ms_lossy();
+    Ok(match next_start_ms {
+        Some(start_ms) => Duration::from_millis(start_ms.saturating_sub(now_ms).max(0) as u64)
+            .min(SCHEDULER_MAX_INTERVAL),
+        None => SCHEDULER_MAX_INTERVAL,
+    })
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+mod tests {
+    use crate::sweettest::*;
+    use holochain_types::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn scheduled_fn_runs_until_done() {
+        observability::test_run().ok();
+        let (dna_file, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Schedule])
+            .await
+            .unwrap();
+        let mut conductor = SweetConductor::from_standard_config().await;
+        let app = conductor.setup_app("app", &[dna_file]).await.unwrap();
+        let (cell,) = app.into_tuple();
+        let zome = cell.zome(TestWasm::Schedule);
+
+        let _: () = conductor.call(&zome, "schedule", ()).await;
+
+        // The scheduled function commits a tick each time it runs
+        // and stops rescheduling itself after five ticks.
+        crate::assert_eq_retry_10s!(
+            {
+                let ticks: Vec<Element> = conductor.call(&zome, "query_tick", ()).await;
+                ticks.len()
+            },
+            5
+        );
+
+        // Once done the function is removed from the schedule.
+        let scheduled: Vec<ScheduledFn> = cell
+            .env()
+            .async_reader(|txn| {
+                holochain_state::schedule::due_scheduled_fns(
+                    &txn,
+                    Timestamp(i64::MAX / 1_000_000, 0),
+                )
+            })
+            .await
+            .unwrap();
+        assert!(scheduled.is_empty());
+    }
+}
@@ -96,6 +96,14 @@ async fn sys_validation_workflow_inner(
         )
         .await?;
 
+        conductor_api.metrics().record_validation_outcome(
+            "sys",
+            match outcome {
+                Outcome::Accepted | Outcome::SkipAppValidation => "accepted",
+                Outcome::AwaitingOpDep(_) | Outcome::MissingDhtDep => "awaiting_deps",
+                Outcome::Rejected => "rejected",
+            },
+        );
         match outcome {
             Outcome::Accepted => {
                 workspace
@@ -188,6 +196,9 @@ async fn validate_op(
 fn handle_failed(error: ValidationOutcome) -> Outcome {
     use Outcome::*;
     match error {
+        ValidationOutcome::AuthorKeyInvalid(_, _) => Rejected,
+        // Try again once the key registry can answer
+        ValidationOutcome::AuthorKeyStateUnknown(_) => MissingDhtDep,
         ValidationOutcome::Counterfeit(_, _) => {
             unreachable!("Counterfeit ops are dropped before sys validation")
         }
@@ -222,6 +233,8 @@ async fn validate_op_inner(
     conductor_api: &impl CellConductorApiT,
     incoming_dht_ops_sender: Option<IncomingDhtOpSender>,
 ) -> SysValidationResult<()> {
+    let header = op.header();
+    author_key_is_valid(header.author(), header.timestamp(), conductor_api).await?;
     match op {
         DhtOp::StoreElement(_, header, entry) => {
             store_element(header, workspace, network.clone()).await?;
@@ -392,6 +405,7 @@ async fn sys_validate_element_inner(
     let header = element.header();
     let maybe_entry = element.entry().as_option();
     counterfeit_check(signature, header).await?;
+    author_key_is_valid(header.author(), header.timestamp(), conductor_api).await?;
 
     async fn validate(
         header: &Header,
@@ -455,11 +469,10 @@ async fn sys_validate_element_inner(
     }
 }
 
-/// Check if the op has valid signature and author.
+/// Check if the op has a valid signature.
 /// Ops that fail this check should be dropped.
 pub async fn counterfeit_check(signature: &Signature, header: &Header) -> SysValidationResult<()> {
     verify_header_signature(&signature, &header).await?;
-    author_key_is_valid(header.author()).await?;
     Ok(())
 }
 
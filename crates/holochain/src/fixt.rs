@@ -12,6 +12,8 @@ use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentHostAccess
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitHostAccess;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitInvocation;
+use crate::core::ribosome::guest_callback::scheduled::ScheduledHostAccess;
+use crate::core::ribosome::guest_callback::scheduled::ScheduledInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateHostAccess;
 use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
 use crate::core::ribosome::guest_callback::validate_link::ValidateCreateLinkInvocation;
@@ -345,6 +347,16 @@ fixturator!(
     constructor fn new(HostFnWorkspace, KeystoreSender, HolochainP2pCell);
 );
 
+fixturator!(
+    ScheduledInvocation;
+    constructor fn new(Zome, FunctionName);
+);
+
+fixturator!(
+    ScheduledHostAccess;
+    constructor fn new(HostFnWorkspace, KeystoreSender, HolochainP2pCell, SignalBroadcaster, CellConductorReadHandle, CellId);
+);
+
 fixturator!(
     ZomesToInvoke;
     constructor fn one(Zome);
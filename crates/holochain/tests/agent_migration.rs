@@ -0,0 +1,346 @@
+#![cfg(feature = "test_utils")]
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use hdk::prelude::*;
+use holochain::conductor::dpki::DpkiError;
+use holochain::conductor::dpki::DpkiResult;
+use holochain::conductor::dpki::KeyRegistry;
+use holochain::conductor::error::ConductorError;
+use holochain::conductor::error::ConductorResult;
+use holochain::conductor::Conductor;
+use holochain::sweettest::standard_config;
+use holochain::sweettest::SweetAgents;
+use holochain::sweettest::SweetConductor;
+use holochain::sweettest::SweetDnaFile;
+use holochain::sweettest::SweetZome;
+use holochain_sqlite::db::DbKind;
+use holochain_state::source_chain::SourceChain;
+use holochain_types::env::EnvWrite;
+use holochain_types::prelude::*;
+use holochain_wasm_test_utils::TestWasm;
+use holochain_zome_types::dpki::KeyState;
+
+/// A key registry which accepts every key but can't record rotations.
+struct NoRotationKeyRegistry;
+
+#[async_trait::async_trait]
+impl KeyRegistry for NoRotationKeyRegistry {
+    async fn key_state(&self, _key: AgentPubKey, _at: Timestamp) -> DpkiResult<KeyState> {
+        Ok(KeyState::Valid)
+    }
+
+    async fn register_key(&self, _key: AgentPubKey) -> DpkiResult<()> {
+        Ok(())
+    }
+
+    async fn rotate_key(&self, _old_key: AgentPubKey, _new_key: AgentPubKey) -> DpkiResult<()> {
+        Err(DpkiError::RegistryMissing)
+    }
+
+    async fn revoke_key(&self, _key: AgentPubKey) -> DpkiResult<()> {
+        Ok(())
+    }
+}
+
+/// The headers of a cell's chain in order,
+/// whether or not the cell is running.
+async fn chain_headers(conductor: &SweetConductor, cell_id: &CellId) -> Vec<Header> {
+    let env = EnvWrite::open(
+        conductor.envs().path(),
+        DbKind::Cell(cell_id.clone()),
+        conductor.keystore(),
+    )
+    .unwrap();
+    let mut headers: Vec<Header> = SourceChain::new(env, cell_id.agent_pubkey().clone())
+        .await
+        .unwrap()
+        .query(QueryFilter::new())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|element| element.header().clone())
+        .collect();
+    headers.sort_by_key(|header| header.header_seq());
+    headers
+}
+
+/// The number of cell databases on disk.
+fn cell_dbs(conductor: &SweetConductor) -> usize {
+    std::fs::read_dir(conductor.envs().path().join("cell"))
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension() == Some("sqlite3".as_ref()))
+        .count()
+}
+
+/// Set up an app with a cell that has committed an entry.
+async fn setup(conductor: &mut SweetConductor, migrate_agent: TestWasm) -> CellId {
+    let (dna_file, _) = SweetDnaFile::unique_from_test_wasms(vec![migrate_agent, TestWasm::Create])
+        .await
+        .unwrap();
+    let (alice,) = conductor
+        .setup_app("app", &[dna_file])
+        .await
+        .unwrap()
+        .into_tuple();
+    let _: HeaderHash = conductor
+        .call(&alice.zome(TestWasm::Create), "create_entry", ())
+        .await;
+    alice.cell_id().clone()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+/// Rotating the key closes the old chain and opens a new one,
+/// which runs init even though it starts with an extra header.
+async fn rotate_agent_key_migrates_chains() {
+    let _g = observability::test_run().ok();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let old_cell = setup(&mut conductor, TestWasm::MigrateAgentPass).await;
+
+    let new_key = conductor
+        .inner_handle()
+        .rotate_app_agent_key(&"app".to_string())
+        .await
+        .unwrap();
+    let new_cell = CellId::new(old_cell.dna_hash().clone(), new_key.clone());
+
+    // - Each chain points at the other.
+    let old_headers = chain_headers(&conductor, &old_cell).await;
+    match old_headers.last() {
+        Some(Header::CloseChain(close)) => {
+            assert_eq!(close.new_dna_hash, *old_cell.dna_hash());
+            assert_eq!(close.new_agent, new_key);
+        }
+        other => panic!("The old chain wasn't closed: {:?}", other),
+    }
+    let new_headers = chain_headers(&conductor, &new_cell).await;
+    match new_headers.last() {
+        Some(Header::OpenChain(open)) => {
+            assert_eq!(open.prev_dna_hash, *old_cell.dna_hash());
+            assert_eq!(open.prev_agent, *old_cell.agent_pubkey());
+        }
+        other => panic!("The new chain wasn't opened: {:?}", other),
+    }
+
+    // - Init runs on the new chain the first time it is called, and only then.
+    let zome = SweetZome::new(new_cell.clone(), TestWasm::Create.into());
+    let _: HeaderHash = conductor.call(&zome, "create_entry", ()).await;
+    let _: HeaderHash = conductor.call(&zome, "create_entry", ()).await;
+    let inits = chain_headers(&conductor, &new_cell)
+        .await
+        .into_iter()
+        .filter(|header| matches!(header, Header::InitZomesComplete(_)))
+        .count();
+    assert_eq!(inits, 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+/// A `migrate_agent` callback which refuses leaves the app on its old chain.
+async fn refused_rotation_leaves_old_chain_open() {
+    let _g = observability::test_run().ok();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let old_cell = setup(&mut conductor, TestWasm::MigrateAgentFail).await;
+    let before = chain_headers(&conductor, &old_cell).await;
+
+    let result = conductor
+        .inner_handle()
+        .rotate_app_agent_key(&"app".to_string())
+        .await;
+    assert!(matches!(
+        result,
+        Err(ConductorError::MigrateAgentFailed(..))
+    ));
+
+    // - The old chain is untouched and its cell still runs.
+    assert_eq!(chain_headers(&conductor, &old_cell).await, before);
+    let zome = SweetZome::new(old_cell.clone(), TestWasm::Create.into());
+    let _: HeaderHash = conductor.call(&zome, "create_entry", ()).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+/// A key registry which fails after the chains were migrated
+/// has the old chain reopened and the new chain removed.
+async fn failed_key_registry_reopens_old_chain() {
+    let _g = observability::test_run().ok();
+    let mut conductor = SweetConductor::from_builder(
+        Conductor::builder()
+            .config(standard_config())
+            .with_key_registry(Arc::new(NoRotationKeyRegistry)),
+    )
+    .await;
+    let old_cell = setup(&mut conductor, TestWasm::MigrateAgentPass).await;
+    let before = chain_headers(&conductor, &old_cell).await;
+    let cell_dbs = cell_dbs(&conductor);
+
+    let result = conductor
+        .inner_handle()
+        .rotate_app_agent_key(&"app".to_string())
+        .await;
+    assert!(matches!(result, Err(ConductorError::DpkiError(_))));
+
+    // - The close header is gone and the app still runs on the old key.
+    assert_eq!(chain_headers(&conductor, &old_cell).await, before);
+    let zome = SweetZome::new(old_cell.clone(), TestWasm::Create.into());
+    let _: HeaderHash = conductor.call(&zome, "create_entry", ()).await;
+
+    // - The new cell's database is gone.
+    assert_eq!(cell_dbs(&conductor), cell_dbs);
+}
+
+/// A bundle with a single slot running the given DNA,
+/// which may be upgraded from the given older version.
+async fn app_bundle(dna_file: DnaFile, upgrades: Option<&DnaHash>) -> AppBundle {
+    let path = PathBuf::from(format!("{}", dna_file.dna_hash()));
+    let manifest = AppManifestCurrent {
+        name: "app".to_string(),
+        description: None,
+        slots: vec![AppSlotManifest {
+            id: "slot".into(),
+            provisioning: Some(CellProvisioning::Create { deferred: false }),
+            dna: AppSlotDnaManifest {
+                location: Some(DnaLocation::Bundled(path.clone())),
+                properties: None,
+                uid: None,
+                version: upgrades.map(|hash| DnaHashB64::from(hash.clone()).into()),
+                clone_limit: 0,
+            },
+        }],
+    };
+    AppBundle::new(
+        manifest.into(),
+        vec![(path, DnaBundle::from_dna_file(dna_file).await.unwrap())],
+        PathBuf::from("."),
+    )
+    .await
+    .unwrap()
+}
+
+/// Install and enable an app from a bundle, then commit an entry on its cell.
+async fn setup_bundle(conductor: &SweetConductor) -> (CellId, DnaHash) {
+    let (dna_file, _) =
+        SweetDnaFile::unique_from_test_wasms(vec![TestWasm::MigrateAgentPass, TestWasm::Create])
+            .await
+            .unwrap();
+    let dna_hash = dna_file.dna_hash().clone();
+    let agent_key = SweetAgents::one(conductor.keystore()).await;
+    let app = conductor
+        .inner_handle()
+        .install_app_bundle(InstallAppBundlePayload {
+            source: AppBundleSource::Bundle(app_bundle(dna_file, None).await),
+            agent_key,
+            installed_app_id: Some("app".into()),
+            membrane_proofs: HashMap::new(),
+            uid: None,
+        })
+        .await
+        .unwrap();
+    let (_, cell_id) = app.provisioned_cells().next().unwrap();
+    let cell_id = cell_id.clone();
+    conductor
+        .inner_handle()
+        .enable_app(&"app".to_string())
+        .await
+        .unwrap();
+    let zome = SweetZome::new(cell_id.clone(), TestWasm::Create.into());
+    let _: HeaderHash = conductor.call(&zome, "create_entry", ()).await;
+    (cell_id, dna_hash)
+}
+
+/// Upgrade the app to a new DNA running the given `migrate_agent` callback.
+async fn upgrade(
+    conductor: &SweetConductor,
+    old_dna_hash: &DnaHash,
+    migrate_agent: TestWasm,
+) -> (ConductorResult<()>, CellId) {
+    let (dna_file, _) = SweetDnaFile::unique_from_test_wasms(vec![migrate_agent, TestWasm::Create])
+        .await
+        .unwrap();
+    let new_dna_hash = dna_file.dna_hash().clone();
+    let result = conductor
+        .inner_handle()
+        .upgrade_app(UpgradeAppPayload {
+            source: AppBundleSource::Bundle(app_bundle(dna_file, Some(old_dna_hash)).await),
+            installed_app_id: "app".into(),
+            membrane_proofs: HashMap::new(),
+            uid: None,
+        })
+        .await;
+    (result, new_dna_hash)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+/// Upgrading an app closes the old chain and opens one on the new DNA.
+async fn upgrade_app_migrates_chains() {
+    let _g = observability::test_run().ok();
+    let conductor = SweetConductor::from_standard_config().await;
+    let (old_cell, old_dna_hash) = setup_bundle(&conductor).await;
+
+    let (result, new_dna_hash) =
+        upgrade(&conductor, &old_dna_hash, TestWasm::MigrateAgentPass).await;
+    result.unwrap();
+    let new_cell = CellId::new(new_dna_hash.clone(), old_cell.agent_pubkey().clone());
+
+    // - Each chain points at the other.
+    match chain_headers(&conductor, &old_cell).await.last() {
+        Some(Header::CloseChain(close)) => {
+            assert_eq!(close.new_dna_hash, new_dna_hash);
+            assert_eq!(close.new_agent, *old_cell.agent_pubkey());
+        }
+        other => panic!("The old chain wasn't closed: {:?}", other),
+    }
+    match chain_headers(&conductor, &new_cell).await.last() {
+        Some(Header::OpenChain(open)) => {
+            assert_eq!(open.prev_dna_hash, old_dna_hash);
+            assert_eq!(open.prev_agent, *old_cell.agent_pubkey());
+        }
+        other => panic!("The new chain wasn't opened: {:?}", other),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+/// A `migrate_agent` callback which refuses the upgrade
+/// leaves the app running on its old chain.
+async fn refused_upgrade_leaves_old_chain_open() {
+    let _g = observability::test_run().ok();
+    let conductor = SweetConductor::from_standard_config().await;
+    let (old_cell, old_dna_hash) = setup_bundle(&conductor).await;
+    let before = chain_headers(&conductor, &old_cell).await;
+    let cell_dbs = cell_dbs(&conductor);
+
+    let (result, _) = upgrade(&conductor, &old_dna_hash, TestWasm::MigrateAgentFail).await;
+    assert!(matches!(
+        result,
+        Err(ConductorError::MigrateAgentFailed(..))
+    ));
+
+    // - The old chain is untouched, its cell still runs and the new cell is gone.
+    assert_eq!(chain_headers(&conductor, &old_cell).await, before);
+    let zome = SweetZome::new(old_cell.clone(), TestWasm::Create.into());
+    let _: HeaderHash = conductor.call(&zome, "create_entry", ()).await;
+    assert_eq!(cell_dbs(&conductor), cell_dbs);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+/// Failing to record the upgrade after the chains were migrated
+/// reopens the old chain and removes the new one.
+async fn failed_upgrade_state_write_reopens_old_chain() {
+    let _g = observability::test_run().ok();
+    let conductor = SweetConductor::from_standard_config().await;
+    let (old_cell, old_dna_hash) = setup_bundle(&conductor).await;
+    let before = chain_headers(&conductor, &old_cell).await;
+    let cell_dbs = cell_dbs(&conductor);
+
+    conductor.inner_handle().fail_next_app_upgrade();
+    let (result, _) = upgrade(&conductor, &old_dna_hash, TestWasm::MigrateAgentPass).await;
+    assert!(result.is_err());
+
+    // - The close header is gone, the app still runs on the old cell
+    //   and the new cell is gone.
+    assert_eq!(chain_headers(&conductor, &old_cell).await, before);
+    let zome = SweetZome::new(old_cell.clone(), TestWasm::Create.into());
+    let _: HeaderHash = conductor.call(&zome, "create_entry", ()).await;
+    assert_eq!(cell_dbs(&conductor), cell_dbs);
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "test_utils")]
+
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use ::fixt::prelude::*;
+use hdk::prelude::*;
+use holochain::conductor::dpki::DPKI_ZOME_NAME;
+use holochain::sweettest::standard_config;
+use holochain::sweettest::SweetConductor;
+use holochain::sweettest::SweetDnaFile;
+use holochain_conductor_api::config::conductor::DpkiConfig;
+use holochain_types::prelude::*;
+use holochain_types::timestamp;
+use holochain_zome_types::dpki::KeyState;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dna_key_registry_caches_key_states() {
+    let watched = fixt!(AgentPubKey);
+    let lookups = Arc::new(AtomicUsize::new(0));
+    let revoked_at = Arc::new(parking_lot::Mutex::new(None));
+    let zome = InlineZome::new_unique(Vec::new())
+        .callback("key_state", {
+            let watched = watched.clone();
+            let lookups = lookups.clone();
+            let revoked_at = revoked_at.clone();
+            move |_, (key, _at): (AgentPubKey, Timestamp)| {
+                // Sys validation also asks about the DPKI cell's own agent.
+                if key != watched {
+                    return Ok(KeyState::NotFound);
+                }
+                lookups.fetch_add(1, Ordering::SeqCst);
+                Ok(match *revoked_at.lock() {
+                    Some(timestamp) => KeyState::Invalidated { timestamp },
+                    None => KeyState::Valid,
+                })
+            }
+        })
+        .callback("revoke_key", {
+            let revoked_at = revoked_at.clone();
+            move |_, _key: AgentPubKey| {
+                *revoked_at.lock() = Some(timestamp::now());
+                Ok(())
+            }
+        });
+    let (dna, _) = SweetDnaFile::unique_from_inline_zome(DPKI_ZOME_NAME, zome)
+        .await
+        .unwrap();
+    let mut config = standard_config();
+    config.dpki = Some(DpkiConfig {
+        instance_id: "dpki".into(),
+        init_params: String::new(),
+    });
+    let mut conductor = SweetConductor::from_config(config).await;
+    conductor.setup_app("dpki", &[dna]).await.unwrap();
+    let registry = conductor.key_registry().unwrap();
+
+    let before = timestamp::now();
+    for _ in 0..3 {
+        assert_eq!(
+            registry.key_state(watched.clone(), before).await.unwrap(),
+            KeyState::Valid
+        );
+    }
+    // - Only the first check called into the DPKI cell.
+    assert_eq!(lookups.load(Ordering::SeqCst), 1);
+
+    // - Revoking the key through the registry drops its cached state.
+    registry.revoke_key(watched.clone()).await.unwrap();
+    let state = registry
+        .key_state(watched.clone(), timestamp::now())
+        .await
+        .unwrap();
+    assert!(matches!(state, KeyState::Invalidated { .. }));
+
+    // - A revocation is final, so it is answered from the cache.
+    assert_eq!(
+        registry.key_state(watched, before).await.unwrap(),
+        KeyState::Valid
+    );
+    assert_eq!(lookups.load(Ordering::SeqCst), 2);
+}
@@ -128,6 +128,112 @@ async fn multi_conductor() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Test that an observer cell installs without running genesis or init,
+/// and can read entries and links which another conductor authored.
+#[cfg(feature = "test_utils")]
+#[tokio::test(flavor = "multi_thread")]
+async fn observer_reads_from_other_conductor() -> anyhow::Result<()> {
+    use holochain::sweettest::SweetAgents;
+    use holochain::sweettest::SweetZome;
+    use holochain_state::source_chain::SourceChain;
+    use holochain_state::source_chain::SourceChainError;
+    use holochain_types::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    let _g = observability::test_run().ok();
+
+    let mut conductors = SweetConductorBatch::from_standard_config(2).await;
+    let (dna_file, _) =
+        SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create, TestWasm::Link]).await?;
+
+    // Install an app which observes the DNA on the second conductor.
+    let path = PathBuf::from(format!("{}", dna_file.dna_hash()));
+    let manifest = AppManifestCurrent {
+        name: "observer".to_string(),
+        description: None,
+        slots: vec![AppSlotManifest {
+            id: "observed".into(),
+            provisioning: Some(CellProvisioning::Observe { deferred: false }),
+            dna: AppSlotDnaManifest {
+                location: Some(DnaLocation::Bundled(path.clone())),
+                properties: None,
+                uid: None,
+                version: None,
+                clone_limit: 0,
+            },
+        }],
+    };
+    let bundle = AppBundle::new(
+        manifest.into(),
+        vec![(path, DnaBundle::from_dna_file(dna_file).await?)],
+        PathBuf::from("."),
+    )
+    .await?;
+    let observer = SweetAgents::one(conductors[1].keystore()).await;
+    let app = conductors[1]
+        .inner_handle()
+        .install_app_bundle(InstallAppBundlePayload {
+            source: AppBundleSource::Bundle(bundle),
+            agent_key: observer.clone(),
+            installed_app_id: Some("observer".into()),
+            membrane_proofs: HashMap::new(),
+            uid: None,
+        })
+        .await?;
+    let observer_cell = app
+        .observer_cells()
+        .next()
+        .expect("The app has no observer cell")
+        .clone();
+    let (_, cell_errors) = conductors[1].enable_app(app.id()).await?;
+    assert!(cell_errors.is_empty());
+
+    // The author's app runs the DNA the observer registered.
+    let dna_file = conductors[1]
+        .get_dna(observer_cell.dna_hash())
+        .await
+        .expect("The observed DNA is registered");
+    let alice = conductors[0].setup_app("app", &[dna_file]).await?;
+    let (alice,) = alice.into_tuple();
+    conductors.exchange_peer_info().await;
+
+    let _: HeaderHash = conductors[0]
+        .call(&alice.zome(TestWasm::Create), "create_entry", ())
+        .await;
+    let _: HeaderHash = conductors[0]
+        .call(&alice.zome(TestWasm::Link), "create_link", ())
+        .await;
+
+    // The observer reads alice's entry and link from the DHT.
+    let create_zome = SweetZome::new(observer_cell.clone(), TestWasm::Create.into());
+    let link_zome = SweetZome::new(observer_cell.clone(), TestWasm::Link.into());
+    let mut element: Option<Element> = None;
+    let mut links: Vec<Link> = Vec::new();
+    for _ in 0..100 {
+        element = conductors[1].call(&create_zome, "get_entry", ()).await;
+        let got: Links = conductors[1].call(&link_zome, "get_links", ()).await;
+        links = got.into_inner();
+        if element.is_some() && !links.is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    let element = element.expect("The observer couldn't get alice's entry");
+    assert_eq!(element.header().author(), alice.agent_pubkey());
+    assert_eq!(links.len(), 1);
+
+    // Neither genesis nor init wrote to the observer's chain.
+    let env = conductors[1].get_cell_env(&observer_cell).await?;
+    assert!(matches!(
+        SourceChain::new(env, observer).await,
+        Err(SourceChainError::ChainEmpty)
+    ));
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread")]
 #[cfg(feature = "test_utils")]
 #[ignore = "I'm not convinced this test is actually adding value and worth fixing right now"]
@@ -14,6 +14,17 @@ struct AppString(String);
 #[cfg(feature = "test_utils")]
 #[tokio::test(flavor = "multi_thread")]
 async fn fullsync_sharded_gossip() -> anyhow::Result<()> {
+    fullsync("sharded-gossip").await
+}
+
+#[cfg(feature = "test_utils")]
+#[tokio::test(flavor = "multi_thread")]
+async fn fullsync_quantized_gossip() -> anyhow::Result<()> {
+    fullsync("quantized-gossip").await
+}
+
+#[cfg(feature = "test_utils")]
+async fn fullsync(gossip_strategy: &str) -> anyhow::Result<()> {
     use holochain::test_utils::inline_zomes::simple_create_read_zome;
 
     let _g = observability::test_run().ok();
@@ -21,7 +32,7 @@ async fn fullsync_sharded_gossip() -> anyhow::Result<()> {
 
     let mut tuning =
         kitsune_p2p_types::config::tuning_params_struct::KitsuneP2pTuningParams::default();
-    tuning.gossip_strategy = "sharded-gossip".to_string();
+    tuning.gossip_strategy = gossip_strategy.to_string();
 
     let mut network = KitsuneP2pConfig::default();
     network.transport_pool = vec![kitsune_p2p::TransportConfig::Quic {
@@ -128,3 +139,80 @@ async fn fullsync_sharded_local_gossip() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[cfg(feature = "test_utils")]
+#[tokio::test(flavor = "multi_thread")]
+async fn sharded_gossip_heals_after_partition() -> anyhow::Result<()> {
+    use holochain::test_utils::inline_zomes::simple_create_read_zome;
+    use holochain_state::prelude::fresh_reader_test;
+    use kitsune_p2p_types::tx2::{SimLink, SimNet};
+    use rusqlite::named_params;
+    use std::time::Duration;
+
+    let _g = observability::test_run().ok();
+    const NUM_CONDUCTORS: usize = 2;
+    const SIM_NET: &str = "sharded_gossip_heals_after_partition";
+
+    let net = SimNet::new(1);
+    net.set_default_link(SimLink {
+        latency: Duration::from_millis(20),
+        jitter: Duration::from_millis(10),
+        ..Default::default()
+    });
+    net.register(SIM_NET);
+
+    let mut tuning =
+        kitsune_p2p_types::config::tuning_params_struct::KitsuneP2pTuningParams::default();
+    tuning.gossip_strategy = "sharded-gossip".to_string();
+
+    let mut network = KitsuneP2pConfig::default();
+    network.transport_pool = vec![kitsune_p2p::TransportConfig::Sim {
+        network: SIM_NET.to_string(),
+    }];
+    network.tuning_params = Arc::new(tuning);
+    let mut config = ConductorConfig::default();
+    config.network = Some(network);
+
+    let mut conductors = SweetConductorBatch::from_config(NUM_CONDUCTORS, config).await;
+    for c in conductors.iter() {
+        c.set_skip_publish(true);
+    }
+
+    // Each conductor binds one endpoint
+    let nodes = net.nodes();
+    net.partition(&nodes[..1], &nodes[1..]);
+
+    let (dna_file, _) = SweetDnaFile::unique_from_inline_zome("zome1", simple_create_read_zome())
+        .await
+        .unwrap();
+
+    let apps = conductors.setup_app("app", &[dna_file]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    let hash: HeaderHash = conductors[0].call(&alice.zome("zome1"), "create", ()).await;
+    let all_cells = vec![&alice, &bobbo];
+
+    // Gossip fails across the partition until it heals
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    let bobbo_holds_op: bool = fresh_reader_test(bobbo.env().clone(), |txn| {
+        txn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM DhtOp WHERE header_hash = :hash)",
+            named_params! {
+                ":hash": hash,
+            },
+            |row| row.get(0),
+        )
+        .unwrap()
+    });
+    assert!(!bobbo_holds_op, "bobbo got alice's op across the partition");
+    net.heal();
+    consistency_10s(&all_cells).await;
+
+    let element: Option<Element> = conductors[1].call(&bobbo.zome("zome1"), "read", hash).await;
+    let element = element.expect("Element was None: bobbo couldn't `get` it");
+    assert_eq!(element.header().author(), alice.agent_pubkey());
+
+    Ok(())
+}
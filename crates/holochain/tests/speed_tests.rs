@@ -190,7 +190,10 @@ async fn speed_test(n: Option<usize>) -> TestEnvs {
 
     // Setup websocket handle and app interface
     let (mut client, _) = websocket_client(&handle).await.unwrap();
-    let request = AdminRequest::AttachAppInterface { port: None };
+    let request = AdminRequest::AttachAppInterface {
+        port: None,
+        installed_app_id: None,
+    };
     let response = client.request(request);
     let response = response.await.unwrap();
     let app_port = match response {
@@ -114,7 +114,10 @@ pub async fn call_zome_fn<S>(
 }
 
 pub async fn attach_app_interface(client: &mut WebsocketSender, port: Option<u16>) -> u16 {
-    let request = AdminRequest::AttachAppInterface { port };
+    let request = AdminRequest::AttachAppInterface {
+        port,
+        installed_app_id: None,
+    };
     let response = client.request(request);
     let response = check_timeout(response, 3000).await;
     match response {
@@ -259,6 +262,9 @@ pub fn create_config(port: u16, environment_path: PathBuf) -> ConductorConfig {
         },
         keystore_path: None,
         use_dangerous_test_keystore: true,
+        metrics: None,
+        task_supervision: None,
+        zome_call_timeout_s: None,
     }
 }
 
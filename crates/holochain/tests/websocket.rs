@@ -7,12 +7,14 @@ use holochain::sweettest::SweetConductorBatch;
 use holochain::sweettest::SweetDnaFile;
 use holochain::{
     conductor::{
-        api::{AdminRequest, AdminResponse},
+        api::{AdminRequest, AdminResponse, AppRequest, AppResponse},
         error::ConductorError,
         Conductor,
     },
     fixt::*,
 };
+use holochain_conductor_api::signal_subscription::{SignalFilterSet, SignalSubscription};
+use holochain_conductor_api::AdminEvent;
 use holochain_types::{
     prelude::*,
     test_utils::{fake_agent_pubkey_1, fake_dna_zomes, write_fake_dna_file},
@@ -246,7 +248,7 @@ async fn remote_signals() -> anyhow::Result<()> {
     for mut rx in rxs {
         let r = rx.try_recv();
         // Each handle should recv a signal
-        assert_matches!(r, Ok(Signal::App(_, a)) if a == signal);
+        assert_matches!(r, Ok(Signal::App(_, _, a)) if a == signal);
     }
 
     Ok(())
@@ -310,6 +312,15 @@ async fn emit_signals() {
 
     let (mut app_tx_1, app_rx_1) = websocket_client_by_port(app_port).await.unwrap();
     let (_, app_rx_2) = websocket_client_by_port(app_port).await.unwrap();
+    let (mut app_tx_3, app_rx_3) = websocket_client_by_port(app_port).await.unwrap();
+
+    // The third connection unsubscribes from all signals of the app.
+    let request = AppRequest::SignalSubscription(SignalSubscription::new(
+        "test".to_string(),
+        SignalFilterSet::block_all(),
+    ));
+    let response = check_timeout(app_tx_3.request(request), 3000).await;
+    assert_matches!(response, AppResponse::SignalSubscriptionUpdated);
 
     call_zome_fn(
         &mut app_tx_1,
@@ -335,10 +346,19 @@ async fn emit_signals() {
     assert!(!msg2.is_request());
 
     assert_eq!(
-        Signal::App(cell_id, AppSignal::new(ExternIO::encode(()).unwrap())),
+        Signal::App(
+            cell_id,
+            TestWasm::EmitSignal.into(),
+            AppSignal::new(ExternIO::encode(()).unwrap()),
+        ),
         Signal::try_from(sig1.clone()).unwrap(),
     );
     assert_eq!(sig1, sig2);
+    assert!(Box::pin(app_rx_3.timeout(Duration::from_secs(1)))
+        .next()
+        .await
+        .unwrap()
+        .is_err());
 
     ///////////////////////////////////////////////////////
 }
@@ -371,6 +391,42 @@ async fn conductor_admin_interface_runs_from_config() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn admin_events_reach_subscribed_connections() -> Result<()> {
+    observability::test_run().ok();
+    let tmp_dir = TempDir::new("conductor_cfg").unwrap();
+    let environment_path = tmp_dir.path().to_path_buf();
+    let config = create_config(0, environment_path);
+    let conductor_handle = Conductor::builder().config(config).build().await?;
+    let (mut subscribed_tx, subscribed_rx) = websocket_client(&conductor_handle).await?;
+    let (_, unsubscribed_rx) = websocket_client(&conductor_handle).await?;
+    let event = |n| AdminEvent::CellLeftNetwork {
+        cell_id: holochain_types::test_utils::fake_cell_id(n),
+    };
+
+    // Events from before the subscription are not sent
+    conductor_handle.emit_admin_event(event(1));
+    let response = subscribed_tx.request(AdminRequest::SubscribeEvents).await?;
+    assert_matches!(response, AdminResponse::EventsSubscribed);
+    conductor_handle.emit_admin_event(event(2));
+
+    let (bytes, msg) = Box::pin(subscribed_rx.timeout(Duration::from_secs(1)))
+        .next()
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!msg.is_request());
+    assert_eq!(AdminEvent::try_from(bytes)?, event(2));
+    assert!(Box::pin(unsubscribed_rx.timeout(Duration::from_secs(1)))
+        .next()
+        .await
+        .unwrap()
+        .is_err());
+
+    conductor_handle.shutdown().await;
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn conductor_admin_interface_ends_with_shutdown() -> Result<()> {
     if let Err(e) = conductor_admin_interface_ends_with_shutdown_inner().await {
@@ -1,6 +1,7 @@
 use self::get_agent_activity_query::hashes::GetAgentActivityQuery;
 use self::get_entry_ops_query::GetEntryOpsQuery;
 use self::get_links_ops_query::GetLinksOpsQuery;
+use self::get_meta_query::GetMetaQuery;
 use self::{
     get_agent_activity_query::deterministic::DeterministicGetAgentActivityQuery,
     get_element_query::GetElementOpsQuery,
@@ -8,6 +9,7 @@ use self::{
 
 use super::error::CascadeResult;
 use holo_hash::AgentPubKey;
+use holo_hash::AnyDhtHash;
 use holo_hash::HeaderHash;
 use holochain_state::query::Query;
 use holochain_state::query::Txn;
@@ -24,6 +26,7 @@ mod get_agent_activity_query;
 mod get_element_query;
 mod get_entry_ops_query;
 mod get_links_ops_query;
+mod get_meta_query;
 
 #[instrument(skip(state_env))]
 pub async fn handle_get_entry(
@@ -51,6 +54,19 @@ pub async fn handle_get_element(
     Ok(results)
 }
 
+#[instrument(skip(env))]
+pub async fn handle_get_meta(
+    env: EnvRead,
+    hash: AnyDhtHash,
+    options: holochain_p2p::event::GetMetaOptions,
+) -> CascadeResult<MetadataSet> {
+    let query = GetMetaQuery::new(hash, options.metadata_request);
+    let results = env
+        .async_reader(move |txn| query.run(Txn::from(&txn)))
+        .await?;
+    Ok(results)
+}
+
 #[instrument(skip(env))]
 pub async fn handle_get_agent_activity(
     env: EnvRead,
@@ -0,0 +1,202 @@
+use std::collections::BTreeSet;
+use std::collections::HashSet;
+
+use holo_hash::hash_type::AnyDht;
+use holo_hash::AnyDhtHash;
+use holo_hash::HeaderHash;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::Row;
+use holochain_state::query::prelude::*;
+use holochain_state::query::StateQueryError;
+use holochain_types::dht_op::DhtOpType;
+use holochain_types::metadata::EntryDhtStatus;
+use holochain_types::metadata::MetadataSet;
+use holochain_types::metadata::TimedHeaderHash;
+use holochain_zome_types::request::MetadataRequest;
+use holochain_zome_types::HasValidationStatus;
+use holochain_zome_types::Header;
+use holochain_zome_types::Judged;
+use holochain_zome_types::SignedHeader;
+use holochain_zome_types::ValidationStatus;
+
+#[derive(Debug, Clone)]
+pub struct GetMetaQuery {
+    hash: AnyDhtHash,
+    request: MetadataRequest,
+}
+
+impl GetMetaQuery {
+    pub fn new(hash: AnyDhtHash, request: MetadataRequest) -> Self {
+        Self { hash, request }
+    }
+}
+
+pub struct Item {
+    op_type: DhtOpType,
+    header: Header,
+    header_hash: HeaderHash,
+}
+
+#[derive(Debug, Default)]
+pub struct State {
+    headers: BTreeSet<TimedHeaderHash>,
+    invalid_headers: BTreeSet<TimedHeaderHash>,
+    any_rejected: bool,
+    deletes: BTreeSet<TimedHeaderHash>,
+    deleted_headers: HashSet<HeaderHash>,
+    updates: BTreeSet<TimedHeaderHash>,
+}
+
+impl Query for GetMetaQuery {
+    type Item = Judged<Item>;
+    type State = State;
+    type Output = MetadataSet;
+
+    fn query(&self) -> String {
+        // Entry and header hashes never collide so the basis
+        // alone picks out the right set of op types.
+        "
+        SELECT Header.blob AS header_blob, Header.hash AS header_hash,
+        DhtOp.type AS dht_type, DhtOp.validation_status AS status
+        FROM DhtOp
+        JOIN Header On DhtOp.header_hash = Header.hash
+        WHERE DhtOp.type IN (
+            :store_entry, :store_element,
+            :delete_entry, :delete_element,
+            :update_entry, :update_element
+        )
+        AND
+        DhtOp.basis_hash = :hash
+        AND
+        DhtOp.when_integrated IS NOT NULL
+        "
+        .into()
+    }
+
+    fn params(&self) -> Vec<Params> {
+        let params = named_params! {
+            ":store_entry": DhtOpType::StoreEntry,
+            ":store_element": DhtOpType::StoreElement,
+            ":delete_entry": DhtOpType::RegisterDeletedEntryHeader,
+            ":delete_element": DhtOpType::RegisterDeletedBy,
+            ":update_entry": DhtOpType::RegisterUpdatedContent,
+            ":update_element": DhtOpType::RegisterUpdatedElement,
+            ":hash": self.hash,
+        };
+        params.to_vec()
+    }
+
+    fn as_map(&self) -> Arc<dyn Fn(&Row) -> StateQueryResult<Self::Item>> {
+        let f = |row: &Row| {
+            let SignedHeader(header, _) =
+                from_blob::<SignedHeader>(row.get(row.column_index("header_blob")?)?)?;
+            let header_hash = row.get(row.column_index("header_hash")?)?;
+            let op_type = row.get(row.column_index("dht_type")?)?;
+            let validation_status = row.get(row.column_index("status")?)?;
+            Ok(Judged::raw(
+                Item {
+                    op_type,
+                    header,
+                    header_hash,
+                },
+                validation_status,
+            ))
+        };
+        Arc::new(f)
+    }
+
+    fn init_fold(&self) -> StateQueryResult<Self::State> {
+        Ok(Default::default())
+    }
+
+    fn fold(&self, mut state: Self::State, dht_op: Self::Item) -> StateQueryResult<Self::State> {
+        let status = dht_op.validation_status();
+        let Item {
+            op_type,
+            header,
+            header_hash,
+        } = dht_op.data;
+        let timed = TimedHeaderHash {
+            timestamp: header.timestamp(),
+            header_hash,
+        };
+        match op_type {
+            DhtOpType::StoreEntry | DhtOpType::StoreElement => match status {
+                Some(ValidationStatus::Valid) => {
+                    state.headers.insert(timed);
+                }
+                Some(ValidationStatus::Rejected) => {
+                    state.any_rejected = true;
+                    state.invalid_headers.insert(timed);
+                }
+                Some(ValidationStatus::Abandoned) => {
+                    state.invalid_headers.insert(timed);
+                }
+                None => (),
+            },
+            DhtOpType::RegisterDeletedEntryHeader | DhtOpType::RegisterDeletedBy => {
+                if let (Some(ValidationStatus::Valid), Header::Delete(delete)) = (status, &header) {
+                    state.deleted_headers.insert(delete.deletes_address.clone());
+                    state.deletes.insert(timed);
+                }
+            }
+            DhtOpType::RegisterUpdatedContent | DhtOpType::RegisterUpdatedElement => {
+                if let Some(ValidationStatus::Valid) = status {
+                    state.updates.insert(timed);
+                }
+            }
+            _ => return Err(StateQueryError::UnexpectedOp(op_type)),
+        }
+        Ok(state)
+    }
+
+    fn render<S>(&self, state: Self::State, _stores: S) -> StateQueryResult<Self::Output>
+    where
+        S: Store,
+    {
+        let entry_dht_status = match *self.hash.hash_type() {
+            AnyDht::Entry if self.request.entry_dht_status => {
+                let live = state
+                    .headers
+                    .iter()
+                    .any(|h| !state.deleted_headers.contains(&h.header_hash));
+                if live {
+                    Some(EntryDhtStatus::Live)
+                } else if !state.headers.is_empty() {
+                    Some(EntryDhtStatus::Dead)
+                } else if state.any_rejected {
+                    Some(EntryDhtStatus::Rejected)
+                } else if !state.invalid_headers.is_empty() {
+                    Some(EntryDhtStatus::Abandoned)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+        let request = &self.request;
+        Ok(MetadataSet {
+            headers: if request.all_valid_headers {
+                state.headers
+            } else {
+                Default::default()
+            },
+            invalid_headers: if request.all_invalid_headers {
+                state.invalid_headers
+            } else {
+                Default::default()
+            },
+            deletes: if request.all_deletes {
+                state.deletes
+            } else {
+                Default::default()
+            },
+            updates: if request.all_updates {
+                state.updates
+            } else {
+                Default::default()
+            },
+            entry_dht_status,
+        })
+    }
+}
@@ -64,6 +64,58 @@ async fn get_entry() {
     assert_eq!(result, expected);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn get_meta() {
+    observability::test_run().ok();
+    let env = test_cell_env();
+
+    let td = EntryTestData::create();
+    let options = holochain_p2p::event::GetMetaOptions {
+        metadata_request: MetadataRequest {
+            entry_dht_status: true,
+            ..Default::default()
+        },
+    };
+    let hashes = |set: &std::collections::BTreeSet<TimedHeaderHash>| {
+        set.iter()
+            .map(|h| h.header_hash.clone())
+            .collect::<Vec<_>>()
+    };
+
+    let result = handle_get_meta(env.env().into(), td.hash.clone().into(), options.clone())
+        .await
+        .unwrap();
+    assert_eq!(result, MetadataSet::default());
+
+    fill_db(&env.env(), td.store_entry_op.clone());
+
+    let result = handle_get_meta(env.env().into(), td.hash.clone().into(), options.clone())
+        .await
+        .unwrap();
+    assert_eq!(hashes(&result.headers), vec![td.create_hash.clone()]);
+    assert!(result.deletes.is_empty());
+    assert!(result.updates.is_empty());
+    assert_eq!(result.entry_dht_status, Some(EntryDhtStatus::Live));
+
+    fill_db(&env.env(), td.update_content_op.clone());
+
+    let result = handle_get_meta(env.env().into(), td.hash.clone().into(), options.clone())
+        .await
+        .unwrap();
+    assert_eq!(hashes(&result.updates), vec![td.update_hash.clone()]);
+    assert_eq!(result.entry_dht_status, Some(EntryDhtStatus::Live));
+
+    fill_db(&env.env(), td.delete_entry_header_op.clone());
+
+    let result = handle_get_meta(env.env().into(), td.hash.clone().into(), options.clone())
+        .await
+        .unwrap();
+    assert_eq!(hashes(&result.headers), vec![td.create_hash.clone()]);
+    assert_eq!(hashes(&result.deletes), vec![td.delete_hash.clone()]);
+    assert_eq!(hashes(&result.updates), vec![td.update_hash.clone()]);
+    assert_eq!(result.entry_dht_status, Some(EntryDhtStatus::Dead));
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn get_element() {
     observability::test_run().ok();
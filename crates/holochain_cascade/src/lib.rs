@@ -12,6 +12,7 @@ use holo_hash::EntryHash;
 use holo_hash::HeaderHash;
 use holochain_p2p::actor::GetActivityOptions;
 use holochain_p2p::actor::GetLinksOptions;
+use holochain_p2p::actor::GetMetaOptions;
 use holochain_p2p::actor::GetOptions as NetworkGetOptions;
 use holochain_p2p::HolochainP2pCell;
 use holochain_p2p::HolochainP2pCellT;
@@ -256,6 +257,16 @@ where
         Ok(())
     }
 
+    #[instrument(skip(self, options))]
+    async fn fetch_meta(
+        &mut self,
+        hash: AnyDhtHash,
+        options: GetMetaOptions,
+    ) -> CascadeResult<Vec<MetadataSet>> {
+        let network = ok_or_return!(self.network.as_mut(), Vec::with_capacity(0));
+        Ok(network.get_meta(hash, options).await?)
+    }
+
     #[instrument(skip(self, options))]
     async fn fetch_agent_activity(
         &mut self,
@@ -598,6 +609,31 @@ where
         }
     }
 
+    #[instrument(skip(self, options))]
+    /// Get the metadata (validation status, updates and deletes)
+    /// for a hash without fetching the content.
+    /// If we are not an authority for the hash the sets
+    /// returned by the authorities are merged with our own.
+    pub async fn get_meta(
+        &mut self,
+        hash: AnyDhtHash,
+        options: GetMetaOptions,
+    ) -> CascadeResult<MetadataSet> {
+        let authority = self.am_i_an_authority(hash.clone()).await?;
+        let mut result = match &self.vault {
+            Some(vault) => {
+                authority::handle_get_meta(vault.clone(), hash.clone(), (&options).into()).await?
+            }
+            None => MetadataSet::default(),
+        };
+        if !authority {
+            for set in self.fetch_meta(hash, options).await? {
+                result.merge(set);
+            }
+        }
+        Ok(result)
+    }
+
     #[instrument(skip(self, options))]
     /// Gets an links from the cas or cache depending on it's metadata
     // The default behavior is to skip deleted or replaced entries.
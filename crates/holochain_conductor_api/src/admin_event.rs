@@ -0,0 +1,72 @@
+//! Events about the lifecycle of a conductor, which admin clients can
+//! subscribe to with [`AdminRequest::SubscribeEvents`].
+//!
+//! [`AdminRequest::SubscribeEvents`]: ../enum.AdminRequest.html#variant.SubscribeEvents
+
+use holochain_serialized_bytes::prelude::*;
+use holochain_types::app::DisabledAppReason;
+use holochain_types::app::InstalledAppId;
+use holochain_types::app::PausedAppReason;
+use holochain_zome_types::cell::CellId;
+
+/// An event in the lifecycle of the conductor, sent as a signal
+/// to every admin interface connection which subscribed to events.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+#[serde(rename_all = "snake_case", tag = "type", content = "data")]
+pub enum AdminEvent {
+    /// An app was paused, and can be started again.
+    AppPaused {
+        /// The app which was paused
+        installed_app_id: InstalledAppId,
+        /// Why the app was paused
+        reason: PausedAppReason,
+    },
+    /// An app was disabled, and must be enabled again to run.
+    AppDisabled {
+        /// The app which was disabled
+        installed_app_id: InstalledAppId,
+        /// Why the app was disabled
+        reason: DisabledAppReason,
+    },
+    /// A cell could not be created because its genesis failed.
+    CellGenesisFailed {
+        /// The cell whose genesis failed
+        cell_id: CellId,
+        /// The genesis error
+        error: String,
+    },
+    /// A cell failed to start and join the network.
+    CellStartupFailed {
+        /// The cell which failed to start
+        cell_id: CellId,
+        /// The startup error
+        error: String,
+    },
+    /// A task managed by the conductor failed.
+    TaskFailed {
+        /// The description of the task
+        context: String,
+        /// The error the task failed with
+        error: String,
+        /// The cell the task belonged to, if any.
+        /// The apps using this cell are stopped.
+        cell_id: Option<CellId>,
+    },
+    /// A cell joined the network.
+    CellJoinedNetwork {
+        /// The cell which joined
+        cell_id: CellId,
+    },
+    /// A cell left the network.
+    CellLeftNetwork {
+        /// The cell which left
+        cell_id: CellId,
+    },
+    /// The connection fell behind and events were dropped before they
+    /// could be sent to it. The client should query the state it
+    /// follows again, e.g. with `ListApps`.
+    EventsMissed {
+        /// How many events were dropped
+        missed: u64,
+    },
+}
@@ -3,6 +3,10 @@ use holochain_types::prelude::*;
 use holochain_zome_types::cell::CellId;
 use kitsune_p2p::agent_store::AgentInfoSigned;
 
+use crate::app_backup::SignedAppBackup;
+use crate::network_metrics::NetworkMetrics;
+use crate::state_dump::{DhtOpDumpFilter, FullStateDump};
+use crate::task_restarts::TaskRestarts;
 use crate::InstalledAppInfo;
 
 /// Represents the available conductor functions to call over an Admin interface
@@ -53,6 +57,43 @@ pub enum AdminRequest {
     /// [`AdminResponse::DnaCloned`]: enum.AdminResponse.html#variant.DnaCloned
     CreateCloneCell(Box<CreateCloneCellPayload>),
 
+    /// Archive a clone cell of an app.
+    ///
+    /// The cell stops running and no longer counts towards the clone limit of
+    /// its slot, but its data is kept so it can be restored later with
+    /// [`AdminRequest::RestoreArchivedCloneCell`] or removed for good with
+    /// [`AdminRequest::DeleteArchivedCloneCell`].
+    ///
+    /// Will be responded to with an [`AdminResponse::CloneCellArchived`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminRequest::RestoreArchivedCloneCell`]: enum.AdminRequest.html#variant.RestoreArchivedCloneCell
+    /// [`AdminRequest::DeleteArchivedCloneCell`]: enum.AdminRequest.html#variant.DeleteArchivedCloneCell
+    /// [`AdminResponse::CloneCellArchived`]: enum.AdminResponse.html#variant.CloneCellArchived
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    ArchiveCloneCell(Box<CloneCellPayload>),
+
+    /// Restore an archived clone cell to its app, if the clone limit allows.
+    /// The cell is started if the app is running.
+    ///
+    /// Will be responded to with an [`AdminResponse::ArchivedCloneCellRestored`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::ArchivedCloneCellRestored`]: enum.AdminResponse.html#variant.ArchivedCloneCellRestored
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    RestoreArchivedCloneCell(Box<CloneCellPayload>),
+
+    /// Permanently delete an archived clone cell.
+    ///
+    /// The cell's source chain, cache and peer info are deleted, so this can't be undone.
+    ///
+    /// Will be responded to with an [`AdminResponse::ArchivedCloneCellDeleted`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::ArchivedCloneCellDeleted`]: enum.AdminResponse.html#variant.ArchivedCloneCellDeleted
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    DeleteArchivedCloneCell(Box<CloneCellPayload>),
+
     /// Install an app from a list of `Dna` paths.
     /// Triggers genesis to be run on all `Cell`s and to be stored.
     /// An `App` is intended for use by
@@ -192,6 +233,20 @@ pub enum AdminRequest {
         installed_app_id: InstalledAppId,
     },
 
+    /// Uninstall the `App` specified by argument `installed_app_id`, removing it from the conductor.
+    /// The cells of the `App` which no other `App` uses are removed, along with their databases
+    /// and their agent info. The DNAs stay registered, so the `App` can be installed again.
+    ///
+    /// Will be responded to with an [`AdminResponse::AppUninstalled`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::AppUninstalled`]: enum.AdminResponse.html#variant.AppUninstalled
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    UninstallApp {
+        /// The InstalledAppId to uninstall
+        installed_app_id: InstalledAppId,
+    },
+
     StartApp {
         /// The InstalledAppId to (re)start
         installed_app_id: InstalledAppId,
@@ -213,6 +268,12 @@ pub enum AdminRequest {
         /// Optional port, use None to let the
         /// OS choose a free port
         port: Option<u16>,
+        /// Optional app to attach the interface for.
+        /// Only this app can make [`AppRequest::Crypto`] requests over it.
+        ///
+        /// [`AppRequest::Crypto`]: enum.AppRequest.html#variant.Crypto
+        #[serde(default)]
+        installed_app_id: Option<InstalledAppId>,
     },
 
     /// List all the app interfaces currently attached with [`AttachAppInterface`].
@@ -231,6 +292,24 @@ pub enum AdminRequest {
         cell_id: Box<CellId>,
     },
 
+    /// Dump the state of the `Cell` specified by argument `cell_id` as a
+    /// typed [`FullStateDump`], with the details of each op it holds and
+    /// has published, and the storage arcs of its peers.
+    ///
+    /// Will be responded to with an [`AdminResponse::FullStateDumped`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`FullStateDump`]: ../state_dump/struct.FullStateDump.html
+    /// [`AdminResponse::Error`]: enum.AppResponse.html#variant.Error
+    /// [`AdminResponse::FullStateDumped`]: enum.AdminResponse.html#variant.FullStateDumped
+    DumpFullState {
+        /// The `CellId` for which to dump state
+        cell_id: Box<CellId>,
+        /// Which ops to include in the dump. All ops are included if `None`.
+        #[serde(default)]
+        dht_ops_filter: Option<DhtOpDumpFilter>,
+    },
+
     /// Add a list [AgentInfoSigned] to this conductor's peer store.
     /// This is another way of finding peers on a dht.
     ///
@@ -257,6 +336,181 @@ pub enum AdminRequest {
         /// Optionally choose a specific agent info
         cell_id: Option<CellId>,
     },
+
+    /// Block an agent from the network space of a DNA.
+    ///
+    /// Their agent info is dropped from the peer store and this conductor
+    /// will no longer gossip with them or answer their requests.
+    /// The conductor also blocks agents by itself once it can prove they
+    /// have broken the rules of the network, e.g. by forking their chain.
+    ///
+    /// Will be responded to with an [`AdminResponse::AgentBlocked`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::AgentBlocked`]: enum.AdminResponse.html#variant.AgentBlocked
+    /// [`AdminResponse::Error`]: enum.AdminResponse.html#variant.Error
+    BlockAgent {
+        /// The DNA whose space the agent is blocked from
+        dna_hash: DnaHash,
+        /// The agent to block
+        agent: AgentPubKey,
+    },
+
+    /// Lift a block on an agent, whether it was added with
+    /// [`AdminRequest::BlockAgent`] or by the conductor itself.
+    ///
+    /// Will be responded to with an [`AdminResponse::AgentUnblocked`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminRequest::BlockAgent`]: enum.AdminRequest.html#variant.BlockAgent
+    /// [`AdminResponse::AgentUnblocked`]: enum.AdminResponse.html#variant.AgentUnblocked
+    /// [`AdminResponse::Error`]: enum.AdminResponse.html#variant.Error
+    UnblockAgent {
+        /// The DNA whose space the agent was blocked from
+        dna_hash: DnaHash,
+        /// The agent to unblock
+        agent: AgentPubKey,
+    },
+
+    /// List the agents blocked from the network space of a DNA.
+    ///
+    /// Will be responded to with an [`AdminResponse::BlockedAgentsListed`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::BlockedAgentsListed`]: enum.AdminResponse.html#variant.BlockedAgentsListed
+    /// [`AdminResponse::Error`]: enum.AdminResponse.html#variant.Error
+    ListBlockedAgents {
+        /// The DNA to list blocked agents for
+        dna_hash: DnaHash,
+    },
+
+    /// Get the network metrics of the spaces of this conductor, with the
+    /// latency, traffic and gossip recorded for each remote agent.
+    ///
+    /// Will be responded to with an [`AdminResponse::NetworkMetrics`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::NetworkMetrics`]: enum.AdminResponse.html#variant.NetworkMetrics
+    /// [`AdminResponse::Error`]: enum.AdminResponse.html#variant.Error
+    NetworkMetrics {
+        /// Only get the metrics of this DNA's space.
+        /// The metrics of every running DNA are returned if `None`.
+        #[serde(default)]
+        dna_hash: Option<DnaHash>,
+    },
+
+    /// Revoke an agent key in the conductor's DPKI key registry.
+    /// Anything signed with the key from now on will fail validation.
+    ///
+    /// Will be responded to with an [`AdminResponse::AgentKeyRevoked`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::AgentKeyRevoked`]: enum.AdminResponse.html#variant.AgentKeyRevoked
+    /// [`AdminResponse::Error`]: enum.AdminResponse.html#variant.Error
+    RevokeAgentKey {
+        /// The key to revoke
+        agent_key: AgentPubKey,
+    },
+
+    /// Move an app onto a newly generated agent key.
+    ///
+    /// Each of the app's source chains is closed and continued on a new chain
+    /// for the new key. The app's `migrate_agent` callbacks are run first,
+    /// and any of them can refuse the migration. An enabled app is disabled
+    /// while its chains are migrated.
+    ///
+    /// Will be responded to with an [`AdminResponse::AppAgentKeyRotated`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::AppAgentKeyRotated`]: enum.AdminResponse.html#variant.AppAgentKeyRotated
+    /// [`AdminResponse::Error`]: enum.AdminResponse.html#variant.Error
+    RotateAppAgentKey {
+        /// The app to move onto a new key
+        installed_app_id: InstalledAppId,
+    },
+
+    /// Upgrade an installed app to a new version from an [`AppBundle`].
+    ///
+    /// The DNAs of the bundle are installed. Each slot whose DNA changed is
+    /// migrated to a new `Cell`: the old source chain is closed and the agent
+    /// carried over to a new chain, once the `migrate_agent` callbacks agree.
+    /// This is only allowed if the slot's version spec in the new manifest
+    /// includes the DNA being replaced. The old `Cell`s keep running, so their
+    /// data can still be read, until [`AdminRequest::ConfirmAppUpgrade`] is called.
+    ///
+    /// Will be responded to with an [`AdminResponse::AppUpgraded`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AppBundle`]: ../../../holochain_types/app/struct.AppBundle.html
+    /// [`AdminRequest::ConfirmAppUpgrade`]: enum.AdminRequest.html#variant.ConfirmAppUpgrade
+    /// [`AdminResponse::AppUpgraded`]: enum.AdminResponse.html#variant.AppUpgraded
+    /// [`AdminResponse::Error`]: enum.AdminResponse.html#variant.Error
+    UpgradeApp(Box<UpgradeAppPayload>),
+
+    /// Confirm the pending upgrade of an app, dropping the `Cell`s it replaced.
+    ///
+    /// Will be responded to with an [`AdminResponse::AppUpgradeConfirmed`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::AppUpgradeConfirmed`]: enum.AdminResponse.html#variant.AppUpgradeConfirmed
+    /// [`AdminResponse::Error`]: enum.AdminResponse.html#variant.Error
+    ConfirmAppUpgrade {
+        /// The app whose upgrade to confirm
+        installed_app_id: InstalledAppId,
+    },
+    /// Subscribe this connection to the conductor's [`AdminEvent`]s.
+    ///
+    /// From then on, each event is sent to this connection as a signal.
+    /// The subscription lasts until the connection is closed.
+    ///
+    /// Will be responded to with an [`AdminResponse::EventsSubscribed`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminEvent`]: ../admin_event/enum.AdminEvent.html
+    /// [`AdminResponse::EventsSubscribed`]: enum.AdminResponse.html#variant.EventsSubscribed
+    /// [`AdminResponse::Error`]: enum.AdminResponse.html#variant.Error
+    SubscribeEvents,
+
+    /// List the managed tasks which the conductor restarted after they failed,
+    /// with how many times each was restarted.
+    ///
+    /// Will be responded to with an [`AdminResponse::TaskRestartsListed`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`AdminResponse::TaskRestartsListed`]: enum.AdminResponse.html#variant.TaskRestartsListed
+    /// [`AdminResponse::Error`]: enum.AdminResponse.html#variant.Error
+    ListTaskRestarts,
+
+    /// Back up the source chains authored by the cells of an installed app,
+    /// along with the app itself, into a [`SignedAppBackup`] signed by the app's agent.
+    ///
+    /// Will be responded to with an [`AdminResponse::AppBackedUp`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`SignedAppBackup`]: ../app_backup/struct.SignedAppBackup.html
+    /// [`AdminResponse::AppBackedUp`]: enum.AdminResponse.html#variant.AppBackedUp
+    /// [`AdminResponse::Error`]: enum.AdminResponse.html#variant.Error
+    BackupApp {
+        /// The app to back up
+        installed_app_id: InstalledAppId,
+    },
+
+    /// Install an app again from a [`SignedAppBackup`], continuing the chains it
+    /// authored rather than running genesis. The app's agent key must be held
+    /// by this conductor's keystore, and its DNAs must already be registered.
+    /// The restored app is disabled, and once enabled its cells publish
+    /// their authored ops again.
+    ///
+    /// The backup doesn't record the clone limit of each slot, so it is set
+    /// to the number of clones, archived ones included, which the slot had.
+    ///
+    /// Will be responded to with an [`AdminResponse::AppRestored`]
+    /// or an [`AdminResponse::Error`]
+    ///
+    /// [`SignedAppBackup`]: ../app_backup/struct.SignedAppBackup.html
+    /// [`AdminResponse::AppRestored`]: enum.AdminResponse.html#variant.AppRestored
+    /// [`AdminResponse::Error`]: enum.AdminResponse.html#variant.Error
+    RestoreApp(Box<SignedAppBackup>),
 }
 
 /// Represents the possible responses to an [`AdminRequest`]
@@ -318,6 +572,21 @@ pub enum AdminResponse {
     /// [`CellId`]: ../../../holochain_types/cell/struct.CellId.html
     CloneCellCreated(CellId),
 
+    /// The successful response to an [`AdminRequest::ArchiveCloneCell`].
+    ///
+    /// [`AdminRequest::ArchiveCloneCell`]: enum.AdminRequest.html#variant.ArchiveCloneCell
+    CloneCellArchived,
+
+    /// The successful response to an [`AdminRequest::RestoreArchivedCloneCell`].
+    ///
+    /// [`AdminRequest::RestoreArchivedCloneCell`]: enum.AdminRequest.html#variant.RestoreArchivedCloneCell
+    ArchivedCloneCellRestored,
+
+    /// The successful response to an [`AdminRequest::DeleteArchivedCloneCell`].
+    ///
+    /// [`AdminRequest::DeleteArchivedCloneCell`]: enum.AdminRequest.html#variant.DeleteArchivedCloneCell
+    ArchivedCloneCellDeleted,
+
     /// The succesful response to an [`AdminRequest::AddAdminInterfaces`].
     ///
     /// It means the `AdminInterface`s have successfully been added
@@ -416,6 +685,13 @@ pub enum AdminResponse {
     #[deprecated = "alias for AppDisabled"]
     AppDeactivated,
 
+    /// The succesful response to an [`AdminRequest::UninstallApp`].
+    ///
+    /// It means the `App` and its data were removed successfully.
+    ///
+    /// [`AdminRequest::UninstallApp`]: enum.AdminRequest.html#variant.UninstallApp
+    AppUninstalled,
+
     /// The succesful response to an [`AdminRequest::DumpState`].
     ///
     /// The result contains a string of serialized JSON data which can be deserialized to access the
@@ -424,6 +700,11 @@ pub enum AdminResponse {
     /// [`AdminRequest::DumpState`]: enum.AdminRequest.html#variant.DumpState
     StateDumped(String),
 
+    /// The succesful response to an [`AdminRequest::DumpFullState`].
+    ///
+    /// [`AdminRequest::DumpFullState`]: enum.AdminRequest.html#variant.DumpFullState
+    FullStateDumped(Box<FullStateDump>),
+
     /// The succesful response to an [`AdminRequest::AddAgentInfo`].
     ///
     /// This means the agent info was successfully added to the peer store.
@@ -437,6 +718,78 @@ pub enum AdminResponse {
     ///
     /// [`AdminRequest::RequestAgentInfo`]: enum.AdminRequest.html#variant.RequestAgentInfo
     AgentInfoRequested(Vec<AgentInfoSigned>),
+
+    /// The succesful response to an [`AdminRequest::BlockAgent`].
+    ///
+    /// [`AdminRequest::BlockAgent`]: enum.AdminRequest.html#variant.BlockAgent
+    AgentBlocked,
+
+    /// The succesful response to an [`AdminRequest::UnblockAgent`].
+    ///
+    /// [`AdminRequest::UnblockAgent`]: enum.AdminRequest.html#variant.UnblockAgent
+    AgentUnblocked,
+
+    /// The succesful response to an [`AdminRequest::ListBlockedAgents`].
+    ///
+    /// Contains every agent currently blocked from the DNA's space.
+    ///
+    /// [`AdminRequest::ListBlockedAgents`]: enum.AdminRequest.html#variant.ListBlockedAgents
+    BlockedAgentsListed(Vec<AgentPubKey>),
+
+    /// The succesful response to an [`AdminRequest::NetworkMetrics`].
+    ///
+    /// Contains the metrics of each space.
+    ///
+    /// [`AdminRequest::NetworkMetrics`]: enum.AdminRequest.html#variant.NetworkMetrics
+    NetworkMetrics(Vec<NetworkMetrics>),
+
+    /// The succesful response to an [`AdminRequest::RevokeAgentKey`].
+    ///
+    /// [`AdminRequest::RevokeAgentKey`]: enum.AdminRequest.html#variant.RevokeAgentKey
+    AgentKeyRevoked,
+
+    /// The succesful response to an [`AdminRequest::RotateAppAgentKey`].
+    ///
+    /// Contains the app's new agent key.
+    ///
+    /// [`AdminRequest::RotateAppAgentKey`]: enum.AdminRequest.html#variant.RotateAppAgentKey
+    AppAgentKeyRotated(AgentPubKey),
+
+    /// The succesful response to an [`AdminRequest::UpgradeApp`].
+    ///
+    /// The resulting [`InstalledAppInfo`] lists the new `Cell`s of the app,
+    /// along with the `Cell`s they replaced.
+    ///
+    /// [`AdminRequest::UpgradeApp`]: enum.AdminRequest.html#variant.UpgradeApp
+    /// [`InstalledAppInfo`]: ../../../holochain_types/app/struct.InstalledAppInfo.html
+    AppUpgraded(InstalledAppInfo),
+
+    /// The succesful response to an [`AdminRequest::ConfirmAppUpgrade`].
+    ///
+    /// [`AdminRequest::ConfirmAppUpgrade`]: enum.AdminRequest.html#variant.ConfirmAppUpgrade
+    AppUpgradeConfirmed,
+
+    /// The succesful response to an [`AdminRequest::SubscribeEvents`].
+    ///
+    /// [`AdminRequest::SubscribeEvents`]: enum.AdminRequest.html#variant.SubscribeEvents
+    EventsSubscribed,
+
+    /// The succesful response to an [`AdminRequest::ListTaskRestarts`].
+    ///
+    /// [`AdminRequest::ListTaskRestarts`]: enum.AdminRequest.html#variant.ListTaskRestarts
+    TaskRestartsListed(Vec<TaskRestarts>),
+
+    /// The succesful response to an [`AdminRequest::BackupApp`].
+    ///
+    /// [`AdminRequest::BackupApp`]: enum.AdminRequest.html#variant.BackupApp
+    AppBackedUp(Box<SignedAppBackup>),
+
+    /// The succesful response to an [`AdminRequest::RestoreApp`].
+    ///
+    /// Contains the restored app, which is disabled.
+    ///
+    /// [`AdminRequest::RestoreApp`]: enum.AdminRequest.html#variant.RestoreApp
+    AppRestored(InstalledAppInfo),
 }
 
 /// Error type that goes over the websocket wire.
@@ -0,0 +1,57 @@
+//! Backups of the source chains an app authored, as made by
+//! [`AdminRequest::BackupApp`] and restored by [`AdminRequest::RestoreApp`].
+//!
+//! [`AdminRequest::BackupApp`]: ../enum.AdminRequest.html#variant.BackupApp
+//! [`AdminRequest::RestoreApp`]: ../enum.AdminRequest.html#variant.RestoreApp
+
+use crate::InstalledAppInfo;
+use holo_hash::AgentPubKey;
+use holochain_serialized_bytes::prelude::*;
+use holochain_zome_types::cell::CellId;
+use holochain_zome_types::element::SignedHeader;
+use holochain_zome_types::signature::Signature;
+use holochain_zome_types::Entry;
+
+/// The source chain a cell of an app authored.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CellChainBackup {
+    /// The cell whose chain this is
+    pub cell_id: CellId,
+    /// The signed headers of the chain, in order
+    pub headers: Vec<SignedHeader>,
+    /// The entries of the chain, private entries included
+    pub entries: Vec<Entry>,
+}
+
+/// Everything needed to install an app again with the chains it authored.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct AppBackup {
+    /// The agent key of the app, which signs the backup
+    pub agent_key: AgentPubKey,
+    /// The app as it was installed
+    pub app: InstalledAppInfo,
+    /// The chain of each of the app's cells, archived clones included
+    pub chains: Vec<CellChainBackup>,
+}
+
+/// An [`AppBackup`] along with the signature of its agent.
+/// The backup is kept serialized so the signature can be checked
+/// against the exact bytes which were signed.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct SignedAppBackup {
+    /// The serialized [`AppBackup`]
+    pub backup: SerializedBytes,
+    /// The signature of the app's agent over the tagged serialized backup
+    pub signature: Signature,
+}
+
+impl SignedAppBackup {
+    /// Prepended to the serialized backup before signing it, so the signature
+    /// can't be passed off as one over a header or any other data.
+    pub const SIGNATURE_TAG: &'static [u8] = b"holochain app backup\0";
+
+    /// The data the agent signs for a serialized backup
+    pub fn signed_data(backup: &SerializedBytes) -> Vec<u8> {
+        [Self::SIGNATURE_TAG, backup.bytes().as_slice()].concat()
+    }
+}
@@ -20,10 +20,15 @@ pub enum AppRequest {
         /// The InstalledAppId for which to get information
         installed_app_id: InstalledAppId,
     },
-    /// Asks the conductor to do some crypto.
+    /// Asks the conductor to do some crypto for a running app.
+    /// See [`CryptoRequest`] for the available operations.
     ///
-    /// Is currently unimplemented and will return
-    /// an [`AppResponse::Unimplemented`](enum.AppResponse.html#variant.Unimplemented)
+    /// Will be responded to with an [`AppResponse::Crypto`]
+    /// or an [`AppResponse::Error`]
+    ///
+    /// [`CryptoRequest`]: enum.CryptoRequest.html
+    /// [`AppResponse::Crypto`]: enum.AppResponse.html#variant.Crypto
+    /// [`AppResponse::Error`]: enum.AppResponse.html#variant.Error
     Crypto(Box<CryptoRequest>),
     /// Call a zome function. See the inner [`ZomeCall`]
     /// struct to understand the data that must be provided.
@@ -39,10 +44,17 @@ pub enum AppRequest {
     /// DEPRECATED. Use `ZomeCall`.
     ZomeCallInvocation(Box<ZomeCall>),
 
-    /// Update signal subscriptions.
+    /// Update the signal subscription of this connection for an app.
+    ///
+    /// Once a connection has subscribed to any app, it only receives the
+    /// signals of those apps' Cells which pass the subscription's filters.
+    /// A connection without subscriptions receives every signal.
+    ///
+    /// Will be responded to with an [`AppResponse::SignalSubscriptionUpdated`]
+    /// or an [`AppResponse::Error`]
     ///
-    /// Is currently unimplemented and will return
-    /// an [`AppResponse::Unimplemented`](enum.AppResponse.html#variant.Unimplemented)
+    /// [`AppResponse::SignalSubscriptionUpdated`]: enum.AppResponse.html#variant.SignalSubscriptionUpdated
+    /// [`AppResponse::Error`]: enum.AppResponse.html#variant.Error
     SignalSubscription(SignalSubscription),
 }
 
@@ -83,6 +95,16 @@ pub enum AppResponse {
 
     /// DEPRECATED. See `ZomeCall`.
     ZomeCallInvocation(Box<ExternIO>),
+
+    /// The successful response to an [`AppRequest::Crypto`].
+    ///
+    /// [`AppRequest::Crypto`]: enum.AppRequest.html#variant.Crypto
+    Crypto(Box<CryptoResponse>),
+
+    /// The successful response to an [`AppRequest::SignalSubscription`].
+    ///
+    /// [`AppRequest::SignalSubscription`]: enum.AppRequest.html#variant.SignalSubscription
+    SignalSubscriptionUpdated,
 }
 
 /// The data provided across an App interface in order to make a zome call
@@ -109,13 +131,108 @@ pub struct ZomeCall {
     pub provenance: AgentPubKey,
 }
 
-#[allow(missing_docs)]
+/// The crypto operations available to an app over an App interface.
+///
+/// The app must be installed and running, and the interface must have been
+/// attached for the app. An app can only use the X25519 keys it created
+/// with [`CryptoRequest::CreateX25519Keypair`].
+///
+/// [`CryptoRequest::CreateX25519Keypair`]: enum.CryptoRequest.html#variant.CreateX25519Keypair
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case", tag = "type", content = "data")]
 pub enum CryptoRequest {
-    Sign(String),
-    Decrypt(String),
-    Encrypt(String),
+    /// Sign some data with the agent key of the app.
+    /// What is signed is the data prefixed with [`CryptoRequest::SIGNATURE_TAG`],
+    /// as given by [`CryptoRequest::signed_data`], so verify the signature against that.
+    ///
+    /// Will be responded to with a [`CryptoResponse::Signature`]
+    ///
+    /// [`CryptoResponse::Signature`]: enum.CryptoResponse.html#variant.Signature
+    Sign {
+        /// The app whose agent key signs the data
+        installed_app_id: InstalledAppId,
+        /// The data to sign
+        data: Bytes,
+    },
+    /// Create a new X25519 keypair in the keystore for the app,
+    /// like the `create_x25519_keypair` host fn.
+    ///
+    /// Will be responded to with a [`CryptoResponse::X25519PubKey`]
+    ///
+    /// [`CryptoResponse::X25519PubKey`]: enum.CryptoResponse.html#variant.X25519PubKey
+    CreateX25519Keypair {
+        /// The app the keypair is created for
+        installed_app_id: InstalledAppId,
+    },
+    /// Encrypt some data from one of the app's X25519 keys to a recipient,
+    /// like the `x_25519_x_salsa20_poly1305_encrypt` host fn.
+    ///
+    /// Will be responded to with a [`CryptoResponse::Encrypted`]
+    ///
+    /// [`CryptoResponse::Encrypted`]: enum.CryptoResponse.html#variant.Encrypted
+    Encrypt {
+        /// The app the data is encrypted for
+        installed_app_id: InstalledAppId,
+        /// The keys and the data to encrypt
+        payload: X25519XSalsa20Poly1305Encrypt,
+    },
+    /// Decrypt some data sent to one of the app's X25519 keys,
+    /// like the `x_25519_x_salsa20_poly1305_decrypt` host fn.
+    ///
+    /// Will be responded to with a [`CryptoResponse::Decrypted`]
+    ///
+    /// [`CryptoResponse::Decrypted`]: enum.CryptoResponse.html#variant.Decrypted
+    Decrypt {
+        /// The app the data is decrypted for
+        installed_app_id: InstalledAppId,
+        /// The keys and the data to decrypt
+        payload: X25519XSalsa20Poly1305Decrypt,
+    },
+}
+
+impl CryptoRequest {
+    /// Prepended to the data of a [`CryptoRequest::Sign`] before signing it, so
+    /// a client can't get a signature which passes for one over a header or any
+    /// other data the agent signs.
+    pub const SIGNATURE_TAG: &'static [u8] = b"holochain app crypto\0";
+
+    /// The data the agent signs for a [`CryptoRequest::Sign`] of the given data
+    pub fn signed_data(data: &[u8]) -> Vec<u8> {
+        [Self::SIGNATURE_TAG, data].concat()
+    }
+
+    /// The app this request is made for
+    pub fn installed_app_id(&self) -> &InstalledAppId {
+        match self {
+            Self::Sign {
+                installed_app_id, ..
+            }
+            | Self::CreateX25519Keypair { installed_app_id }
+            | Self::Encrypt {
+                installed_app_id, ..
+            }
+            | Self::Decrypt {
+                installed_app_id, ..
+            } => installed_app_id,
+        }
+    }
+}
+
+/// The results of a [`CryptoRequest`], returned as part of [`AppResponse::Crypto`]
+///
+/// [`CryptoRequest`]: enum.CryptoRequest.html
+/// [`AppResponse::Crypto`]: enum.AppResponse.html#variant.Crypto
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type", content = "data")]
+pub enum CryptoResponse {
+    /// The signature of the data by the app's agent key
+    Signature(Signature),
+    /// The public key of the new X25519 keypair
+    X25519PubKey(X25519PubKey),
+    /// The encrypted data
+    Encrypted(XSalsa20Poly1305EncryptedData),
+    /// The decrypted data, or `None` if the data could not be decrypted
+    Decrypted(Option<XSalsa20Poly1305Data>),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
@@ -125,6 +242,13 @@ pub struct InstalledAppInfo {
     pub installed_app_id: InstalledAppId,
     /// Info about the Cells installed in this app
     pub cell_data: Vec<InstalledCell>,
+    /// Info about the clone Cells of this app, with the slot each was cloned from
+    pub clone_cells: Vec<InstalledCell>,
+    /// Info about the archived clone Cells of this app, which are not running
+    pub archived_clone_cells: Vec<InstalledCell>,
+    /// Info about the Cells replaced by a pending upgrade, with the slot each
+    /// was in. They keep running until the upgrade is confirmed.
+    pub upgraded_cells: Vec<InstalledCell>,
     /// The app's current status, in an API-friendly format
     pub status: InstalledAppInfoStatus,
 }
@@ -137,9 +261,24 @@ impl InstalledAppInfo {
             .provisioned_cells()
             .map(|(nick, id)| InstalledCell::new(id.clone(), nick.clone()))
             .collect();
+        let clone_cells = app
+            .cloned_cells_by_slot()
+            .map(|(nick, id)| InstalledCell::new(id.clone(), nick.clone()))
+            .collect();
+        let archived_clone_cells = app
+            .archived_cells_by_slot()
+            .map(|(nick, id)| InstalledCell::new(id.clone(), nick.clone()))
+            .collect();
+        let upgraded_cells = app
+            .upgraded_cells()
+            .map(|(nick, id)| InstalledCell::new(id.clone(), nick.clone()))
+            .collect();
         Self {
             installed_app_id,
             cell_data,
+            clone_cells,
+            archived_clone_cells,
+            upgraded_cells,
             status,
         }
     }
@@ -9,8 +9,10 @@ mod admin_interface_config;
 mod dpki_config;
 #[allow(missing_docs)]
 mod error;
+mod metrics_config;
 mod passphrase_service_config;
 pub mod paths;
+mod supervision_config;
 //mod logger_config;
 //mod signal_config;
 pub use paths::EnvironmentRootPath;
@@ -19,7 +21,10 @@ pub use super::*;
 pub use dpki_config::DpkiConfig;
 //pub use logger_config::LoggerConfig;
 pub use error::*;
+pub use metrics_config::MetricsConfig;
 pub use passphrase_service_config::PassphraseServiceConfig;
+pub use supervision_config::SupervisionPolicy;
+pub use supervision_config::TaskSupervisionConfig;
 //pub use signal_config::SignalConfig;
 use std::path::Path;
 use std::path::PathBuf;
@@ -57,6 +62,18 @@ pub struct ConductorConfig {
 
     /// Config options for the network module. Optional.
     pub network: Option<holochain_p2p::kitsune_p2p::KitsuneP2pConfig>,
+
+    /// Optional endpoint serving the conductor's metrics for scraping.
+    pub metrics: Option<MetricsConfig>,
+
+    /// How the conductor reacts to the failure of the tasks it manages.
+    /// Each kind of task gets its default policy if omitted.
+    pub task_supervision: Option<TaskSupervisionConfig>,
+
+    /// The longest a zome call, along with any calls it makes to other zomes,
+    /// may wait on host functions like `sleep`.
+    /// Defaults to [DEFAULT_ZOME_CALL_TIMEOUT_S] if omitted.
+    pub zome_call_timeout_s: Option<u64>,
     //
     //
     // /// Which signals to emit
@@ -64,6 +81,11 @@ pub struct ConductorConfig {
     // pub signals: SignalConfig,
 }
 
+/// The default for [ConductorConfig::zome_call_timeout_s].
+/// App interface clients stop waiting for a response after 30 seconds by default,
+/// so there is no point in a zome call waiting longer than that.
+pub const DEFAULT_ZOME_CALL_TIMEOUT_S: u64 = 30;
+
 /// helper fnction function to load a `Config` from a yaml string.
 fn config_from_yaml<T>(yaml: &str) -> ConductorConfigResult<T>
 where
@@ -83,6 +105,14 @@ impl ConductorConfig {
         })?;
         config_from_yaml(&config_yaml)
     }
+
+    /// The longest a zome call may wait on host functions like `sleep`
+    pub fn zome_call_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(
+            self.zome_call_timeout_s
+                .unwrap_or(DEFAULT_ZOME_CALL_TIMEOUT_S),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -132,6 +162,9 @@ pub mod tests {
                 keystore_path: None,
                 admin_interfaces: None,
                 use_dangerous_test_keystore: false,
+                metrics: None,
+                task_supervision: None,
+                zome_call_timeout_s: None,
             }
         );
     }
@@ -180,6 +213,21 @@ pub mod tests {
         proxy_keepalive_ms: 42
         proxy_to_expire_ms: 42
       network_type: quic_bootstrap
+
+    metrics:
+      port: 9100
+      bind_address: 0.0.0.0
+
+    task_supervision:
+      queue_consumers:
+        type: restart
+        max_restarts: 3
+        backoff_ms: 100
+        max_backoff_ms: 1000
+      app_interfaces:
+        type: shutdown
+
+    zome_call_timeout_s: 60
     "#;
         let result: ConductorConfigResult<ConductorConfig> = config_from_yaml(yaml);
         use holochain_p2p::kitsune_p2p::*;
@@ -223,6 +271,21 @@ pub mod tests {
                     driver: InterfaceDriver::Websocket { port: 1234 }
                 }]),
                 network: Some(network_config),
+                metrics: Some(MetricsConfig {
+                    port: 9100,
+                    bind_address: Some([0, 0, 0, 0].into()),
+                }),
+                task_supervision: Some(TaskSupervisionConfig {
+                    queue_consumers: SupervisionPolicy::Restart {
+                        max_restarts: 3,
+                        backoff_ms: 100,
+                        max_backoff_ms: 1000,
+                        reset_after_ms: 600_000,
+                    },
+                    app_interfaces: SupervisionPolicy::Shutdown,
+                    ..Default::default()
+                }),
+                zome_call_timeout_s: Some(60),
             }
         );
     }
@@ -251,6 +314,9 @@ pub mod tests {
                 keystore_path: Some(PathBuf::from("/path/to/keystore").into()),
                 admin_interfaces: None,
                 use_dangerous_test_keystore: true,
+                metrics: None,
+                task_supervision: None,
+                zome_call_timeout_s: None,
             }
         );
     }
@@ -8,6 +8,8 @@ use serde::Serialize;
 /// as well as what parameters to pass it on its initialization
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
 pub struct DpkiConfig {
+    /// The installed app id of the DPKI app. Its first cell is used as
+    /// the conductor's key registry once the app is installed.
     pub instance_id: String,
     pub init_params: String,
 }
@@ -0,0 +1,27 @@
+use serde::Deserialize;
+use serde::Serialize;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::SocketAddr;
+
+/// Configure an HTTP endpoint which serves the conductor's metrics in the
+/// OpenMetrics text format at the path `/metrics`, for scraping by a
+/// monitoring system such as Prometheus.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct MetricsConfig {
+    /// The port to listen on. Use 0 to listen on any free port.
+    pub port: u16,
+    /// The address to listen on. Defaults to localhost, so the endpoint
+    /// can only be scraped from other hosts if this is set.
+    pub bind_address: Option<IpAddr>,
+}
+
+impl MetricsConfig {
+    /// The socket address to listen on.
+    pub fn socket_addr(&self) -> SocketAddr {
+        let ip = self
+            .bind_address
+            .unwrap_or_else(|| Ipv4Addr::LOCALHOST.into());
+        SocketAddr::new(ip, self.port)
+    }
+}
@@ -0,0 +1,128 @@
+use serde::Deserialize;
+use serde::Serialize;
+use std::time::Duration;
+
+/// How the conductor reacts to the failure of each kind of task it manages.
+/// Any kind which is left out gets its default policy.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+#[serde(default)]
+pub struct TaskSupervisionConfig {
+    /// The policy for the queue consumers which run the workflows of a cell.
+    /// Defaults to stopping the apps which use the cell.
+    pub queue_consumers: SupervisionPolicy,
+    /// The policy for app interfaces. Defaults to restarting them.
+    pub app_interfaces: SupervisionPolicy,
+    /// The policy for admin interfaces. Defaults to restarting them.
+    pub admin_interfaces: SupervisionPolicy,
+}
+
+impl Default for TaskSupervisionConfig {
+    fn default() -> Self {
+        Self {
+            queue_consumers: SupervisionPolicy::StopApps,
+            app_interfaces: SupervisionPolicy::default_restart(),
+            admin_interfaces: SupervisionPolicy::default_restart(),
+        }
+    }
+}
+
+/// What to do when a managed task fails.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum SupervisionPolicy {
+    /// Restart the task after a backoff, which doubles with each restart
+    /// up to `max_backoff_ms`. Once the task has been restarted `max_restarts`
+    /// times, a further failure is handled as with `StopApps`.
+    /// A task which runs for `reset_after_ms` without failing starts
+    /// counting its restarts again from zero.
+    ///
+    /// The queue consumers of a cell can't be restarted one at a time,
+    /// so the cell is removed and created again instead.
+    Restart {
+        /// How many times the task may be restarted
+        max_restarts: u32,
+        /// The backoff before the first restart, in milliseconds
+        backoff_ms: u64,
+        /// The longest backoff between restarts, in milliseconds
+        max_backoff_ms: u64,
+        /// How long the task must run without failing for its restarts
+        /// to be forgotten, in milliseconds. Defaults to ten minutes.
+        #[serde(default = "SupervisionPolicy::default_reset_after_ms")]
+        reset_after_ms: u64,
+    },
+    /// Pause the apps which use the task's cell if the error is recoverable,
+    /// or disable them if it is not.
+    /// The failure of a task which doesn't belong to a cell is only logged.
+    StopApps,
+    /// Shut the conductor down.
+    Shutdown,
+}
+
+impl SupervisionPolicy {
+    fn default_restart() -> Self {
+        Self::Restart {
+            max_restarts: 10,
+            backoff_ms: 100,
+            max_backoff_ms: 30_000,
+            reset_after_ms: Self::default_reset_after_ms(),
+        }
+    }
+
+    fn default_reset_after_ms() -> u64 {
+        600_000
+    }
+
+    /// How long a task must run without failing for its restarts to be
+    /// forgotten, if it is restarted at all.
+    pub fn reset_after(&self) -> Option<Duration> {
+        match self {
+            Self::Restart { reset_after_ms, .. } => Some(Duration::from_millis(*reset_after_ms)),
+            _ => None,
+        }
+    }
+
+    /// The backoff before restarting a task which was restarted
+    /// `restarts` times already, if the task should be restarted.
+    pub fn restart_backoff(&self, restarts: u32) -> Option<Duration> {
+        match self {
+            Self::Restart {
+                max_restarts,
+                backoff_ms,
+                max_backoff_ms,
+                ..
+            } if restarts < *max_restarts => {
+                let factor = 1u64.checked_shl(restarts).unwrap_or(u64::MAX);
+                let backoff = backoff_ms.saturating_mul(factor).min(*max_backoff_ms);
+                Some(Duration::from_millis(backoff))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restart_backoff_doubles_up_to_max() {
+        let policy = SupervisionPolicy::Restart {
+            max_restarts: 4,
+            backoff_ms: 100,
+            max_backoff_ms: 500,
+            reset_after_ms: 60_000,
+        };
+        let backoffs: Vec<_> = (0..5).map(|n| policy.restart_backoff(n)).collect();
+        assert_eq!(
+            backoffs,
+            vec![
+                Some(Duration::from_millis(100)),
+                Some(Duration::from_millis(200)),
+                Some(Duration::from_millis(400)),
+                Some(Duration::from_millis(500)),
+                None,
+            ]
+        );
+        assert_eq!(SupervisionPolicy::StopApps.restart_backoff(0), None);
+    }
+}
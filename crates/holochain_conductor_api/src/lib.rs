@@ -1,12 +1,20 @@
 #![allow(deprecated)]
 
+pub mod admin_event;
 mod admin_interface;
+pub mod app_backup;
 mod app_interface;
 pub mod config;
+pub mod network_metrics;
 pub mod signal_subscription;
 pub mod state_dump;
+pub mod task_restarts;
 
+pub use admin_event::*;
 pub use admin_interface::*;
+pub use app_backup::*;
 pub use app_interface::*;
 pub use config::*;
+pub use network_metrics::*;
 pub use state_dump::*;
+pub use task_restarts::*;
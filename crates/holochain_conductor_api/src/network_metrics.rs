@@ -0,0 +1,56 @@
+//! Metrics about the network activity of the spaces of a conductor.
+
+use holo_hash::AgentPubKey;
+use holo_hash::DnaHash;
+use holochain_p2p::dht_arc::DhtArc;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// The network metrics of a space, by remote agent.
+pub struct NetworkMetrics {
+    /// The DNA of the space.
+    pub dna_hash: DnaHash,
+    /// The metrics about each agent in the space.
+    pub agents: Vec<AgentNetworkMetrics>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// The network metrics about an agent in a space.
+/// The totals cover what was recorded over the last week.
+pub struct AgentNetworkMetrics {
+    /// The agent.
+    pub agent: AgentPubKey,
+    /// The storage arc the agent is publishing,
+    /// if its agent info is in the peer store.
+    pub storage_arc: Option<DhtArc>,
+    /// The number of requests the agent answered.
+    pub requests: u64,
+    /// The mean round trip time of those requests, in microseconds.
+    pub mean_round_trip_micros: Option<u64>,
+    /// The bytes of requests, publishes and gossip sent to the agent.
+    /// Gossip with a node is shared evenly between its agents.
+    pub bytes_sent: u64,
+    /// The bytes of responses and gossip received from the agent.
+    pub bytes_received: u64,
+    /// The number of gossip rounds with the agent which completed.
+    pub gossip_rounds_complete: u64,
+    /// The number of gossip rounds with the agent which failed.
+    pub gossip_rounds_failed: u64,
+}
+
+impl AgentNetworkMetrics {
+    /// Metrics for an agent which nothing has been recorded about yet.
+    pub fn empty(agent: AgentPubKey) -> Self {
+        Self {
+            agent,
+            storage_arc: None,
+            requests: 0,
+            mean_round_trip_micros: None,
+            bytes_sent: 0,
+            bytes_received: 0,
+            gossip_rounds_complete: 0,
+            gossip_rounds_failed: 0,
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use holochain_serialized_bytes::prelude::*;
 use holochain_types::app::InstalledAppId;
 use holochain_zome_types::cell::CellId;
+use holochain_zome_types::zome::ZomeName;
 use std::collections::HashMap;
+use std::collections::HashSet;
 
 /// Declares updated Signal subscription settings for an App.
 /// This message is part of the AppInterfaceApi
@@ -13,6 +15,26 @@ pub struct SignalSubscription {
     filters: SignalFilterSet,
 }
 
+impl SignalSubscription {
+    /// Constructor
+    pub fn new(installed_app_id: InstalledAppId, filters: SignalFilterSet) -> Self {
+        Self {
+            installed_app_id,
+            filters,
+        }
+    }
+
+    /// Accessor
+    pub fn installed_app_id(&self) -> &InstalledAppId {
+        &self.installed_app_id
+    }
+
+    /// Accessor
+    pub fn filters(&self) -> &SignalFilterSet {
+        &self.filters
+    }
+}
+
 /// Associate a SignalFilter with each Cell in an App.
 /// The filtering can be interpreted as inclusive or exclusive,
 /// depending on the use case.
@@ -46,22 +68,107 @@ impl SignalFilterSet {
     pub fn block_all() -> Self {
         SignalFilterSet::Include(HashMap::new())
     }
-}
-
-/// Specifies fine-grained filter controls for the signals
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
-pub struct SignalFilter;
 
-impl Default for SignalFilter {
-    fn default() -> Self {
-        // The default is no filter
-        Self::empty()
+    /// Whether a signal from the given Cell and zome, with the given tag,
+    /// gets through this filter set
+    pub fn allows(&self, cell_id: &CellId, zome_name: &ZomeName, tag: Option<&str>) -> bool {
+        match self {
+            Self::Include(filters) => filters
+                .get(cell_id)
+                .map(|filter| filter.matches(zome_name, tag))
+                .unwrap_or(false),
+            Self::Exclude(filters) => !filters
+                .get(cell_id)
+                .map(|filter| filter.matches(zome_name, tag))
+                .unwrap_or(false),
+        }
     }
 }
 
+/// Specifies fine-grained filter controls for the signals of a Cell.
+/// A signal matches the filter if it matches every criterion that is set.
+#[derive(
+    Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes,
+)]
+pub struct SignalFilter {
+    /// Only match signals emitted by these zomes.
+    /// If `None`, signals from any zome match.
+    #[serde(default)]
+    pub zomes: Option<HashSet<ZomeName>>,
+    /// Only match signals with one of these tags.
+    /// If `None`, signals with any tag or no tag match.
+    ///
+    /// The tag of a signal is the one it was emitted with, e.g. by the hdk's
+    /// `emit_tagged_signal`.
+    #[serde(default)]
+    pub tags: Option<HashSet<String>>,
+}
+
 impl SignalFilter {
     /// A passthrough filter which filters nothing
     pub fn empty() -> Self {
-        SignalFilter
+        Self::default()
+    }
+
+    /// Whether a signal from the given zome, with the given tag,
+    /// matches this filter
+    pub fn matches(&self, zome_name: &ZomeName, tag: Option<&str>) -> bool {
+        let zome_matches = self
+            .zomes
+            .as_ref()
+            .map(|zomes| zomes.contains(zome_name))
+            .unwrap_or(true);
+        let tag_matches = self
+            .tags
+            .as_ref()
+            .map(|tags| tag.map(|tag| tags.contains(tag)).unwrap_or(false))
+            .unwrap_or(true);
+        zome_matches && tag_matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holo_hash::AgentPubKey;
+    use holo_hash::DnaHash;
+
+    fn test_cell_id(n: u8) -> CellId {
+        CellId::new(
+            DnaHash::from_raw_32(vec![n; 32]),
+            AgentPubKey::from_raw_32(vec![n; 32]),
+        )
+    }
+
+    #[test]
+    fn filter_set_by_cell_zome_and_tag() {
+        let (cell_id, other_cell_id) = (test_cell_id(1), test_cell_id(2));
+        let zome: ZomeName = "zome".into();
+        let other_zome: ZomeName = "other_zome".into();
+        let filter = SignalFilter {
+            zomes: Some(vec![zome.clone()].into_iter().collect()),
+            tags: Some(vec!["tag".to_string()].into_iter().collect()),
+        };
+
+        let include = SignalFilterSet::Include(
+            vec![(cell_id.clone(), filter.clone())]
+                .into_iter()
+                .collect(),
+        );
+        assert!(include.allows(&cell_id, &zome, Some("tag")));
+        assert!(!include.allows(&cell_id, &zome, Some("other_tag")));
+        assert!(!include.allows(&cell_id, &zome, None));
+        assert!(!include.allows(&cell_id, &other_zome, Some("tag")));
+        assert!(!include.allows(&other_cell_id, &zome, Some("tag")));
+
+        let exclude =
+            SignalFilterSet::Exclude(vec![(cell_id.clone(), filter)].into_iter().collect());
+        assert!(!exclude.allows(&cell_id, &zome, Some("tag")));
+        assert!(exclude.allows(&cell_id, &zome, None));
+        assert!(exclude.allows(&cell_id, &other_zome, Some("tag")));
+        assert!(exclude.allows(&other_cell_id, &zome, Some("tag")));
+
+        assert!(SignalFilterSet::allow_all().allows(&cell_id, &zome, None));
+        assert!(!SignalFilterSet::block_all().allows(&cell_id, &zome, None));
     }
 }
@@ -1,6 +1,13 @@
 use holo_hash::AgentPubKey;
+use holo_hash::DhtOpHash;
 use holo_hash::DnaHash;
+use holo_hash::HeaderHash;
+use holochain_p2p::dht_arc::DhtArc;
 use holochain_state::source_chain::SourceChainJsonDump;
+use holochain_state::validation_db::ValidationLimboStatus;
+use holochain_state::validation_receipts::SignedValidationReceipt;
+use holochain_types::dht_op::DhtOpType;
+use holochain_zome_types::timestamp::Timestamp;
 use serde::Deserialize;
 use serde::Serialize;
 use std::sync::Arc;
@@ -33,6 +40,149 @@ pub struct IntegrationStateDump {
     pub integrated: usize,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// A typed view of the state of a cell, for tools to query
+/// instead of reading the [`JsonDump`] text.
+pub struct FullStateDump {
+    /// The ops held by the cell, by how far they
+    /// have got through validation and integration.
+    pub integration_dump: FullIntegrationStateDump,
+    /// The ops the cell has authored and published,
+    /// with the validation receipts collected for them.
+    pub published_ops: Vec<PublishedOpDump>,
+    /// The peers in the cell's space, with their storage arcs.
+    pub peers: Vec<PeerDump>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// The incoming ops of a cell with their details.
+/// The typed counterpart of [`IntegrationStateDump`].
+pub struct FullIntegrationStateDump {
+    /// Ops in validation limbo awaiting sys
+    /// or app validation.
+    pub validation_limbo: Vec<ValidationLimboOpDump>,
+    /// Ops waiting to be integrated.
+    pub integration_limbo: Vec<DhtOpDump>,
+    /// The number of ops that are integrated.
+    /// This includes rejected.
+    pub integrated: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// The details of an op common to every part of a [`FullStateDump`].
+pub struct DhtOpDump {
+    /// The hash of the op.
+    pub hash: DhtOpHash,
+    /// The type of the op.
+    pub op_type: DhtOpType,
+    /// The header the op was made from.
+    pub header_hash: HeaderHash,
+    /// The author of the header.
+    pub author: AgentPubKey,
+    /// When the header was authored.
+    pub authored_timestamp: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// An op in validation limbo.
+pub struct ValidationLimboOpDump {
+    /// The op.
+    pub op: DhtOpDump,
+    /// Where the op is in validation.
+    pub status: ValidationLimboStage,
+    /// How many times validation of the op has been attempted.
+    pub num_validation_attempts: u32,
+    /// When validation of the op was last attempted.
+    pub last_validation_attempt: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+/// The [`ValidationLimboStatus`] of an op in limbo, without the
+/// dependencies it is awaiting, which are not stored.
+pub enum ValidationLimboStage {
+    /// Is awaiting to be system validated
+    Pending,
+    /// Is waiting for dependencies so the op can proceed to system validation
+    AwaitingSysDeps,
+    /// Is awaiting to be app validated
+    SysValidated,
+    /// Is waiting for dependencies so the op can proceed to app validation
+    AwaitingAppDeps,
+    /// Is awaiting to be integrated.
+    AwaitingIntegration,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// An authored op which has been published.
+pub struct PublishedOpDump {
+    /// The op.
+    pub op: DhtOpDump,
+    /// When the op was last published.
+    pub last_publish_time: Option<Timestamp>,
+    /// Whether the op has received enough validation receipts.
+    pub receipts_complete: bool,
+    /// The validation receipts received for the op.
+    pub receipts: Vec<SignedValidationReceipt>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// A peer in a cell's space.
+pub struct PeerDump {
+    /// The agent of the peer.
+    pub agent: AgentPubKey,
+    /// The storage arc the peer is publishing.
+    pub storage_arc: DhtArc,
+    /// The urls the peer can be reached at.
+    pub url_list: Vec<String>,
+    /// When the peer's agent info was signed, in unix ms.
+    pub signed_at_ms: u64,
+    /// When the peer's agent info expires, in unix ms.
+    pub expires_at_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+/// Which ops to include in a [`FullStateDump`].
+/// Every op is included by default.
+pub struct DhtOpDumpFilter {
+    /// Only include ops of these types.
+    #[serde(default)]
+    pub op_types: Option<Vec<DhtOpType>>,
+    /// Only include ops authored by this agent.
+    #[serde(default)]
+    pub author: Option<AgentPubKey>,
+    /// Only include ops authored at or after this time.
+    #[serde(default)]
+    pub authored_after: Option<Timestamp>,
+    /// Only include ops authored before this time.
+    #[serde(default)]
+    pub authored_before: Option<Timestamp>,
+}
+
+impl ValidationLimboStage {
+    /// The stage of an op from the `validation_stage` column of the database.
+    pub fn from_db(stage: Option<i32>) -> Self {
+        match stage {
+            None => Self::Pending,
+            Some(0) => Self::AwaitingSysDeps,
+            Some(1) => Self::SysValidated,
+            Some(2) => Self::AwaitingAppDeps,
+            Some(_) => Self::AwaitingIntegration,
+        }
+    }
+}
+
+impl From<&ValidationLimboStatus> for ValidationLimboStage {
+    fn from(status: &ValidationLimboStatus) -> Self {
+        match status {
+            ValidationLimboStatus::Pending => Self::Pending,
+            ValidationLimboStatus::AwaitingSysDeps(_) => Self::AwaitingSysDeps,
+            ValidationLimboStatus::SysValidated => Self::SysValidated,
+            ValidationLimboStatus::AwaitingAppDeps(_) => Self::AwaitingAppDeps,
+            ValidationLimboStatus::AwaitingIntegration => Self::AwaitingIntegration,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 /// State dump of all the peer info
 pub struct P2pAgentsDump {
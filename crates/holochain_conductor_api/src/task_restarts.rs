@@ -0,0 +1,21 @@
+//! The restarts of the tasks a conductor manages, as reported by
+//! [`AdminRequest::ListTaskRestarts`].
+//!
+//! [`AdminRequest::ListTaskRestarts`]: ../enum.AdminRequest.html#variant.ListTaskRestarts
+
+use holochain_zome_types::cell::CellId;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// A task which the conductor restarted after it failed.
+pub struct TaskRestarts {
+    /// The description of the task
+    pub name: String,
+    /// The cell the task belongs to, if any
+    pub cell_id: Option<CellId>,
+    /// How many times the task was restarted
+    pub restarts: u32,
+    /// The error the task last failed with
+    pub last_error: String,
+}
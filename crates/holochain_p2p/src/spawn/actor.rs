@@ -18,6 +18,7 @@ use ghost_actor::dependencies::tracing_futures::Instrument;
 use holochain_zome_types::zome::FunctionName;
 use kitsune_p2p::actor::KitsuneP2pSender;
 use kitsune_p2p::agent_store::AgentInfoSigned;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::future::Future;
 use std::time::SystemTime;
@@ -334,6 +335,21 @@ impl WrapEvtSender {
         )
     }
 
+    fn fetch_op_sizes(
+        &self,
+        dna_hash: DnaHash,
+        to_agent: AgentPubKey,
+        op_hashes: Vec<holo_hash::DhtOpHash>,
+    ) -> impl Future<Output = HolochainP2pResult<Vec<(holo_hash::DhtOpHash, u32)>>> + 'static + Send
+    {
+        let op_count = op_hashes.len();
+        timing_trace!(
+            { self.0.fetch_op_sizes(dna_hash, to_agent, op_hashes) },
+            %op_count,
+            "(hp2p:handle) fetch_op_sizes",
+        )
+    }
+
     fn sign_network_data(
         &self,
         dna_hash: DnaHash,
@@ -719,13 +735,41 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
         .into())
     }
 
+    #[tracing::instrument(skip(self), level = "trace")]
+    fn handle_is_blocked(
+        &mut self,
+        space: Arc<kitsune_p2p::KitsuneSpace>,
+        target: kitsune_p2p::event::BlockTarget,
+    ) -> kitsune_p2p::event::KitsuneP2pEventHandlerResult<bool> {
+        let h_space = DnaHash::from_kitsune(&space);
+        let evt_sender = self.evt_sender.clone();
+        Ok(
+            async move { Ok(evt_sender.is_blocked(h_space, target).await?) }
+                .boxed()
+                .into(),
+        )
+    }
+
+    #[tracing::instrument(skip(self), level = "trace")]
+    fn handle_block(
+        &mut self,
+        space: Arc<kitsune_p2p::KitsuneSpace>,
+        target: kitsune_p2p::event::BlockTarget,
+    ) -> kitsune_p2p::event::KitsuneP2pEventHandlerResult<()> {
+        let h_space = DnaHash::from_kitsune(&space);
+        let evt_sender = self.evt_sender.clone();
+        Ok(async move { Ok(evt_sender.block(h_space, target).await?) }
+            .boxed()
+            .into())
+    }
+
     fn handle_put_metric_datum(
         &mut self,
         datum: MetricDatum,
     ) -> kitsune_p2p::event::KitsuneP2pEventHandlerResult<()> {
         let evt_sender = self.evt_sender.clone();
-        // These dummy values are not used
-        let dna_hash = DnaHash::from_raw_32([0; 32].to_vec());
+        let dna_hash = DnaHash::from_kitsune(&datum.space);
+        // This dummy value is not used
         let to_agent = AgentPubKey::from_raw_32([0; 32].to_vec());
 
         let agent = AgentPubKey::from_kitsune(&datum.agent);
@@ -742,12 +786,13 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
 
     fn handle_query_metrics(
         &mut self,
+        space: Arc<kitsune_p2p::KitsuneSpace>,
         query: kitsune_p2p::event::MetricQuery,
     ) -> kitsune_p2p::event::KitsuneP2pEventHandlerResult<MetricQueryAnswer> {
         let evt_sender = self.evt_sender.clone();
+        let dna_hash = DnaHash::from_kitsune(&space);
 
-        // These dummy values are not used
-        let dna_hash = DnaHash::from_raw_32([0; 32].to_vec());
+        // This dummy value is not used
         let to_agent = AgentPubKey::from_raw_32([0; 32].to_vec());
 
         Ok(
@@ -961,6 +1006,41 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
         .into())
     }
 
+    #[tracing::instrument(skip(self), level = "trace")]
+    fn handle_fetch_op_sizes(
+        &mut self,
+        input: kitsune_p2p::event::FetchOpSizesEvt,
+    ) -> kitsune_p2p::event::KitsuneP2pEventHandlerResult<Vec<(Arc<kitsune_p2p::KitsuneOpHash>, u32)>>
+    {
+        let kitsune_p2p::event::FetchOpSizesEvt {
+            space,
+            agents,
+            op_hashes,
+        } = input;
+        let space = DnaHash::from_kitsune(&space);
+        let agents: Vec<_> = agents.iter().map(AgentPubKey::from_kitsune).collect();
+        let op_hashes: Vec<_> = op_hashes
+            .into_iter()
+            .map(|h| DhtOpHash::from_kitsune(&h))
+            .collect();
+
+        let evt_sender = self.evt_sender.clone();
+        Ok(async move {
+            let mut out = HashMap::new();
+            for agent in agents {
+                for (op_hash, size) in evt_sender
+                    .fetch_op_sizes(space.clone(), agent.clone(), op_hashes.clone())
+                    .await?
+                {
+                    out.insert(op_hash.into_kitsune(), size);
+                }
+            }
+            Ok(out.into_iter().collect())
+        }
+        .boxed()
+        .into())
+    }
+
     #[tracing::instrument(skip(self), level = "trace")]
     fn handle_sign_network_data(
         &mut self,
@@ -26,6 +26,10 @@ pub enum HolochainP2pError {
     #[error("InvalidP2pMessage: {0}")]
     InvalidP2pMessage(String),
 
+    /// Data sent by a remote node was found to be counterfeit
+    #[error("Counterfeit: {0}")]
+    Counterfeit(String),
+
     /// Other
     #[error("Other: {0}")]
     Other(Box<dyn std::error::Error + Send + Sync>),
@@ -41,6 +45,11 @@ impl HolochainP2pError {
     pub fn invalid_p2p_message(s: String) -> Self {
         Self::InvalidP2pMessage(s)
     }
+
+    /// construct a counterfeit data error variant
+    pub fn counterfeit(s: String) -> Self {
+        Self::Counterfeit(s)
+    }
 }
 
 // do some manual type translation so we get better error displays
@@ -54,6 +63,7 @@ impl From<kitsune_p2p::KitsuneP2pError> for HolochainP2pError {
             RoutingAgentError(agent) => {
                 Self::RoutingAgentError(holo_hash::AgentPubKey::from_kitsune(&agent))
             }
+            Counterfeit(s) => Self::Counterfeit(s.into()),
             _ => Self::OtherKitsuneP2pError(e),
         }
     }
@@ -65,6 +75,7 @@ impl From<HolochainP2pError> for kitsune_p2p::KitsuneP2pError {
         match e {
             RoutingDnaError(dna) => Self::RoutingSpaceError(dna.to_kitsune()),
             RoutingAgentError(agent) => Self::RoutingAgentError(agent.to_kitsune()),
+            Counterfeit(s) => Self::counterfeit(s),
             OtherKitsuneP2pError(e) => e,
             _ => Self::other(e),
         }
@@ -7,7 +7,7 @@ use crate::*;
 use holochain_zome_types::signature::Signature;
 use kitsune_p2p::{
     agent_store::AgentInfoSigned,
-    event::{MetricKind, MetricQuery, MetricQueryAnswer, TimeWindowMs},
+    event::{BlockTarget, MetricKind, MetricQuery, MetricQueryAnswer, TimeWindowMs},
 };
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
@@ -54,12 +54,17 @@ impl Default for GetRequest {
 }
 
 /// GetMeta options help control how the get is processed at various levels.
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
-pub struct GetMetaOptions {}
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GetMetaOptions {
+    /// Which metadata the remote-end should return.
+    pub metadata_request: MetadataRequest,
+}
 
 impl From<&actor::GetMetaOptions> for GetMetaOptions {
-    fn from(_a: &actor::GetMetaOptions) -> Self {
-        Self {}
+    fn from(a: &actor::GetMetaOptions) -> Self {
+        Self {
+            metadata_request: a.metadata_request.clone(),
+        }
     }
 }
 
@@ -134,6 +139,12 @@ ghost_actor::ghost_chan! {
         /// Query the peer density of a space for a given [`DhtArc`].
         fn query_peer_density(dna_hash: DnaHash, kitsune_space: Arc<kitsune_p2p::KitsuneSpace>, dht_arc: kitsune_p2p_types::dht_arc::DhtArc) -> kitsune_p2p_types::dht_arc::PeerDensity;
 
+        /// Is this agent or node blocked from this space?
+        fn is_blocked(dna_hash: DnaHash, target: BlockTarget) -> bool;
+
+        /// Block an agent or node from this space.
+        fn block(dna_hash: DnaHash, target: BlockTarget) -> ();
+
         /// We need to store some metric data on behalf of kitsune.
         fn put_metric_datum(dna_hash: DnaHash, to_agent: AgentPubKey, agent: AgentPubKey, metric: MetricKind, timestamp: SystemTime) -> ();
 
@@ -228,6 +239,13 @@ ghost_actor::ghost_chan! {
             op_hashes: Vec<holo_hash::DhtOpHash>,
         ) -> Vec<(holo_hash::DhtOpHash, holochain_types::dht_op::DhtOp)>;
 
+        /// The p2p module needs the size of each DhtOp for a given set of DhtOpHashes.
+        fn fetch_op_sizes(
+            dna_hash: DnaHash,
+            to_agent: AgentPubKey,
+            op_hashes: Vec<holo_hash::DhtOpHash>,
+        ) -> Vec<(holo_hash::DhtOpHash, u32)>;
+
         /// P2p operations require cryptographic signatures and validation.
         fn sign_network_data(
             // The dna_hash / space_hash context.
@@ -261,6 +279,7 @@ macro_rules! match_p2p_evt {
             HolochainP2pEvent::GetAgentActivity { $i, .. } => { $($t)* }
             HolochainP2pEvent::ValidationReceiptReceived { $i, .. } => { $($t)* }
             HolochainP2pEvent::FetchOpData { $i, .. } => { $($t)* }
+            HolochainP2pEvent::FetchOpSizes { $i, .. } => { $($t)* }
             HolochainP2pEvent::SignNetworkData { $i, .. } => { $($t)* }
             HolochainP2pEvent::GetAgentInfoSigned { $i, .. } => { $($t)* }
             HolochainP2pEvent::PutMetricDatum { $i, .. } => { $($t)* }
@@ -281,6 +300,8 @@ impl HolochainP2pEvent {
             HolochainP2pEvent::QueryGossipAgents { dna_hash, .. } => { dna_hash }
             HolochainP2pEvent::PutAgentInfoSigned { dna_hash, .. } => { dna_hash }
             HolochainP2pEvent::QueryPeerDensity { dna_hash, .. } => { dna_hash }
+            HolochainP2pEvent::IsBlocked { dna_hash, .. } => { dna_hash }
+            HolochainP2pEvent::Block { dna_hash, .. } => { dna_hash }
         })
     }
 
@@ -293,6 +314,8 @@ impl HolochainP2pEvent {
             HolochainP2pEvent::QueryGossipAgents { .. } => { unimplemented!("There is no single agent target for QueryGossipAgents") },
             HolochainP2pEvent::PutAgentInfoSigned { .. } => { unimplemented!("There is no single agent target for PutAgentInfoSigned") },
             HolochainP2pEvent::QueryPeerDensity { .. } => { unimplemented!() },
+            HolochainP2pEvent::IsBlocked { .. } => { unimplemented!("There is no single agent target for IsBlocked") },
+            HolochainP2pEvent::Block { .. } => { unimplemented!("There is no single agent target for Block") },
         })
     }
 }
@@ -57,6 +57,17 @@ impl DbRead {
         &self.path
     }
 
+    /// The current usage of the environment's connection pool.
+    pub fn connection_pool_usage(&self) -> ConnectionPoolUsage {
+        let state = self.connection_pool.state();
+        ConnectionPoolUsage {
+            kind: self.kind.clone(),
+            connections: state.connections,
+            idle_connections: state.idle_connections,
+            max_size: self.connection_pool.max_size(),
+        }
+    }
+
     /// Get a connection from the pool.
     /// TODO: We should eventually swap this for an async solution.
     fn connection_pooled(&self) -> DatabaseResult<PConn> {
@@ -150,16 +161,48 @@ impl DbWrite {
         Self::new(tmpdir.path(), kind)
     }
 
-    /// Remove the db and directory
-    #[deprecated = "is this used?"]
+    /// Remove the database file, along with its write-ahead log, and forget
+    /// the shared handle so that opening it again creates a fresh database.
+    /// Any other handles to this database must be dropped beforehand.
     pub async fn remove(self) -> DatabaseResult<()> {
-        if let Some(parent) = self.0.path.parent() {
-            std::fs::remove_dir_all(parent)?;
+        DATABASE_HANDLES.remove(&self.0.path);
+        let path = self.0.path.clone();
+        // Close the connection pool before deleting the files.
+        drop(self);
+        for suffix in &["", "-wal", "-shm"] {
+            let mut file = path.clone().into_os_string();
+            file.push(suffix);
+            match std::fs::remove_file(&file) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => (),
+            }
         }
         Ok(())
     }
 }
 
+/// The usage of a database's connection pool.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConnectionPoolUsage {
+    /// The kind of database the pool connects to.
+    pub kind: DbKind,
+    /// The number of open connections, both in use and idle.
+    pub connections: u32,
+    /// The number of open connections which are idle.
+    pub idle_connections: u32,
+    /// The maximum number of connections the pool will open.
+    pub max_size: u32,
+}
+
+/// The connection pool usage of every database opened with [`DbWrite::open`].
+pub fn connection_pool_usage() -> Vec<ConnectionPoolUsage> {
+    DATABASE_HANDLES
+        .clone()
+        .into_iter()
+        .map(|(_, db)| db.connection_pool_usage())
+        .collect()
+}
+
 /// The various types of database, used to specify the list of databases to initialize
 #[derive(Clone, Debug, PartialEq, Eq, Hash, derive_more::Display)]
 pub enum DbKind {
@@ -3,8 +3,11 @@
 use crate::prelude::*;
 use crate::sql::*;
 use kitsune_p2p::agent_store::AgentInfoSigned;
+use kitsune_p2p::dependencies::kitsune_p2p_proxy::ProxyUrl;
+use kitsune_p2p::dependencies::kitsune_p2p_types::Tx2Cert;
 use kitsune_p2p::dht_arc::ArcInterval;
 use kitsune_p2p::dht_arc::DhtArcSet;
+use kitsune_p2p::event::BlockTarget;
 use kitsune_p2p::KitsuneAgent;
 use rusqlite::*;
 use std::sync::Arc;
@@ -32,6 +35,15 @@ pub trait AsP2pAgentStoreConExt {
         basis: u32,
         limit: u32,
     ) -> DatabaseResult<Vec<AgentInfoSigned>>;
+
+    /// Is this agent or node on the block list
+    fn p2p_is_blocked(&mut self, target: &BlockTarget) -> DatabaseResult<bool>;
+
+    /// List all agents on the block list
+    fn p2p_list_blocked_agents(&mut self) -> DatabaseResult<Vec<KitsuneAgent>>;
+
+    /// List every agent and node on the block list
+    fn p2p_list_block_targets(&mut self) -> DatabaseResult<Vec<BlockTarget>>;
 }
 
 /// Extension trait to treat transaction instances
@@ -53,6 +65,15 @@ pub trait AsP2pStateTxExt {
 
     /// Query agents sorted by nearness to basis loc
     fn p2p_query_near_basis(&self, basis: u32, limit: u32) -> DatabaseResult<Vec<AgentInfoSigned>>;
+
+    /// Is this agent or node on the block list
+    fn p2p_is_blocked(&self, target: &BlockTarget) -> DatabaseResult<bool>;
+
+    /// List all agents on the block list
+    fn p2p_list_blocked_agents(&self) -> DatabaseResult<Vec<KitsuneAgent>>;
+
+    /// List every agent and node on the block list
+    fn p2p_list_block_targets(&self) -> DatabaseResult<Vec<BlockTarget>>;
 }
 
 impl AsP2pAgentStoreConExt for crate::db::PConn {
@@ -80,6 +101,18 @@ impl AsP2pAgentStoreConExt for crate::db::PConn {
     ) -> DatabaseResult<Vec<AgentInfoSigned>> {
         self.with_reader(move |reader| reader.p2p_query_near_basis(basis, limit))
     }
+
+    fn p2p_is_blocked(&mut self, target: &BlockTarget) -> DatabaseResult<bool> {
+        self.with_reader(move |reader| reader.p2p_is_blocked(target))
+    }
+
+    fn p2p_list_blocked_agents(&mut self) -> DatabaseResult<Vec<KitsuneAgent>> {
+        self.with_reader(move |reader| reader.p2p_list_blocked_agents())
+    }
+
+    fn p2p_list_block_targets(&mut self) -> DatabaseResult<Vec<BlockTarget>> {
+        self.with_reader(move |reader| reader.p2p_list_block_targets())
+    }
 }
 
 /// Put an AgentInfoSigned record into the p2p_store
@@ -143,6 +176,93 @@ pub async fn p2p_prune(db: &DbWrite) -> DatabaseResult<()> {
     Ok(())
 }
 
+/// Remove the AgentInfoSigned record for an agent from the p2p_store
+pub async fn p2p_remove_agent(db: &DbWrite, agent: &KitsuneAgent) -> DatabaseResult<()> {
+    let agent = agent.clone();
+    db.async_commit(move |txn| {
+        txn.execute(
+            sql_p2p_agent_store::DELETE,
+            named_params! { ":agent": &agent.0 },
+        )?;
+        DatabaseResult::Ok(())
+    })
+    .await
+}
+
+/// Block an agent from this space.
+/// Any nodes the agent was last seen at are blocked along with it
+/// and its AgentInfoSigned is removed from the p2p_store.
+pub async fn p2p_block_agent(db: &DbWrite, agent: &KitsuneAgent) -> DatabaseResult<()> {
+    let agent = agent.clone();
+    db.async_commit(move |txn| {
+        let nodes = txn
+            .p2p_get_agent(&agent)?
+            .map(|info| {
+                info.url_list
+                    .iter()
+                    .filter_map(|url| ProxyUrl::from_full(url.as_str()).ok())
+                    .map(|purl| Tx2Cert::from(purl.digest()).as_bytes().to_vec())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        txn.execute(
+            sql_p2p_agent_store::BLOCK,
+            named_params! {
+                ":target": &agent.0,
+                ":is_node": false,
+                ":blocked_agent": &agent.0,
+            },
+        )?;
+        for node in nodes {
+            txn.execute(
+                sql_p2p_agent_store::BLOCK,
+                named_params! {
+                    ":target": node,
+                    ":is_node": true,
+                    ":blocked_agent": &agent.0,
+                },
+            )?;
+        }
+        txn.execute(
+            sql_p2p_agent_store::DELETE,
+            named_params! { ":agent": &agent.0 },
+        )?;
+        DatabaseResult::Ok(())
+    })
+    .await
+}
+
+/// Block a node from this space on its own account,
+/// so unblocking an agent it hosted won't lift the block.
+pub async fn p2p_block_node(db: &DbWrite, cert: &Tx2Cert) -> DatabaseResult<()> {
+    let cert = cert.as_bytes().to_vec();
+    db.async_commit(move |txn| {
+        txn.execute(
+            sql_p2p_agent_store::BLOCK,
+            named_params! {
+                ":target": &cert,
+                ":is_node": true,
+                ":blocked_agent": &cert,
+            },
+        )?;
+        DatabaseResult::Ok(())
+    })
+    .await
+}
+
+/// Remove an agent, and any nodes blocked on its account, from the block list
+pub async fn p2p_unblock_agent(db: &DbWrite, agent: &KitsuneAgent) -> DatabaseResult<()> {
+    let agent = agent.clone();
+    db.async_commit(move |txn| {
+        txn.execute(
+            sql_p2p_agent_store::UNBLOCK,
+            named_params! { ":blocked_agent": &agent.0 },
+        )?;
+        DatabaseResult::Ok(())
+    })
+    .await
+}
+
 impl AsP2pStateTxExt for Transaction<'_> {
     fn p2p_get_agent(&self, agent: &KitsuneAgent) -> DatabaseResult<Option<AgentInfoSigned>> {
         let mut stmt = self
@@ -247,6 +367,48 @@ impl AsP2pStateTxExt for Transaction<'_> {
         }
         Ok(out)
     }
+
+    fn p2p_is_blocked(&self, target: &BlockTarget) -> DatabaseResult<bool> {
+        let (target, is_node): (&[u8], bool) = match target {
+            BlockTarget::Agent(agent) => (agent.0.as_slice(), false),
+            BlockTarget::Node(cert) => (cert.as_bytes(), true),
+        };
+        Ok(self.query_row(
+            sql_p2p_agent_store::IS_BLOCKED,
+            named_params! { ":target": target, ":is_node": is_node },
+            |r| r.get(0),
+        )?)
+    }
+
+    fn p2p_list_blocked_agents(&self) -> DatabaseResult<Vec<KitsuneAgent>> {
+        let mut stmt = self
+            .prepare(sql_p2p_agent_store::SELECT_BLOCKED)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()))?;
+        let mut out = Vec::new();
+        for r in stmt.query_map([], |r| Ok(KitsuneAgent(r.get(0)?)))? {
+            out.push(r?);
+        }
+        Ok(out)
+    }
+
+    fn p2p_list_block_targets(&self) -> DatabaseResult<Vec<BlockTarget>> {
+        let mut stmt = self
+            .prepare(sql_p2p_agent_store::SELECT_BLOCK_TARGETS)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()))?;
+        let mut out = Vec::new();
+        for r in stmt.query_map([], |r| {
+            let target: Vec<u8> = r.get(0)?;
+            let is_node: bool = r.get(1)?;
+            Ok(if is_node {
+                BlockTarget::Node(Tx2Cert::from(target))
+            } else {
+                BlockTarget::Agent(Arc::new(KitsuneAgent(target)))
+            })
+        })? {
+            out.push(r?);
+        }
+        Ok(out)
+    }
 }
 
 /// Owned data dealing with a full p2p_agent_store record.
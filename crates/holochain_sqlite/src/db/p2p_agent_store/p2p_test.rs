@@ -196,3 +196,103 @@ async fn test_p2p_agent_store_gossip_query_sanity() {
     // clean up temp dir
     tmp_dir.close().unwrap();
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_p2p_agent_store_block_list() {
+    use kitsune_p2p::event::BlockTarget;
+
+    let tmp_dir = tempdir::TempDir::new("p2p_agent_store_block_list").unwrap();
+
+    let space = rand_space();
+
+    let db = DbWrite::test(&tmp_dir, DbKind::P2pAgentStore(space.clone())).unwrap();
+
+    let agent = rand_agent();
+    let other_agent = rand_agent();
+    rand_insert(&db, &space, &agent).await;
+    rand_insert(&db, &space, &other_agent).await;
+
+    let mut con = db.connection_pooled().unwrap();
+
+    // blocking an agent removes their info
+    p2p_block_agent(&db, &agent).await.unwrap();
+    assert!(con.p2p_get_agent(&agent).unwrap().is_none());
+    assert!(con.p2p_get_agent(&other_agent).unwrap().is_some());
+    assert!(con
+        .p2p_is_blocked(&BlockTarget::Agent(agent.clone()))
+        .unwrap());
+    assert!(!con
+        .p2p_is_blocked(&BlockTarget::Agent(other_agent.clone()))
+        .unwrap());
+    assert_eq!(
+        con.p2p_list_blocked_agents().unwrap(),
+        vec![(*agent).clone()]
+    );
+
+    // blocked agents can't be put back into the store
+    rand_insert(&db, &space, &agent).await;
+    assert!(con.p2p_get_agent(&agent).unwrap().is_none());
+
+    // once unblocked they can
+    p2p_unblock_agent(&db, &agent).await.unwrap();
+    assert!(!con
+        .p2p_is_blocked(&BlockTarget::Agent(agent.clone()))
+        .unwrap());
+    assert!(con.p2p_list_blocked_agents().unwrap().is_empty());
+    rand_insert(&db, &space, &agent).await;
+    assert!(con.p2p_get_agent(&agent).unwrap().is_some());
+
+    // clean up temp dir
+    tmp_dir.close().unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_p2p_agent_store_node_block_list() {
+    use kitsune_p2p::dependencies::kitsune_p2p_types::Tx2Cert;
+    use kitsune_p2p::event::BlockTarget;
+
+    let tmp_dir = tempdir::TempDir::new("p2p_agent_store_node_block_list").unwrap();
+
+    let space = rand_space();
+
+    let db = DbWrite::test(&tmp_dir, DbKind::P2pAgentStore(space.clone())).unwrap();
+
+    let agent = rand_agent();
+    let node = Tx2Cert::from(vec![0xdb; 32]);
+
+    let mut con = db.connection_pooled().unwrap();
+
+    p2p_block_agent(&db, &agent).await.unwrap();
+    p2p_block_node(&db, &node).await.unwrap();
+    assert!(con
+        .p2p_is_blocked(&BlockTarget::Node(node.clone()))
+        .unwrap());
+
+    // a node blocked on its own account isn't listed as an agent
+    assert_eq!(
+        con.p2p_list_blocked_agents().unwrap(),
+        vec![(*agent).clone()]
+    );
+    let mut targets = con.p2p_list_block_targets().unwrap();
+    targets.sort_by_key(|t| matches!(t, BlockTarget::Node(_)));
+    assert_eq!(
+        targets,
+        vec![
+            BlockTarget::Agent(agent.clone()),
+            BlockTarget::Node(node.clone())
+        ]
+    );
+
+    // and stays blocked when agents are unblocked
+    p2p_unblock_agent(&db, &agent).await.unwrap();
+    assert!(con
+        .p2p_is_blocked(&BlockTarget::Node(node.clone()))
+        .unwrap());
+    assert_eq!(
+        con.p2p_list_block_targets().unwrap(),
+        vec![BlockTarget::Node(node)]
+    );
+
+    // clean up temp dir
+    tmp_dir.close().unwrap();
+}
@@ -44,12 +44,32 @@ pub fn put_metric_datum(
         named_params! {
             ":agent": agent_bytes,
             ":kind": metric.to_string(),
-            ":moment": time_to_micros(timestamp)?
+            ":moment": time_to_micros(timestamp)?,
+            ":value": metric.value().map(|value| value.min(i64::MAX as u64) as i64),
         },
     )?;
     Ok(())
 }
 
+/// Remove the p2p metric data recorded before the cutoff
+pub fn prune_metrics(txn: &mut Transaction, cutoff: SystemTime) -> DatabaseResult<()> {
+    txn.execute(
+        sql_p2p_metrics::PRUNE,
+        named_params! { ":cutoff": time_to_micros(cutoff)? },
+    )?;
+    Ok(())
+}
+
+/// Remove all the p2p metric data recorded about an agent
+pub fn remove_agent_metrics(txn: &mut Transaction, agent: Arc<KitsuneAgent>) -> DatabaseResult<()> {
+    let agent_bytes: &[u8] = agent.as_ref();
+    txn.execute(
+        sql_p2p_metrics::DELETE_AGENT,
+        named_params! { ":agent": agent_bytes },
+    )?;
+    Ok(())
+}
+
 /// Query the p2p_metrics database in a variety of ways
 pub fn query_metrics(
     txn: &mut Transaction,
@@ -90,3 +110,108 @@ pub fn query_metrics(
         }
     })
 }
+
+/// The metrics recorded about an agent, added up.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AgentMetricsSummary {
+    /// The agent the metrics are about.
+    pub agent: Arc<KitsuneAgent>,
+    /// The number of requests the agent answered.
+    pub requests: u64,
+    /// The total round trip time of those requests, in microseconds.
+    pub total_round_trip_micros: u64,
+    /// The bytes of data sent to the agent.
+    pub bytes_sent: u64,
+    /// The bytes of data received from the agent.
+    pub bytes_received: u64,
+    /// The number of gossip rounds with the agent which completed.
+    pub gossip_rounds_complete: u64,
+    /// The number of gossip rounds with the agent which failed.
+    pub gossip_rounds_failed: u64,
+}
+
+impl AgentMetricsSummary {
+    fn new(agent: Arc<KitsuneAgent>) -> Self {
+        Self {
+            agent,
+            requests: 0,
+            total_round_trip_micros: 0,
+            bytes_sent: 0,
+            bytes_received: 0,
+            gossip_rounds_complete: 0,
+            gossip_rounds_failed: 0,
+        }
+    }
+}
+
+/// Add up the metrics recorded about each agent
+pub fn summarize_metrics(txn: &mut Transaction) -> DatabaseResult<Vec<AgentMetricsSummary>> {
+    let mut summaries = std::collections::BTreeMap::new();
+    let mut stmt = txn.prepare(sql_p2p_metrics::SUMMARIZE)?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let agent: Vec<u8> = row.get(0)?;
+        let kind: String = row.get(1)?;
+        let count: i64 = row.get(2)?;
+        let total: Option<i64> = row.get(3)?;
+        let count = count as u64;
+        let total = total.unwrap_or(0) as u64;
+        let summary = summaries
+            .entry(agent.clone())
+            .or_insert_with(|| AgentMetricsSummary::new(Arc::new(KitsuneAgent::new(agent))));
+        if kind == (MetricKind::RoundTripLatency { micros: 0 }).to_string() {
+            summary.requests = count;
+            summary.total_round_trip_micros = total;
+        } else if kind == (MetricKind::BytesSent { bytes: 0 }).to_string() {
+            summary.bytes_sent = total;
+        } else if kind == (MetricKind::BytesReceived { bytes: 0 }).to_string() {
+            summary.bytes_received = total;
+        } else if kind == MetricKind::GossipRoundComplete.to_string() {
+            summary.gossip_rounds_complete = count;
+        } else if kind == MetricKind::GossipRoundFailed.to_string() {
+            summary.gossip_rounds_failed = count;
+        }
+    }
+    Ok(summaries.into_iter().map(|(_, summary)| summary).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn old_metrics_table_is_replaced() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        // The table of a database created before metrics had values
+        conn.execute_batch(
+            "CREATE TABLE p2p_metrics (
+              agent BLOB NOT NULL,
+              kind TEXT NOT NULL,
+              moment INTEGER NOT NULL,
+              PRIMARY KEY (agent, kind, moment)
+            );",
+        )
+        .unwrap();
+        crate::schema::SCHEMA_P2P_METRICS
+            .initialize(&mut conn, None)
+            .unwrap();
+
+        let agent = Arc::new(KitsuneAgent::new(vec![0; 36]));
+        let moment = SystemTime::now();
+        let mut txn = conn.transaction().unwrap();
+        for bytes in [10, 30] {
+            put_metric_datum(
+                &mut txn,
+                agent.clone(),
+                MetricKind::BytesSent { bytes },
+                moment,
+            )
+            .unwrap();
+        }
+
+        // - Both data recorded at the same moment are kept
+        let summaries = summarize_metrics(&mut txn).unwrap();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].bytes_sent, 40);
+    }
+}
@@ -36,11 +36,21 @@ pub(crate) mod sql_p2p_agent_store {
     pub(crate) const QUERY_NEAR_BASIS: &str =
         include_str!("sql/p2p_agent_store/query_near_basis.sql");
     pub(crate) const PRUNE: &str = include_str!("sql/p2p_agent_store/prune.sql");
+    pub(crate) const DELETE: &str = include_str!("sql/p2p_agent_store/delete.sql");
+    pub(crate) const BLOCK: &str = include_str!("sql/p2p_agent_store/block.sql");
+    pub(crate) const UNBLOCK: &str = include_str!("sql/p2p_agent_store/unblock.sql");
+    pub(crate) const IS_BLOCKED: &str = include_str!("sql/p2p_agent_store/is_blocked.sql");
+    pub(crate) const SELECT_BLOCKED: &str = include_str!("sql/p2p_agent_store/select_blocked.sql");
+    pub(crate) const SELECT_BLOCK_TARGETS: &str =
+        include_str!("sql/p2p_agent_store/select_block_targets.sql");
 }
 
 pub(crate) mod sql_p2p_metrics {
     pub(crate) const SCHEMA: &str = include_str!("sql/p2p_metrics/schema.sql");
     pub(crate) const INSERT: &str = include_str!("sql/p2p_metrics/insert.sql");
+    pub(crate) const PRUNE: &str = include_str!("sql/p2p_metrics/prune.sql");
+    pub(crate) const DELETE_AGENT: &str = include_str!("sql/p2p_metrics/delete_agent.sql");
     pub(crate) const QUERY_LAST_SYNC: &str = include_str!("sql/p2p_metrics/query_last_sync.sql");
     pub(crate) const QUERY_OLDEST: &str = include_str!("sql/p2p_metrics/query_oldest.sql");
+    pub(crate) const SUMMARIZE: &str = include_str!("sql/p2p_metrics/summarize.sql");
 }
@@ -64,7 +64,7 @@ CREATE TABLE IF NOT EXISTS Header (
     -- AgentValidationPkg
     membrane_proof   BLOB           NULL,
 
-    -- OpenChain / CloseChain
+    -- OpenChain / CloseChain: the DNA of the chain migrated from / to
     prev_dna_hash    BLOB           NULL
 
     -- We can't have any of these constraint because
@@ -160,4 +160,15 @@ CREATE TABLE IF NOT EXISTS ValidationReceipt (
 CREATE TABLE IF NOT EXISTS ChainLock (
     lock BLOB PRIMARY KEY ON CONFLICT ROLLBACK,
     end INTEGER NOT NULL
-);
\ No newline at end of file
+);
+
+-- Zome functions scheduled to run later via the `schedule` host fn.
+CREATE TABLE IF NOT EXISTS ScheduledFunctions (
+    zome_name        TEXT           NOT NULL,
+    scheduled_fn     TEXT           NOT NULL,
+    -- The function may run any time after this.
+    start_at_ms      INTEGER        NOT NULL,
+
+    PRIMARY KEY (zome_name, scheduled_fn) ON CONFLICT REPLACE
+);
+CREATE INDEX IF NOT EXISTS ScheduledFunctions_start_at_idx ON ScheduledFunctions ( start_at_ms );
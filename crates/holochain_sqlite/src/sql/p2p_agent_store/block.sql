@@ -0,0 +1,5 @@
+-- block an agent, or a node on account of an agent
+INSERT INTO
+  p2p_block_list (target, is_node, blocked_agent)
+VALUES
+  (:target, :is_node, :blocked_agent);
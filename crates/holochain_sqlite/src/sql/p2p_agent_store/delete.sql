@@ -0,0 +1,5 @@
+-- delete the entry for a single agent from the p2p_agent_store
+DELETE FROM
+  p2p_agent_store
+WHERE
+  agent = :agent;
@@ -22,4 +22,12 @@ WHERE
       agent = :agent
       AND signed_at_ms > :signed_at_ms
   ) = 0 -- if there are none, proceed with the insert
-;
+  -- never store info for blocked agents
+  AND NOT EXISTS (
+    SELECT
+      1
+    FROM
+      p2p_block_list
+    WHERE
+      target = :agent
+  );
@@ -0,0 +1,11 @@
+-- is this agent or node blocked
+SELECT
+  EXISTS (
+    SELECT
+      1
+    FROM
+      p2p_block_list
+    WHERE
+      target = :target
+      AND is_node = :is_node
+  );
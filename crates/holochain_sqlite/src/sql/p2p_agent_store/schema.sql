@@ -26,3 +26,19 @@ CREATE TABLE IF NOT EXISTS p2p_agent_store (
   storage_start_loc       INTEGER   NULL,
   storage_end_loc         INTEGER   NULL
 );
+
+-- agents and nodes that are blocked from this space
+CREATE TABLE IF NOT EXISTS p2p_block_list (
+  -- the blocked agent, or the cert digest of a blocked node
+  target                  BLOB      NOT NULL,
+
+  -- 1 = target is a node, 0 = target is an agent
+  is_node                 INTEGER   NOT NULL,
+
+  -- the agent whose block this row is part of,
+  -- a node stays blocked while any agent it hosted is blocked,
+  -- or the node itself when it was blocked on its own account
+  blocked_agent           BLOB      NOT NULL,
+
+  PRIMARY KEY (target, blocked_agent) ON CONFLICT IGNORE
+);
@@ -0,0 +1,6 @@
+-- list every blocked agent and node
+SELECT
+  DISTINCT target,
+  is_node
+FROM
+  p2p_block_list;
@@ -0,0 +1,7 @@
+-- list the blocked agents
+SELECT
+  DISTINCT blocked_agent
+FROM
+  p2p_block_list
+WHERE
+  is_node = 0;
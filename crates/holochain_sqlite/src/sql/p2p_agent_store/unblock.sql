@@ -0,0 +1,5 @@
+-- remove an agent and every node blocked on its account
+DELETE FROM
+  p2p_block_list
+WHERE
+  blocked_agent = :blocked_agent;
@@ -0,0 +1,5 @@
+-- delete all metrics recorded about an agent
+DELETE FROM
+  p2p_metric_data
+WHERE
+  agent = :agent;
@@ -1,4 +1,4 @@
 INSERT INTO
-  p2p_metrics (agent, kind, moment)
+  p2p_metric_data (agent, kind, moment, value)
 VALUES
-  (:agent, :kind, :moment);
+  (:agent, :kind, :moment, :value);
@@ -0,0 +1,5 @@
+-- delete all metrics recorded before the cutoff
+DELETE FROM
+  p2p_metric_data
+WHERE
+  moment < :cutoff;
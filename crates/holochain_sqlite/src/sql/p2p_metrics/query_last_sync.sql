@@ -1,7 +1,7 @@
 SELECT
   MAX(moment)
 FROM
-  p2p_metrics
+  p2p_metric_data
 WHERE
   agent = :agent
   AND kind = :kind
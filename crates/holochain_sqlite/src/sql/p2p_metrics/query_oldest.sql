@@ -7,13 +7,13 @@ FROM
       agent,
       MAX(moment) AS most_recent_error
     FROM
-      p2p_metrics
+      p2p_metric_data
     WHERE
       kind = :kind_error
     GROUP BY
       agent
   ) AS i
-  JOIN p2p_metrics o ON i.agent = o.agent
+  JOIN p2p_metric_data o ON i.agent = o.agent
 WHERE
   i.most_recent_error < :error_threshold
   AND kind = :kind_slow_gossip
@@ -1,9 +1,17 @@
 -- no-sql-format --
 
+-- The table this one replaced had no room for the values of metrics,
+-- and could only hold one datum of each kind per agent and moment.
+DROP TABLE IF EXISTS p2p_metrics;
+
 -- p2p store
-CREATE TABLE IF NOT EXISTS p2p_metrics (
+CREATE TABLE IF NOT EXISTS p2p_metric_data (
   agent          BLOB NOT NULL,
   kind           TEXT NOT NULL,
   moment         INTEGER NOT NULL,
-  PRIMARY KEY (agent, kind, moment)
+  -- The value measured, for the kinds of metric which have one.
+  value          INTEGER NULL
 );
+
+CREATE INDEX IF NOT EXISTS p2p_metric_data_agent_idx ON p2p_metric_data (agent, kind, moment);
+CREATE INDEX IF NOT EXISTS p2p_metric_data_moment_idx ON p2p_metric_data (moment);
@@ -0,0 +1,10 @@
+SELECT
+  agent,
+  kind,
+  COUNT(moment),
+  SUM(value)
+FROM
+  p2p_metric_data
+GROUP BY
+  agent,
+  kind
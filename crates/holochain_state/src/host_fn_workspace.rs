@@ -11,6 +11,9 @@ pub struct HostFnWorkspace {
     source_chain: SourceChain,
     vault: EnvWrite,
     cache: EnvWrite,
+    /// When the call this workspace was made for must be done by,
+    /// shared by any calls it makes in turn.
+    deadline: Option<std::time::Instant>,
 }
 
 pub struct HostFnStores {
@@ -32,9 +35,32 @@ impl HostFnWorkspace {
             source_chain,
             vault,
             cache,
+            deadline: None,
         })
     }
 
+    /// A workspace for an observer, whose source chain can't be written to
+    pub fn observer(vault: EnvWrite, cache: EnvWrite, author: AgentPubKey) -> Self {
+        let source_chain = SourceChain::observer(vault.clone(), author);
+        Self {
+            source_chain,
+            vault,
+            cache,
+            deadline: None,
+        }
+    }
+
+    /// Set the instant by which the call using this workspace must be done
+    pub fn with_deadline(mut self, deadline: std::time::Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// The instant by which the call using this workspace must be done, if any
+    pub fn deadline(&self) -> Option<std::time::Instant> {
+        self.deadline
+    }
+
     pub async fn flush(self) -> SourceChainResult<()> {
         self.source_chain.flush().await
     }
@@ -505,6 +505,7 @@ pub fn insert_header(txn: &mut Transaction, header: SignedHeaderHashed) -> State
                 "seq": header_seq,
                 "author": author,
                 "prev_hash": prev_hash,
+                "prev_dna_hash": open.prev_dna_hash.clone(),
                 "blob": to_blob(SignedHeader::from((Header::OpenChain(open), signature)))?,
             })?;
         }
@@ -515,6 +516,7 @@ pub fn insert_header(txn: &mut Transaction, header: SignedHeaderHashed) -> State
                 "seq": header_seq,
                 "author": author,
                 "prev_hash": prev_hash,
+                "prev_dna_hash": close.new_dna_hash.clone(),
                 "blob": to_blob(SignedHeader::from((Header::CloseChain(close), signature)))?,
             })?;
         }
@@ -598,3 +600,48 @@ pub fn unlock_chain(txn: &mut Transaction) -> StateMutationResult<()> {
     txn.execute("DELETE FROM ChainLock", [])?;
     Ok(())
 }
+
+/// Remove a header authored on this chain along with its ops.
+/// This is only for undoing a header which has not been published yet.
+pub fn delete_authored_header(txn: &mut Transaction, hash: &HeaderHash) -> StateMutationResult<()> {
+    txn.execute(
+        "DELETE FROM DhtOp WHERE header_hash = :hash",
+        named_params! { ":hash": hash },
+    )?;
+    txn.execute(
+        "DELETE FROM Header WHERE hash = :hash",
+        named_params! { ":hash": hash },
+    )?;
+    Ok(())
+}
+
+/// Schedule a zome function to run any time after the given start time.
+/// This replaces any existing schedule for the same function.
+pub fn schedule_fn(
+    txn: &mut Transaction,
+    scheduled_fn: &ScheduledFn,
+    start_at: Timestamp,
+) -> StateMutationResult<()> {
+    sql_insert!(txn, ScheduledFunctions, {
+        "zome_name": scheduled_fn.zome_name().0,
+        "scheduled_fn": scheduled_fn.fn_name().0,
+        "start_at_ms": start_at.to_sql_ms_lossy(),
+    })?;
+    Ok(())
+}
+
+/// Remove the schedule for a zome function so it will not run again.
+pub fn unschedule_fn(txn: &mut Transaction, scheduled_fn: &ScheduledFn) -> StateMutationResult<()> {
+    txn.execute(
+        "
+        DELETE FROM ScheduledFunctions
+        WHERE zome_name = :zome_name
+        AND scheduled_fn = :scheduled_fn
+        ",
+        named_params! {
+            ":zome_name": scheduled_fn.zome_name().0,
+            ":scheduled_fn": scheduled_fn.fn_name().0,
+        },
+    )?;
+    Ok(())
+}
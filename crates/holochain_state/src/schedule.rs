@@ -0,0 +1,41 @@
+//! Queries over the zome functions scheduled with the `schedule` host fn.
+
+use crate::prelude::StateQueryResult;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_zome_types::FunctionName;
+use holochain_zome_types::ScheduledFn;
+use holochain_zome_types::Timestamp;
+use holochain_zome_types::ZomeName;
+
+/// All the scheduled functions that are due to run at the given time.
+pub fn due_scheduled_fns(txn: &Transaction, now: Timestamp) -> StateQueryResult<Vec<ScheduledFn>> {
+    let mut stmt = txn.prepare(
+        "
+        SELECT zome_name, scheduled_fn
+        FROM ScheduledFunctions
+        WHERE start_at_ms <= :now
+        ORDER BY start_at_ms ASC
+        ",
+    )?;
+    let r = stmt
+        .query_map(named_params! { ":now": now.to_sql_ms_lossy() }, |row| {
+            Ok(ScheduledFn::new(
+                ZomeName(row.get("zome_name")?),
+                FunctionName(row.get("scheduled_fn")?),
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(r)
+}
+
+/// The time in milliseconds since the unix epoch when the next
+/// scheduled function is due, if there are any.
+pub fn next_scheduled_fn_start_ms(txn: &Transaction) -> StateQueryResult<Option<i64>> {
+    // MIN always returns a single row which is NULL when the table is empty.
+    Ok(txn.query_row(
+        "SELECT MIN(start_at_ms) FROM ScheduledFunctions",
+        [],
+        |row| row.get::<_, Option<i64>>(0),
+    )?)
+}
@@ -11,7 +11,9 @@ use holochain_zome_types::entry::EntryHashed;
 use holochain_zome_types::ChainTopOrdering;
 use holochain_zome_types::Element;
 use holochain_zome_types::Entry;
+use holochain_zome_types::ScheduledFn;
 use holochain_zome_types::SignedHeaderHashed;
+use holochain_zome_types::Timestamp;
 use thiserror::Error;
 
 use crate::prelude::Query;
@@ -33,6 +35,7 @@ use crate::query::Store;
 pub struct Scratch {
     headers: Vec<SignedHeaderHashed>,
     entries: HashMap<EntryHash, Arc<Entry>>,
+    scheduled_fns: Vec<(ScheduledFn, Timestamp)>,
     chain_top_ordering: ChainTopOrdering,
 }
 
@@ -73,6 +76,12 @@ impl Scratch {
         self.entries.insert(hash, Arc::new(entry));
     }
 
+    /// Schedule a zome function to run any time after `start_at`.
+    /// This is written along with the headers when the scratch is flushed.
+    pub fn add_scheduled_fn(&mut self, scheduled_fn: ScheduledFn, start_at: Timestamp) {
+        self.scheduled_fns.push((scheduled_fn, start_at));
+    }
+
     pub fn as_filter(&self, f: impl Fn(&SignedHeaderHashed) -> bool) -> FilteredScratch {
         let headers = self.headers.iter().filter(|&t| f(t)).cloned().collect();
         FilteredScratch { headers }
@@ -151,6 +160,11 @@ impl Scratch {
         self.headers.drain(..)
     }
 
+    /// Drain out all the scheduled functions.
+    pub fn drain_scheduled_fns(&mut self) -> impl Iterator<Item = (ScheduledFn, Timestamp)> + '_ {
+        self.scheduled_fns.drain(..)
+    }
+
     /// Drain out all the entries.
     pub fn drain_entries(&mut self) -> impl Iterator<Item = EntryHashed> + '_ {
         self.entries.drain().map(|(hash, entry)| {
@@ -20,6 +20,8 @@ use holochain_types::Timestamp;
 use holochain_zome_types::entry::EntryHashed;
 use holochain_zome_types::header;
 use holochain_zome_types::CapAccess;
+use holochain_zome_types::CapClaim;
+use holochain_zome_types::CapClaimQuery;
 use holochain_zome_types::CapGrant;
 use holochain_zome_types::CapSecret;
 use holochain_zome_types::ChainTopOrdering;
@@ -36,6 +38,7 @@ use holochain_zome_types::HeaderHashed;
 use holochain_zome_types::HeaderInner;
 use holochain_zome_types::PreflightRequest;
 use holochain_zome_types::QueryFilter;
+use holochain_zome_types::ScheduledFn;
 use holochain_zome_types::Signature;
 use holochain_zome_types::SignedHeader;
 use holochain_zome_types::SignedHeaderHashed;
@@ -57,9 +60,9 @@ pub struct SourceChain {
     scratch: SyncScratch,
     vault: EnvWrite,
     author: Arc<AgentPubKey>,
-    persisted_seq: u32,
-    persisted_head: HeaderHash,
-    persisted_timestamp: Timestamp,
+    /// The hash, seq and timestamp of the head of the persisted chain.
+    /// Observers have no chain, so they have no head.
+    persisted_head: Option<(HeaderHash, u32, Timestamp)>,
     public_only: bool,
 }
 
@@ -85,7 +88,7 @@ impl SourceChain {
     pub async fn new(vault: EnvWrite, author: AgentPubKey) -> SourceChainResult<Self> {
         let scratch = Scratch::new().into_sync();
         let author = Arc::new(author);
-        let (persisted_head, persisted_seq, persisted_timestamp) = vault
+        let persisted_head = vault
             .async_reader({
                 let author = author.clone();
                 move |txn| chain_head_db(&txn, author)
@@ -95,12 +98,28 @@ impl SourceChain {
             scratch,
             vault,
             author,
-            persisted_seq,
-            persisted_head,
-            persisted_timestamp,
+            persisted_head: Some(persisted_head),
             public_only: false,
         })
     }
+
+    /// A chain for an observer, which can query the vault but never
+    /// writes to it. Anything put on the chain fails to flush.
+    pub fn observer(vault: EnvWrite, author: AgentPubKey) -> Self {
+        Self {
+            scratch: Scratch::new().into_sync(),
+            vault,
+            author: Arc::new(author),
+            persisted_head: None,
+            public_only: false,
+        }
+    }
+
+    /// Is this the chain of an observer?
+    pub fn is_observer(&self) -> bool {
+        self.persisted_head.is_none()
+    }
+
     pub fn public_only(&mut self) {
         self.public_only = true;
     }
@@ -114,6 +133,18 @@ impl SourceChain {
         self.scratch.clone()
     }
 
+    /// Schedule a zome function to run any time after `start_at`.
+    /// The schedule is only persisted when the scratch is flushed.
+    pub fn schedule_fn(
+        &self,
+        scheduled_fn: ScheduledFn,
+        start_at: Timestamp,
+    ) -> SourceChainResult<()> {
+        Ok(self
+            .scratch
+            .apply(|scratch| scratch.add_scheduled_fn(scheduled_fn, start_at))?)
+    }
+
     pub fn agent_pubkey(&self) -> &AgentPubKey {
         self.author.as_ref()
     }
@@ -128,16 +159,14 @@ impl SourceChain {
     }
 
     pub fn chain_head(&self) -> SourceChainResult<(HeaderHash, u32, Timestamp)> {
+        let persisted_head = self
+            .persisted_head
+            .clone()
+            .ok_or(SourceChainError::ObserverChain)?;
         // Check scratch for newer head.
         Ok(self.scratch.apply(|scratch| {
             let chain_head = chain_head_scratch(&(*scratch), self.author.as_ref());
-            let (prev_header, header_seq, timestamp) = chain_head.unwrap_or_else(|| {
-                (
-                    self.persisted_head.clone(),
-                    self.persisted_seq,
-                    self.persisted_timestamp,
-                )
-            });
+            let (prev_header, header_seq, timestamp) = chain_head.unwrap_or(persisted_head);
             (prev_header, header_seq, timestamp)
         })?)
     }
@@ -203,8 +232,56 @@ impl SourceChain {
         .await
     }
 
+    /// Has init run on this chain? Checks for the [`header::InitZomesComplete`]
+    /// header rather than the chain length, as chains opened by migrating from
+    /// another chain start with an extra [`header::OpenChain`] header.
     pub fn has_initialized(&self) -> SourceChainResult<bool> {
-        Ok(self.len()? > 3)
+        let in_scratch = self.scratch.apply(|scratch| {
+            scratch
+                .headers()
+                .any(|shh| shh.header().header_type() == header::HeaderType::InitZomesComplete)
+        })?;
+        if in_scratch {
+            return Ok(true);
+        }
+        let author = self.author.clone();
+        Ok(self.vault.conn()?.with_reader(|txn| {
+            txn.query_row(
+                "
+                SELECT EXISTS(
+                    SELECT 1 FROM Header
+                    WHERE author = :author AND type = :type
+                )
+                ",
+                named_params! {
+                    ":author": author.as_ref(),
+                    ":type": header::HeaderType::InitZomesComplete,
+                },
+                |row| row.get(0),
+            )
+            .map_err(StateQueryError::from)
+        })?)
+    }
+
+    /// The membrane proof this chain was created with, if it had one.
+    pub fn membrane_proof(&self) -> SourceChainResult<Option<SerializedBytes>> {
+        let author = self.author.clone();
+        let header = self.vault.conn()?.with_reader(|txn| {
+            txn.prepare("SELECT blob FROM Header WHERE author = :author AND type = :type")?
+                .query_and_then(
+                    named_params! {
+                        ":author": author.as_ref(),
+                        ":type": header::HeaderType::AgentValidationPkg,
+                    },
+                    |row| from_blob::<SignedHeader>(row.get("blob")?),
+                )?
+                .next()
+                .transpose()
+        })?;
+        Ok(match header {
+            Some(SignedHeader(Header::AgentValidationPkg(avp), _)) => avp.membrane_proof,
+            _ => None,
+        })
     }
 
     pub fn is_empty(&self) -> SourceChainResult<bool> {
@@ -213,12 +290,17 @@ impl SourceChain {
 
     #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> SourceChainResult<u32> {
+        let persisted_seq = match &self.persisted_head {
+            Some((_, seq, _)) => *seq,
+            // Nothing can be put on an observer's chain
+            None => return Ok(0),
+        };
         Ok(self.scratch.apply(|scratch| {
             let scratch_max =
                 chain_head_scratch(&(*scratch), self.author.as_ref()).map(|(_, s, _)| s);
             scratch_max
-                .map(|s| std::cmp::max(s, self.persisted_seq))
-                .unwrap_or(self.persisted_seq)
+                .map(|s| std::cmp::max(s, persisted_seq))
+                .unwrap_or(persisted_seq)
                 + 1
         })?)
     }
@@ -327,6 +409,81 @@ impl SourceChain {
         Ok(valid_cap_grant)
     }
 
+    /// Query the capability claims on this chain that have not been deleted,
+    /// including any claims created in the current scratch.
+    pub async fn capability_claims(
+        &self,
+        query: CapClaimQuery,
+    ) -> SourceChainResult<Vec<CapClaim>> {
+        let author = self.author.clone();
+        let mut claims = self
+            .vault
+            .async_reader({
+                let query = query.clone();
+                move |txn| {
+                    // Only claims set the grantor column.
+                    let sql = "
+                    SELECT DISTINCT Entry.blob
+                    FROM Entry
+                    JOIN Header ON Header.entry_hash = Entry.hash
+                    JOIN DhtOp ON DhtOp.header_hash = Header.hash
+                    WHERE
+                    Header.author = :author
+                    AND
+                    DhtOp.is_authored = 1
+                    AND
+                    Entry.grantor IS NOT NULL
+                    AND
+                    (:tag IS NULL OR Entry.tag = :tag)
+                    AND
+                    (:grantor IS NULL OR Entry.grantor = :grantor)
+                    AND
+                    (:cap_secret IS NULL OR Entry.cap_secret = :cap_secret)
+                    AND
+                    NOT EXISTS (
+                        SELECT 1
+                        FROM Header AS H_REF
+                        JOIN DhtOp AS D_REF ON D_REF.header_hash = H_REF.hash
+                        WHERE
+                        D_REF.is_authored = 1
+                        AND
+                        H_REF.deletes_header_hash = Header.hash
+                    )
+                    ";
+                    let cap_secret = query.secret.as_ref().map(to_blob).transpose()?;
+                    txn.prepare(sql)?
+                        .query_and_then(
+                            named_params! {
+                                ":author": author.as_ref(),
+                                ":tag": query.tag,
+                                ":grantor": query.grantor,
+                                ":cap_secret": cap_secret,
+                            },
+                            |row| from_blob::<Entry>(row.get("blob")?),
+                        )?
+                        .filter_map(|entry| match entry {
+                            Ok(Entry::CapClaim(claim)) => Some(Ok(claim)),
+                            Ok(_) => None,
+                            Err(e) => Some(Err(e)),
+                        })
+                        .collect::<StateQueryResult<Vec<_>>>()
+                }
+            })
+            .await?;
+        self.scratch.apply(|scratch| {
+            let scratch_iter = scratch
+                .entries()
+                .filter_map(|(_, entry)| match entry.as_ref() {
+                    Entry::CapClaim(claim) if query.check(claim) => Some(claim.clone()),
+                    _ => None,
+                })
+                .filter(|claim| !claims.contains(claim))
+                .collect::<Vec<_>>();
+            claims.extend(scratch_iter);
+        })?;
+        Ok(claims)
+    }
+
     /// Query Headers in the source chain.
     /// This returns a Vec rather than an iterator because it is intended to be
     /// used by the `query` host function, which crosses the wasm boundary
@@ -578,10 +735,19 @@ impl SourceChain {
             Ok((headers, ops))
         }
 
+        // Scheduled functions are written even if there are no headers.
+        let scheduled_fns = self
+            .scratch
+            .apply(|scratch| scratch.drain_scheduled_fns().collect::<Vec<_>>())?;
+
         // Nothing to write
-        if self.scratch.apply(|s| s.is_empty())? {
+        if self.scratch.apply(|s| s.is_empty())? && scheduled_fns.is_empty() {
             return Ok(());
         }
+        let persisted_head = match &self.persisted_head {
+            Some((head, _, _)) => head.clone(),
+            None => return Err(SourceChainError::ObserverChain),
+        };
         let (headers, ops, entries) = self.scratch.apply_and_then(|scratch| {
             let (headers, ops) =
                 build_ops_from_headers(scratch.drain_headers().collect::<Vec<_>>())?;
@@ -610,9 +776,12 @@ impl SourceChain {
 
         // Write the entries, headers and ops to the database in one transaction.
         let author = self.author.clone();
-        let persisted_head = self.persisted_head.clone();
         self.vault
             .async_commit(move |txn: &mut Transaction| {
+                for (scheduled_fn, start_at) in scheduled_fns {
+                    schedule_fn(txn, &scheduled_fn, start_at)?;
+                }
+
                 // As at check.
                 let (new_persisted_head, _, _) = chain_head_db(&txn, author)?;
                 if headers.last().is_none() {
@@ -1279,4 +1448,36 @@ pub mod tests {
 
         Ok(())
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn observer_chain_is_read_only() -> SourceChainResult<()> {
+        let test_env = test_cell_env();
+        let vault = test_env.env();
+        let author = test_env.cell_id().unwrap().agent_pubkey().clone();
+
+        let chain = SourceChain::observer(vault.clone().into(), author.clone());
+        assert!(chain.is_observer());
+        assert!(chain.is_empty()?);
+        assert!(chain.query(QueryFilter::new()).await?.is_empty());
+
+        let (entry, entry_hash) =
+            EntryHashed::from_content_sync(Entry::Agent(author.clone())).into_inner();
+        let header_builder = builder::Create {
+            entry_type: EntryType::AgentPubKey,
+            entry_hash,
+        };
+        assert_matches!(
+            chain
+                .put(header_builder, Some(entry), ChainTopOrdering::default())
+                .await,
+            Err(SourceChainError::ObserverChain)
+        );
+
+        // Nothing was written, so there is no chain to open
+        assert!(matches!(
+            SourceChain::new(vault.clone().into(), author).await,
+            Err(SourceChainError::ChainEmpty)
+        ));
+        Ok(())
+    }
 }
@@ -89,6 +89,17 @@ impl CreateCloneCellPayload {
     }
 }
 
+/// Identifies an existing clone cell of an app, to archive, restore or delete it
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct CloneCellPayload {
+    /// The App the clone cell belongs to
+    pub installed_app_id: InstalledAppId,
+    /// The SlotId the cell was cloned from
+    pub slot_id: SlotId,
+    /// The CellId of the clone cell
+    pub cell_id: CellId,
+}
+
 /// A collection of [DnaHash]es paired with an [AgentPubKey] and an app id
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct InstallAppPayload {
@@ -126,6 +137,24 @@ pub struct InstallAppBundlePayload {
     pub uid: Option<Uid>,
 }
 
+/// A new version of an installed app, as an [AppBundle]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct UpgradeAppPayload {
+    /// The bundle of the new version of the app.
+    #[serde(flatten)]
+    pub source: AppBundleSource,
+
+    /// The app to upgrade.
+    pub installed_app_id: InstalledAppId,
+
+    /// Include proof-of-membrane-membership data for cells that require it,
+    /// keyed by the CellNick specified in the app bundle manifest.
+    pub membrane_proofs: HashMap<CellNick, MembraneProof>,
+
+    /// Optional: overwrites all UIDs for all DNAs of Cells created by this app.
+    pub uid: Option<Uid>,
+}
+
 /// The possible locations of an AppBundle
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -383,12 +412,16 @@ impl From<StoppedApp> for InstalledApp {
 pub struct InstalledAppCommon {
     /// The unique identifier for an installed app in this conductor
     installed_app_id: InstalledAppId,
-    /// The agent key used to install this app. Currently this is meaningless,
-    /// but I'm leaving it here as a placeholder in case we ever want it to
-    /// have formal significance.
+    /// The agent key used to install this app. This is replaced when the
+    /// app's agent key is rotated, but otherwise has no formal significance.
     _agent_key: AgentPubKey,
     /// The "slots" as specified in the AppManifest
     slots: HashMap<CellNick, AppSlot>,
+    /// The cells replaced by a pending upgrade, by the slot they were in.
+    /// They keep running so their data can still be read, until the
+    /// upgrade is confirmed.
+    #[serde(default)]
+    upgraded_cells: HashMap<SlotId, CellId>,
 }
 
 impl InstalledAppCommon {
@@ -402,6 +435,7 @@ impl InstalledAppCommon {
             installed_app_id: installed_app_id.to_string(),
             _agent_key,
             slots: slots.into_iter().collect(),
+            upgraded_cells: HashMap::new(),
         }
     }
 
@@ -429,11 +463,39 @@ impl InstalledAppCommon {
         self.slots.iter().map(|(_, slot)| &slot.clones).flatten()
     }
 
-    /// Iterator of all cells, both provisioned and cloned
+    /// Accessor
+    pub fn cloned_cells_by_slot(&self) -> impl Iterator<Item = (&SlotId, &CellId)> {
+        self.slots
+            .iter()
+            .flat_map(|(slot_id, slot)| slot.clones.iter().map(move |c| (slot_id, c)))
+    }
+
+    /// Accessor
+    pub fn archived_cells_by_slot(&self) -> impl Iterator<Item = (&SlotId, &CellId)> {
+        self.slots
+            .iter()
+            .flat_map(|(slot_id, slot)| slot.archived_clones.iter().map(move |c| (slot_id, c)))
+    }
+
+    /// Accessor
+    pub fn upgraded_cells(&self) -> impl Iterator<Item = (&SlotId, &CellId)> {
+        self.upgraded_cells.iter()
+    }
+
+    /// Iterator of all cells: provisioned, cloned, and replaced by a pending upgrade
     pub fn all_cells(&self) -> impl Iterator<Item = &CellId> {
         self.provisioned_cells()
             .map(|(_, c)| c)
             .chain(self.cloned_cells())
+            .chain(self.upgraded_cells.values())
+    }
+
+    /// Iterator of the provisioned cells which are observers
+    pub fn observer_cells(&self) -> impl Iterator<Item = &CellId> {
+        self.slots
+            .values()
+            .filter(|slot| slot.is_observer)
+            .filter_map(|slot| slot.provisioned_cell())
     }
 
     /// Iterator of all "required" cells, meaning Cells which must be running
@@ -482,11 +544,97 @@ impl InstalledAppCommon {
         Ok(slot.clones.remove(cell_id))
     }
 
+    /// Archive a cloned cell. An archived cell no longer counts towards the
+    /// clone limit and does not run, but its data is kept so it can be restored.
+    /// Returns false if there is no such clone.
+    pub fn archive_clone(&mut self, slot_id: &SlotId, cell_id: &CellId) -> AppResult<bool> {
+        let slot = self.slot_mut(slot_id)?;
+        if slot.clones.remove(cell_id) {
+            slot.archived_clones.insert(cell_id.clone());
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Restore an archived cell as a clone, subject to the clone limit.
+    /// Returns false if there is no such archived clone.
+    pub fn restore_clone(&mut self, slot_id: &SlotId, cell_id: &CellId) -> AppResult<bool> {
+        let slot = self.slot_mut(slot_id)?;
+        if !slot.archived_clones.contains(cell_id) {
+            return Ok(false);
+        }
+        if slot.clones.len() as u32 >= slot.clone_limit {
+            return Err(AppError::CloneLimitExceeded(slot.clone_limit, slot.clone()));
+        }
+        slot.archived_clones.remove(cell_id);
+        slot.clones.insert(cell_id.clone());
+        Ok(true)
+    }
+
+    /// Remove an archived cell.
+    /// Returns false if there is no such archived clone.
+    pub fn remove_archived_clone(&mut self, slot_id: &SlotId, cell_id: &CellId) -> AppResult<bool> {
+        let slot = self.slot_mut(slot_id)?;
+        Ok(slot.archived_clones.remove(cell_id))
+    }
+
     /// Accessor
     pub fn _agent_key(&self) -> &AgentPubKey {
         &self._agent_key
     }
 
+    /// Move the app onto a new agent key. Every cell using the app's current
+    /// agent key, including archived clones, now uses the new key instead.
+    pub fn rotate_agent_key(&mut self, new_key: AgentPubKey) {
+        let old_key = std::mem::replace(&mut self._agent_key, new_key.clone());
+        let rotate = |cell_id: &CellId| {
+            if *cell_id.agent_pubkey() == old_key {
+                CellId::new(cell_id.dna_hash().clone(), new_key.clone())
+            } else {
+                cell_id.clone()
+            }
+        };
+        for slot in self.slots.values_mut() {
+            slot.base_cell_id = rotate(&slot.base_cell_id);
+            slot.clones = slot.clones.iter().map(rotate).collect();
+            slot.archived_clones = slot.archived_clones.iter().map(rotate).collect();
+        }
+    }
+
+    /// Put the slots of an upgrade in place, adding or replacing slots.
+    /// The provisioned cell of each replaced slot is kept as an upgraded cell
+    /// until the upgrade is confirmed. Clones of a replaced slot are carried
+    /// over to the new slot, as they can't be migrated along with it.
+    pub fn upgrade_slots<I: IntoIterator<Item = (SlotId, AppSlot)>>(
+        &mut self,
+        slots: I,
+    ) -> AppResult<()> {
+        if !self.upgraded_cells.is_empty() {
+            return Err(AppError::UpgradePending(self.installed_app_id.clone()));
+        }
+        for (slot_id, mut slot) in slots {
+            if let Some(mut old_slot) = self.slots.remove(&slot_id) {
+                slot.clones = std::mem::take(&mut old_slot.clones);
+                slot.archived_clones = std::mem::take(&mut old_slot.archived_clones);
+                if let Some(cell_id) = old_slot.into_provisioned_cell() {
+                    self.upgraded_cells.insert(slot_id.clone(), cell_id);
+                }
+            }
+            self.slots.insert(slot_id, slot);
+        }
+        Ok(())
+    }
+
+    /// Confirm a pending upgrade, returning the cells it replaced,
+    /// which are no longer part of this app.
+    pub fn confirm_upgrade(&mut self) -> Vec<CellId> {
+        self.upgraded_cells
+            .drain()
+            .map(|(_, cell_id)| cell_id)
+            .collect()
+    }
+
     /// Constructor for apps not using a manifest.
     /// Disables cloning, and implies immediate provisioning.
     pub fn new_legacy<S: ToString, I: IntoIterator<Item = InstalledCell>>(
@@ -536,6 +684,8 @@ impl InstalledAppCommon {
                     is_provisioned: true,
                     clones: HashSet::new(),
                     clone_limit: 0,
+                    archived_clones: HashSet::new(),
+                    is_observer: false,
                 };
                 (cell_nick, slot)
             })
@@ -544,6 +694,7 @@ impl InstalledAppCommon {
             installed_app_id,
             _agent_key,
             slots,
+            upgraded_cells: HashMap::new(),
         })
     }
 }
@@ -765,6 +916,14 @@ pub struct AppSlot {
     /// Cells which were cloned at runtime. The length cannot grow beyond
     /// `clone_limit`
     clones: HashSet<CellId>,
+    /// Clones which have been archived. They don't run, but their data is
+    /// kept until they are deleted.
+    #[serde(default)]
+    archived_clones: HashSet<CellId>,
+    /// Records whether the base cell is an observer, which reads from the
+    /// DHT but never runs genesis or authors a source chain
+    #[serde(default)]
+    is_observer: bool,
 }
 
 impl AppSlot {
@@ -775,6 +934,17 @@ impl AppSlot {
             is_provisioned,
             clone_limit,
             clones: HashSet::new(),
+            archived_clones: HashSet::new(),
+            is_observer: false,
+        }
+    }
+
+    /// Constructor for the slot of a provisioned observer cell,
+    /// which can't be cloned.
+    pub fn observer(base_cell_id: CellId) -> Self {
+        Self {
+            is_observer: true,
+            ..Self::new(base_cell_id, true, 0)
         }
     }
 
@@ -793,6 +963,11 @@ impl AppSlot {
         &self.base_cell_id.agent_pubkey()
     }
 
+    /// Accessor
+    pub fn is_observer(&self) -> bool {
+        self.is_observer
+    }
+
     /// Accessor
     pub fn provisioned_cell(&self) -> Option<&CellId> {
         if self.is_provisioned {
@@ -866,4 +1041,123 @@ mod tests {
             app.all_cells().collect::<HashSet<_>>()
         );
     }
+
+    #[test]
+    fn clone_archiving() {
+        let base_cell_id = fixt!(CellId);
+        let agent = base_cell_id.agent_pubkey().clone();
+        let new_clone = || CellId::new(fixt!(DnaHash), agent.clone());
+        let slot1 = AppSlot::new(base_cell_id, false, 1);
+        let slot_id: SlotId = "slot_id".into();
+        let mut app: RunningApp =
+            InstalledAppCommon::new("app", fixt!(AgentPubKey), vec![(slot_id.clone(), slot1)])
+                .into();
+
+        let (clone1, clone2) = (new_clone(), new_clone());
+        app.add_clone(&slot_id, clone1.clone()).unwrap();
+
+        // Archiving frees up room under the clone limit
+        assert_eq!(app.archive_clone(&slot_id, &clone1).unwrap(), true);
+        assert_eq!(app.archive_clone(&slot_id, &clone1).unwrap(), false);
+        assert_eq!(app.all_cells().count(), 0);
+        assert_eq!(
+            app.archived_cells_by_slot().collect::<Vec<_>>(),
+            vec![(&slot_id, &clone1)]
+        );
+        app.add_clone(&slot_id, clone2.clone()).unwrap();
+
+        // Restoring is subject to the clone limit
+        matches::assert_matches!(
+            app.restore_clone(&slot_id, &clone1),
+            Err(AppError::CloneLimitExceeded(1, _))
+        );
+        app.remove_clone(&slot_id, &clone2).unwrap();
+        assert_eq!(app.restore_clone(&slot_id, &clone1).unwrap(), true);
+        assert_eq!(app.restore_clone(&slot_id, &clone1).unwrap(), false);
+        assert_eq!(
+            app.cloned_cells().collect::<HashSet<_>>(),
+            maplit::hashset! { &clone1 }
+        );
+
+        // Only archived clones can be removed as archived
+        assert_eq!(app.remove_archived_clone(&slot_id, &clone1).unwrap(), false);
+        app.archive_clone(&slot_id, &clone1).unwrap();
+        assert_eq!(app.remove_archived_clone(&slot_id, &clone1).unwrap(), true);
+        assert_eq!(app.archived_cells_by_slot().count(), 0);
+    }
+
+    #[test]
+    fn agent_key_rotation() {
+        let base_cell_id = fixt!(CellId);
+        let old_key = base_cell_id.agent_pubkey().clone();
+        let new_key = fixt!(AgentPubKey);
+        let slot_id: SlotId = "slot_id".into();
+        let mut app: RunningApp = InstalledAppCommon::new(
+            "app",
+            old_key.clone(),
+            vec![(slot_id.clone(), AppSlot::new(base_cell_id.clone(), true, 2))],
+        )
+        .into();
+        let (clone1, clone2) = (
+            CellId::new(fixt!(DnaHash), old_key.clone()),
+            CellId::new(fixt!(DnaHash), old_key.clone()),
+        );
+        app.add_clone(&slot_id, clone1.clone()).unwrap();
+        app.add_clone(&slot_id, clone2.clone()).unwrap();
+        app.archive_clone(&slot_id, &clone2).unwrap();
+
+        app.rotate_agent_key(new_key.clone());
+
+        let with_new_key =
+            |cell_id: &CellId| CellId::new(cell_id.dna_hash().clone(), new_key.clone());
+        assert_eq!(app._agent_key(), &new_key);
+        assert_eq!(
+            app.all_cells().cloned().collect::<HashSet<_>>(),
+            maplit::hashset! { with_new_key(&base_cell_id), with_new_key(&clone1) }
+        );
+        assert_eq!(
+            app.archived_cells_by_slot().collect::<Vec<_>>(),
+            vec![(&slot_id, &with_new_key(&clone2))]
+        );
+    }
+
+    #[test]
+    fn slot_upgrade() {
+        let old_cell_id = fixt!(CellId);
+        let agent = old_cell_id.agent_pubkey().clone();
+        let new_cell_id = CellId::new(fixt!(DnaHash), agent.clone());
+        let slot_id: SlotId = "slot_id".into();
+        let mut app: RunningApp = InstalledAppCommon::new(
+            "app",
+            agent.clone(),
+            vec![(slot_id.clone(), AppSlot::new(old_cell_id.clone(), true, 1))],
+        )
+        .into();
+        let clone = CellId::new(fixt!(DnaHash), agent.clone());
+        app.add_clone(&slot_id, clone.clone()).unwrap();
+
+        app.upgrade_slots(vec![(
+            slot_id.clone(),
+            AppSlot::new(new_cell_id.clone(), true, 1),
+        )])
+        .unwrap();
+
+        // The old cell stays around until the upgrade is confirmed.
+        assert_eq!(app.slot(&slot_id).unwrap().cell_id(), &new_cell_id);
+        assert_eq!(
+            app.upgraded_cells().collect::<Vec<_>>(),
+            vec![(&slot_id, &old_cell_id)]
+        );
+        assert_eq!(
+            app.all_cells().cloned().collect::<HashSet<_>>(),
+            maplit::hashset! { new_cell_id.clone(), clone.clone(), old_cell_id.clone() }
+        );
+        matches::assert_matches!(app.upgrade_slots(vec![]), Err(AppError::UpgradePending(_)));
+
+        assert_eq!(app.confirm_upgrade(), vec![old_cell_id]);
+        assert_eq!(
+            app.all_cells().cloned().collect::<HashSet<_>>(),
+            maplit::hashset! { new_cell_id, clone }
+        );
+    }
 }
@@ -79,6 +79,13 @@ impl AppBundle {
                                 resolution.dnas_to_register.push((dna, proof));
                                 resolution.slots.push((slot_id, slot));
                             }
+                            CellProvisioningOp::Observe(dna) => {
+                                let agent = resolution.agent.clone();
+                                let dna_hash = dna.dna_hash().clone();
+                                let slot = AppSlot::observer(CellId::new(dna_hash, agent));
+                                resolution.dnas_to_register.push((dna, None));
+                                resolution.slots.push((slot_id, slot));
+                            }
                             CellProvisioningOp::Existing(cell_id, clone_limit) => {
                                 let slot = AppSlot::new(cell_id, true, clone_limit);
                                 resolution.slots.push((slot_id, slot));
@@ -107,6 +114,67 @@ impl AppBundle {
         Ok(resolution)
     }
 
+    /// Resolve the cells of this bundle as an upgrade of an installed app.
+    ///
+    /// Slots whose DNA is unchanged are left out of the resolution. A slot
+    /// whose DNA changed will have its cell migrated to a new cell, which is
+    /// only allowed if the slot's version spec includes the DNA being
+    /// replaced.
+    pub async fn resolve_upgrade(
+        self,
+        app: &InstalledAppCommon,
+        membrane_proofs: HashMap<SlotId, MembraneProof>,
+    ) -> AppBundleResult<AppUpgradeResolution> {
+        let AppManifestValidated { name: _, slots } = self.manifest().clone().validate()?;
+        let mut resolution = self
+            .resolve_cells(
+                app._agent_key().clone(),
+                DnaGamut::new(app.all_cells().cloned()),
+                membrane_proofs,
+            )
+            .await?;
+        let mut migrations = Vec::new();
+        let mut new_slots = Vec::new();
+        for (slot_id, slot) in resolution.slots {
+            match app.slots().get(&slot_id) {
+                Some(old_slot) if old_slot.dna_hash() == slot.dna_hash() => continue,
+                Some(old_slot) => {
+                    let old_dna_hash = old_slot.dna_hash().clone();
+                    let allowed = slots
+                        .get(&slot_id)
+                        .and_then(|s| s.version())
+                        .map(|version| version.matches(old_dna_hash.clone()))
+                        .unwrap_or(false);
+                    if !allowed {
+                        return Err(AppBundleError::UpgradeNotAllowed(slot_id, old_dna_hash));
+                    }
+                    // Observers have no chain to migrate, and can't become
+                    // the target of a migration either.
+                    if let Some(old_cell) = old_slot.provisioned_cell() {
+                        if old_slot.is_observer() != slot.is_observer() {
+                            return Err(AppBundleError::ObserverUpgrade(slot_id));
+                        }
+                        if !slot.is_observer() {
+                            migrations.push((old_cell.clone(), slot.cell_id().clone()));
+                        }
+                    }
+                }
+                None => (),
+            }
+            new_slots.push((slot_id, slot));
+        }
+        resolution.dnas_to_register.retain(|(dna, _)| {
+            new_slots
+                .iter()
+                .any(|(_, slot)| slot.dna_hash() == dna.dna_hash())
+        });
+        resolution.slots = new_slots;
+        Ok(AppUpgradeResolution {
+            resolution,
+            migrations,
+        })
+    }
+
     async fn resolve_cell(
         &self,
         slot: AppSlotManifestValidated,
@@ -160,6 +228,23 @@ impl AppBundle {
                 CellProvisioningOp::Noop(_, _) => {
                     unreachable!("resolve_cell_existing will never return a Noop")
                 }
+                CellProvisioningOp::Observe(_) => {
+                    unreachable!("resolve_cell_existing will never return an Observe op")
+                }
+            },
+            AppSlotManifestValidated::Observe {
+                location,
+                version,
+                properties,
+                uid,
+                clone_limit: _,
+                deferred: _,
+            } => match self
+                .resolve_cell_create(&location, version.as_ref(), 0, uid, properties)
+                .await?
+            {
+                CellProvisioningOp::Create(dna, _) => CellProvisioningOp::Observe(dna),
+                op => op,
             },
             AppSlotManifestValidated::Disabled {
                 version: _,
@@ -227,7 +312,8 @@ impl CellSlotResolution {
     }
 
     /// Return the IDs of new cells to be created as part of the resolution.
-    /// Does not return existing cells to be reused.
+    /// Does not return existing cells to be reused, nor observer cells,
+    /// which have no source chain to create.
     // TODO: remove clone of MembraneProof
     pub fn cells_to_create(&self) -> Vec<(CellId, Option<MembraneProof>)> {
         self.dnas_to_register
@@ -238,16 +324,33 @@ impl CellSlotResolution {
                     proof.clone(),
                 )
             })
+            .filter(|(cell_id, _)| {
+                !self
+                    .slots
+                    .iter()
+                    .any(|(_, slot)| slot.is_observer() && slot.cell_id() == cell_id)
+            })
             .collect()
     }
 }
 
+/// The result of resolving an AppBundle as an upgrade of an installed app
+#[derive(PartialEq, Eq, Debug)]
+pub struct AppUpgradeResolution {
+    /// The new and changed slots, and the DNAs they need
+    pub resolution: CellSlotResolution,
+    /// The cells to migrate, as pairs of old and new CellId
+    pub migrations: Vec<(CellId, CellId)>,
+}
+
 /// Specifies what step should be taken to provision a cell while installing an App
 #[warn(missing_docs)]
 #[derive(Debug)]
 pub enum CellProvisioningOp {
     /// Create a new Cell
     Create(DnaFile, u32),
+    /// Create a new observer Cell, which has no source chain
+    Observe(DnaFile),
     /// Use an existing Cell
     Existing(CellId, u32),
     /// No provisioning needed, but there might be a clone_limit, and so we need
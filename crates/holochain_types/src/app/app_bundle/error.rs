@@ -1,7 +1,7 @@
 use holochain_util::ffs;
 use mr_bundle::error::MrBundleError;
 
-use crate::prelude::{AppManifestError, CellNick, DnaError};
+use crate::prelude::{AppManifestError, CellNick, DnaError, DnaHash};
 
 /// Errors occurring while installing an AppBundle
 #[derive(thiserror::Error, Debug)]
@@ -9,6 +9,12 @@ pub enum AppBundleError {
     #[error("Could not resolve the cell slot '{0}'")]
     CellResolutionFailure(CellNick),
 
+    #[error("The cell slot '{0}' can't be upgraded from DNA {1}, as its version spec doesn't include it")]
+    UpgradeNotAllowed(CellNick, DnaHash),
+
+    #[error("The cell slot '{0}' can't be upgraded between an observer and an authoring cell")]
+    ObserverUpgrade(CellNick),
+
     #[error(transparent)]
     AppManifestError(#[from] AppManifestError),
 
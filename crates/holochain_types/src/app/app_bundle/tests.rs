@@ -69,3 +69,105 @@ async fn provisioning_1_create() {
     };
     assert_eq!(resolution, expected);
 }
+
+/// Test that a slot can only be upgraded from a DNA listed in its version spec
+#[tokio::test]
+async fn upgrade_slot_from_listed_dna() {
+    observability::test_run().ok();
+    let agent = fixt!(AgentPubKey);
+    let (bundle, original_dna) = app_bundle_fixture().await;
+    let dna = original_dna
+        .clone()
+        .with_uid("uid".to_string())
+        .await
+        .unwrap()
+        .with_properties(SerializedBytes::try_from(app_manifest_properties_fixture()).unwrap())
+        .await
+        .unwrap();
+    let app_with_dna = |dna_hash: DnaHash| {
+        let cell_id = CellId::new(dna_hash, agent.clone());
+        InstalledAppCommon::new(
+            "app",
+            agent.clone(),
+            vec![("nick".into(), AppSlot::new(cell_id, true, 0))],
+        )
+    };
+
+    // The version spec in the manifest fixture lists the original DNA.
+    let old_cell_id = CellId::new(original_dna.dna_hash().clone(), agent.clone());
+    let new_cell_id = CellId::new(dna.dna_hash().clone(), agent.clone());
+    let upgrade = bundle
+        .resolve_upgrade(
+            &app_with_dna(original_dna.dna_hash().clone()),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(upgrade.migrations, vec![(old_cell_id, new_cell_id.clone())]);
+    assert_eq!(upgrade.resolution.dnas_to_register, vec![(dna, None)]);
+    assert_eq!(
+        upgrade.resolution.slots,
+        vec![("nick".into(), AppSlot::new(new_cell_id, true, 50))]
+    );
+
+    let (bundle, _) = app_bundle_fixture().await;
+    let upgrade = bundle
+        .resolve_upgrade(&app_with_dna(fixt!(DnaHash)), Default::default())
+        .await;
+    assert!(matches!(
+        upgrade,
+        Err(AppBundleError::UpgradeNotAllowed(slot_id, _)) if slot_id == "nick"
+    ));
+}
+
+/// Test that an Observed cell registers its DNA, but has no source chain to create
+#[tokio::test]
+async fn provisioning_1_observe() {
+    observability::test_run().ok();
+    let agent = fixt!(AgentPubKey);
+    let (bundle, dna) = app_bundle_fixture().await;
+    let dna = dna
+        .with_uid("uid".to_string())
+        .await
+        .unwrap()
+        .with_properties(SerializedBytes::try_from(app_manifest_properties_fixture()).unwrap())
+        .await
+        .unwrap();
+    let cell_id = CellId::new(dna.dna_hash().to_owned(), agent.clone());
+
+    let observe = |bundle: AppBundle, clone_limit| {
+        let AppManifest::V1(mut manifest) = bundle.manifest().clone();
+        manifest.slots[0].provisioning = Some(CellProvisioning::Observe { deferred: false });
+        manifest.slots[0].dna.clone_limit = clone_limit;
+        AppBundle::from(
+            bundle
+                .into_inner()
+                .update_manifest(AppManifest::V1(manifest))
+                .unwrap(),
+        )
+    };
+
+    // Observers can't be cloned
+    let (clonable, _) = app_bundle_fixture().await;
+    let resolution = observe(clonable, 50)
+        .resolve_cells(agent.clone(), DnaGamut::placeholder(), Default::default())
+        .await;
+    assert!(matches!(
+        resolution,
+        Err(AppBundleError::AppManifestError(
+            AppManifestError::InvalidStrategyObserve(_)
+        ))
+    ));
+
+    let resolution = observe(bundle, 0)
+        .resolve_cells(agent.clone(), DnaGamut::placeholder(), Default::default())
+        .await
+        .unwrap();
+    let expected = CellSlotResolution {
+        agent,
+        dnas_to_register: vec![(dna, None)],
+        slots: vec![("nick".into(), AppSlot::observer(cell_id))],
+    };
+    assert_eq!(resolution, expected);
+    assert!(resolution.cells_to_create().is_empty());
+}
@@ -177,6 +177,10 @@ pub enum CellProvisioning {
     /// Disallow provisioning altogether. In this case, we expect
     /// `clone_limit > 0`: otherwise, no Cells will ever be created.
     Disabled,
+    /// Create a read-only Cell when installing the App. It joins the DNA's
+    /// network and can read from the DHT, but never runs genesis or authors
+    /// a source chain, so it can't be cloned.
+    Observe { deferred: bool },
 }
 
 impl Default for CellProvisioning {
@@ -264,6 +268,16 @@ impl AppManifestV1 {
                             clone_limit,
                             version: Self::require(version, "slots.dna.version")?,
                         },
+                        CellProvisioning::Observe { deferred } => {
+                            AppSlotManifestValidated::Observe {
+                                deferred,
+                                clone_limit,
+                                location: Self::require(location, "slots.dna.(path|url)")?,
+                                properties,
+                                uid,
+                                version,
+                            }
+                        }
                     };
                     Ok((id, validated))
                 },
@@ -35,6 +35,11 @@ impl AppManifestValidated {
                     return Err(AppManifestError::InvalidStrategyDisabled(nick.to_owned()));
                 }
             }
+            if let AppSlotManifestValidated::Observe { clone_limit, .. } = cell {
+                if *clone_limit > 0 {
+                    return Err(AppManifestError::InvalidStrategyObserve(nick.to_owned()));
+                }
+            }
         }
         Ok(AppManifestValidated { name, slots })
     }
@@ -85,4 +90,28 @@ pub enum AppSlotManifestValidated {
         version: DnaVersionSpec,
         clone_limit: u32,
     },
+    /// Create a read-only cell which never authors a source chain.
+    /// We expect `clone_limit == 0`, since it has no chain to clone.
+    Observe {
+        clone_limit: u32,
+        deferred: bool,
+        location: DnaLocation,
+        properties: Option<YamlProperties>,
+        uid: Option<String>,
+        version: Option<DnaVersionSpec>,
+    },
+}
+
+impl AppSlotManifestValidated {
+    /// The version spec of the slot's DNA, if any
+    pub fn version(&self) -> Option<&DnaVersionSpec> {
+        match self {
+            Self::Create { version, .. }
+            | Self::CreateClone { version, .. }
+            | Self::Observe { version, .. } => version.as_ref(),
+            Self::UseExisting { version, .. }
+            | Self::CreateIfNotExists { version, .. }
+            | Self::Disabled { version, .. } => Some(version),
+        }
+    }
 }
@@ -10,6 +10,9 @@ pub enum AppManifestError {
 
     #[error("Invalid manifest for cell nick '{0}': Using strategy 'disabled' with clone_limit == 0 is pointless")]
     InvalidStrategyDisabled(CellNick),
+
+    #[error("Invalid manifest for cell nick '{0}': Using strategy 'observe' with clone_limit > 0 is not allowed, since observer cells can't be cloned")]
+    InvalidStrategyObserve(CellNick),
 }
 
 pub type AppManifestResult<T> = Result<T, AppManifestError>;
@@ -13,5 +13,8 @@ pub enum AppError {
 
     #[error("Tried to install app '{0}' which contains duplicate slot ids. The following slot ids have duplicates: {1:?}")]
     DuplicateSlotIds(InstalledAppId, Vec<SlotId>),
+
+    #[error("App '{0}' has an upgrade pending which must be confirmed first")]
+    UpgradePending(InstalledAppId),
 }
 pub type AppResult<T> = Result<T, AppError>;
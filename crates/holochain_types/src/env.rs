@@ -65,7 +65,7 @@ impl EnvWrite {
         self.keystore.clone()
     }
 
-    /// Remove the db and directory
+    /// Remove the db from disk
     pub async fn remove(self) -> DatabaseResult<()> {
         self.db.remove().await
     }
@@ -19,13 +19,12 @@ pub struct TimedHeaderHash {
 
 /// Metadata returned from a GetMeta request.
 /// The Ord derive on TimedHeaderHash means each set is ordered by time.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, SerializedBytes)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, SerializedBytes)]
 pub struct MetadataSet {
     /// Headers that created or updated an entry.
     /// These are the headers that show the entry exists.
     pub headers: BTreeSet<TimedHeaderHash>,
-    // TODO: Implement after validation
-    /// Placeholder
+    /// Headers that were rejected or abandoned during validation.
     pub invalid_headers: BTreeSet<TimedHeaderHash>,
     /// Deletes on a header
     pub deletes: BTreeSet<TimedHeaderHash>,
@@ -37,6 +36,23 @@ pub struct MetadataSet {
     pub entry_dht_status: Option<EntryDhtStatus>,
 }
 
+impl MetadataSet {
+    /// Combine the metadata from another authority into this set.
+    /// An entry is live if any authority has seen a live header.
+    pub fn merge(&mut self, other: MetadataSet) {
+        self.headers.extend(other.headers);
+        self.invalid_headers.extend(other.invalid_headers);
+        self.deletes.extend(other.deletes);
+        self.updates.extend(other.updates);
+        self.entry_dht_status = match (self.entry_dht_status, other.entry_dht_status) {
+            (Some(EntryDhtStatus::Live), _) | (_, Some(EntryDhtStatus::Live)) => {
+                Some(EntryDhtStatus::Live)
+            }
+            (a, b) => a.or(b),
+        };
+    }
+}
+
 impl From<HeaderHashed> for TimedHeaderHash {
     fn from(h: HeaderHashed) -> Self {
         let (header, hash) = h.into_inner();
@@ -11,12 +11,23 @@ use holochain_zome_types::prelude::*;
 /// an Interface
 #[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes, PartialEq, Eq)]
 pub enum Signal {
-    /// Signal from a Cell, generated by `emit_signal`
-    App(CellId, AppSignal),
+    /// Signal from a Cell, generated by `emit_signal` in the given zome
+    App(CellId, ZomeName, AppSignal),
     /// System-defined signals
     System(SystemSignal),
 }
 
+impl Signal {
+    /// The tag an app gave its signal, which signal subscriptions can filter on.
+    /// System signals have no tag.
+    pub fn tag(&self) -> Option<&str> {
+        match self {
+            Self::App(_, _, signal) => signal.tag(),
+            Self::System(_) => None,
+        }
+    }
+}
+
 /// A Signal which originates from within the Holochain system, as opposed to
 /// from within a Cell
 ///
@@ -39,3 +50,23 @@ pub fn test_signal(s: &str) -> Signal {
 impl_from! {
     SystemSignal => Signal, |s| { Self::System(s) },
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use ::fixt::prelude::*;
+
+    #[test]
+    fn app_signal_tag() {
+        let app_signal = |signal| Signal::App(fixt!(CellId), "zome".into(), signal);
+        let payload = ExternIO::encode("greeting").unwrap();
+
+        assert_eq!(
+            app_signal(AppSignal::tagged("greeting".into(), payload.clone())).tag(),
+            Some("greeting")
+        );
+        // The tag is never taken from the payload
+        assert_eq!(app_signal(AppSignal::new(payload)).tag(), None);
+        assert_eq!(test_signal("test").tag(), None);
+    }
+}
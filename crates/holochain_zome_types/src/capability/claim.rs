@@ -45,3 +45,59 @@ impl CapClaim {
         &self.grantor
     }
 }
+
+/// Filter for the claims returned by the `capability_claims` host function.
+///
+/// Every filter that is set must match, so the default query returns
+/// every claim on the local chain that has not been deleted.
+///
+/// Claims do not record which functions the grantor granted access to,
+/// so to find the claims for a function filter on the tag the grant was
+/// committed with.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, SerializedBytes)]
+pub struct CapClaimQuery {
+    /// Only claims with this tag.
+    pub tag: Option<String>,
+    /// Only claims on grants authored by this agent.
+    pub grantor: Option<AgentPubKey>,
+    /// Only claims holding this secret.
+    pub secret: Option<CapSecret>,
+}
+
+impl CapClaimQuery {
+    /// Create a query that matches every claim.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Filter on tag
+    pub fn tag(mut self, tag: String) -> Self {
+        self.tag = Some(tag);
+        self
+    }
+
+    /// Filter on grantor
+    pub fn grantor(mut self, grantor: AgentPubKey) -> Self {
+        self.grantor = Some(grantor);
+        self
+    }
+
+    /// Filter on secret
+    pub fn secret(mut self, secret: CapSecret) -> Self {
+        self.secret = Some(secret);
+        self
+    }
+
+    /// Perform the boolean check which this query represents
+    pub fn check(&self, claim: &CapClaim) -> bool {
+        self.tag.as_ref().map_or(true, |tag| tag == &claim.tag)
+            && self
+                .grantor
+                .as_ref()
+                .map_or(true, |grantor| grantor == &claim.grantor)
+            && self
+                .secret
+                .as_ref()
+                .map_or(true, |secret| secret == &claim.secret)
+    }
+}
@@ -0,0 +1,35 @@
+//! Types for the DPKI key registry
+use crate::timestamp::Timestamp;
+use holo_hash::AgentPubKey;
+pub use holochain_serialized_bytes::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+/// The state of an agent key according to a key registry,
+/// as of some point in time.
+pub enum KeyState {
+    /// The registry has no record of this key.
+    NotFound,
+    /// The key was registered and had not been replaced or revoked.
+    Valid,
+    /// The key had been replaced by a new key.
+    Updated {
+        /// The key that replaced this one.
+        new_key: AgentPubKey,
+        /// When the key was replaced.
+        timestamp: Timestamp,
+    },
+    /// The key had been revoked.
+    Invalidated {
+        /// When the key was revoked.
+        timestamp: Timestamp,
+    },
+}
+
+impl KeyState {
+    /// Can this key be trusted for signatures made at the time asked about?
+    /// Keys the registry doesn't know about are trusted, as their
+    /// agents may simply not be using DPKI.
+    pub fn is_valid(&self) -> bool {
+        matches!(self, KeyState::NotFound | KeyState::Valid)
+    }
+}
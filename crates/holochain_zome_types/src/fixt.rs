@@ -536,12 +536,12 @@ fixturator!(
 
 fixturator!(
     OpenChain;
-    constructor fn from_builder(HeaderBuilderCommon, DnaHash);
+    constructor fn from_builder(HeaderBuilderCommon, DnaHash, AgentPubKey);
 );
 
 fixturator!(
     CloseChain;
-    constructor fn from_builder(HeaderBuilderCommon, DnaHash);
+    constructor fn from_builder(HeaderBuilderCommon, DnaHash, AgentPubKey);
 );
 
 fixturator!(
@@ -353,8 +353,8 @@ pub struct DeleteLink {
     pub link_add_address: HeaderHash,
 }
 
-/// When migrating to a new version of a DNA, this header is committed to the
-/// new chain to declare the migration path taken. **Currently unused**
+/// When migrating to a new version of a DNA or a new agent key, this header
+/// is committed to the new chain to declare the migration path taken.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, SerializedBytes)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct OpenChain {
@@ -364,10 +364,12 @@ pub struct OpenChain {
     pub prev_header: HeaderHash,
 
     pub prev_dna_hash: DnaHash,
+    /// The agent of the closed chain, which differs when the key was rotated.
+    pub prev_agent: AgentPubKey,
 }
 
-/// When migrating to a new version of a DNA, this header is committed to the
-/// old chain to declare the migration path taken. **Currently unused**
+/// When migrating to a new version of a DNA or a new agent key, this header
+/// is committed to the old chain to declare the migration path taken.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, SerializedBytes)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct CloseChain {
@@ -377,6 +379,8 @@ pub struct CloseChain {
     pub prev_header: HeaderHash,
 
     pub new_dna_hash: DnaHash,
+    /// The agent of the new chain, which differs when the key was rotated.
+    pub new_agent: AgentPubKey,
 }
 
 /// A header which "speaks" Entry content into being. The same content can be
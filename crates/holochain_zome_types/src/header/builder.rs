@@ -129,10 +129,12 @@ builder_variant!(DeleteLink {
 
 builder_variant!(OpenChain {
     prev_dna_hash: DnaHash,
+    prev_agent: AgentPubKey,
 });
 
 builder_variant!(CloseChain {
     new_dna_hash: DnaHash,
+    new_agent: AgentPubKey,
 });
 
 builder_variant!(Create {
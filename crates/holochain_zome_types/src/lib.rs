@@ -22,6 +22,7 @@ pub mod countersigning;
 #[allow(missing_docs)]
 pub mod crdt;
 pub mod dna_def;
+pub mod dpki;
 pub mod element;
 pub mod entry;
 #[allow(missing_docs)]
@@ -44,6 +45,7 @@ pub mod post_commit;
 pub mod prelude;
 pub mod query;
 pub mod request;
+pub mod schedule;
 pub mod signal;
 pub mod signature;
 pub mod timestamp;
@@ -9,6 +9,7 @@ pub use crate::cell::*;
 pub use crate::countersigning::*;
 pub use crate::crdt::*;
 pub use crate::dna_def::*;
+pub use crate::dpki::*;
 pub use crate::element::*;
 pub use crate::entry::*;
 pub use crate::entry::*;
@@ -26,6 +27,7 @@ pub use crate::post_commit::*;
 pub use crate::query::ChainQueryFilter as QueryFilter;
 pub use crate::query::*;
 pub use crate::request::*;
+pub use crate::schedule::*;
 pub use crate::signal::*;
 pub use crate::signature::*;
 pub use crate::timestamp::*;
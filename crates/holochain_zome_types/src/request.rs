@@ -8,8 +8,8 @@ pub struct MetadataRequest {
     /// Get all the headers on an entry.
     /// Invalid request on a header.
     pub all_valid_headers: bool,
-    // TODO: Implement after validation
-    /// Placeholder
+    /// Get all the headers that were rejected or abandoned
+    /// during validation.
     pub all_invalid_headers: bool,
     /// Get all the deletes on a header
     pub all_deletes: bool,
@@ -0,0 +1,83 @@
+//! Types for scheduling zome functions to run at a later time.
+
+use crate::zome::FunctionName;
+use crate::zome::ZomeName;
+use crate::CallbackResult;
+use holochain_serialized_bytes::prelude::*;
+use holochain_wasmer_common::WasmError;
+
+/// Input to the `schedule` host function.
+///
+/// The scheduled function must be an extern in the same zome as the caller
+/// that takes `()` as input and returns a [`ScheduledCallbackResult`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct ScheduleInput {
+    /// The function to run.
+    pub scheduled_fn: FunctionName,
+    /// How long to wait before running the function.
+    pub execute_after: core::time::Duration,
+}
+
+impl ScheduleInput {
+    /// Constructor
+    pub fn new(scheduled_fn: FunctionName, execute_after: core::time::Duration) -> Self {
+        Self {
+            scheduled_fn,
+            execute_after,
+        }
+    }
+}
+
+/// A function that has been scheduled by a zome.
+/// There is at most one pending schedule per function in each zome.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, SerializedBytes)]
+pub struct ScheduledFn(ZomeName, FunctionName);
+
+impl ScheduledFn {
+    /// Constructor
+    pub fn new(zome_name: ZomeName, fn_name: FunctionName) -> Self {
+        Self(zome_name, fn_name)
+    }
+
+    /// The zome the function lives in.
+    pub fn zome_name(&self) -> &ZomeName {
+        &self.0
+    }
+
+    /// The name of the function to call.
+    pub fn fn_name(&self) -> &FunctionName {
+        &self.1
+    }
+}
+
+/// The return value of a scheduled function.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub enum ScheduledCallbackResult {
+    /// The function has finished and should not run again.
+    Done,
+    /// Run the function again after the given delay.
+    Reschedule(core::time::Duration),
+    /// The function failed and will not run again.
+    Fail(String),
+}
+
+impl CallbackResult for ScheduledCallbackResult {
+    fn is_definitive(&self) -> bool {
+        matches!(self, ScheduledCallbackResult::Fail(_))
+    }
+    fn try_from_wasm_error(wasm_error: WasmError) -> Result<Self, WasmError> {
+        match wasm_error {
+            WasmError::Guest(_) | WasmError::Serialize(_) | WasmError::Deserialize(_) => {
+                Ok(ScheduledCallbackResult::Fail(wasm_error.to_string()))
+            }
+            WasmError::Host(_)
+            | WasmError::HostShortCircuit(_)
+            | WasmError::GuestResultHandling(_)
+            | WasmError::Compile(_)
+            | WasmError::CallError(_)
+            | WasmError::PointerMap
+            | WasmError::ErrorWhileError
+            | WasmError::Memory => Err(wasm_error),
+        }
+    }
+}
@@ -5,19 +5,38 @@ use holochain_serialized_bytes::prelude::*;
 
 /// A signal emitted by an app via `emit_signal`
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
-#[repr(transparent)]
-#[serde(transparent)]
-pub struct AppSignal(crate::ExternIO);
+pub struct AppSignal {
+    /// The tag the app gave the signal, which signal subscriptions can filter on
+    tag: Option<String>,
+    /// The signal's payload
+    payload: crate::ExternIO,
+}
 
 impl AppSignal {
     /// Constructor
     pub fn new(extern_io: crate::ExternIO) -> Self {
-        Self(extern_io)
+        Self {
+            tag: None,
+            payload: extern_io,
+        }
+    }
+
+    /// Constructor for a signal with a tag
+    pub fn tagged(tag: String, extern_io: crate::ExternIO) -> Self {
+        Self {
+            tag: Some(tag),
+            payload: extern_io,
+        }
+    }
+
+    /// The tag of the signal, if it has one
+    pub fn tag(&self) -> Option<&str> {
+        self.tag.as_deref()
     }
 
     /// Access the inner type
     pub fn into_inner(self) -> crate::ExternIO {
-        self.0
+        self.payload
     }
 }
 
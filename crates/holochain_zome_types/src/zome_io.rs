@@ -101,8 +101,8 @@ wasm_io_types! {
 
     fn call (Vec<zt::call::Call>) -> Vec<zt::ZomeCallResponse>;
 
-    // @todo List all the local capability claims.
-    fn capability_claims (()) -> ();
+    // List the local capability claims that match the query.
+    fn capability_claims (zt::capability::CapClaimQuery) -> Vec<zt::capability::CapClaim>;
 
     // @todo List all the local capability grants.
     fn capability_grants (()) -> ();
@@ -168,8 +168,8 @@ wasm_io_types! {
     // // @todo
     // fn send (()) -> ();
 
-    // @todo
-    fn schedule (core::time::Duration) -> ();
+    // Schedule a function in this zome to run after a delay.
+    fn schedule (zt::schedule::ScheduleInput) -> ();
 
     // @todo
     fn sleep (core::time::Duration) -> ();
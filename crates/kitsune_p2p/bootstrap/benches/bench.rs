@@ -9,10 +9,14 @@ use fixt::prelude::*;
 use kitsune_p2p::agent_store::AgentInfoSigned;
 use kitsune_p2p::dependencies::url2::url2;
 use kitsune_p2p::fixt::*;
+use kitsune_p2p::KitsuneAgent;
+use kitsune_p2p::KitsuneBinType;
 use kitsune_p2p::KitsuneP2pResult;
+use kitsune_p2p::KitsuneSignature;
 use kitsune_p2p::KitsuneSpace;
 use kitsune_p2p_types::bootstrap::RandomLimit;
 use kitsune_p2p_types::bootstrap::RandomQuery;
+use kitsune_p2p_types::dependencies::lair_keystore_api::internal::sign_ed25519::sign_ed25519_keypair_new_from_entropy;
 use tokio::runtime::Builder;
 use tokio::runtime::Runtime;
 
@@ -56,14 +60,26 @@ fn bootstrap(bench: &mut Criterion) {
     group.bench_function(BenchmarkId::new("test", format!("put")), |b| {
         b.iter(|| {
             runtime.block_on(async {
+                // The server checks signatures, so each agent needs a real key.
+                let keypair = sign_ed25519_keypair_new_from_entropy().await.unwrap();
+                let agent = KitsuneAgent::new((*keypair.pub_key.0).clone());
                 let info = AgentInfoSigned::sign(
                     space.clone(),
-                    Arc::new(fixt!(KitsuneAgent, Unpredictable)),
+                    Arc::new(agent),
                     u32::MAX / 4,
                     fixt!(UrlList, Empty),
                     0,
                     std::time::UNIX_EPOCH.elapsed().unwrap().as_millis() as u64 + 60_000_000,
-                    |_| async move { Ok(Arc::new(fixt!(KitsuneSignature, Unpredictable))) },
+                    |d| {
+                        let d = Arc::new(d.to_vec());
+                        async {
+                            keypair
+                                .sign(d)
+                                .await
+                                .map(|s| Arc::new(KitsuneSignature(s.0.to_vec())))
+                                .map_err(kitsune_p2p_types::KitsuneError::other)
+                        }
+                    },
                 )
                 .await
                 .unwrap();
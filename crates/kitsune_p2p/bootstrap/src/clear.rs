@@ -3,58 +3,118 @@ use crate::store::Store;
 use super::*;
 use warp::Filter;
 
+/// Clearing every agent info is only for the admin of the server.
+/// Without an admin token all `clear` requests are rejected.
 pub(crate) fn clear(
     store: Store,
+    admin_token: Option<String>,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
     warp::post()
         .and(warp::header::exact("content-type", "application/octet"))
         .and(warp::header::exact("X-Op", "clear"))
+        .and(warp::header::optional::<String>(ADMIN_TOKEN_HEADER))
+        .and_then(move |token: Option<String>| {
+            let admin_token = admin_token.clone();
+            async move {
+                match (admin_token, token) {
+                    (None, _) => Err(warp::reject::not_found()),
+                    (Some(admin_token), Some(token))
+                        if constant_time_eq(admin_token.as_bytes(), token.as_bytes()) =>
+                    {
+                        Ok(())
+                    }
+                    _ => Err(warp::reject::custom(Unauthorized)),
+                }
+            }
+        })
+        .untuple_one()
         .and(with_store(store))
         .and_then(clear_info)
 }
 
 async fn clear_info(store: Store) -> Result<impl warp::Reply, warp::Rejection> {
-    store.clear();
+    store
+        .clear()
+        .await
+        .map_err(|_| warp::reject::custom(StoreError))?;
     Ok(warp::reply())
 }
 
+/// Compare without leaking how long the matching prefix is.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
     use super::*;
+    use crate::test_util::signed_agent_info;
     use fixt::prelude::*;
-    use kitsune_p2p::{agent_store::AgentInfoSigned, fixt::*, KitsuneSpace};
+    use kitsune_p2p::{fixt::*, KitsuneSpace};
 
-    #[tokio::test(flavor = "multi_thread")]
-    async fn test_clear() {
+    async fn filled_store() -> Store {
         let store = Store::new();
-
-        let filter = super::clear(store.clone());
         let space: Arc<KitsuneSpace> = Arc::new(fixt!(KitsuneSpace));
-
         for _ in 0..20 {
-            let info = AgentInfoSigned::sign(
-                space.clone(),
-                Arc::new(fixt!(KitsuneAgent, Unpredictable)),
-                u32::MAX / 4,
-                fixt!(UrlList, Empty),
-                0,
-                std::time::UNIX_EPOCH.elapsed().unwrap().as_millis() as u64 + 60_000_000,
-                |_| async move { Ok(Arc::new(fixt!(KitsuneSignature, Unpredictable))) },
-            )
-            .await
-            .unwrap();
-            store.put(info);
+            let info = signed_agent_info(space.clone(), fixt!(UrlList, Empty)).await;
+            store.put(info).await.unwrap();
         }
+        store
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_clear() {
+        let store = filled_store().await;
+        let filter = super::clear(store.clone(), Some("secret".to_string()));
 
         let res = warp::test::request()
             .method("POST")
             .header("Content-type", "application/octet")
             .header("X-Op", "clear")
+            .header(ADMIN_TOKEN_HEADER, "secret")
             .reply(&filter)
             .await;
         assert_eq!(res.status(), 200);
         assert!(store.all().is_empty());
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_clear_unauthorized() {
+        let store = filled_store().await;
+        let filter =
+            super::clear(store.clone(), Some("secret".to_string())).recover(handle_rejection);
+
+        let res = warp::test::request()
+            .method("POST")
+            .header("Content-type", "application/octet")
+            .header("X-Op", "clear")
+            .header(ADMIN_TOKEN_HEADER, "guess")
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), 401);
+
+        let res = warp::test::request()
+            .method("POST")
+            .header("Content-type", "application/octet")
+            .header("X-Op", "clear")
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), 401);
+
+        // Without an admin token there is no clearing at all
+        let filter = super::clear(store.clone(), None);
+        let res = warp::test::request()
+            .method("POST")
+            .header("Content-type", "application/octet")
+            .header("X-Op", "clear")
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), 404);
+        assert_eq!(store.all().values().next().unwrap().len(), 20);
+    }
 }
@@ -1,19 +1,28 @@
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::atomic::AtomicUsize;
 
 use kitsune_p2p_types::codec::rmp_decode;
 use kitsune_p2p_types::codec::rmp_encode;
+use rate_limit::RateLimiter;
 use store::Store;
-use warp::{hyper::body::Bytes, Filter};
+use warp::{http::StatusCode, hyper::body::Bytes, Filter};
+
+pub use rate_limit::RateLimits;
 
 static NOW: AtomicUsize = AtomicUsize::new(0);
 static RANDOM: AtomicUsize = AtomicUsize::new(0);
 static PUT: AtomicUsize = AtomicUsize::new(0);
+static NEAR_BASIS: AtomicUsize = AtomicUsize::new(0);
+static STATS: AtomicUsize = AtomicUsize::new(0);
 
 mod clear;
+mod near_basis;
 mod now;
 mod put;
 mod random;
+mod rate_limit;
+mod stats;
 mod store;
 
 /// No reason to accept a peer data bigger then 1KB.
@@ -23,25 +32,70 @@ const SIZE_LIMIT: u64 = 1024;
 /// how often should we prune the expired entries?
 const PRUNE_EXPIRED_FREQ_S: u64 = 5;
 
+/// The header carrying the admin token of `clear` requests.
+const ADMIN_TOKEN_HEADER: &str = "X-Admin-Token";
+
 pub type BootstrapDriver = futures::future::BoxFuture<'static, ()>;
 
+/// Configuration for a bootstrap server.
+#[derive(Clone, Debug, Default)]
+pub struct BootstrapConfig {
+    /// Persist agent infos to a SQLite database at this path,
+    /// so they survive a restart.
+    /// Default: None = in memory only.
+    pub db_path: Option<PathBuf>,
+    /// The token `clear` requests must carry in the `X-Admin-Token` header.
+    /// Default: None = `clear` is disabled.
+    pub admin_token: Option<String>,
+    /// Limits on requests per IP address and per space.
+    /// Default: unlimited.
+    pub rate_limits: RateLimits,
+}
+
+/// Run a bootstrap server with the default config:
+/// in memory, unlimited and without `clear`.
 pub async fn run(
     addr: impl Into<SocketAddr> + 'static,
 ) -> Result<(BootstrapDriver, SocketAddr), String> {
-    let store = Store::new();
+    run_with_config(addr, BootstrapConfig::default()).await
+}
+
+/// Run a bootstrap server.
+pub async fn run_with_config(
+    addr: impl Into<SocketAddr> + 'static,
+    config: BootstrapConfig,
+) -> Result<(BootstrapDriver, SocketAddr), String> {
+    let BootstrapConfig {
+        db_path,
+        admin_token,
+        rate_limits,
+    } = config;
+    let store = match db_path {
+        Some(path) => Store::open(&path)
+            .map_err(|e| format!("Failed to open database {:?}: {:?}", path, e))?,
+        None => Store::new(),
+    };
+    let limiter = RateLimiter::new(rate_limits);
     {
         let store = store.clone();
+        let limiter = limiter.clone();
         tokio::task::spawn(async move {
             loop {
                 tokio::time::sleep(std::time::Duration::from_secs(PRUNE_EXPIRED_FREQ_S)).await;
-                store.prune();
+                if let Err(e) = store.prune().await {
+                    tracing::error!(?e, "Failed to prune expired agent infos");
+                }
+                limiter.prune();
             }
         });
     }
     let boot = now::now()
-        .or(put::put(store.clone()))
-        .or(random::random(store.clone()))
-        .or(clear::clear(store));
+        .or(put::put(store.clone(), limiter.clone()))
+        .or(random::random(store.clone(), limiter.clone()))
+        .or(near_basis::near_basis(store.clone(), limiter.clone()))
+        .or(stats::stats(store.clone(), limiter.clone()))
+        .or(clear::clear(store, admin_token));
+    let boot = ip_limit(limiter).and(boot).recover(handle_rejection);
     match warp::serve(boot).try_bind_ephemeral(addr) {
         Ok((addr, server)) => {
             let driver = futures::future::FutureExt::boxed(server);
@@ -56,3 +110,164 @@ fn with_store(
 ) -> impl Filter<Extract = (Store,), Error = std::convert::Infallible> + Clone {
     warp::any().map(move || store.clone())
 }
+
+fn with_limiter(
+    limiter: RateLimiter,
+) -> impl Filter<Extract = (RateLimiter,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || limiter.clone())
+}
+
+/// Rejects requests from IP addresses which are over their limit.
+fn ip_limit(limiter: RateLimiter) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::addr::remote()
+        .and(with_limiter(limiter))
+        .and_then(check_ip)
+        .untuple_one()
+}
+
+async fn check_ip(addr: Option<SocketAddr>, limiter: RateLimiter) -> Result<(), warp::Rejection> {
+    match addr {
+        Some(addr) if !limiter.check_ip(addr.ip()) => Err(warp::reject::custom(RateLimited)),
+        _ => Ok(()),
+    }
+}
+
+/// The request is over a rate limit.
+#[derive(Debug)]
+struct RateLimited;
+
+impl warp::reject::Reject for RateLimited {}
+
+/// The signature of a put agent info doesn't match its agent.
+#[derive(Debug)]
+struct InvalidSignature;
+
+impl warp::reject::Reject for InvalidSignature {}
+
+/// The request is missing the admin token, or has the wrong one.
+#[derive(Debug)]
+struct Unauthorized;
+
+impl warp::reject::Reject for Unauthorized {}
+
+/// The store failed to persist a change.
+#[derive(Debug)]
+struct StoreError;
+
+impl warp::reject::Reject for StoreError {}
+
+async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply, warp::Rejection> {
+    let status = if err.find::<RateLimited>().is_some() {
+        StatusCode::TOO_MANY_REQUESTS
+    } else if err.find::<InvalidSignature>().is_some() {
+        StatusCode::BAD_REQUEST
+    } else if err.find::<Unauthorized>().is_some() {
+        StatusCode::UNAUTHORIZED
+    } else if err.find::<StoreError>().is_some() {
+        StatusCode::INTERNAL_SERVER_ERROR
+    } else {
+        return Err(err);
+    };
+    Ok(warp::reply::with_status(warp::reply(), status))
+}
+
+#[cfg(test)]
+pub(crate) mod test_util {
+    use kitsune_p2p_types::agent_info::{AgentInfoSigned, UrlList};
+    use kitsune_p2p_types::bin_types::{
+        KitsuneAgent, KitsuneBinType, KitsuneSignature, KitsuneSpace,
+    };
+    use kitsune_p2p_types::dependencies::lair_keystore_api::internal::sign_ed25519::sign_ed25519_keypair_new_from_entropy;
+    use kitsune_p2p_types::KitsuneError;
+    use std::sync::Arc;
+
+    /// Agent info signed by a new agent key, so it passes verification
+    pub async fn signed_agent_info(space: Arc<KitsuneSpace>, url_list: UrlList) -> AgentInfoSigned {
+        signed_agent_info_with_arc(space, url_list, u32::MAX / 4).await
+    }
+
+    /// Signed agent info with a storage arc of this half length
+    pub async fn signed_agent_info_with_arc(
+        space: Arc<KitsuneSpace>,
+        url_list: UrlList,
+        half_length: u32,
+    ) -> AgentInfoSigned {
+        let keypair = sign_ed25519_keypair_new_from_entropy().await.unwrap();
+        let agent = KitsuneAgent::new((*keypair.pub_key.0).clone());
+        AgentInfoSigned::sign(
+            space,
+            Arc::new(agent),
+            half_length,
+            url_list,
+            0,
+            std::time::UNIX_EPOCH.elapsed().unwrap().as_millis() as u64 + 60_000_000,
+            |d| {
+                let d = Arc::new(d.to_vec());
+                async {
+                    keypair
+                        .sign(d)
+                        .await
+                        .map(|s| Arc::new(KitsuneSignature(s.0.to_vec())))
+                        .map_err(KitsuneError::other)
+                }
+            },
+        )
+        .await
+        .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::signed_agent_info_with_arc;
+    use fixt::prelude::*;
+    use kitsune_p2p::bootstrap::{near_basis, put, stats, NearBasisQuery, RandomLimit};
+    use kitsune_p2p::dependencies::url2::url2;
+    use kitsune_p2p::fixt::*;
+    use kitsune_p2p::KitsuneSpace;
+    use kitsune_p2p_types::dht_arc::MAX_HALF_LENGTH;
+    use std::sync::Arc;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_clients_against_server() {
+        let (driver, addr) = super::run(([127, 0, 0, 1], 0)).await.unwrap();
+        tokio::task::spawn(driver);
+        let url = Some(url2!("http://{}", addr));
+        let space: Arc<KitsuneSpace> = Arc::new(fixt!(KitsuneSpace, Unpredictable));
+
+        // Every test agent is centered on location 0,
+        // only the full arcs reach the opposite side of the circle.
+        let mut full = Vec::new();
+        for i in 0..6 {
+            let half_length = if i % 2 == 0 {
+                MAX_HALF_LENGTH
+            } else {
+                u32::MAX / 4
+            };
+            let info =
+                signed_agent_info_with_arc(space.clone(), vec!["fake:".into()], half_length).await;
+            if half_length == MAX_HALF_LENGTH {
+                full.push(info.clone());
+            }
+            put(url.clone(), info).await.unwrap();
+        }
+
+        let near = near_basis(
+            url.clone(),
+            NearBasisQuery {
+                space: space.clone(),
+                basis: (u32::MAX / 2).into(),
+                limit: RandomLimit(10),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(near.len(), full.len());
+        for peer in &near {
+            assert!(full.contains(peer));
+        }
+
+        let stats = stats(url, space).await.unwrap().unwrap();
+        assert_eq!(stats.agent_count, 6);
+    }
+}
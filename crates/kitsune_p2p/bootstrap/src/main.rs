@@ -1,6 +1,46 @@
+use kitsune_p2p_bootstrap::{BootstrapConfig, RateLimits};
+use structopt::StructOpt;
+
+/// Option Parsing
+#[derive(structopt::StructOpt, Debug)]
+#[structopt(name = "kitsune-bootstrap")]
+pub struct Opt {
+    /// To which network interface / port should we bind?
+    #[structopt(short = "b", long, default_value = "127.0.0.1:0")]
+    pub bind_to: std::net::SocketAddr,
+
+    /// Persist agent infos to a SQLite database at this path.
+    /// Without it they are kept in memory and lost on restart.
+    #[structopt(long)]
+    pub db_path: Option<std::path::PathBuf>,
+
+    /// Enable the `clear` op for requests carrying this token
+    /// in the `X-Admin-Token` header.
+    #[structopt(long, env = "KITSUNE_BOOTSTRAP_ADMIN_TOKEN", hide_env_values = true)]
+    pub admin_token: Option<String>,
+
+    /// How many requests to accept from a single IP address each minute.
+    #[structopt(long)]
+    pub ip_per_minute: Option<u32>,
+
+    /// How many `put` and `random` requests to accept for a single space
+    /// each minute.
+    #[structopt(long)]
+    pub space_per_minute: Option<u32>,
+}
+
 #[tokio::main(flavor = "multi_thread")]
 async fn main() {
-    match kitsune_p2p_bootstrap::run(([127, 0, 0, 1], 0)).await {
+    let opt = Opt::from_args();
+    let config = BootstrapConfig {
+        db_path: opt.db_path,
+        admin_token: opt.admin_token,
+        rate_limits: RateLimits {
+            ip_per_minute: opt.ip_per_minute,
+            space_per_minute: opt.space_per_minute,
+        },
+    };
+    match kitsune_p2p_bootstrap::run_with_config(opt.bind_to, config).await {
         Ok((driver, addr)) => {
             println!("http://{}", addr);
             driver.await;
@@ -0,0 +1,101 @@
+use crate::rate_limit::RateLimiter;
+use crate::store::Store;
+
+use super::*;
+use kitsune_p2p_types::bootstrap::NearBasisQuery;
+use warp::Filter;
+
+pub(crate) fn near_basis(
+    store: Store,
+    limiter: RateLimiter,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::post()
+        .and(warp::header::exact("content-type", "application/octet"))
+        .and(warp::header::exact("X-Op", "near_basis"))
+        .and(warp::body::content_length_limit(SIZE_LIMIT))
+        .and(warp::body::bytes())
+        .and(with_store(store))
+        .and(with_limiter(limiter))
+        .and_then(near_basis_info)
+}
+
+async fn near_basis_info(
+    query: Bytes,
+    store: Store,
+    limiter: RateLimiter,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let query: NearBasisQuery =
+        rmp_decode(&mut AsRef::<[u8]>::as_ref(&query)).map_err(|_| warp::reject())?;
+    if !limiter.check_space(&query.space) {
+        return Err(warp::reject::custom(RateLimited));
+    }
+    let result = store.near_basis(query);
+    let mut buf = Vec::with_capacity(result.len());
+    rmp_encode(&mut buf, result).map_err(|_| warp::reject())?;
+    NEAR_BASIS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::rate_limit::RateLimits;
+    use crate::test_util::signed_agent_info_with_arc;
+    use fixt::prelude::*;
+    use kitsune_p2p::{agent_store::AgentInfoSigned, fixt::*, KitsuneSpace};
+    use kitsune_p2p_types::bootstrap::RandomLimit;
+    use kitsune_p2p_types::dht_arc::MAX_HALF_LENGTH;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_near_basis() {
+        let store = Store::new();
+        let filter = super::near_basis(store.clone(), RateLimiter::new(RateLimits::default()));
+        let space: Arc<KitsuneSpace> = Arc::new(fixt!(KitsuneSpace));
+
+        // Every test agent is centered on location 0.
+        // Quarter arcs don't reach the opposite side of the circle,
+        // full arcs do.
+        let mut full = Vec::new();
+        for i in 0..10 {
+            let half_length = if i % 2 == 0 {
+                MAX_HALF_LENGTH
+            } else {
+                u32::MAX / 4
+            };
+            let info =
+                signed_agent_info_with_arc(space.clone(), vec!["fake:".into()], half_length).await;
+            if half_length == MAX_HALF_LENGTH {
+                full.push(info.clone());
+            }
+            store.put(info).await.unwrap();
+        }
+
+        let query = NearBasisQuery {
+            space,
+            basis: (u32::MAX / 2).into(),
+            limit: RandomLimit(10),
+        };
+        let mut buf = Vec::new();
+        rmp_encode(&mut buf, query).unwrap();
+
+        let res = warp::test::request()
+            .method("POST")
+            .header("Content-type", "application/octet")
+            .header("X-Op", "near_basis")
+            .body(buf)
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), 200);
+        let result: Vec<Vec<u8>> = rmp_decode(&mut res.body().as_ref()).unwrap();
+        let result: Vec<AgentInfoSigned> = result
+            .into_iter()
+            .map(|bytes| rmp_decode(&mut AsRef::<[u8]>::as_ref(&bytes)).unwrap())
+            .collect();
+        assert_eq!(result.len(), full.len());
+        for peer in &result {
+            assert!(full.iter().any(|p| p == peer));
+        }
+    }
+}
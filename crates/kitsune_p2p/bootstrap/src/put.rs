@@ -1,11 +1,18 @@
+use crate::rate_limit::RateLimiter;
 use crate::store::Store;
 
 use super::*;
 use kitsune_p2p_types::agent_info::AgentInfoSigned;
+use kitsune_p2p_types::bin_types::KitsuneBinType;
+use kitsune_p2p_types::dependencies::lair_keystore_api::internal::sign_ed25519::{
+    SignEd25519PubKey, SignEd25519Signature,
+};
+use std::sync::Arc;
 use warp::Filter;
 
 pub(crate) fn put(
     store: Store,
+    limiter: RateLimiter,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
     warp::post()
         .and(warp::header::exact("content-type", "application/octet"))
@@ -13,15 +20,30 @@ pub(crate) fn put(
         .and(warp::body::content_length_limit(SIZE_LIMIT))
         .and(warp::body::bytes())
         .and(with_store(store))
+        .and(with_limiter(limiter))
         .and_then(put_info)
 }
 
-async fn put_info(peer: Bytes, store: Store) -> Result<impl warp::Reply, warp::Rejection> {
+async fn put_info(
+    peer: Bytes,
+    store: Store,
+    limiter: RateLimiter,
+) -> Result<impl warp::Reply, warp::Rejection> {
     let peer: AgentInfoSigned =
         rmp_decode(&mut AsRef::<[u8]>::as_ref(&peer)).map_err(|_| warp::reject())?;
-    // TODO: Return rejection if agent info was invalid?
-    if valid(&peer) {
-        store.put(peer);
+    if !verify(&peer).await {
+        return Err(warp::reject::custom(InvalidSignature));
+    }
+    // Only valid puts count towards the space's limit.
+    if !limiter.check_space(&peer.space) {
+        return Err(warp::reject::custom(RateLimited));
+    }
+    // Expired infos are dropped without an error.
+    if !expired(&peer) {
+        store
+            .put(peer)
+            .await
+            .map_err(|_| warp::reject::custom(StoreError))?;
     }
     PUT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     let mut buf = Vec::with_capacity(1);
@@ -29,11 +51,19 @@ async fn put_info(peer: Bytes, store: Store) -> Result<impl warp::Reply, warp::R
     Ok(buf)
 }
 
-fn valid(peer: &AgentInfoSigned) -> bool {
-    // TODO: verify signature
-    // Verify time
+/// Check the info was signed by the key of its agent.
+async fn verify(peer: &AgentInfoSigned) -> bool {
+    let pub_key = SignEd25519PubKey::from(peer.agent.get_bytes().to_vec());
+    let signature = SignEd25519Signature::from(peer.signature.0.clone());
+    pub_key
+        .verify(Arc::new(peer.encoded_bytes.to_vec()), signature)
+        .await
+        .unwrap_or(false)
+}
+
+fn expired(peer: &AgentInfoSigned) -> bool {
     peer.expires_at_ms as u128
-        > std::time::UNIX_EPOCH
+        <= std::time::UNIX_EPOCH
             .elapsed()
             .expect("Bootstrap system clock is set before the epoch")
             .as_millis()
@@ -44,27 +74,14 @@ mod tests {
     use std::sync::Arc;
 
     use super::*;
+    use crate::test_util::signed_agent_info;
     use fixt::prelude::*;
     use kitsune_p2p::fixt::*;
 
-    #[tokio::test(flavor = "multi_thread")]
-    async fn test_put() {
-        let store = Store::new();
-        let filter = put(store.clone());
-
-        let info = AgentInfoSigned::sign(
-            Arc::new(fixt!(KitsuneSpace, Unpredictable)),
-            Arc::new(fixt!(KitsuneAgent, Unpredictable)),
-            u32::MAX / 4,
-            fixt!(UrlList, Empty),
-            0,
-            std::time::UNIX_EPOCH.elapsed().unwrap().as_millis() as u64 + 60_000_000,
-            |_| async move { Ok(Arc::new(fixt!(KitsuneSignature, Unpredictable))) },
-        )
-        .await
-        .unwrap();
+    async fn put_request(store: &Store, limiter: &RateLimiter, info: AgentInfoSigned) -> u16 {
+        let filter = put(store.clone(), limiter.clone()).recover(handle_rejection);
         let mut buf = Vec::new();
-        rmp_encode(&mut buf, info.clone()).unwrap();
+        rmp_encode(&mut buf, info).unwrap();
 
         let res = warp::test::request()
             .method("POST")
@@ -73,7 +90,21 @@ mod tests {
             .body(buf)
             .reply(&filter)
             .await;
-        assert_eq!(res.status(), 200);
+        res.status().as_u16()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_put() {
+        let store = Store::new();
+        let limiter = RateLimiter::new(RateLimits::default());
+
+        let info = signed_agent_info(
+            Arc::new(fixt!(KitsuneSpace, Unpredictable)),
+            fixt!(UrlList, Empty),
+        )
+        .await;
+
+        assert_eq!(put_request(&store, &limiter, info.clone()).await, 200);
         assert_eq!(
             *store
                 .all()
@@ -84,4 +115,41 @@ mod tests {
             info
         );
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_put_bad_signature() {
+        let store = Store::new();
+        let limiter = RateLimiter::new(RateLimits::default());
+
+        // Signed by someone other than the agent
+        let info = AgentInfoSigned::sign(
+            Arc::new(fixt!(KitsuneSpace, Unpredictable)),
+            Arc::new(fixt!(KitsuneAgent, Unpredictable)),
+            u32::MAX / 4,
+            fixt!(UrlList, Empty),
+            0,
+            std::time::UNIX_EPOCH.elapsed().unwrap().as_millis() as u64 + 60_000_000,
+            |_| async move { Ok(Arc::new(fixt!(KitsuneSignature, Unpredictable))) },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(put_request(&store, &limiter, info).await, 400);
+        assert!(store.all().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_put_space_rate_limit() {
+        let store = Store::new();
+        let limiter = RateLimiter::new(RateLimits {
+            ip_per_minute: None,
+            space_per_minute: Some(1),
+        });
+        let space = Arc::new(fixt!(KitsuneSpace, Unpredictable));
+
+        let info = signed_agent_info(space.clone(), fixt!(UrlList, Empty)).await;
+        assert_eq!(put_request(&store, &limiter, info).await, 200);
+        let info = signed_agent_info(space, fixt!(UrlList, Empty)).await;
+        assert_eq!(put_request(&store, &limiter, info).await, 429);
+    }
 }
@@ -1,3 +1,4 @@
+use crate::rate_limit::RateLimiter;
 use crate::store::Store;
 
 use super::*;
@@ -6,6 +7,7 @@ use warp::Filter;
 
 pub(crate) fn random(
     store: Store,
+    limiter: RateLimiter,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
     warp::post()
         .and(warp::header::exact("content-type", "application/octet"))
@@ -13,12 +15,20 @@ pub(crate) fn random(
         .and(warp::body::content_length_limit(SIZE_LIMIT))
         .and(warp::body::bytes())
         .and(with_store(store))
+        .and(with_limiter(limiter))
         .and_then(random_info)
 }
 
-async fn random_info(query: Bytes, store: Store) -> Result<impl warp::Reply, warp::Rejection> {
+async fn random_info(
+    query: Bytes,
+    store: Store,
+    limiter: RateLimiter,
+) -> Result<impl warp::Reply, warp::Rejection> {
     let query: RandomQuery =
         rmp_decode(&mut AsRef::<[u8]>::as_ref(&query)).map_err(|_| warp::reject())?;
+    if !limiter.check_space(&query.space) {
+        return Err(warp::reject::custom(RateLimited));
+    }
     let result = store.random(query);
     let mut buf = Vec::with_capacity(result.len());
     rmp_encode(&mut buf, result).map_err(|_| warp::reject())?;
@@ -31,12 +41,14 @@ mod tests {
     use std::sync::Arc;
 
     use super::*;
+    use crate::rate_limit::RateLimits;
+    use crate::test_util::signed_agent_info;
     use fixt::prelude::*;
     use kitsune_p2p::{agent_store::AgentInfoSigned, fixt::*, KitsuneSpace};
     use kitsune_p2p_types::bootstrap::RandomLimit;
 
     async fn put(store: Store, peers: Vec<AgentInfoSigned>) {
-        let filter = crate::put::put(store);
+        let filter = crate::put::put(store, RateLimiter::new(RateLimits::default()));
 
         for peer in peers {
             let mut buf = Vec::new();
@@ -56,21 +68,11 @@ mod tests {
     #[tokio::test(flavor = "multi_thread")]
     async fn test_random() {
         let store = Store::new();
-        let filter = super::random(store.clone());
+        let filter = super::random(store.clone(), RateLimiter::new(RateLimits::default()));
         let space: Arc<KitsuneSpace> = Arc::new(fixt!(KitsuneSpace));
         let mut peers = Vec::new();
         for _ in 0..20 {
-            let info = AgentInfoSigned::sign(
-                space.clone(),
-                Arc::new(fixt!(KitsuneAgent, Unpredictable)),
-                u32::MAX / 4,
-                vec!["fake:".into()],
-                0,
-                std::time::UNIX_EPOCH.elapsed().unwrap().as_millis() as u64 + 60_000_000,
-                |_| async move { Ok(Arc::new(fixt!(KitsuneSignature, Unpredictable))) },
-            )
-            .await
-            .unwrap();
+            let info = signed_agent_info(space.clone(), vec!["fake:".into()]).await;
             peers.push(info);
         }
         put(store.clone(), peers.clone()).await;
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use kitsune_p2p_types::bin_types::KitsuneSpace;
+use parking_lot::Mutex;
+
+/// Limits are counted over fixed windows of this length.
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// How many requests the server accepts each minute.
+#[derive(Clone, Debug, Default)]
+pub struct RateLimits {
+    /// Requests from a single IP address.
+    /// Default: None = unlimited.
+    pub ip_per_minute: Option<u32>,
+    /// `put` and `random` requests for a single space.
+    /// Default: None = unlimited.
+    pub space_per_minute: Option<u32>,
+}
+
+/// The start of the current window of a key, and the requests counted in it.
+type Windows<K> = HashMap<K, (Instant, u32)>;
+
+#[derive(Clone)]
+pub(crate) struct RateLimiter {
+    limits: RateLimits,
+    ips: Arc<Mutex<Windows<IpAddr>>>,
+    spaces: Arc<Mutex<Windows<Arc<KitsuneSpace>>>>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> Self {
+        Self {
+            limits,
+            ips: Arc::new(Mutex::new(HashMap::new())),
+            spaces: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Count a request from an IP address.
+    /// False if the address is over its limit.
+    pub fn check_ip(&self, ip: IpAddr) -> bool {
+        check(&self.ips, ip, self.limits.ip_per_minute)
+    }
+
+    /// Count a request for a space.
+    /// False if the space is over its limit.
+    pub fn check_space(&self, space: &Arc<KitsuneSpace>) -> bool {
+        check(&self.spaces, space.clone(), self.limits.space_per_minute)
+    }
+
+    /// Forget the windows which are over.
+    pub fn prune(&self) {
+        let now = Instant::now();
+        self.ips
+            .lock()
+            .retain(|_, (start, _)| now.duration_since(*start) < WINDOW);
+        self.spaces
+            .lock()
+            .retain(|_, (start, _)| now.duration_since(*start) < WINDOW);
+    }
+}
+
+fn check<K: Eq + Hash>(windows: &Mutex<Windows<K>>, key: K, limit: Option<u32>) -> bool {
+    let limit = match limit {
+        Some(limit) => limit,
+        None => return true,
+    };
+    let now = Instant::now();
+    let mut windows = windows.lock();
+    let (start, count) = windows.entry(key).or_insert((now, 0));
+    if now.duration_since(*start) >= WINDOW {
+        *start = now;
+        *count = 0;
+    }
+    if *count >= limit {
+        return false;
+    }
+    *count += 1;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limits() {
+        let limiter = RateLimiter::new(RateLimits {
+            ip_per_minute: Some(2),
+            space_per_minute: None,
+        });
+        let alice: IpAddr = [10, 0, 0, 1].into();
+        let bob: IpAddr = [10, 0, 0, 2].into();
+        assert!(limiter.check_ip(alice));
+        assert!(limiter.check_ip(alice));
+        assert!(!limiter.check_ip(alice));
+        // Limits are per address
+        assert!(limiter.check_ip(bob));
+        // Spaces are unlimited
+        let space = Arc::new(KitsuneSpace(vec![0; 36]));
+        for _ in 0..10 {
+            assert!(limiter.check_space(&space));
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::rate_limit::RateLimiter;
+use crate::store::Store;
+
+use super::*;
+use kitsune_p2p_types::bin_types::KitsuneSpace;
+use std::sync::Arc;
+use warp::Filter;
+
+pub(crate) fn stats(
+    store: Store,
+    limiter: RateLimiter,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::post()
+        .and(warp::header::exact("content-type", "application/octet"))
+        .and(warp::header::exact("X-Op", "stats"))
+        .and(warp::body::content_length_limit(SIZE_LIMIT))
+        .and(warp::body::bytes())
+        .and(with_store(store))
+        .and(with_limiter(limiter))
+        .and_then(space_stats)
+}
+
+async fn space_stats(
+    space: Bytes,
+    store: Store,
+    limiter: RateLimiter,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let space: Arc<KitsuneSpace> =
+        rmp_decode(&mut AsRef::<[u8]>::as_ref(&space)).map_err(|_| warp::reject())?;
+    if !limiter.check_space(&space) {
+        return Err(warp::reject::custom(RateLimited));
+    }
+    let result = store.stats(&space);
+    let mut buf = Vec::new();
+    rmp_encode(&mut buf, result).map_err(|_| warp::reject())?;
+    STATS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rate_limit::RateLimits;
+    use crate::test_util::signed_agent_info;
+    use fixt::prelude::*;
+    use kitsune_p2p::fixt::*;
+    use kitsune_p2p_types::bootstrap::SpaceStats;
+
+    async fn request_stats(store: Store, space: Arc<KitsuneSpace>) -> SpaceStats {
+        let filter = super::stats(store, RateLimiter::new(RateLimits::default()));
+        let mut buf = Vec::new();
+        rmp_encode(&mut buf, space).unwrap();
+
+        let res = warp::test::request()
+            .method("POST")
+            .header("Content-type", "application/octet")
+            .header("X-Op", "stats")
+            .body(buf)
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), 200);
+        rmp_decode(&mut res.body().as_ref()).unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_stats() {
+        let store = Store::new();
+        let space: Arc<KitsuneSpace> = Arc::new(fixt!(KitsuneSpace, Unpredictable));
+        let mut arcs = Vec::new();
+        for _ in 0..20 {
+            let info = signed_agent_info(space.clone(), vec!["fake:".into()]).await;
+            arcs.push(info.storage_arc);
+            store.put(info).await.unwrap();
+        }
+        // Agents without urls can't be reached so they aren't counted
+        for _ in 0..5 {
+            let info = signed_agent_info(space.clone(), fixt!(UrlList, Empty)).await;
+            store.put(info).await.unwrap();
+        }
+
+        let stats = request_stats(store.clone(), space).await;
+        assert_eq!(stats.agent_count, 20);
+        assert_eq!(stats, SpaceStats::from_arcs(arcs));
+
+        // Unknown spaces are empty
+        let stats = request_stats(store, Arc::new(fixt!(KitsuneSpace, Unpredictable))).await;
+        assert_eq!(stats.agent_count, 0);
+        assert_eq!(stats.est_total_peers, 0);
+    }
+}
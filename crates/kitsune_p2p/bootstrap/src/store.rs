@@ -1,63 +1,167 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, path::Path, sync::Arc};
 
 use kitsune_p2p_types::{
     agent_info::AgentInfoSigned,
     bin_types::{KitsuneAgent, KitsuneSpace},
-    bootstrap::RandomQuery,
+    bootstrap::{NearBasisQuery, RandomLimit, RandomQuery, SpaceStats},
     codec::rmp_encode,
 };
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use rand::seq::IteratorRandom;
+use rusqlite::{params, Connection};
 
 type AgentMap = HashMap<Arc<KitsuneAgent>, AgentInfoSigned>;
 type SpaceMap = HashMap<Arc<KitsuneSpace>, AgentMap>;
 
+/// The agent infos in memory, written through to a database if there is one.
 #[derive(Clone, Debug)]
-pub(crate) struct Store(Arc<RwLock<SpaceMap>>);
+pub(crate) struct Store {
+    map: Arc<RwLock<SpaceMap>>,
+    db: Option<Arc<Mutex<Connection>>>,
+    /// Held across the writes to both the database and the map,
+    /// so they apply puts, clears and prunes in the same order.
+    writes: Arc<tokio::sync::Mutex<()>>,
+}
 
 impl Store {
     pub fn new() -> Self {
-        Self(Arc::new(RwLock::new(HashMap::new())))
+        Self {
+            map: Arc::new(RwLock::new(HashMap::new())),
+            db: None,
+            writes: Arc::new(tokio::sync::Mutex::new(())),
+        }
+    }
+
+    /// Open or create the database at this path,
+    /// and load the agent infos which haven't expired.
+    pub fn open(path: &Path) -> rusqlite::Result<Self> {
+        let db = Connection::open(path)?;
+        db.execute_batch(
+            "CREATE TABLE IF NOT EXISTS agent_info (
+                space           BLOB    NOT NULL,
+                agent           BLOB    NOT NULL,
+                signed_at_ms    INTEGER NOT NULL,
+                expires_at_ms   INTEGER NOT NULL,
+                encoded         BLOB    NOT NULL,
+                PRIMARY KEY (space, agent)
+            );",
+        )?;
+        let mut map = SpaceMap::new();
+        {
+            let mut stmt = db.prepare("SELECT encoded FROM agent_info WHERE expires_at_ms > ?1")?;
+            let rows = stmt.query_map(params![now() as i64], |row| row.get::<_, Vec<u8>>(0))?;
+            for encoded in rows {
+                // Skip rows which no longer decode rather than refusing to start.
+                if let Ok(info) = AgentInfoSigned::decode(&encoded?) {
+                    insert(&mut map, info);
+                }
+            }
+        }
+        Ok(Self {
+            map: Arc::new(RwLock::new(map)),
+            db: Some(Arc::new(Mutex::new(db))),
+            writes: Arc::new(tokio::sync::Mutex::new(())),
+        })
     }
 
-    pub fn prune(&self) {
-        let now = std::time::UNIX_EPOCH
-            .elapsed()
-            .expect("Bootstrap server time set before epoch")
-            .as_millis() as u64;
+    pub async fn prune(&self) -> rusqlite::Result<()> {
+        let _writes = self.writes.lock().await;
+        let now = now();
 
-        self.0.write().retain(|_, map| {
+        self.map.write().retain(|_, map| {
             map.retain(|_, info| info.expires_at_ms >= now);
             !map.is_empty()
         });
+        if let Some(db) = &self.db {
+            write(db, move |db| {
+                db.execute(
+                    "DELETE FROM agent_info WHERE expires_at_ms < ?1",
+                    params![now as i64],
+                )
+                .map(|_| ())
+            })
+            .await?;
+        }
+        Ok(())
     }
 
-    pub fn put(&self, info: AgentInfoSigned) {
-        let mut lock = self.0.write();
-        let space_map = lock.entry(info.space.clone()).or_insert_with(HashMap::new);
-        match space_map.entry(info.agent.clone()) {
-            std::collections::hash_map::Entry::Occupied(mut e) => {
-                if info.signed_at_ms > e.get().signed_at_ms {
-                    e.insert(info);
-                }
-            }
-            std::collections::hash_map::Entry::Vacant(e) => {
-                e.insert(info);
-            }
+    pub async fn put(&self, info: AgentInfoSigned) -> rusqlite::Result<()> {
+        let _writes = self.writes.lock().await;
+        if let Some(db) = &self.db {
+            let encoded = info
+                .encode()
+                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+            let space = info.space.clone();
+            let agent = info.agent.clone();
+            let signed_at_ms = info.signed_at_ms as i64;
+            let expires_at_ms = info.expires_at_ms as i64;
+            write(db, move |db| {
+                db.execute(
+                    "INSERT INTO agent_info
+                        (space, agent, signed_at_ms, expires_at_ms, encoded)
+                    VALUES (?1, ?2, ?3, ?4, ?5)
+                    ON CONFLICT (space, agent) DO UPDATE SET
+                        signed_at_ms = excluded.signed_at_ms,
+                        expires_at_ms = excluded.expires_at_ms,
+                        encoded = excluded.encoded
+                    WHERE excluded.signed_at_ms > agent_info.signed_at_ms",
+                    params![
+                        &space.0,
+                        &agent.0,
+                        signed_at_ms,
+                        expires_at_ms,
+                        &encoded[..]
+                    ],
+                )
+                .map(|_| ())
+            })
+            .await?;
         }
+        // Only reached if the database write succeeded,
+        // so a failed put leaves both unchanged.
+        insert(&mut self.map.write(), info);
+        Ok(())
     }
 
     pub fn random(&self, query: RandomQuery) -> Vec<Vec<u8>> {
+        self.choose(&query.space, query.limit, |_| true)
+    }
+
+    /// Random agents whose storage arcs cover the basis.
+    pub fn near_basis(&self, query: NearBasisQuery) -> Vec<Vec<u8>> {
+        let basis = query.basis;
+        self.choose(&query.space, query.limit, |i| i.storage_arc.contains(basis))
+    }
+
+    /// Stats of the same reachable agents `random` and `near_basis` choose from.
+    pub fn stats(&self, space: &KitsuneSpace) -> SpaceStats {
+        let now = now();
+        SpaceStats::from_arcs(
+            self.map
+                .read()
+                .get(space)
+                .into_iter()
+                .flat_map(|space| space.values())
+                .filter(|i| i.expires_at_ms > now && !i.url_list.is_empty())
+                .map(|i| i.storage_arc),
+        )
+    }
+
+    /// Choose up to `limit` reachable agents from a space at random,
+    /// out of those passing the filter.
+    fn choose(
+        &self,
+        space: &KitsuneSpace,
+        limit: RandomLimit,
+        filter: impl Fn(&AgentInfoSigned) -> bool,
+    ) -> Vec<Vec<u8>> {
         // TODO: Max this limit
-        let limit = query.limit.0 as usize;
+        let limit = limit.0 as usize;
         let mut rng = rand::thread_rng();
-        let now = std::time::UNIX_EPOCH
-            .elapsed()
-            .expect("Bootstrap server time set before epoch")
-            .as_millis() as u64;
-        self.0
+        let now = now();
+        self.map
             .read()
-            .get(query.space.as_ref())
+            .get(space)
             .map(|space| {
                 space
                     .values()
@@ -68,6 +172,9 @@ impl Store {
                         if i.url_list.is_empty() {
                             return None;
                         }
+                        if !filter(i) {
+                            return None;
+                        }
                         let mut buf = Vec::new();
                         match rmp_encode(&mut buf, i) {
                             Ok(_) => Some(buf),
@@ -79,12 +186,105 @@ impl Store {
             .unwrap_or_default()
     }
 
-    pub fn clear(&self) {
-        self.0.write().clear()
+    pub async fn clear(&self) -> rusqlite::Result<()> {
+        let _writes = self.writes.lock().await;
+        if let Some(db) = &self.db {
+            write(db, |db| {
+                db.execute("DELETE FROM agent_info", []).map(|_| ())
+            })
+            .await?;
+        }
+        self.map.write().clear();
+        Ok(())
     }
 
     #[cfg(test)]
     pub fn all(&self) -> HashMap<Arc<KitsuneSpace>, HashMap<Arc<KitsuneAgent>, AgentInfoSigned>> {
-        self.0.read().clone()
+        self.map.read().clone()
+    }
+}
+
+/// Insert an agent info unless the map has a newer one for the agent.
+fn insert(map: &mut SpaceMap, info: AgentInfoSigned) {
+    let space_map = map.entry(info.space.clone()).or_insert_with(HashMap::new);
+    match space_map.entry(info.agent.clone()) {
+        std::collections::hash_map::Entry::Occupied(mut e) => {
+            if info.signed_at_ms > e.get().signed_at_ms {
+                e.insert(info);
+            }
+        }
+        std::collections::hash_map::Entry::Vacant(e) => {
+            e.insert(info);
+        }
+    }
+}
+
+/// Run a database write on the blocking pool so it doesn't stall the server.
+async fn write<F>(db: &Arc<Mutex<Connection>>, f: F) -> rusqlite::Result<()>
+where
+    F: FnOnce(&Connection) -> rusqlite::Result<()> + Send + 'static,
+{
+    let db = db.clone();
+    match tokio::task::spawn_blocking(move || f(&db.lock())).await {
+        Ok(result) => result,
+        Err(e) => std::panic::resume_unwind(e.into_panic()),
+    }
+}
+
+fn now() -> u64 {
+    std::time::UNIX_EPOCH
+        .elapsed()
+        .expect("Bootstrap server time set before epoch")
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::signed_agent_info;
+    use fixt::prelude::*;
+    use kitsune_p2p::fixt::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_store_persists() {
+        let dir = tempdir::TempDir::new("kitsune_bootstrap").unwrap();
+        let path = dir.path().join("bootstrap.sqlite3");
+        let space = Arc::new(fixt!(KitsuneSpace));
+        let info = signed_agent_info(space.clone(), fixt!(UrlList, Empty)).await;
+
+        let store = Store::open(&path).unwrap();
+        store.put(info.clone()).await.unwrap();
+        drop(store);
+
+        let store = Store::open(&path).unwrap();
+        assert_eq!(
+            *store
+                .all()
+                .get(info.space.as_ref())
+                .unwrap()
+                .get(info.agent.as_ref())
+                .unwrap(),
+            info
+        );
+
+        store.clear().await.unwrap();
+        drop(store);
+        assert!(Store::open(&path).unwrap().all().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_store_agrees_with_db_after_racing_writes() {
+        let dir = tempdir::TempDir::new("kitsune_bootstrap").unwrap();
+        let path = dir.path().join("bootstrap.sqlite3");
+        let space = Arc::new(fixt!(KitsuneSpace));
+
+        let store = Store::open(&path).unwrap();
+        for _ in 0..10 {
+            let info = signed_agent_info(space.clone(), fixt!(UrlList, Empty)).await;
+            let (put, clear) = tokio::join!(store.put(info), store.clear());
+            put.unwrap();
+            clear.unwrap();
+            assert_eq!(store.all(), Store::open(&path).unwrap().all());
+        }
     }
 }
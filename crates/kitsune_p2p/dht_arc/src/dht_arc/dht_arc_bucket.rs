@@ -24,6 +24,16 @@ impl PeerDensity {
         }
     }
 
+    /// The number of peers in the bucket.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The average coverage of peers in the bucket.
+    pub fn average_coverage(&self) -> f64 {
+        self.average_coverage
+    }
+
     /// The expected number of peers for this arc over time.
     pub fn expected_count(&self) -> usize {
         (self.count as f64 * DEFAULT_UPTIME) as usize
@@ -688,6 +688,14 @@ async fn handle_events(
                         .boxed()
                         .into()));
                 }
+                event::KitsuneP2pEvent::IsBlocked { respond, .. } => {
+                    // kitsune direct has no block list
+                    respond.r(Ok(async move { Ok(false) }.boxed().into()));
+                }
+                event::KitsuneP2pEvent::Block { respond, .. } => {
+                    // kitsune direct has no block list
+                    respond.r(Ok(async move { Ok(()) }.boxed().into()));
+                }
                 event::KitsuneP2pEvent::Call {
                     respond,
                     space,
@@ -754,6 +762,12 @@ async fn handle_events(
                         .boxed()
                         .into()));
                 }
+                event::KitsuneP2pEvent::FetchOpSizes { respond, input, .. } => {
+                    respond.r(Ok(handle_fetch_op_sizes(kdirect.clone(), input)
+                        .map_err(KitsuneP2pError::other)
+                        .boxed()
+                        .into()));
+                }
                 event::KitsuneP2pEvent::SignNetworkData { respond, input, .. } => {
                     respond.r(Ok(handle_sign_network_data(kdirect.clone(), input)
                         .map_err(KitsuneP2pError::other)
@@ -1003,6 +1017,31 @@ async fn handle_fetch_op_data(
     Ok(out)
 }
 
+async fn handle_fetch_op_sizes(
+    kdirect: Arc<Kd1>,
+    input: FetchOpSizesEvt,
+) -> KdResult<Vec<(Arc<KitsuneOpHash>, u32)>> {
+    let FetchOpSizesEvt {
+        space,
+        agents,
+        op_hashes,
+        ..
+    } = input;
+
+    Ok(handle_fetch_op_data(
+        kdirect,
+        FetchOpDataEvt {
+            space,
+            agents,
+            op_hashes,
+        },
+    )
+    .await?
+    .into_iter()
+    .map(|(op_hash, data)| (op_hash, data.len() as u32))
+    .collect())
+}
+
 async fn handle_sign_network_data(
     kdirect: Arc<Kd1>,
     input: SignNetworkDataEvt,
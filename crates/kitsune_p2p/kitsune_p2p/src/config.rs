@@ -13,7 +13,16 @@ pub const BOOTSTRAP_SERVICE_DEV: &str = "https://bootstrap-dev.holohost.workers.
 
 pub(crate) enum KitsuneP2pTx2Backend {
     Mem,
-    Quic { bind_to: TxUrl },
+    Quic {
+        bind_to: TxUrl,
+    },
+    Tcp {
+        bind_to: TxUrl,
+    },
+    #[cfg(feature = "test_utils")]
+    Sim {
+        network: String,
+    },
 }
 
 pub(crate) struct KitsuneP2pTx2Config {
@@ -50,10 +59,10 @@ impl Default for KitsuneP2pConfig {
     }
 }
 
-fn cnv_bind_to(bind_to: &Option<url2::Url2>) -> TxUrl {
+fn cnv_bind_to(bind_to: &Option<url2::Url2>, default: &str) -> TxUrl {
     match bind_to {
         Some(bind_to) => bind_to.clone().into(),
-        None => "kitsune-quic://0.0.0.0:0".into(),
+        None => default.into(),
     }
 }
 
@@ -69,11 +78,19 @@ impl KitsuneP2pConfig {
             }) => {
                 let backend = match &**sub_transport {
                     TransportConfig::Mem {} => KitsuneP2pTx2Backend::Mem,
+                    #[cfg(feature = "test_utils")]
+                    TransportConfig::Sim { network } => KitsuneP2pTx2Backend::Sim {
+                        network: network.clone(),
+                    },
                     TransportConfig::Quic { bind_to, .. } => {
-                        let bind_to = cnv_bind_to(bind_to);
+                        let bind_to = cnv_bind_to(bind_to, "kitsune-quic://0.0.0.0:0");
                         KitsuneP2pTx2Backend::Quic { bind_to }
                     }
-                    _ => return Err("kitsune tx2 backend must be mem or quic".into()),
+                    TransportConfig::Tcp { bind_to } => {
+                        let bind_to = cnv_bind_to(bind_to, "kitsune-tcp://0.0.0.0:0");
+                        KitsuneP2pTx2Backend::Tcp { bind_to }
+                    }
+                    _ => return Err("kitsune tx2 backend must be mem, sim, quic or tcp".into()),
                 };
                 let use_proxy = match proxy_config {
                     ProxyConfig::RemoteProxyClient { proxy_url } => Some(proxy_url.clone().into()),
@@ -82,12 +99,26 @@ impl KitsuneP2pConfig {
                 Ok(KitsuneP2pTx2Config { backend, use_proxy })
             }
             Some(TransportConfig::Quic { bind_to, .. }) => {
-                let bind_to = cnv_bind_to(bind_to);
+                let bind_to = cnv_bind_to(bind_to, "kitsune-quic://0.0.0.0:0");
                 Ok(KitsuneP2pTx2Config {
                     backend: KitsuneP2pTx2Backend::Quic { bind_to },
                     use_proxy: None,
                 })
             }
+            Some(TransportConfig::Tcp { bind_to }) => {
+                let bind_to = cnv_bind_to(bind_to, "kitsune-tcp://0.0.0.0:0");
+                Ok(KitsuneP2pTx2Config {
+                    backend: KitsuneP2pTx2Backend::Tcp { bind_to },
+                    use_proxy: None,
+                })
+            }
+            #[cfg(feature = "test_utils")]
+            Some(TransportConfig::Sim { network }) => Ok(KitsuneP2pTx2Config {
+                backend: KitsuneP2pTx2Backend::Sim {
+                    network: network.clone(),
+                },
+                use_proxy: None,
+            }),
             None | Some(TransportConfig::Mem {}) => Ok(KitsuneP2pTx2Config {
                 backend: KitsuneP2pTx2Backend::Mem,
                 use_proxy: None,
@@ -103,6 +134,16 @@ pub enum TransportConfig {
     /// A transport that uses the local memory transport protocol
     /// (this is mainly for testing).
     Mem {},
+    /// A local memory transport whose links are degraded by a simulated
+    /// network, for testing under realistic conditions.
+    /// The network must be registered under this name with
+    /// `kitsune_p2p_types::tx2::SimNet::register` before kitsune starts.
+    /// Only available with the `test_utils` feature.
+    #[cfg(feature = "test_utils")]
+    Sim {
+        /// The name the simulated network was registered under.
+        network: String,
+    },
     /// A transport that uses the QUIC protocol
     Quic {
         /// To which network interface / port should we bind?
@@ -120,6 +161,14 @@ pub enum TransportConfig {
         /// Default: None = use NIC port.
         override_port: Option<u16>,
     },
+    /// A transport that uses TLS 1.3 over TCP, multiplexing all the
+    /// channels of a connection over one stream.
+    /// Use this where UDP, and so QUIC, is blocked.
+    Tcp {
+        /// To which network interface / port should we bind?
+        /// Default: "kitsune-tcp://0.0.0.0:0".
+        bind_to: Option<Url2>,
+    },
     /// A transport that tls tunnels through a sub-transport (ALPN kitsune-proxy/0)
     Proxy {
         /// The 'Proxy' transport is a wrapper around a sub-transport
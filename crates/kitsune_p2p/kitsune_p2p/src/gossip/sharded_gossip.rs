@@ -31,12 +31,14 @@ use self::state_map::RoundStateMap;
 use super::simple_bloom::{HowToConnect, MetaOpKey};
 
 pub use bandwidth::BandwidthThrottles;
+pub use regions::{Region, RegionCoords, RegionData};
 
 mod accept;
 mod agents;
 mod bloom;
 mod initiate;
 mod ops;
+mod regions;
 mod state_map;
 mod store;
 
@@ -82,6 +84,18 @@ pub enum GossipType {
     Historical,
 }
 
+/// How a node compares the ops it holds with its gossip partner.
+/// Nodes answer both kinds of requests, so they can gossip with
+/// partners that use the other strategy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpSyncStrategy {
+    /// Send a bloom filter of the op hashes in each time window.
+    Bloom,
+    /// Send fingerprints of regions of dht space and time, splitting the
+    /// regions that differ until they are small enough to send their hashes.
+    Regions,
+}
+
 /// The entry point for the sharded gossip strategy.
 ///
 /// This struct encapsulates the network communication concerns, mainly
@@ -106,6 +120,7 @@ impl ShardedGossip {
         ep_hnd: Tx2EpHnd<wire::Wire>,
         evt_sender: EventSender,
         gossip_type: GossipType,
+        op_sync: OpSyncStrategy,
         bandwidth: Arc<BandwidthThrottle>,
     ) -> Arc<Self> {
         let this = Arc::new(Self {
@@ -117,6 +132,7 @@ impl ShardedGossip {
                 evt_sender,
                 inner: Share::new(ShardedGossipLocalState::default()),
                 gossip_type,
+                op_sync,
                 closing: AtomicBool::new(false),
             },
             bandwidth,
@@ -167,6 +183,8 @@ impl ShardedGossip {
         // actually sending the gossip.
         self.bandwidth.outgoing_bytes(bytes).await;
         con.notify(&gossip, timeout).await?;
+        metric_push_gossip_bytes_sent(bytes as u64);
+        self.gossip.record_gossip_bytes(con.peer_cert(), bytes, 0);
         Ok(())
     }
 
@@ -174,9 +192,24 @@ impl ShardedGossip {
         let (incoming, outgoing) = self.pop_queues()?;
         if let Some((con, remote_url, msg, bytes)) = incoming {
             self.bandwidth.incoming_bytes(bytes).await;
+            self.gossip.record_gossip_bytes(con.peer_cert(), 0, bytes);
             let outgoing = match self.gossip.process_incoming(con.peer_cert(), msg).await {
                 Ok(r) => r,
                 Err(e) => {
+                    // Honest nodes drop counterfeit ops before holding them,
+                    // so the node gossiping them to us made them.
+                    if is_counterfeit(&e) {
+                        tracing::warn!(cert = ?con.peer_cert(), "blocking node which gossiped counterfeit ops");
+                        if let Err(err) = store::block(
+                            &self.gossip.evt_sender,
+                            &self.gossip.space,
+                            BlockTarget::Node(con.peer_cert()),
+                        )
+                        .await
+                        {
+                            tracing::error!("Gossip failed to block a node because of: {:?}", err);
+                        }
+                    }
                     self.gossip.remove_state(&con.peer_cert(), true).await?;
                     vec![ShardedGossipWire::error(e.to_string())]
                 }
@@ -230,6 +263,9 @@ impl ShardedGossip {
             tracing::error!("Gossip failed to process a message because of: {:?}", err);
         }
         self.gossip.record_timeouts();
+        if let Err(err) = self.gossip.flush_node_metrics().await {
+            tracing::error!("Gossip failed to record node metrics because of: {:?}", err);
+        }
     }
 
     fn pop_queues(&self) -> KitsuneResult<(Option<Incoming>, Option<Outgoing>)> {
@@ -241,6 +277,17 @@ impl ShardedGossip {
     }
 }
 
+/// Did the host find the data in a gossip message to be counterfeit?
+fn is_counterfeit(err: &KitsuneError) -> bool {
+    match err.kind() {
+        KitsuneErrorKind::Other(e) => matches!(
+            e.downcast_ref::<KitsuneP2pError>(),
+            Some(KitsuneP2pError::Counterfeit(_))
+        ),
+        _ => false,
+    }
+}
+
 /// The parts of sharded gossip which are concerned only with the gossiping node:
 /// - managing local state
 /// - making requests to the local backend
@@ -248,6 +295,7 @@ impl ShardedGossip {
 ///     get sent by the enclosing `ShardedGossip`
 pub struct ShardedGossipLocal {
     gossip_type: GossipType,
+    op_sync: OpSyncStrategy,
     tuning_params: KitsuneP2pTuningParams,
     space: Arc<KitsuneSpace>,
     evt_sender: EventSender,
@@ -277,6 +325,12 @@ pub struct ShardedGossipLocalState {
     last_local_sync: Option<std::time::Instant>,
     /// Trigger local sync to run on the next iteration.
     trigger_local_sync: bool,
+    /// The rounds which have finished since they were last recorded
+    /// as metrics about the agents of the remote node.
+    finished_rounds: Vec<(StateKey, MetricKind, SystemTime)>,
+    /// The gossip bytes sent to and received from each node
+    /// since they were last recorded as metrics.
+    gossip_bytes: HashMap<StateKey, (usize, usize)>,
 }
 
 impl ShardedGossipLocalState {
@@ -291,15 +345,25 @@ impl ShardedGossipLocalState {
         }
         let r = self.round_map.remove(state_key);
         if r.is_some() {
-            if error {
-                self.metrics.record_error(state_key.clone());
-            } else {
-                self.metrics.record_success(state_key.clone());
-            }
+            self.record_round_outcome(state_key.clone(), error);
         }
         r
     }
 
+    /// Record how a round with a remote node ended, both to choose
+    /// the next node to gossip with and for the space's metrics.
+    fn record_round_outcome(&mut self, state_key: StateKey, error: bool) {
+        let kind = if error {
+            self.metrics.record_error(state_key.clone());
+            MetricKind::GossipRoundFailed
+        } else {
+            self.metrics.record_success(state_key.clone());
+            MetricKind::GossipRoundComplete
+        };
+        self.finished_rounds
+            .push((state_key, kind, SystemTime::now()));
+    }
+
     fn check_tgt_expired(&mut self) {
         if let Some(cert) = self.initiate_tgt.as_ref().map(|tgt| tgt.0.cert().clone()) {
             if self.round_map.check_timeout(&cert) {
@@ -341,9 +405,9 @@ pub struct ShardedGossipState {
 pub struct RoundState {
     /// The common ground with our gossip partner for the purposes of this round
     common_arc_set: Arc<DhtArcSet>,
-    /// Number of ops blooms we have sent for this round, which is also the
-    /// number of MissingOps sets we expect in response
-    num_sent_ops_blooms: u8,
+    /// Number of ops blooms (or region requests) we have sent for this round,
+    /// which is also the number of responses we expect
+    num_sent_ops_blooms: u32,
     /// We've received the last op bloom filter from our partner
     /// (the one with `finished` == true)
     received_all_incoming_ops_blooms: bool,
@@ -351,6 +415,13 @@ pub struct RoundState {
     created_at: std::time::Instant,
     /// Amount of time before a round is considered expired.
     round_timeout: u32,
+    /// Number of regions we have answered for our partner this round.
+    num_regions_received: usize,
+    /// Number of op hashes in the regions we have answered this round.
+    num_hashes_received: usize,
+    /// The regions we have sent our fingerprints of this round
+    /// which our partner has not yet answered.
+    regions_requested: HashSet<RegionCoords>,
 }
 
 impl ShardedGossipLocal {
@@ -394,6 +465,9 @@ impl ShardedGossipLocal {
             round_timeout: self
                 .tuning_params
                 .gossip_peer_on_success_next_gossip_delay_ms,
+            num_regions_received: 0,
+            num_hashes_received: 0,
+            regions_requested: HashSet::new(),
         })
     }
 
@@ -414,11 +488,7 @@ impl ShardedGossipLocal {
                 .unwrap_or(false)
             {
                 i.initiate_tgt = None;
-                if error {
-                    i.metrics.record_error(id.clone());
-                } else {
-                    i.metrics.record_success(id.clone());
-                }
+                i.record_round_outcome(id.clone(), error);
             }
             Ok(())
         })
@@ -446,9 +516,18 @@ impl ShardedGossipLocal {
     }
 
     async fn decrement_ops_blooms(&self, state_id: &StateKey) -> KitsuneResult<Option<RoundState>> {
+        self.replace_ops_request(state_id, 0).await
+    }
+
+    /// One of our requests was answered and we sent `num_sent` new requests in reply.
+    async fn replace_ops_request(
+        &self,
+        state_id: &StateKey,
+        num_sent: u32,
+    ) -> KitsuneResult<Option<RoundState>> {
         self.inner.share_mut(|i, _| {
             let update_state = |state: &mut RoundState| {
-                let num_ops_blooms = state.num_sent_ops_blooms.saturating_sub(1);
+                let num_ops_blooms = (state.num_sent_ops_blooms + num_sent).saturating_sub(1);
                 state.num_sent_ops_blooms = num_ops_blooms;
                 state.num_sent_ops_blooms == 0 && state.received_all_incoming_ops_blooms
             };
@@ -533,10 +612,46 @@ impl ShardedGossipLocal {
                 } else {
                     self.get_state(&cert).await?
                 };
+                let mut gossip = Vec::with_capacity(0);
                 if let Some(state) = state {
+                    // Region requests are sent as the round goes, so let our
+                    // partner know once the last one has been answered.
+                    if finished
+                        && self.op_sync == OpSyncStrategy::Regions
+                        && state.num_sent_ops_blooms == 0
+                    {
+                        gossip.push(ShardedGossipWire::op_regions(Vec::with_capacity(0), true));
+                    }
                     self.incoming_missing_ops(state, ops).await?;
                 }
-                Vec::with_capacity(0)
+                gossip
+            }
+            ShardedGossipWire::OpRegions(OpRegions { regions, finished }) => {
+                let state = if finished {
+                    self.incoming_ops_finished(&cert).await?
+                } else {
+                    self.get_state(&cert).await?
+                };
+                match state {
+                    Some(state) if !finished => {
+                        self.incoming_op_regions(cert, state, regions).await?
+                    }
+                    _ => Vec::with_capacity(0),
+                }
+            }
+            ShardedGossipWire::RegionDiffs(RegionDiffs { regions }) => {
+                if let Some(state) = self.get_state(&cert).await? {
+                    self.incoming_region_diffs(cert, state, regions).await?
+                } else {
+                    Vec::with_capacity(0)
+                }
+            }
+            ShardedGossipWire::RegionHashes(RegionHashes { regions }) => {
+                if let Some(state) = self.get_state(&cert).await? {
+                    self.incoming_region_hashes(cert, state, regions).await?
+                } else {
+                    Vec::with_capacity(0)
+                }
             }
             ShardedGossipWire::NoAgents(_) => {
                 self.remove_state(&cert, true).await?;
@@ -625,13 +740,84 @@ impl ShardedGossipLocal {
         self.inner
             .share_mut(|i, _| {
                 for cert in i.round_map.take_timed_out_rounds() {
-                    i.metrics.record_error(cert);
+                    i.record_round_outcome(cert, true);
                 }
                 Ok(())
             })
             .ok();
     }
 
+    /// Add to the gossip bytes sent to and received from a node.
+    fn record_gossip_bytes(&self, cert: StateKey, sent: usize, received: usize) {
+        self.inner
+            .share_mut(|i, _| {
+                let bytes = i.gossip_bytes.entry(cert).or_default();
+                bytes.0 += sent;
+                bytes.1 += received;
+                Ok(())
+            })
+            .ok();
+    }
+
+    /// Record the rounds which have finished and the gossip bytes exchanged
+    /// since the last call as metrics about each agent of the remote node.
+    /// A node's bytes are split evenly between its agents, so adding up
+    /// the agents' metrics gives the bytes exchanged with the node.
+    async fn flush_node_metrics(&self) -> KitsuneResult<()> {
+        let (finished, gossip_bytes) = self.inner.share_mut(|i, _| {
+            Ok((
+                std::mem::take(&mut i.finished_rounds),
+                std::mem::take(&mut i.gossip_bytes),
+            ))
+        })?;
+        if finished.is_empty() && gossip_bytes.is_empty() {
+            return Ok(());
+        }
+        let agent_infos = store::all_agent_info(&self.evt_sender, &self.space).await?;
+        let node_agents = |cert: &StateKey| {
+            agent_infos
+                .iter()
+                .filter(|info| next_target::agent_node(info).map_or(false, |(c, _)| &c == cert))
+                .map(|info| info.agent.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let mut data = Vec::new();
+        for (cert, kind, timestamp) in finished {
+            for agent in node_agents(&cert) {
+                data.push((agent, kind.clone(), timestamp));
+            }
+        }
+        let now = SystemTime::now();
+        for (cert, (sent, received)) in gossip_bytes {
+            let agents = node_agents(&cert);
+            let count = agents.len() as u64;
+            for agent in agents {
+                if sent > 0 {
+                    let bytes = sent as u64 / count;
+                    data.push((agent.clone(), MetricKind::BytesSent { bytes }, now));
+                }
+                if received > 0 {
+                    let bytes = received as u64 / count;
+                    data.push((agent, MetricKind::BytesReceived { bytes }, now));
+                }
+            }
+        }
+
+        for (agent, kind, timestamp) in data {
+            self.evt_sender
+                .put_metric_datum(MetricDatum {
+                    space: self.space.clone(),
+                    agent,
+                    kind,
+                    timestamp,
+                })
+                .await
+                .map_err(KitsuneError::other)?;
+        }
+        Ok(())
+    }
+
     fn show_local_agents(&self) -> HashSet<Arc<KitsuneAgent>> {
         self.inner
             .share_mut(|i, _| Ok(i.local_agents.clone()))
@@ -676,7 +862,7 @@ fn local_sync_arcset(arcs: &[ArcInterval]) -> DhtArcSet {
 }
 
 impl RoundState {
-    fn increment_sent_ops_blooms(&mut self) -> u8 {
+    fn increment_sent_ops_blooms(&mut self) -> u32 {
         self.num_sent_ops_blooms += 1;
         self.num_sent_ops_blooms
     }
@@ -762,6 +948,27 @@ kitsune_p2p_types::write_codec_enum! {
             finished.1: bool,
         },
 
+        /// Send the fingerprints of regions of ops
+        OpRegions(0x51) {
+            /// The regions and our fingerprint of each
+            regions.0: Vec<Region>,
+            /// Is this the last request of the round?
+            /// The last one carries no regions.
+            finished.1: bool,
+        },
+
+        /// The regions that differ from the fingerprints we were sent.
+        RegionDiffs(0x52) {
+            /// The regions and our own fingerprint of each
+            regions.0: Vec<Region>,
+        },
+
+        /// Send the op hashes held in regions
+        RegionHashes(0x53) {
+            /// The regions and the op hashes we hold in each
+            regions.0: Vec<(RegionCoords, Vec<Arc<KitsuneOpHash>>)>,
+        },
+
         /// Any ops that were missing from the remote bloom.
         MissingOps(0x60) {
             /// The missing ops
@@ -847,11 +1054,12 @@ impl AsGossipModule for ShardedGossip {
 
 struct ShardedRecentGossipFactory {
     bandwidth: Arc<BandwidthThrottle>,
+    op_sync: OpSyncStrategy,
 }
 
 impl ShardedRecentGossipFactory {
-    fn new(bandwidth: Arc<BandwidthThrottle>) -> Self {
-        Self { bandwidth }
+    fn new(bandwidth: Arc<BandwidthThrottle>, op_sync: OpSyncStrategy) -> Self {
+        Self { bandwidth, op_sync }
     }
 }
 
@@ -869,6 +1077,7 @@ impl AsGossipModuleFactory for ShardedRecentGossipFactory {
             ep_hnd,
             evt_sender,
             GossipType::Recent,
+            self.op_sync,
             self.bandwidth.clone(),
         ))
     }
@@ -876,11 +1085,12 @@ impl AsGossipModuleFactory for ShardedRecentGossipFactory {
 
 struct ShardedHistoricalGossipFactory {
     bandwidth: Arc<BandwidthThrottle>,
+    op_sync: OpSyncStrategy,
 }
 
 impl ShardedHistoricalGossipFactory {
-    fn new(bandwidth: Arc<BandwidthThrottle>) -> Self {
-        Self { bandwidth }
+    fn new(bandwidth: Arc<BandwidthThrottle>, op_sync: OpSyncStrategy) -> Self {
+        Self { bandwidth, op_sync }
     }
 }
 
@@ -898,19 +1108,30 @@ impl AsGossipModuleFactory for ShardedHistoricalGossipFactory {
             ep_hnd,
             evt_sender,
             GossipType::Historical,
+            self.op_sync,
             self.bandwidth.clone(),
         ))
     }
 }
 
 /// Create a recent [`GossipModuleFactory`]
-pub fn recent_factory(bandwidth: Arc<BandwidthThrottle>) -> GossipModuleFactory {
-    GossipModuleFactory(Arc::new(ShardedRecentGossipFactory::new(bandwidth)))
+pub fn recent_factory(
+    bandwidth: Arc<BandwidthThrottle>,
+    op_sync: OpSyncStrategy,
+) -> GossipModuleFactory {
+    GossipModuleFactory(Arc::new(ShardedRecentGossipFactory::new(
+        bandwidth, op_sync,
+    )))
 }
 
 /// Create a [`GossipModuleFactory`]
-pub fn historical_factory(bandwidth: Arc<BandwidthThrottle>) -> GossipModuleFactory {
-    GossipModuleFactory(Arc::new(ShardedHistoricalGossipFactory::new(bandwidth)))
+pub fn historical_factory(
+    bandwidth: Arc<BandwidthThrottle>,
+    op_sync: OpSyncStrategy,
+) -> GossipModuleFactory {
+    GossipModuleFactory(Arc::new(ShardedHistoricalGossipFactory::new(
+        bandwidth, op_sync,
+    )))
 }
 
 #[allow(dead_code)]
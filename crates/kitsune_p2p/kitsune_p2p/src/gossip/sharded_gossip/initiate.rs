@@ -134,6 +134,7 @@ impl ShardedGossipLocal {
     /// Generate the bloom filters and generate a new state.
     /// - Agent bloom is only generated if this is a `Recent` gossip type.
     /// - Empty blooms are not created.
+    /// - Region fingerprints are sent instead of ops blooms if this node syncs ops by region.
     /// - A new state is created for this round.
     pub(super) async fn generate_blooms(
         &self,
@@ -159,6 +160,11 @@ impl ShardedGossipLocal {
             }
         }
 
+        if let OpSyncStrategy::Regions = self.op_sync {
+            self.generate_regions(&mut state, gossip).await?;
+            return Ok(state);
+        }
+
         let time_ranges = self.calculate_time_ranges();
         let len = time_ranges.len();
         // Generate the ops bloom for all local agents within the common arc.
@@ -2,6 +2,7 @@ use std::cmp::Ordering;
 
 use super::metrics::Metrics;
 use super::*;
+use crate::event::BlockTarget;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// A remote node we can connect to.
@@ -36,22 +37,7 @@ impl ShardedGossipLocal {
             .filter(|a| remote_agents_within_arc_set.contains(&a.agent))
         {
             // Get an address if there is one.
-            let info = info
-                .url_list
-                .iter()
-                .filter_map(|url| {
-                    kitsune_p2p_proxy::ProxyUrl::from_full(url.as_str())
-                        .map_err(|e| tracing::error!("Failed to parse url {:?}", e))
-                        .ok()
-                        .map(|purl| {
-                            (
-                                info.agent.clone(),
-                                Tx2Cert::from(purl.digest()),
-                                TxUrl::from(url.as_str()),
-                            )
-                        })
-                })
-                .next();
+            let info = agent_node(&info).map(|(cert, url)| (info.agent.clone(), cert, url));
 
             // If we found a remote address add this agent to the node
             // or create the node if it doesn't exist.
@@ -76,7 +62,14 @@ impl ShardedGossipLocal {
             }
         }
 
-        let remote_nodes = remote_nodes.into_iter().map(|(_, v)| v).collect();
+        // Don't gossip with nodes that have been blocked from this space.
+        let mut unblocked_nodes = Vec::with_capacity(remote_nodes.len());
+        for (cert, node) in remote_nodes {
+            if !store::is_blocked(&self.evt_sender, &self.space, BlockTarget::Node(cert)).await? {
+                unblocked_nodes.push(node);
+            }
+        }
+        let remote_nodes = unblocked_nodes;
         let tuning_params = self.tuning_params.clone();
         // We could clone the metrics store out of the lock here but I don't think
         // the next_remote_node will be that slow so we can just choose the next node inline.
@@ -88,6 +81,19 @@ impl ShardedGossipLocal {
     }
 }
 
+/// The node an agent can be reached at, and its address, if it has one.
+pub(super) fn agent_node(info: &AgentInfoSigned) -> Option<(Tx2Cert, TxUrl)> {
+    info.url_list
+        .iter()
+        .filter_map(|url| {
+            kitsune_p2p_proxy::ProxyUrl::from_full(url.as_str())
+                .map_err(|e| tracing::error!("Failed to parse url {:?}", e))
+                .ok()
+                .map(|purl| (Tx2Cert::from(purl.digest()), TxUrl::from(url.as_str())))
+        })
+        .next()
+}
+
 /// Find the next remote node to sync with.
 fn next_remote_node(
     mut remote_nodes: Vec<Node>,
@@ -66,7 +66,10 @@ impl ShardedGossipLocal {
 }
 
 /// Separate gossip into chunks to keep messages under the max size.
-fn into_chunks(gossip: &mut Vec<ShardedGossipWire>, ops: HashMap<Arc<KitsuneOpHash>, Vec<u8>>) {
+pub(super) fn into_chunks(
+    gossip: &mut Vec<ShardedGossipWire>,
+    ops: HashMap<Arc<KitsuneOpHash>, Vec<u8>>,
+) {
     let mut chunk = Vec::with_capacity(ops.len());
     let mut size = 0;
 
@@ -0,0 +1,554 @@
+//! Syncing ops by comparing fingerprints of regions of dht space and time.
+//!
+//! The requester fingerprints the top level regions of the common arc set
+//! and the responder replies with its own fingerprint of every region that
+//! differs. The requester then splits those regions and sends the
+//! fingerprints of their children, until a region holds few enough ops to
+//! send its hashes. The responder answers the hashes with the ops missing
+//! from them. Unlike bloom filters, there are no false positives, so two
+//! nodes always converge.
+//!
+//! Fingerprints aren't cached, because ops can arrive at any time.
+//! Each level of a round queries the hashes and sizes of every op held
+//! in the regions it compares, so the first level passes over every op
+//! held in the common arc set, and each level after it only over the
+//! ops in the regions which differed.
+
+use std::ops::Range;
+
+use super::*;
+
+/// A region where we hold this many ops or fewer
+/// is synced by sending its op hashes instead of splitting it.
+const LEAF_MAX_HASHES: u32 = 256;
+
+/// The most regions sent in a single message.
+/// Any more in an incoming message are ignored.
+pub(super) const MAX_REGIONS_PER_MSG: usize = 128;
+
+/// The most op hashes sent in a single message.
+/// Any more in an incoming message are ignored.
+pub(super) const MAX_HASHES_PER_MSG: usize = 4 * LEAF_MAX_HASHES as usize;
+
+/// The most regions we answer in a round.
+/// The rest are compared again next round.
+pub(super) const MAX_REGIONS_PER_ROUND: usize = 16_384;
+
+/// The most op hashes we answer in a round.
+/// The rest are compared again next round.
+pub(super) const MAX_HASHES_PER_ROUND: usize = 65_536;
+
+/// The top level regions split the dht space into 16 segments.
+const TOP_LEVEL_SPACE_POWER: u8 = 28;
+
+/// A region of dht space and time.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RegionCoords {
+    /// The first location of the region.
+    pub space_start: u32,
+    /// The region covers `2^space_power` locations.
+    pub space_power: u8,
+    /// The time window of the region.
+    pub time: Range<u64>,
+}
+
+/// The fingerprint of the ops a node holds in a region.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegionData {
+    /// The number of ops.
+    pub count: u32,
+    /// The total size of the ops in bytes.
+    pub size: u64,
+    /// The XOR of the op hashes.
+    #[serde(with = "serde_bytes")]
+    pub hash: Vec<u8>,
+}
+
+/// A region along with the fingerprint of its ops.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    /// The region.
+    pub coords: RegionCoords,
+    /// The fingerprint of the ops held in the region.
+    pub data: RegionData,
+}
+
+impl RegionCoords {
+    /// The last location of the region (inclusive).
+    pub fn space_end(&self) -> u32 {
+        let len = 1u64 << self.space_power.min(32);
+        (self.space_start as u64 + len - 1).min(u32::MAX as u64) as u32
+    }
+
+    /// Is this location within the region's space?
+    pub fn contains(&self, loc: u32) -> bool {
+        (self.space_start..=self.space_end()).contains(&loc)
+    }
+
+    /// The region's space as an arc set.
+    /// Arc sets can't hold `u32::MAX`, so the last region's set stops
+    /// just short of it and is taken to hold it.
+    pub fn arc_set(&self) -> DhtArcSet {
+        DhtArcSet::from_bounds(self.space_start, self.space_end().min(u32::MAX - 1))
+    }
+
+    /// Split the region into quarters of its space and halves of its time.
+    /// Empty if the region can't be split any further.
+    pub fn children(&self) -> Vec<RegionCoords> {
+        let spaces = if (2..=32).contains(&self.space_power) {
+            let power = self.space_power - 2;
+            (0..4u64)
+                .map(|i| ((self.space_start as u64 + (i << power)) as u32, power))
+                .collect()
+        } else {
+            vec![(self.space_start, self.space_power)]
+        };
+        let times = if self.time.end.saturating_sub(self.time.start) >= 2 {
+            let mid = self.time.start + (self.time.end - self.time.start) / 2;
+            vec![self.time.start..mid, mid..self.time.end]
+        } else {
+            vec![self.time.clone()]
+        };
+        if spaces.len() == 1 && times.len() == 1 {
+            return Vec::with_capacity(0);
+        }
+        spaces
+            .into_iter()
+            .flat_map(|(space_start, space_power)| {
+                times.iter().map(move |time| RegionCoords {
+                    space_start,
+                    space_power,
+                    time: time.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+impl RegionData {
+    /// Add an op to the fingerprint.
+    pub fn add(&mut self, hash: &KitsuneOpHash, size: u32) {
+        self.count += 1;
+        self.size += size as u64;
+        if self.hash.len() < hash.0.len() {
+            self.hash.resize(hash.0.len(), 0);
+        }
+        for (a, b) in self.hash.iter_mut().zip(hash.0.iter()) {
+            *a ^= b;
+        }
+    }
+}
+
+impl ShardedGossipLocal {
+    /// The top level regions of a round:
+    /// the dht space split into equal segments for each time window.
+    fn top_level_regions(&self) -> Vec<RegionCoords> {
+        let segments = 1u64 << (32 - TOP_LEVEL_SPACE_POWER);
+        self.calculate_time_ranges()
+            .into_iter()
+            .flat_map(|time| {
+                (0..segments).map(move |i| RegionCoords {
+                    space_start: (i << TOP_LEVEL_SPACE_POWER) as u32,
+                    space_power: TOP_LEVEL_SPACE_POWER,
+                    time: time.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Get the local agents that are relevant to this common arc set.
+    async fn local_agents_within_common_arc(
+        &self,
+        common_arc_set: Arc<DhtArcSet>,
+    ) -> KitsuneResult<Vec<(Arc<KitsuneAgent>, ArcInterval)>> {
+        let local_agents = self
+            .inner
+            .share_mut(|inner, _| Ok(inner.local_agents.clone()))?;
+        Ok(
+            store::agents_within_arcset(&self.evt_sender, &self.space, common_arc_set)
+                .await?
+                .into_iter()
+                .filter(|(a, _)| local_agents.contains(a))
+                .collect(),
+        )
+    }
+
+    /// Fingerprint the ops these agents hold in each region,
+    /// only counting the ops within the common arc set.
+    /// - Returns the op hashes of each region along with its fingerprint.
+    /// - Ops in limbo are left out so both sides compare the same ops.
+    /// - So are ops the store can't give the size of, which are
+    /// compared again next round.
+    async fn region_data(
+        &self,
+        agents: &[(Arc<KitsuneAgent>, ArcInterval)],
+        common_arc_set: &DhtArcSet,
+        coords: &[RegionCoords],
+    ) -> KitsuneResult<Vec<(RegionData, Vec<Arc<KitsuneOpHash>>)>> {
+        if agents.is_empty() {
+            return Ok(vec![Default::default(); coords.len()]);
+        }
+        let mut hashes = vec![HashSet::new(); coords.len()];
+
+        // Make one query per time window for all the regions in it.
+        let mut by_time: HashMap<Range<u64>, Vec<usize>> = HashMap::new();
+        for (i, region) in coords.iter().enumerate() {
+            by_time.entry(region.time.clone()).or_default().push(i);
+        }
+        for (time, indices) in by_time {
+            let arc_set = indices
+                .iter()
+                .map(|i| coords[*i].arc_set())
+                .fold(DhtArcSet::new_empty(), |a, b| a.union(&b));
+            let arc_set = common_arc_set.intersection(&arc_set);
+            if arc_set.is_empty() {
+                continue;
+            }
+            let found = store::all_op_hashes_within_arcset(
+                &self.evt_sender,
+                &self.space,
+                agents,
+                &arc_set,
+                time,
+                usize::MAX,
+                false,
+            )
+            .await?
+            .map(|(found, _)| found)
+            .unwrap_or_default();
+            for hash in found {
+                let loc = hash.get_loc();
+                if !arc_set_holds(common_arc_set, loc) {
+                    continue;
+                }
+                for i in &indices {
+                    if coords[*i].contains(loc) {
+                        hashes[*i].insert(hash.clone());
+                    }
+                }
+            }
+        }
+
+        let all_hashes: HashSet<_> = hashes.iter().flatten().cloned().collect();
+        let sizes: HashMap<_, _> = if all_hashes.is_empty() {
+            HashMap::new()
+        } else {
+            store::fetch_op_sizes(
+                &self.evt_sender,
+                &self.space,
+                agents.iter().map(|(a, _)| a),
+                all_hashes.into_iter().collect(),
+            )
+            .await?
+            .into_iter()
+            .collect()
+        };
+        Ok(hashes
+            .into_iter()
+            .map(|hashes| {
+                let mut data = RegionData::default();
+                let hashes: Vec<_> = hashes
+                    .into_iter()
+                    .filter_map(|hash| {
+                        let size = *sizes.get(&hash)?;
+                        data.add(&hash, size);
+                        Some(hash)
+                    })
+                    .collect();
+                (data, hashes)
+            })
+            .collect())
+    }
+
+    /// Check the incoming regions are all ones we are waiting on an answer for
+    /// and stop waiting on them, so each is only answered once.
+    /// - If any region wasn't asked for the whole message is ignored
+    /// and nothing we are waiting on changes.
+    fn requested_regions(&self, peer_cert: &Tx2Cert, regions: &[Region]) -> KitsuneResult<bool> {
+        self.inner.share_mut(|i, _| {
+            let state = match i.round_map.get_mut(peer_cert) {
+                Some(state) => state,
+                None => return Ok(false),
+            };
+            let coords: HashSet<_> = regions.iter().map(|r| &r.coords).collect();
+            if coords.len() < regions.len()
+                || !coords.iter().all(|c| state.regions_requested.contains(c))
+            {
+                tracing::warn!(?peer_cert, "Ignoring region diffs we didn't ask for");
+                return Ok(false);
+            }
+            for c in coords {
+                state.regions_requested.remove(c);
+            }
+            Ok(true)
+        })
+    }
+
+    /// Wait on an answer for these regions.
+    fn request_regions(&self, peer_cert: &Tx2Cert, regions: &[Region]) -> KitsuneResult<()> {
+        self.inner.share_mut(|i, _| {
+            if let Some(state) = i.round_map.get_mut(peer_cert) {
+                state
+                    .regions_requested
+                    .extend(regions.iter().map(|r| r.coords.clone()));
+            }
+            Ok(())
+        })
+    }
+
+    /// Keep the leading incoming regions that fit within the limits
+    /// of a message and what is left of the limits of the round.
+    fn within_region_limits<T>(
+        &self,
+        peer_cert: &Tx2Cert,
+        mut regions: Vec<T>,
+        num_hashes: impl Fn(&T) -> usize,
+    ) -> KitsuneResult<Vec<T>> {
+        self.inner.share_mut(|i, _| {
+            let state = match i.round_map.get_mut(peer_cert) {
+                Some(state) => state,
+                None => return Ok(Vec::with_capacity(0)),
+            };
+            let max_regions = MAX_REGIONS_PER_MSG
+                .min(MAX_REGIONS_PER_ROUND.saturating_sub(state.num_regions_received));
+            let max_hashes = MAX_HASHES_PER_MSG
+                .min(MAX_HASHES_PER_ROUND.saturating_sub(state.num_hashes_received));
+            let mut hashes = 0;
+            let keep = regions
+                .iter()
+                .take(max_regions)
+                .take_while(|&r| {
+                    hashes += num_hashes(r);
+                    hashes <= max_hashes
+                })
+                .count();
+            if keep < regions.len() {
+                tracing::warn!(
+                    ?peer_cert,
+                    dropped = regions.len() - keep,
+                    "Ignoring regions over the limit"
+                );
+            }
+            regions.truncate(keep);
+            state.num_regions_received += regions.len();
+            state.num_hashes_received += regions.iter().map(&num_hashes).sum::<usize>();
+            Ok(regions)
+        })
+    }
+
+    /// Start a round of syncing ops by region.
+    /// - Send the fingerprints of the top level regions.
+    /// - If we have no agents within the common arc set then only
+    /// let our partner know we are done.
+    pub(super) async fn generate_regions(
+        &self,
+        state: &mut RoundState,
+        gossip: &mut Vec<ShardedGossipWire>,
+    ) -> KitsuneResult<()> {
+        let agents = self
+            .local_agents_within_common_arc(state.common_arc_set.clone())
+            .await?;
+        if !agents.is_empty() {
+            let coords = self.top_level_regions();
+            let data = self
+                .region_data(&agents, &state.common_arc_set, &coords)
+                .await?;
+            let regions = into_regions(coords, data);
+            state
+                .regions_requested
+                .extend(regions.iter().map(|r| r.coords.clone()));
+            for chunk in regions.chunks(MAX_REGIONS_PER_MSG) {
+                state.increment_sent_ops_blooms();
+                gossip.push(ShardedGossipWire::op_regions(chunk.to_vec(), false));
+            }
+        }
+        if state.num_sent_ops_blooms == 0 {
+            gossip.push(ShardedGossipWire::op_regions(Vec::with_capacity(0), true));
+        }
+        Ok(())
+    }
+
+    /// Incoming region fingerprints.
+    /// - Send back our fingerprint of each region where we hold ops
+    /// and the fingerprints differ.
+    pub(super) async fn incoming_op_regions(
+        &self,
+        peer_cert: Tx2Cert,
+        state: RoundState,
+        regions: Vec<Region>,
+    ) -> KitsuneResult<Vec<ShardedGossipWire>> {
+        let regions = self.within_region_limits(&peer_cert, regions, |_| 0)?;
+        let agents = self
+            .local_agents_within_common_arc(state.common_arc_set.clone())
+            .await?;
+        let coords: Vec<_> = regions.iter().map(|r| r.coords.clone()).collect();
+        let ours = self
+            .region_data(&agents, &state.common_arc_set, &coords)
+            .await?;
+        let diffs = regions
+            .into_iter()
+            .zip(ours)
+            .filter(|(theirs, (data, _))| data.count > 0 && theirs.data != *data)
+            .map(|(theirs, (data, _))| Region {
+                coords: theirs.coords,
+                data,
+            })
+            .collect();
+        Ok(vec![ShardedGossipWire::region_diffs(diffs)])
+    }
+
+    /// Incoming fingerprints of the regions which differ from ours.
+    /// - Only the regions we sent and are still waiting on are answered.
+    /// - Send the hashes we hold in regions with few enough ops.
+    /// - Otherwise split the regions and send the fingerprints of their children.
+    /// - Once all our requests are answered let our partner know we are done.
+    pub(super) async fn incoming_region_diffs(
+        &self,
+        peer_cert: Tx2Cert,
+        state: RoundState,
+        regions: Vec<Region>,
+    ) -> KitsuneResult<Vec<ShardedGossipWire>> {
+        // We aren't waiting on any answers, so this wasn't asked for.
+        if state.num_sent_ops_blooms == 0 {
+            return Ok(Vec::with_capacity(0));
+        }
+        if !self.requested_regions(&peer_cert, &regions)? {
+            return Ok(Vec::with_capacity(0));
+        }
+        let regions = self.within_region_limits(&peer_cert, regions, |_| 0)?;
+        let agents = self
+            .local_agents_within_common_arc(state.common_arc_set.clone())
+            .await?;
+        let coords: Vec<_> = regions.iter().map(|r| r.coords.clone()).collect();
+        let ours = self
+            .region_data(&agents, &state.common_arc_set, &coords)
+            .await?;
+
+        let mut leaves = Vec::new();
+        let mut children = Vec::new();
+        for (theirs, (data, hashes)) in regions.into_iter().zip(ours) {
+            // We may have received these ops since we sent the fingerprint.
+            if theirs.data == data {
+                continue;
+            }
+            let split = theirs.coords.children();
+            if data.count <= LEAF_MAX_HASHES || split.is_empty() {
+                leaves.push((theirs.coords, hashes));
+            } else {
+                children.extend(split);
+            }
+        }
+        let data = self
+            .region_data(&agents, &state.common_arc_set, &children)
+            .await?;
+        let children = into_regions(children, data);
+        self.request_regions(&peer_cert, &children)?;
+
+        let mut gossip = Vec::new();
+        for chunk in children.chunks(MAX_REGIONS_PER_MSG) {
+            gossip.push(ShardedGossipWire::op_regions(chunk.to_vec(), false));
+        }
+        into_hash_chunks(&mut gossip, leaves);
+
+        let state = self
+            .replace_ops_request(&peer_cert, gossip.len() as u32)
+            .await?;
+        if state.map(|state| state.num_sent_ops_blooms) == Some(0) {
+            gossip.push(ShardedGossipWire::op_regions(Vec::with_capacity(0), true));
+        }
+        Ok(gossip)
+    }
+
+    /// Incoming op hashes of regions.
+    /// - Send back chunks of the ops we hold in these regions
+    /// that are missing from the hashes.
+    pub(super) async fn incoming_region_hashes(
+        &self,
+        peer_cert: Tx2Cert,
+        state: RoundState,
+        regions: Vec<(RegionCoords, Vec<Arc<KitsuneOpHash>>)>,
+    ) -> KitsuneResult<Vec<ShardedGossipWire>> {
+        let regions = self.within_region_limits(&peer_cert, regions, |(_, h)| h.len())?;
+        let agents = self
+            .local_agents_within_common_arc(state.common_arc_set.clone())
+            .await?;
+        let coords: Vec<_> = regions.iter().map(|(c, _)| c.clone()).collect();
+        let ours = self
+            .region_data(&agents, &state.common_arc_set, &coords)
+            .await?;
+
+        let mut missing = HashSet::new();
+        for ((_, theirs), (_, hashes)) in regions.into_iter().zip(ours) {
+            let theirs: HashSet<_> = theirs.into_iter().collect();
+            missing.extend(hashes.into_iter().filter(|h| !theirs.contains(h)));
+        }
+        let ops = if missing.is_empty() {
+            HashMap::new()
+        } else {
+            store::fetch_ops(
+                &self.evt_sender,
+                &self.space,
+                agents.iter().map(|(a, _)| a),
+                missing.into_iter().collect(),
+            )
+            .await?
+            .into_iter()
+            .collect()
+        };
+
+        let mut gossip = Vec::with_capacity(1);
+        into_chunks(&mut gossip, ops);
+        Ok(gossip)
+    }
+}
+
+/// Is this location within the arc set?
+/// Arc sets can't hold `u32::MAX`, so a set reaching
+/// just short of it holds it too, as the store takes it.
+fn arc_set_holds(arc_set: &DhtArcSet, loc: u32) -> bool {
+    arc_set.contains(loc.min(u32::MAX - 1))
+}
+
+fn into_regions(
+    coords: Vec<RegionCoords>,
+    data: Vec<(RegionData, Vec<Arc<KitsuneOpHash>>)>,
+) -> Vec<Region> {
+    coords
+        .into_iter()
+        .zip(data)
+        .map(|(coords, (data, _))| Region { coords, data })
+        .collect()
+}
+
+/// Separate the region hashes into chunks to keep messages under the max size
+/// and within the limits our partner will answer.
+fn into_hash_chunks(
+    gossip: &mut Vec<ShardedGossipWire>,
+    regions: Vec<(RegionCoords, Vec<Arc<KitsuneOpHash>>)>,
+) {
+    let mut chunk = Vec::new();
+    let mut size = 0;
+    let mut num_hashes = 0;
+    for (coords, mut hashes) in regions {
+        // Only a region that can't be split holds this many, and our
+        // partner sending us a few ops we already hold is harmless.
+        hashes.truncate(MAX_HASHES_PER_MSG);
+        // Rough bytes for this region.
+        let bytes = 32 + hashes.iter().map(|h| h.len()).sum::<usize>();
+        if !chunk.is_empty()
+            && (size + bytes > MAX_SEND_BUF_BYTES
+                || num_hashes + hashes.len() > MAX_HASHES_PER_MSG
+                || chunk.len() == MAX_REGIONS_PER_MSG)
+        {
+            gossip.push(ShardedGossipWire::region_hashes(std::mem::take(&mut chunk)));
+            size = 0;
+            num_hashes = 0;
+        }
+        size += bytes;
+        num_hashes += hashes.len();
+        chunk.push((coords, hashes));
+    }
+    if !chunk.is_empty() {
+        gossip.push(ShardedGossipWire::region_hashes(chunk));
+    }
+}
@@ -4,8 +4,8 @@
 use std::{collections::HashSet, ops::Range, sync::Arc};
 
 use crate::event::{
-    FetchOpDataEvt, PutAgentInfoSignedEvt, QueryAgentInfoSignedEvt, QueryGossipAgentsEvt,
-    QueryOpHashesEvt, TimeWindowMs,
+    BlockTarget, FetchOpDataEvt, FetchOpSizesEvt, PutAgentInfoSignedEvt, QueryAgentInfoSignedEvt,
+    QueryGossipAgentsEvt, QueryOpHashesEvt, TimeWindowMs,
 };
 use crate::types::event::KitsuneP2pEventSender;
 use kitsune_p2p_types::{
@@ -31,6 +31,30 @@ pub(super) async fn all_agent_info(
         .map_err(KitsuneError::other)?)
 }
 
+/// Check if an agent or node is blocked from a space.
+pub(super) async fn is_blocked(
+    evt_sender: &EventSender,
+    space: &Arc<KitsuneSpace>,
+    target: BlockTarget,
+) -> KitsuneResult<bool> {
+    Ok(evt_sender
+        .is_blocked(space.clone(), target)
+        .await
+        .map_err(KitsuneError::other)?)
+}
+
+/// Block an agent or node from a space.
+pub(super) async fn block(
+    evt_sender: &EventSender,
+    space: &Arc<KitsuneSpace>,
+    target: BlockTarget,
+) -> KitsuneResult<()> {
+    evt_sender
+        .block(space.clone(), target)
+        .await
+        .map_err(KitsuneError::other)
+}
+
 /// Get all `AgentInfoSigned` for agents in a space.
 pub(super) async fn query_agent_info(
     evt_sender: &EventSender,
@@ -186,6 +210,23 @@ pub(super) async fn fetch_ops(
         .map_err(KitsuneError::other)
 }
 
+/// Get the size of each op held by these agents.
+pub(super) async fn fetch_op_sizes(
+    evt_sender: &EventSender,
+    space: &Arc<KitsuneSpace>,
+    agents: impl Iterator<Item = &Arc<KitsuneAgent>>,
+    op_hashes: Vec<Arc<KitsuneOpHash>>,
+) -> KitsuneResult<Vec<(Arc<KitsuneOpHash>, u32)>> {
+    evt_sender
+        .fetch_op_sizes(FetchOpSizesEvt {
+            space: space.clone(),
+            agents: agents.cloned().collect(),
+            op_hashes,
+        })
+        .await
+        .map_err(KitsuneError::other)
+}
+
 /// Put new ops into agents that should hold them.
 pub(super) async fn put_ops(
     evt_sender: &EventSender,
@@ -11,6 +11,7 @@ use fixt::prelude::*;
 mod common;
 mod handler_builder;
 mod test_local_sync;
+mod test_regions;
 mod test_two_nodes;
 
 impl ShardedGossipLocal {
@@ -23,6 +24,7 @@ impl ShardedGossipLocal {
         let space = Arc::new(KitsuneSpace::new([0; 36].to_vec()));
         Self {
             gossip_type,
+            op_sync: OpSyncStrategy::Bloom,
             tuning_params: Default::default(),
             space,
             evt_sender,
@@ -49,6 +49,9 @@ async fn standard_responses(
                     .into())
             }
         });
+    evt_handler
+        .expect_handle_is_blocked()
+        .returning(|_, _| Ok(async { Ok(false) }.boxed().into()));
     evt_handler.expect_handle_query_gossip_agents().returning({
         move |_| {
             let agents_with_arcs = agents_with_arcs.clone();
@@ -69,6 +69,9 @@ impl HandlerBuilder {
             }
         });
 
+        self.0
+            .expect_handle_is_blocked()
+            .returning(|_, _| Ok(async { Ok(false) }.boxed().into()));
         self.0.expect_handle_query_gossip_agents().returning({
             move |_| {
                 let agents_arcs = agents_arcs.clone();
@@ -97,7 +100,10 @@ impl HandlerBuilder {
                             Some(
                                 ops.into_iter()
                                     .filter(|(op, time)| {
-                                        window_ms.contains(time) && arcset.contains(op.get_loc())
+                                        // An arc set reaching just short of
+                                        // u32::MAX holds it too.
+                                        window_ms.contains(time)
+                                            && arcset.contains(op.get_loc().min(u32::MAX - 1))
                                     })
                                     .collect::<Vec<_>>(),
                             )
@@ -140,6 +146,22 @@ impl HandlerBuilder {
                 .into())
             });
 
+        self.0
+            .expect_handle_fetch_op_sizes()
+            .returning(|arg: FetchOpSizesEvt| {
+                // The size of the dummy data of each op
+                let FetchOpSizesEvt {
+                    space: _,
+                    agents: _,
+                    op_hashes,
+                } = arg;
+                Ok(async {
+                    Ok(itertools::zip(op_hashes.into_iter(), std::iter::repeat(1)).collect())
+                }
+                .boxed()
+                .into())
+            });
+
         self
     }
 }
@@ -0,0 +1,377 @@
+use kitsune_p2p_types::dht_arc::DhtArc;
+
+use crate::gossip::sharded_gossip::regions::{
+    MAX_HASHES_PER_MSG, MAX_REGIONS_PER_MSG, MAX_REGIONS_PER_ROUND,
+};
+
+use super::common::*;
+use super::handler_builder::HandlerBuilder;
+use super::*;
+
+/// An op hash at this location, unique for each `i`.
+fn op_hash(i: u32, loc: u32) -> KitsuneOpHash {
+    let mut bytes = vec![0; 36];
+    bytes[..4].copy_from_slice(&i.to_le_bytes());
+    bytes[32..].copy_from_slice(&loc.to_le_bytes());
+    KitsuneOpHash(bytes)
+}
+
+/// A thousand ops in the first top level region, all authored two hours ago.
+fn ops() -> Vec<(KitsuneOpHash, TimestampMs)> {
+    let time = (std::time::UNIX_EPOCH.elapsed().unwrap() - Duration::from_secs(2 * 60 * 60))
+        .as_millis() as TimestampMs;
+    (0..1000).map(|i| (op_hash(i, i * 4096), time)).collect()
+}
+
+async fn setup_player(
+    op_sync: OpSyncStrategy,
+    ops: Vec<(KitsuneOpHash, TimestampMs)>,
+) -> ShardedGossipLocal {
+    let agent = agents(1).pop().unwrap();
+    let info = dangerous_fake_agent_info_with_arc(
+        Arc::new(fixt!(KitsuneSpace)),
+        agent.clone(),
+        DhtArc::full(0u32),
+    );
+    let evt_handler = HandlerBuilder::new()
+        .with_agent_persistence(vec![(info, ops)])
+        .with_noop_gossip(vec![])
+        .build();
+    let (evt_sender, _) = spawn_handler(evt_handler).await;
+    let mut gossip = ShardedGossipLocal::test(
+        GossipType::Historical,
+        evt_sender,
+        ShardedGossipLocalState {
+            local_agents: maplit::hashset! { agent },
+            ..Default::default()
+        },
+    );
+    gossip.op_sync = op_sync;
+    gossip
+}
+
+async fn setup_region_player(ops: Vec<(KitsuneOpHash, TimestampMs)>) -> ShardedGossipLocal {
+    setup_player(OpSyncStrategy::Regions, ops).await
+}
+
+async fn setup_bloom_player(ops: Vec<(KitsuneOpHash, TimestampMs)>) -> ShardedGossipLocal {
+    setup_player(OpSyncStrategy::Bloom, ops).await
+}
+
+/// The top level region holding all of [`ops`].
+fn ops_region(player: &ShardedGossipLocal) -> RegionCoords {
+    RegionCoords {
+        space_start: 0,
+        space_power: 28,
+        time: player.calculate_time_ranges()[0].clone(),
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct RoundStats {
+    ops_to_alice: usize,
+    ops_to_bob: usize,
+    region_hashes: usize,
+}
+
+/// Start a round with this peer and send the top level regions
+/// or the blooms, depending on how the player syncs ops.
+async fn start_round(player: &ShardedGossipLocal, peer_cert: Tx2Cert) -> Vec<ShardedGossipWire> {
+    let local_agents = player
+        .inner
+        .share_mut(|i, _| Ok(i.local_agents.clone()))
+        .unwrap();
+    let mut gossip = Vec::new();
+    let state = player
+        .generate_blooms(
+            local_agents
+                .into_iter()
+                .map(|a| (a, ArcInterval::Full))
+                .collect(),
+            vec![ArcInterval::Full],
+            &mut gossip,
+        )
+        .await
+        .unwrap();
+    player
+        .inner
+        .share_mut(|i, _| {
+            i.round_map.insert(peer_cert, state);
+            Ok(())
+        })
+        .unwrap();
+    gossip
+}
+
+/// Start a round from both sides and pass the gossip back and
+/// forth until neither side has anything left to send.
+async fn run_round(alice: &ShardedGossipLocal, bob: &ShardedGossipLocal) -> RoundStats {
+    let mut u = arbitrary::Unstructured::new(&NOISE);
+    let alice_cert = Tx2Cert::arbitrary(&mut u).unwrap();
+    let bob_cert = Tx2Cert::arbitrary(&mut u).unwrap();
+
+    let mut to_bob = start_round(alice, bob_cert.clone()).await;
+    let mut to_alice = start_round(bob, alice_cert.clone()).await;
+
+    let mut stats = RoundStats::default();
+    let mut count = |msg: &ShardedGossipWire, ops_to: &mut usize| match msg {
+        ShardedGossipWire::MissingOps(MissingOps { ops, .. }) => *ops_to += ops.len(),
+        ShardedGossipWire::RegionHashes(_) => stats.region_hashes += 1,
+        _ => (),
+    };
+    let mut ops_to_alice = 0;
+    let mut ops_to_bob = 0;
+    while !(to_alice.is_empty() && to_bob.is_empty()) {
+        for msg in std::mem::take(&mut to_alice) {
+            count(&msg, &mut ops_to_alice);
+            to_bob.extend(alice.process_incoming(bob_cert.clone(), msg).await.unwrap());
+        }
+        for msg in std::mem::take(&mut to_bob) {
+            count(&msg, &mut ops_to_bob);
+            to_alice.extend(bob.process_incoming(alice_cert.clone(), msg).await.unwrap());
+        }
+    }
+
+    // - Both sides have finished the round.
+    for player in [alice, bob] {
+        player
+            .inner
+            .share_mut(|i, _| {
+                assert_eq!(i.round_map.current_rounds().len(), 0);
+                Ok(())
+            })
+            .unwrap();
+    }
+    stats.ops_to_alice = ops_to_alice;
+    stats.ops_to_bob = ops_to_bob;
+    stats
+}
+
+#[test]
+/// Children cover the space and time of their region exactly once.
+fn region_children_cover_region() {
+    let region = RegionCoords {
+        space_start: 1 << 28,
+        space_power: 28,
+        time: 10..21,
+    };
+    let children = region.children();
+    assert_eq!(children.len(), 8);
+    for loc in [1 << 28, (1 << 28) + 12345, (2 << 28) - 1] {
+        for time in [10, 15, 20] {
+            let holders = children
+                .iter()
+                .filter(|c| c.contains(loc) && c.time.contains(&time))
+                .count();
+            assert_eq!(holders, 1);
+        }
+    }
+    assert!(!children.iter().any(|c| c.contains(2 << 28)));
+
+    // - A single location in a single moment can't be split.
+    let region = RegionCoords {
+        space_start: 7,
+        space_power: 0,
+        time: 10..11,
+    };
+    assert!(region.children().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+/// Nodes holding the same ops finish after comparing the top level regions.
+async fn synced_regions_send_no_hashes() {
+    let alice = setup_region_player(ops()).await;
+    let bob = setup_region_player(ops()).await;
+
+    assert_eq!(run_round(&alice, &bob).await, RoundStats::default());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+/// Regions holding too many ops are split until only the
+/// missing ops are sent.
+async fn differing_regions_sync_missing_ops() {
+    let alice = setup_region_player(ops()).await;
+    let bob = setup_region_player(
+        ops()
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| i % 100 != 0)
+            .map(|(_, op)| op)
+            .collect(),
+    )
+    .await;
+
+    let stats = run_round(&alice, &bob).await;
+    assert_eq!(stats.ops_to_alice, 0);
+    assert_eq!(stats.ops_to_bob, 10);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+/// Incoming regions past the limit of a message
+/// or of a round are left unanswered.
+async fn incoming_regions_are_capped() {
+    let bob = setup_region_player(ops()).await;
+    let mut u = arbitrary::Unstructured::new(&NOISE);
+    let alice_cert = Tx2Cert::arbitrary(&mut u).unwrap();
+    start_round(&bob, alice_cert.clone()).await;
+
+    // - Alice claims to hold nothing so bob's fingerprint of every region differs.
+    let region = Region {
+        coords: ops_region(&bob),
+        data: RegionData::default(),
+    };
+    let num_diffs = |gossip: Vec<ShardedGossipWire>| match &gossip[..] {
+        [ShardedGossipWire::RegionDiffs(RegionDiffs { regions })] => regions.len(),
+        _ => panic!("Expected a single region diffs message: {:?}", gossip),
+    };
+    let send = |n| {
+        bob.process_incoming(
+            alice_cert.clone(),
+            ShardedGossipWire::op_regions(vec![region.clone(); n], false),
+        )
+    };
+
+    // - Only the regions that fit in a message are answered.
+    assert_eq!(
+        num_diffs(send(MAX_REGIONS_PER_MSG + 1).await.unwrap()),
+        MAX_REGIONS_PER_MSG
+    );
+    for _ in 1..MAX_REGIONS_PER_ROUND / MAX_REGIONS_PER_MSG {
+        assert_eq!(
+            num_diffs(send(MAX_REGIONS_PER_MSG).await.unwrap()),
+            MAX_REGIONS_PER_MSG
+        );
+    }
+    // - Once the round's limit is used up nothing more is answered.
+    assert_eq!(num_diffs(send(1).await.unwrap()), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+/// Regions past the limit of hashes in a message are left unanswered.
+async fn incoming_hashes_are_capped() {
+    let bob = setup_region_player(ops()).await;
+    let mut u = arbitrary::Unstructured::new(&NOISE);
+    let alice_cert = Tx2Cert::arbitrary(&mut u).unwrap();
+    start_round(&bob, alice_cert.clone()).await;
+
+    // - More hashes than fit in a message, of ops neither side holds.
+    let empty = RegionCoords {
+        space_start: 1 << 28,
+        ..ops_region(&bob)
+    };
+    let unknown = (0..=MAX_HASHES_PER_MSG as u32)
+        .map(|i| Arc::new(op_hash(i, 1 << 28)))
+        .collect();
+    let num_ops = |gossip: Vec<ShardedGossipWire>| {
+        gossip
+            .iter()
+            .map(|msg| match msg {
+                ShardedGossipWire::MissingOps(MissingOps { ops, .. }) => ops.len(),
+                _ => panic!("Expected missing ops: {:?}", msg),
+            })
+            .sum::<usize>()
+    };
+
+    // - Alice holds none of the ops in bob's region so he sends them all.
+    let gossip = bob
+        .process_incoming(
+            alice_cert.clone(),
+            ShardedGossipWire::region_hashes(vec![(ops_region(&bob), vec![])]),
+        )
+        .await
+        .unwrap();
+    assert_eq!(num_ops(gossip), 1000);
+
+    // - Unless the region comes after too many hashes.
+    let gossip = bob
+        .process_incoming(
+            alice_cert.clone(),
+            ShardedGossipWire::region_hashes(vec![(empty, unknown), (ops_region(&bob), vec![])]),
+        )
+        .await
+        .unwrap();
+    assert_eq!(num_ops(gossip), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+/// An op at the very last location is synced like any other.
+async fn last_location_is_synced() {
+    let mut alice_ops = ops();
+    alice_ops.push((op_hash(1000, u32::MAX), alice_ops[0].1));
+    let alice = setup_region_player(alice_ops).await;
+    let bob = setup_region_player(ops()).await;
+
+    let stats = run_round(&alice, &bob).await;
+    assert_eq!(stats.ops_to_alice, 0);
+    assert_eq!(stats.ops_to_bob, 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+/// Region diffs are only answered for regions we sent and haven't
+/// had answered yet.
+async fn unrequested_region_diffs_are_ignored() {
+    let bob = setup_region_player(ops()).await;
+    let mut u = arbitrary::Unstructured::new(&NOISE);
+    let alice_cert = Tx2Cert::arbitrary(&mut u).unwrap();
+    start_round(&bob, alice_cert.clone()).await;
+
+    let known = Region {
+        coords: ops_region(&bob),
+        data: RegionData::default(),
+    };
+    // - Bob never sends regions this small at the start of a round.
+    let unknown = Region {
+        coords: RegionCoords {
+            space_power: 26,
+            ..ops_region(&bob)
+        },
+        data: RegionData::default(),
+    };
+    let send = |regions| {
+        bob.process_incoming(alice_cert.clone(), ShardedGossipWire::region_diffs(regions))
+    };
+
+    assert!(send(vec![unknown.clone()]).await.unwrap().is_empty());
+    // - One unknown region is enough to ignore the whole message.
+    assert!(send(vec![known.clone(), unknown]).await.unwrap().is_empty());
+
+    // - Alice holds nothing in the known region so bob splits it.
+    match &send(vec![known.clone()]).await.unwrap()[..] {
+        [ShardedGossipWire::OpRegions(OpRegions { regions, finished })] => {
+            assert_eq!(regions.len(), 8);
+            assert!(!finished);
+        }
+        gossip => panic!("Expected a single op regions message: {:?}", gossip),
+    }
+
+    // - A region is only answered once.
+    assert!(send(vec![known]).await.unwrap().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+/// A node syncing by region and a node syncing by bloom
+/// can sync with each other.
+async fn bloom_and_region_nodes_sync() {
+    let alice = setup_region_player(
+        ops()
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| i % 100 != 0)
+            .map(|(_, op)| op)
+            .collect(),
+    )
+    .await;
+    let bob = setup_bloom_player(ops()).await;
+
+    let stats = run_round(&alice, &bob).await;
+    assert_eq!(stats.ops_to_alice, 10);
+    assert_eq!(stats.ops_to_bob, 0);
+
+    // - A bloom node holding nothing asks for everything.
+    let alice = setup_bloom_player(vec![]).await;
+    let bob = setup_region_player(ops()).await;
+
+    let stats = run_round(&alice, &bob).await;
+    assert_eq!(stats.ops_to_alice, 1000);
+    assert_eq!(stats.ops_to_bob, 0);
+}
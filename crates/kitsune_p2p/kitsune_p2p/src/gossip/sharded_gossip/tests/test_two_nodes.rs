@@ -92,6 +92,9 @@ async fn sharded_sanity_test() {
             // Assert alice has no current rounds as alice
             // has now finished this round of gossip.
             assert_eq!(i.round_map.current_rounds().len(), 0);
+            // Assert alice will record the round as complete.
+            assert_eq!(i.finished_rounds.len(), 1);
+            assert_eq!(i.finished_rounds[0].1, MetricKind::GossipRoundComplete);
             Ok(())
         })
         .unwrap();
@@ -115,6 +118,9 @@ async fn sharded_sanity_test() {
             // Assert bob has no current rounds as alice
             // has now finished this round of gossip.
             assert_eq!(i.round_map.current_rounds().len(), 0);
+            // Assert bob will record the round as complete.
+            assert_eq!(i.finished_rounds.len(), 1);
+            assert_eq!(i.finished_rounds[0].1, MetricKind::GossipRoundComplete);
             Ok(())
         })
         .unwrap();
@@ -137,6 +143,9 @@ async fn partial_missing_doesnt_finish() {
                 received_all_incoming_ops_blooms: true,
                 created_at: std::time::Instant::now(),
                 round_timeout: u32::MAX,
+                num_regions_received: 0,
+                num_hashes_received: 0,
+                regions_requested: HashSet::new(),
             }
         }
         .into(),
@@ -179,6 +188,9 @@ async fn missing_ops_finishes() {
                 received_all_incoming_ops_blooms: true,
                 created_at: std::time::Instant::now(),
                 round_timeout: u32::MAX,
+                num_regions_received: 0,
+                num_hashes_received: 0,
+                regions_requested: HashSet::new(),
             }
         }
         .into(),
@@ -222,6 +234,9 @@ async fn missing_ops_doesnt_finish_awaiting_bloom_responses() {
                 received_all_incoming_ops_blooms: false,
                 created_at: std::time::Instant::now(),
                 round_timeout: u32::MAX,
+                num_regions_received: 0,
+                num_hashes_received: 0,
+                regions_requested: HashSet::new(),
             }
         }
         .into(),
@@ -265,6 +280,9 @@ async fn bloom_response_finishes() {
                 received_all_incoming_ops_blooms: false,
                 created_at: std::time::Instant::now(),
                 round_timeout: u32::MAX,
+                num_regions_received: 0,
+                num_hashes_received: 0,
+                regions_requested: HashSet::new(),
             }
         }
         .into(),
@@ -308,6 +326,9 @@ async fn bloom_response_doesnt_finish_outstanding_incoming() {
                 received_all_incoming_ops_blooms: false,
                 created_at: std::time::Instant::now(),
                 round_timeout: u32::MAX,
+                num_regions_received: 0,
+                num_hashes_received: 0,
+                regions_requested: HashSet::new(),
             }
         }
         .into(),
@@ -354,6 +375,9 @@ async fn no_data_still_finishes() {
                 received_all_incoming_ops_blooms: false,
                 created_at: std::time::Instant::now(),
                 round_timeout: u32::MAX,
+                num_regions_received: 0,
+                num_hashes_received: 0,
+                regions_requested: HashSet::new(),
             }
         }
         .into(),
@@ -371,6 +395,9 @@ async fn no_data_still_finishes() {
                 received_all_incoming_ops_blooms: true,
                 created_at: std::time::Instant::now(),
                 round_timeout: u32::MAX,
+                num_regions_received: 0,
+                num_hashes_received: 0,
+                regions_requested: HashSet::new(),
             }
         }
         .into(),
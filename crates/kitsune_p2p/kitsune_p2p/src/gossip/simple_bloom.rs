@@ -296,9 +296,12 @@ impl SimpleBloomMod {
             .clone();
         let last_touch = match self
             .evt_sender
-            .query_metrics(MetricQuery::LastSync {
-                agent: arbitrary_agent,
-            })
+            .query_metrics(
+                self.space.clone(),
+                MetricQuery::LastSync {
+                    agent: arbitrary_agent,
+                },
+            )
             .await?
         {
             MetricQueryAnswer::LastSync(time) => time,
@@ -317,22 +320,18 @@ impl SimpleBloomMod {
         agents: Vec<Arc<KitsuneAgent>>,
         info: NodeInfo,
     ) -> KitsuneP2pResult<()> {
-        if info.was_err {
-            for agent in agents {
-                self.evt_sender
-                    .put_metric_datum(MetricDatum {
-                        agent,
-                        kind: MetricKind::ConnectError,
-                        timestamp: info.last_touch,
-                    })
-                    .await?;
-            }
+        let kinds = if info.was_err {
+            [MetricKind::ConnectError, MetricKind::GossipRoundFailed]
         } else {
-            for agent in agents {
+            [MetricKind::QuickGossip, MetricKind::GossipRoundComplete]
+        };
+        for agent in agents {
+            for kind in kinds.iter() {
                 self.evt_sender
                     .put_metric_datum(MetricDatum {
-                        agent,
-                        kind: MetricKind::QuickGossip,
+                        space: self.space.clone(),
+                        agent: agent.clone(),
+                        kind: kind.clone(),
                         timestamp: info.last_touch,
                     })
                     .await?;
@@ -96,6 +96,7 @@ pub(crate) async fn step_4_com_loop_inner_outgoing(
     gossip: GossipWire,
 ) -> KitsuneResult<()> {
     let gossip = gossip.encode_vec().map_err(KitsuneError::other)?;
+    let bytes = gossip.len();
     let gossip = wire::Wire::gossip(space, gossip.into(), GossipModuleType::Simple);
 
     let t = tuning_params.implicit_timeout();
@@ -112,6 +113,7 @@ pub(crate) async fn step_4_com_loop_inner_outgoing(
         HowToConnect::Url(url) => ep_hnd.get_connection(url, t).await?,
     };
     con.notify(&gossip, t).await?;
+    metric_push_gossip_bytes_sent(bytes as u64);
 
     Ok(())
 }
@@ -4,6 +4,12 @@ use crate::event::*;
 mod actor;
 use actor::*;
 
+/// Clients for the bootstrap service, and the types of its queries.
+pub mod bootstrap {
+    pub use super::actor::bootstrap::{near_basis, put, random, stats};
+    pub use kitsune_p2p_types::bootstrap::*;
+}
+
 #[cfg(test)]
 pub use actor::MockKitsuneP2pEventHandler;
 
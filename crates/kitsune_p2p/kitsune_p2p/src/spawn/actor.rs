@@ -13,6 +13,7 @@ use kitsune_p2p_proxy::tx2::*;
 use kitsune_p2p_proxy::ProxyUrl;
 use kitsune_p2p_transport_quic::tx2::*;
 use kitsune_p2p_types::async_lazy::AsyncLazy;
+use kitsune_p2p_types::metrics::metric_push_gossip_bytes_received;
 use kitsune_p2p_types::tx2::tx2_api::*;
 use kitsune_p2p_types::tx2::tx2_pool_promote::*;
 use kitsune_p2p_types::tx2::tx2_utils::TxUrl;
@@ -24,7 +25,7 @@ use std::sync::Arc;
 
 /// The bootstrap service is much more thoroughly documented in the default service implementation.
 /// See https://github.com/holochain/bootstrap
-mod bootstrap;
+pub(crate) mod bootstrap;
 mod discover;
 mod space;
 use ghost_actor::dependencies::tracing;
@@ -105,6 +106,19 @@ impl KitsuneP2pActor {
                     "none:".into(),
                 )
             }
+            #[cfg(feature = "test_utils")]
+            KitsuneP2pTx2Backend::Sim { network } => {
+                let net = SimNet::get(&network).ok_or_else(|| {
+                    KitsuneP2pError::other(format!("no simulated network named {}", network))
+                })?;
+                let mut conf = MemConfig::default();
+                conf.tls = Some(tls_config.clone());
+                conf.tuning_params = Some(config.tuning_params.clone());
+                let sub = tx2_mem_adapter(conf)
+                    .await
+                    .map_err(KitsuneP2pError::other)?;
+                (tx2_sim_adapter(sub, net), "none:".into())
+            }
             KitsuneP2pTx2Backend::Quic { bind_to } => {
                 let mut conf = QuicConfig::default();
                 conf.tls = Some(tls_config.clone());
@@ -116,6 +130,17 @@ impl KitsuneP2pActor {
                     bind_to,
                 )
             }
+            KitsuneP2pTx2Backend::Tcp { bind_to } => {
+                let mut conf = TcpConfig::default();
+                conf.tls = Some(tls_config.clone());
+                conf.tuning_params = Some(config.tuning_params.clone());
+                (
+                    tx2_tcp_adapter(conf)
+                        .await
+                        .map_err(KitsuneP2pError::other)?,
+                    bind_to,
+                )
+            }
         };
 
         // convert to frontend
@@ -223,7 +248,14 @@ impl KitsuneP2pActor {
                         use tx2_api::Tx2EpEvent::*;
                         #[allow(clippy::single_match)]
                         match event {
-                            IncomingRequest(Tx2EpIncomingRequest { data, respond, .. }) => {
+                            IncomingRequest(Tx2EpIncomingRequest {
+                                con, data, respond, ..
+                            }) => {
+                                if is_blocked(evt_sender, &data, con.peer_cert()).await {
+                                    let fail = wire::Wire::failure("blocked".into());
+                                    resp!(respond, fail);
+                                    return;
+                                }
                                 match data {
                                     wire::Wire::Call(wire::Call {
                                         space,
@@ -289,6 +321,13 @@ impl KitsuneP2pActor {
                                 }
                             }
                             IncomingNotify(Tx2EpIncomingNotify { con, data, url, .. }) => {
+                                if is_blocked(evt_sender, &data, con.peer_cert()).await {
+                                    tracing::debug!(
+                                        "dropping notify from blocked node {:?}",
+                                        con.peer_cert()
+                                    );
+                                    return;
+                                }
                                 match data {
                                     wire::Wire::DelegateBroadcast(wire::DelegateBroadcast {
                                         space,
@@ -305,10 +344,21 @@ impl KitsuneP2pActor {
                                         // handler.
                                         if let Err(err) = i_s
                                             .incoming_delegate_broadcast(
-                                                space, basis, to_agent, mod_idx, mod_cnt, data,
+                                                space.clone(),
+                                                basis,
+                                                to_agent,
+                                                mod_idx,
+                                                mod_cnt,
+                                                data,
                                             )
                                             .await
                                         {
+                                            // The node delegating a broadcast is the one
+                                            // publishing the data, so it forged it.
+                                            if let KitsuneP2pError::Counterfeit(_) = err {
+                                                block_node(evt_sender, space, con.peer_cert())
+                                                    .await;
+                                            }
                                             tracing::warn!(
                                                 ?err,
                                                 "failed to handle incoming delegate broadcast"
@@ -321,6 +371,8 @@ impl KitsuneP2pActor {
                                         data,
                                         ..
                                     }) => {
+                                        // Counterfeit data here doesn't block the node, which
+                                        // may only be relaying a delegated broadcast.
                                         if let Err(err) = evt_sender
                                             .notify(space, to_agent.clone(), to_agent, data.into())
                                             .await
@@ -337,6 +389,7 @@ impl KitsuneP2pActor {
                                         module,
                                     }) => {
                                         let data: Vec<u8> = data.into();
+                                        metric_push_gossip_bytes_received(data.len() as u64);
                                         let data: Box<[u8]> = data.into_boxed_slice();
                                         if let Err(e) =
                                             i_s.incoming_gossip(space, con, url, data, module).await
@@ -468,6 +521,54 @@ impl InternalHandler for KitsuneP2pActor {
     }
 }
 
+/// Check an incoming message against the block list of its space.
+/// The sending node is checked, along with the sending agent
+/// if the message names one.
+/// If the host can't answer we let the message through rather than
+/// cutting off every peer on a transient error.
+async fn is_blocked(
+    evt_sender: &futures::channel::mpsc::Sender<KitsuneP2pEvent>,
+    data: &wire::Wire,
+    cert: Tx2Cert,
+) -> bool {
+    let (space, agent) = match data {
+        wire::Wire::Call(wire::Call {
+            space, from_agent, ..
+        }) => (space, Some(from_agent.clone())),
+        wire::Wire::DelegateBroadcast(wire::DelegateBroadcast { space, .. })
+        | wire::Wire::Broadcast(wire::Broadcast { space, .. })
+        | wire::Wire::Gossip(wire::Gossip { space, .. })
+        | wire::Wire::PeerGet(wire::PeerGet { space, .. })
+        | wire::Wire::PeerQuery(wire::PeerQuery { space, .. }) => (space, None),
+        // Every other message is a response to one of our own requests.
+        _ => return false,
+    };
+    let targets = agent
+        .map(BlockTarget::Agent)
+        .into_iter()
+        .chain(std::iter::once(BlockTarget::Node(cert)));
+    for target in targets {
+        match evt_sender.is_blocked(space.clone(), target).await {
+            Ok(true) => return true,
+            Ok(false) => (),
+            Err(err) => tracing::warn!(?err, "failed to check block list"),
+        }
+    }
+    false
+}
+
+/// Block a node from a space for sending us counterfeit data.
+async fn block_node(
+    evt_sender: &futures::channel::mpsc::Sender<KitsuneP2pEvent>,
+    space: KSpace,
+    cert: Tx2Cert,
+) {
+    tracing::warn!(?cert, "blocking node which sent counterfeit data");
+    if let Err(err) = evt_sender.block(space, BlockTarget::Node(cert)).await {
+        tracing::warn!(?err, "failed to block node");
+    }
+}
+
 impl ghost_actor::GhostHandler<KitsuneP2pEvent> for KitsuneP2pActor {}
 
 impl KitsuneP2pEventHandler for KitsuneP2pActor {
@@ -529,9 +630,26 @@ impl KitsuneP2pEventHandler for KitsuneP2pActor {
 
     fn handle_query_metrics(
         &mut self,
+        space: Arc<KitsuneSpace>,
         query: MetricQuery,
     ) -> KitsuneP2pEventHandlerResult<MetricQueryAnswer> {
-        Ok(self.evt_sender.query_metrics(query))
+        Ok(self.evt_sender.query_metrics(space, query))
+    }
+
+    fn handle_is_blocked(
+        &mut self,
+        space: Arc<KitsuneSpace>,
+        target: BlockTarget,
+    ) -> KitsuneP2pEventHandlerResult<bool> {
+        Ok(self.evt_sender.is_blocked(space, target))
+    }
+
+    fn handle_block(
+        &mut self,
+        space: Arc<KitsuneSpace>,
+        target: BlockTarget,
+    ) -> KitsuneP2pEventHandlerResult<()> {
+        Ok(self.evt_sender.block(space, target))
     }
 
     fn handle_call(
@@ -570,6 +688,13 @@ impl KitsuneP2pEventHandler for KitsuneP2pActor {
         Ok(self.evt_sender.fetch_op_data(input))
     }
 
+    fn handle_fetch_op_sizes(
+        &mut self,
+        input: FetchOpSizesEvt,
+    ) -> KitsuneP2pEventHandlerResult<Vec<(Arc<KitsuneOpHash>, u32)>> {
+        Ok(self.evt_sender.fetch_op_sizes(input))
+    }
+
     fn handle_query_op_hashes(
         &mut self,
         input: QueryOpHashesEvt,
@@ -810,6 +935,7 @@ mockall::mock! {
 
         fn handle_query_metrics(
             &mut self,
+            space: Arc<KitsuneSpace>,
             query: MetricQuery,
         ) -> KitsuneP2pEventHandlerResult<MetricQueryAnswer>;
 
@@ -819,6 +945,18 @@ mockall::mock! {
             dht_arc: kitsune_p2p_types::dht_arc::DhtArc,
         ) -> KitsuneP2pEventHandlerResult<kitsune_p2p_types::dht_arc::PeerDensity>;
 
+        fn handle_is_blocked(
+            &mut self,
+            space: Arc<KitsuneSpace>,
+            target: BlockTarget,
+        ) -> KitsuneP2pEventHandlerResult<bool>;
+
+        fn handle_block(
+            &mut self,
+            space: Arc<KitsuneSpace>,
+            target: BlockTarget,
+        ) -> KitsuneP2pEventHandlerResult<()>;
+
         fn handle_call(
             &mut self,
             space: Arc<KitsuneSpace>,
@@ -852,6 +990,11 @@ mockall::mock! {
             input: FetchOpDataEvt,
         ) -> KitsuneP2pEventHandlerResult<Vec<(Arc<KitsuneOpHash>, Vec<u8>)>> ;
 
+        fn handle_fetch_op_sizes(
+            &mut self,
+            input: FetchOpSizesEvt,
+        ) -> KitsuneP2pEventHandlerResult<Vec<(Arc<KitsuneOpHash>, u32)>>;
+
         fn handle_sign_network_data(
             &mut self,
             input: SignNetworkDataEvt,
@@ -1,9 +1,13 @@
 use crate::types::agent_store::AgentInfoSigned;
+use crate::types::KitsuneSpace;
+use kitsune_p2p_types::bootstrap::NearBasisQuery;
 use kitsune_p2p_types::bootstrap::RandomQuery;
+use kitsune_p2p_types::bootstrap::SpaceStats;
 use once_cell::sync::Lazy;
 use once_cell::sync::OnceCell;
 use std::convert::TryFrom;
 use std::convert::TryInto;
+use std::sync::Arc;
 use url2::Url2;
 
 /// Reuse a single reqwest Client for efficiency as we likely need several connections.
@@ -24,6 +28,10 @@ const OP_PUT: &str = "put";
 const OP_NOW: &str = "now";
 /// The header op to tell the service to return a random set of agents in a specific space.
 const OP_RANDOM: &str = "random";
+/// The header op to tell the service to return a random set of agents whose arcs cover a basis.
+const OP_NEAR_BASIS: &str = "near_basis";
+/// The header op to tell the service to return statistics about a space.
+const OP_STATS: &str = "stats";
 
 /// Standard interface to the remote bootstrap service.
 ///
@@ -144,7 +152,6 @@ pub async fn now_once(url: Option<Url2>) -> crate::types::actor::KitsuneP2pResul
 /// The ordering is random, the return is not sorted.
 /// Randomness is determined by the bootstrap service, it is one of the important roles of the
 /// service to mitigate eclipse attacks by having a strong randomness implementation.
-#[allow(dead_code)]
 pub async fn random(
     url: Option<Url2>,
     query: RandomQuery,
@@ -154,6 +161,41 @@ pub async fn random(
         Ok(None) => Vec::new(),
         Err(e) => return Err(e),
     };
+    decode_infos(outer_vec)
+}
+
+/// `do_api` wrapper around the `near_basis` op.
+///
+/// Fetches up to `limit` agent infos randomly from the `space`, out of those whose storage arcs
+/// cover the `basis`. A node joining a big space can use this to find the agents it will gossip
+/// with much sooner than by sampling the whole space.
+pub async fn near_basis(
+    url: Option<Url2>,
+    query: NearBasisQuery,
+) -> crate::types::actor::KitsuneP2pResult<Vec<AgentInfoSigned>> {
+    let outer_vec: Vec<serde_bytes::ByteBuf> = match do_api(url, OP_NEAR_BASIS, query).await {
+        Ok(Some(v)) => v,
+        Ok(None) => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    decode_infos(outer_vec)
+}
+
+/// `do_api` wrapper around the `stats` op.
+///
+/// The number of agents listed in the `space` and an estimate of its coverage,
+/// or None if there is no bootstrap service.
+pub async fn stats(
+    url: Option<Url2>,
+    space: Arc<KitsuneSpace>,
+) -> crate::types::actor::KitsuneP2pResult<Option<SpaceStats>> {
+    do_api(url, OP_STATS, space).await
+}
+
+/// Decode the agent infos the service returns as a list of messagepack encoded byte arrays.
+fn decode_infos(
+    outer_vec: Vec<serde_bytes::ByteBuf>,
+) -> crate::types::actor::KitsuneP2pResult<Vec<AgentInfoSigned>> {
     let ret: Result<Vec<AgentInfoSigned>, _> = outer_vec
         .into_iter()
         .map(|bytes| kitsune_p2p_types::codec::rmp_decode(&mut AsRef::<[u8]>::as_ref(&bytes)))
@@ -1,5 +1,6 @@
 use super::*;
-use crate::types::gossip::GossipModule;
+use crate::gossip::sharded_gossip::OpSyncStrategy;
+use crate::types::gossip::{GossipModule, GossipModuleFactory};
 use ghost_actor::dependencies::tracing;
 use kitsune_p2p_mdns::*;
 use kitsune_p2p_types::agent_info::AgentInfoSigned;
@@ -249,9 +250,11 @@ impl SpaceInternalHandler for Space {
                 data.clone().into(),
             );
             local_events.push(async move {
-                if let Err(err) = fut.await {
+                let res = fut.await;
+                if let Err(err) = &res {
                     tracing::warn!(?err, "failed local broadcast");
                 }
+                res
             });
         }
 
@@ -263,7 +266,12 @@ impl SpaceInternalHandler for Space {
             discover::get_cached_remotes_near_basis(ro_inner.clone(), basis.get_loc(), timeout);
 
         Ok(async move {
-            futures::future::join_all(local_events).await;
+            // don't pass on data one of our agents found to be counterfeit.
+            for res in futures::future::join_all(local_events).await {
+                if let Err(err @ KitsuneP2pError::Counterfeit(_)) = res {
+                    return Err(err);
+                }
+            }
 
             let info_list = fut.await?;
 
@@ -282,21 +290,24 @@ impl SpaceInternalHandler for Space {
                     use discover::PeerDiscoverResult;
 
                     // attempt to establish a connection
-                    let con_hnd = match discover::peer_connect(ro_inner, &info, timeout).await {
-                        PeerDiscoverResult::OkShortcut => return,
-                        PeerDiscoverResult::OkRemote { con_hnd, .. } => con_hnd,
-                        PeerDiscoverResult::Err(err) => {
-                            tracing::warn!(?err, "broadcast error");
-                            return;
-                        }
-                    };
+                    let con_hnd =
+                        match discover::peer_connect(ro_inner.clone(), &info, timeout).await {
+                            PeerDiscoverResult::OkShortcut => return,
+                            PeerDiscoverResult::OkRemote { con_hnd, .. } => con_hnd,
+                            PeerDiscoverResult::Err(err) => {
+                                tracing::warn!(?err, "broadcast error");
+                                return;
+                            }
+                        };
 
                     // generate our broadcast payload
+                    let bytes = data.len();
                     let payload = wire::Wire::broadcast(space, info.agent.clone(), data);
 
                     // forward the data
-                    if let Err(err) = con_hnd.notify(&payload, timeout).await {
-                        tracing::warn!(?err, "broadcast error");
+                    match con_hnd.notify(&payload, timeout).await {
+                        Ok(()) => ro_inner.record_publish_metrics(info.agent.clone(), bytes),
+                        Err(err) => tracing::warn!(?err, "broadcast error"),
                     }
                 })
             }
@@ -441,6 +452,34 @@ async fn update_single_agent_info(
     Ok(agent_info_signed)
 }
 
+/// Store the peers a bootstrap query returned, leaving out our own agents.
+async fn put_bootstrap_peers(
+    i_s: &ghost_actor::GhostSender<SpaceInternal>,
+    evt_sender: &futures::channel::mpsc::Sender<KitsuneP2pEvent>,
+    space: Arc<KitsuneSpace>,
+    list: Vec<AgentInfoSigned>,
+) {
+    let mut peer_data = Vec::with_capacity(list.len());
+    for item in list {
+        // TODO - someday some validation here
+        match i_s.is_agent_local(item.agent.clone()).await {
+            Err(err) => tracing::error!(?err),
+            Ok(is_local) => {
+                if !is_local {
+                    // we got a result - let's add it to our store for the future
+                    peer_data.push(item);
+                }
+            }
+        }
+    }
+    if let Err(err) = evt_sender
+        .put_agent_info_signed(PutAgentInfoSignedEvt { space, peer_data })
+        .await
+    {
+        tracing::error!(?err, "error storing bootstrap agent_info");
+    }
+}
+
 use ghost_actor::dependencies::must_future::MustBoxFuture;
 impl ghost_actor::GhostControlHandler for Space {
     fn handle_ghost_actor_shutdown(mut self) -> MustBoxFuture<'static, ()> {
@@ -521,6 +560,23 @@ impl KitsuneP2pHandler for Space {
             NetworkType::QuicBootstrap => {
                 // quic bootstrap is managed for the whole space
                 // see the Space::new() constructor
+                // but a joining agent first asks for the peers it will
+                // gossip with, those whose arcs cover its location
+                let i_s = self.i_s.clone();
+                let bootstrap_service = self.config.bootstrap_service.clone();
+                let query = kitsune_p2p_types::bootstrap::NearBasisQuery {
+                    space: space.clone(),
+                    basis: agent.get_loc().into(),
+                    limit: Default::default(),
+                };
+                tokio::task::spawn(async move {
+                    match super::bootstrap::near_basis(bootstrap_service, query).await {
+                        Err(e) => {
+                            tracing::error!(msg = "Failed to get near peers from bootstrap", ?e);
+                        }
+                        Ok(list) => put_bootstrap_peers(&i_s, &evt_sender, space, list).await,
+                    }
+                });
             }
         }
 
@@ -549,6 +605,7 @@ impl KitsuneP2pHandler for Space {
         timeout_ms: Option<u64>,
     ) -> KitsuneP2pHandlerResult<Vec<u8>> {
         let evt_sender = self.evt_sender.clone();
+        let ro_inner = self.ro_inner.clone();
 
         let timeout_ms = match timeout_ms {
             None | Some(0) => self.config.tuning_params.default_rpc_single_timeout_ms as u64,
@@ -569,16 +626,27 @@ impl KitsuneP2pHandler for Space {
                     evt_sender.call(space, to_agent, from_agent, payload).await
                 }
                 discover::PeerDiscoverResult::OkRemote { con_hnd, .. } => {
+                    let bytes_sent = payload.len();
                     let payload = wire::Wire::call(
                         space.clone(),
                         from_agent.clone(),
                         to_agent.clone(),
                         payload.into(),
                     );
+                    let started = std::time::Instant::now();
                     let res = con_hnd.request(&payload, timeout).await?;
                     match res {
                         wire::Wire::Failure(wire::Failure { reason }) => Err(reason.into()),
-                        wire::Wire::CallResp(wire::CallResp { data }) => Ok(data.into()),
+                        wire::Wire::CallResp(wire::CallResp { data }) => {
+                            let data: Vec<u8> = data.into();
+                            ro_inner.record_request_metrics(
+                                to_agent,
+                                bytes_sent,
+                                data.len(),
+                                started,
+                            );
+                            Ok(data)
+                        }
                         r => Err(format!("invalid response: {:?}", r).into()),
                     }
                 }
@@ -618,9 +686,11 @@ impl KitsuneP2pHandler for Space {
                 payload.clone(),
             );
             local_events.push(async move {
-                if let Err(err) = fut.await {
+                let res = fut.await;
+                if let Err(err) = &res {
                     tracing::warn!(?err, "failed local broadcast");
                 }
+                res
             });
         }
 
@@ -630,7 +700,12 @@ impl KitsuneP2pHandler for Space {
         let discover_fut =
             discover::search_remotes_covering_basis(ro_inner.clone(), basis.get_loc(), timeout);
         Ok(async move {
-            futures::future::join_all(local_events).await;
+            // don't pass on data one of our agents found to be counterfeit.
+            for res in futures::future::join_all(local_events).await {
+                if let Err(err @ KitsuneP2pError::Counterfeit(_)) = res {
+                    return Err(err);
+                }
+            }
 
             // TODO - FIXME
             // Holochain currently does all its testing without any remote nodes
@@ -690,6 +765,7 @@ impl KitsuneP2pHandler for Space {
                 }
 
                 let mut all = Vec::new();
+                let bytes = payload.len();
 
                 // determine the total number of nodes we'll be publishing to
                 // we'll make each remote responsible for a subset of delegate
@@ -702,16 +778,18 @@ impl KitsuneP2pHandler for Space {
                     let payload = wire::Wire::delegate_broadcast(
                         space.clone(),
                         basis.clone(),
-                        agent,
+                        agent.clone(),
                         mod_idx as u32,
                         mod_cnt as u32,
                         payload.clone().into(),
                     );
 
                     // notify the remote node
+                    let ro_inner = ro_inner.clone();
                     all.push(async move {
-                        if let Err(err) = con_hnd.notify(&payload, timeout).await {
-                            tracing::warn!(?err, "delegate broadcast error");
+                        match con_hnd.notify(&payload, timeout).await {
+                            Ok(()) => ro_inner.record_publish_metrics(agent, bytes),
+                            Err(err) => tracing::warn!(?err, "delegate broadcast error"),
                         }
                     });
                 }
@@ -786,22 +864,23 @@ impl KitsuneP2pHandler for Space {
                                     .boxed()
                             }
                             discover::PeerDiscoverResult::OkRemote { con_hnd, .. } => {
-                                let payload = wire::Wire::broadcast(
+                                let bytes = payload.len();
+                                let wire_payload = wire::Wire::broadcast(
                                     space.clone(),
-                                    agent,
+                                    agent.clone(),
                                     payload.clone().into(),
                                 );
-                                con_hnd
-                                    .notify(&payload, timeout)
-                                    .map(|r| {
-                                        if let Err(e) = r {
-                                            tracing::info!(
-                                                "Failed to broadcast to remote agent because: {:?}",
-                                                e
-                                            )
-                                        }
-                                    })
-                                    .boxed()
+                                let ro_inner = ro_inner.clone();
+                                async move {
+                                    match con_hnd.notify(&wire_payload, timeout).await {
+                                        Ok(()) => ro_inner.record_publish_metrics(agent, bytes),
+                                        Err(e) => tracing::info!(
+                                            "Failed to broadcast to remote agent because: {:?}",
+                                            e
+                                        ),
+                                    }
+                                }
+                                .boxed()
                             }
                             discover::PeerDiscoverResult::Err(e) => async move {
                                 tracing::info!(
@@ -855,6 +934,66 @@ pub(crate) struct SpaceReadOnlyInner {
     pub(crate) parallel_notify_permit: Arc<tokio::sync::Semaphore>,
 }
 
+impl SpaceReadOnlyInner {
+    /// Record the round trip latency and the payload sizes of a request
+    /// answered by a remote agent. The metrics are recorded in the
+    /// background so they don't hold up the request.
+    pub(crate) fn record_request_metrics(
+        &self,
+        agent: KAgent,
+        bytes_sent: usize,
+        bytes_received: usize,
+        started: std::time::Instant,
+    ) {
+        let micros = started.elapsed().as_micros() as u64;
+        self.record_metrics(
+            agent,
+            vec![
+                MetricKind::RoundTripLatency { micros },
+                MetricKind::BytesSent {
+                    bytes: bytes_sent as u64,
+                },
+                MetricKind::BytesReceived {
+                    bytes: bytes_received as u64,
+                },
+            ],
+        );
+    }
+
+    /// Record the size of the data published to a remote agent.
+    /// Published data is only counted by the sender, as the broadcast
+    /// it arrives in doesn't say which agent sent it.
+    pub(crate) fn record_publish_metrics(&self, agent: KAgent, bytes_sent: usize) {
+        self.record_metrics(
+            agent,
+            vec![MetricKind::BytesSent {
+                bytes: bytes_sent as u64,
+            }],
+        );
+    }
+
+    /// Record metrics about a remote agent in the background.
+    fn record_metrics(&self, agent: KAgent, kinds: Vec<MetricKind>) {
+        let space = self.space.clone();
+        let evt_sender = self.evt_sender.clone();
+        let timestamp = std::time::SystemTime::now();
+        tokio::task::spawn(async move {
+            for kind in kinds {
+                let datum = MetricDatum {
+                    space: space.clone(),
+                    agent: agent.clone(),
+                    kind,
+                    timestamp,
+                };
+                if let Err(err) = evt_sender.put_metric_datum(datum).await {
+                    tracing::warn!(?err, "failed to record network metrics");
+                    break;
+                }
+            }
+        });
+    }
+}
+
 /// A Kitsune P2p Node can track multiple "spaces" -- Non-interacting namespaced
 /// areas that share common transport infrastructure for communication.
 pub(crate) struct Space {
@@ -893,18 +1032,12 @@ impl Space {
                     GossipModuleType::Simple,
                     crate::gossip::simple_bloom::factory(),
                 )],
-                "sharded-gossip" => vec![
-                    (
-                        GossipModuleType::ShardedRecent,
-                        crate::gossip::sharded_gossip::recent_factory(bandwidth_throttles.recent()),
-                    ),
-                    (
-                        GossipModuleType::ShardedHistorical,
-                        crate::gossip::sharded_gossip::historical_factory(
-                            bandwidth_throttles.historical(),
-                        ),
-                    ),
-                ],
+                "sharded-gossip" => {
+                    sharded_gossip_modules(&bandwidth_throttles, OpSyncStrategy::Bloom)
+                }
+                "quantized-gossip" => {
+                    sharded_gossip_modules(&bandwidth_throttles, OpSyncStrategy::Regions)
+                }
                 "none" => vec![],
                 _ => {
                     panic!("unknown gossip strategy: {}", module);
@@ -972,28 +1105,7 @@ impl Space {
                             if !i_s_c.ghost_actor_is_active() {
                                 break;
                             }
-                            let mut peer_data = Vec::with_capacity(list.len());
-                            for item in list {
-                                // TODO - someday some validation here
-                                match i_s_c.is_agent_local(item.agent.clone()).await {
-                                    Err(err) => tracing::error!(?err),
-                                    Ok(is_local) => {
-                                        if !is_local {
-                                            // we got a result - let's add it to our store for the future
-                                            peer_data.push(item);
-                                        }
-                                    }
-                                }
-                            }
-                            if let Err(err) = evt_s_c
-                                .put_agent_info_signed(PutAgentInfoSignedEvt {
-                                    space: space_c.clone(),
-                                    peer_data,
-                                })
-                                .await
-                            {
-                                tracing::error!(?err, "error storing bootstrap agent_info");
-                            }
+                            put_bootstrap_peers(&i_s_c, &evt_s_c, space_c.clone(), list).await;
                         }
                     }
                 }
@@ -1100,3 +1212,23 @@ impl Space {
             .unwrap_or_else(|| DhtArc::full(agent.get_loc()))
     }
 }
+
+/// The recent and historical sharded gossip modules.
+fn sharded_gossip_modules(
+    bandwidth_throttles: &BandwidthThrottles,
+    op_sync: OpSyncStrategy,
+) -> Vec<(GossipModuleType, GossipModuleFactory)> {
+    vec![
+        (
+            GossipModuleType::ShardedRecent,
+            crate::gossip::sharded_gossip::recent_factory(bandwidth_throttles.recent(), op_sync),
+        ),
+        (
+            GossipModuleType::ShardedHistorical,
+            crate::gossip::sharded_gossip::historical_factory(
+                bandwidth_throttles.historical(),
+                op_sync,
+            ),
+        ),
+    ]
+}
@@ -337,13 +337,21 @@ impl Outer {
                             PeerDiscoverResult::OkRemote { con_hnd, .. } => con_hnd,
                         };
 
+                    let bytes_sent = payload.len();
                     let msg =
                         wire::Wire::call(space, from_agent, info.agent.clone(), payload.into());
 
+                    let started = std::time::Instant::now();
                     let res = con_hnd.request(&msg, max_timeout).await;
 
                     match res {
                         Ok(wire::Wire::CallResp(c)) => {
+                            ro_inner.record_request_metrics(
+                                info.agent.clone(),
+                                bytes_sent,
+                                c.data.len(),
+                                started,
+                            );
                             report_results(RpcMultiResponse {
                                 agent: info.agent.clone(),
                                 response: c.data.into(),
@@ -208,12 +208,29 @@ impl KitsuneP2pEventHandler for AgentHarness {
 
     fn handle_query_metrics(
         &mut self,
+        _space: Arc<KitsuneSpace>,
         query: MetricQuery,
     ) -> KitsuneP2pEventHandlerResult<MetricQueryAnswer> {
         let answer = self.metric_store.query_metrics(query);
         Ok(async move { Ok(answer) }.boxed().into())
     }
 
+    fn handle_is_blocked(
+        &mut self,
+        _space: Arc<KitsuneSpace>,
+        _target: BlockTarget,
+    ) -> KitsuneP2pEventHandlerResult<bool> {
+        Ok(async move { Ok(false) }.boxed().into())
+    }
+
+    fn handle_block(
+        &mut self,
+        _space: Arc<KitsuneSpace>,
+        _target: BlockTarget,
+    ) -> KitsuneP2pEventHandlerResult<()> {
+        Ok(async move { Ok(()) }.boxed().into())
+    }
+
     fn handle_call(
         &mut self,
         space: Arc<super::KitsuneSpace>,
@@ -292,6 +309,19 @@ impl KitsuneP2pEventHandler for AgentHarness {
         Ok(async move { Ok(out) }.boxed().into())
     }
 
+    fn handle_fetch_op_sizes(
+        &mut self,
+        input: FetchOpSizesEvt,
+    ) -> KitsuneP2pEventHandlerResult<Vec<(Arc<super::KitsuneOpHash>, u32)>> {
+        let mut out = Vec::new();
+        for hash in input.op_hashes {
+            if let Some(op) = self.gossip_store.get(&hash) {
+                out.push((hash.clone(), op.len() as u32));
+            }
+        }
+        Ok(async move { Ok(out) }.boxed().into())
+    }
+
     fn handle_sign_network_data(
         &mut self,
         input: SignNetworkDataEvt,
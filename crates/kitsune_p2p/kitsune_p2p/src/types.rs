@@ -48,6 +48,11 @@ pub enum KitsuneP2pError {
     #[error(transparent)]
     TryFromInt(#[from] std::num::TryFromIntError),
 
+    /// The implementor found data sent by a remote node to be counterfeit.
+    /// The node that sent it can be blocked.
+    #[error("Counterfeit: {0}")]
+    Counterfeit(Box<str>),
+
     /// Other
     #[error("Other: {0}")]
     Other(Box<dyn std::error::Error + Send + Sync>),
@@ -65,6 +70,11 @@ impl KitsuneP2pError {
     pub fn decoding_error(s: String) -> Self {
         Self::DecodingError(s.into_boxed_str())
     }
+
+    /// generate a counterfeit error from a string
+    pub fn counterfeit(s: String) -> Self {
+        Self::Counterfeit(s.into_boxed_str())
+    }
 }
 
 impl From<String> for KitsuneP2pError {
@@ -12,6 +12,8 @@ pub struct QueryOpHashesEvt {
     /// The "space" context.
     pub space: KSpace,
     /// The agents from which to fetch, along with a DhtArcSet to filter by.
+    /// Arc sets can't hold `u32::MAX`, so a set reaching `u32::MAX - 1`
+    /// holds `u32::MAX` too.
     pub agents: Vec<(KAgent, DhtArcSet)>,
     /// The time window to search within.
     pub window_ms: TimeWindowMs,
@@ -32,6 +34,17 @@ pub struct FetchOpDataEvt {
     pub op_hashes: Vec<KOpHash>,
 }
 
+/// Get the size of each op in a list of op-hashes from our implementor.
+#[derive(Debug)]
+pub struct FetchOpSizesEvt {
+    /// The "space" context.
+    pub space: KSpace,
+    /// The "agent" context.
+    pub agents: Vec<KAgent>,
+    /// The op-hashes to size
+    pub op_hashes: Vec<KOpHash>,
+}
+
 /// Request that our implementor sign some data on behalf of an agent.
 #[derive(Debug)]
 pub struct SignNetworkDataEvt {
@@ -86,6 +99,15 @@ pub struct QueryGossipAgentsEvt {
     pub arc_set: Arc<kitsune_p2p_types::dht_arc::DhtArcSet>,
 }
 
+/// Something a space can refuse to gossip with or serve.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BlockTarget {
+    /// A single agent.
+    Agent(KAgent),
+    /// A whole node, identified by the cert of its transport connection.
+    Node(kitsune_p2p_types::Tx2Cert),
+}
+
 /// A single datum of metric info about an Agent, to be recorded by the client.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
 pub enum MetricKind {
@@ -101,11 +123,51 @@ pub enum MetricKind {
     /// ignoring inactivity timeouts.
     /// Lets us skip recently unreachable nodes in gossip loops.
     ConnectError,
+
+    /// A request to this agent was answered after this long.
+    #[display(fmt = "RoundTripLatency")]
+    RoundTripLatency {
+        /// The round trip time in microseconds.
+        micros: u64,
+    },
+
+    /// We sent this many bytes of request, publish or gossip data to this agent.
+    #[display(fmt = "BytesSent")]
+    BytesSent {
+        /// The number of bytes.
+        bytes: u64,
+    },
+
+    /// We received this many bytes of response or gossip data from this agent.
+    #[display(fmt = "BytesReceived")]
+    BytesReceived {
+        /// The number of bytes.
+        bytes: u64,
+    },
+
+    /// A round of gossip with this agent completed.
+    GossipRoundComplete,
+
+    /// A round of gossip with this agent failed.
+    GossipRoundFailed,
+}
+
+impl MetricKind {
+    /// The value measured by this kind of metric, if it has one.
+    pub fn value(&self) -> Option<u64> {
+        match self {
+            Self::RoundTripLatency { micros } => Some(*micros),
+            Self::BytesSent { bytes } | Self::BytesReceived { bytes } => Some(*bytes),
+            _ => None,
+        }
+    }
 }
 
 /// A single row in the metrics database
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct MetricDatum {
+    /// The space this event happened in
+    pub space: KSpace,
     /// The agent this event is about
     pub agent: KAgent,
     /// The kind of event
@@ -119,7 +181,12 @@ pub struct MetricDatum {
 impl PartialOrd for MetricDatum {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         match self.timestamp.cmp(&other.timestamp) {
-            std::cmp::Ordering::Equal => Some(self.agent.cmp(&other.agent)),
+            std::cmp::Ordering::Equal => Some(
+                self.agent
+                    .cmp(&other.agent)
+                    .then_with(|| self.kind.cmp(&other.kind))
+                    .then_with(|| self.space.cmp(&other.space)),
+            ),
             o => Some(o),
         }
     }
@@ -196,8 +263,14 @@ ghost_actor::ghost_chan! {
         /// Record a metric datum about an agent.
         fn put_metric_datum(datum: MetricDatum) -> ();
 
-        /// Ask for metric data.
-        fn query_metrics(query: MetricQuery) -> MetricQueryAnswer;
+        /// Ask for metric data about a space.
+        fn query_metrics(space: KSpace, query: MetricQuery) -> MetricQueryAnswer;
+
+        /// Is this agent or node blocked from this space?
+        fn is_blocked(space: KSpace, target: BlockTarget) -> bool;
+
+        /// Block a node from this space because it sent us counterfeit data.
+        fn block(space: KSpace, target: BlockTarget) -> ();
 
         /// We are receiving a request from a remote node.
         fn call(space: KSpace, to_agent: KAgent, from_agent: KAgent, payload: Payload) -> Vec<u8>;
@@ -216,6 +289,10 @@ ghost_actor::ghost_chan! {
         /// Gather all op-hash data for a list of op-hashes from our implementor.
         fn fetch_op_data(input: FetchOpDataEvt) -> Vec<(KOpHash, Vec<u8>)>;
 
+        /// Get the size of each op in a list of op-hashes from our implementor.
+        /// The size of an op must be the same for every node holding it.
+        fn fetch_op_sizes(input: FetchOpSizesEvt) -> Vec<(KOpHash, u32)>;
+
         /// Request that our implementor sign some data on behalf of an agent.
         fn sign_network_data(input: SignNetworkDataEvt) -> super::KitsuneSignature;
     }
@@ -5,6 +5,7 @@ use kitsune_p2p_types::config::KitsuneP2pTuningParams;
 use kitsune_p2p_types::dependencies::{ghost_actor::dependencies::tracing, serde_json};
 use kitsune_p2p_types::metrics::*;
 use kitsune_p2p_types::tls::*;
+use kitsune_p2p_types::tx2::tx2_adapter::AdapterFactory;
 use kitsune_p2p_types::tx2::tx2_pool::*;
 use kitsune_p2p_types::tx2::tx2_pool_promote::*;
 use kitsune_p2p_types::*;
@@ -24,6 +25,7 @@ pub struct Opt {
     pub danger_use_unenc_cert: Option<std::path::PathBuf>,
 
     /// To which network interface / port should we bind?
+    /// Bind a `kitsune-tcp://` url to proxy over tcp instead of quic.
     #[structopt(short = "b", long, default_value = "kitsune-quic://0.0.0.0:0")]
     pub bind_to: String,
 }
@@ -105,11 +107,7 @@ async fn inner() -> KitsuneResult<()> {
         TlsConfig::new_ephemeral().await?
     };
 
-    let mut conf = QuicConfig::default();
-    conf.tls = Some(tls_conf.clone());
-    conf.tuning_params = Some(tuning_params.clone());
-
-    let f = QuicBackendAdapt::new(conf).await?;
+    let f = backend(&opt.bind_to, tls_conf, tuning_params.clone()).await?;
     let f = tx2_pool_promote(f, tuning_params.clone());
     let mut conf = ProxyConfig::default();
     conf.tuning_params = Some(tuning_params.clone());
@@ -156,3 +154,22 @@ async fn inner() -> KitsuneResult<()> {
 
     Ok(())
 }
+
+/// The backend to proxy over, picked by the scheme of the url we bind.
+async fn backend(
+    bind_to: &str,
+    tls: TlsConfig,
+    tuning_params: KitsuneP2pTuningParams,
+) -> KitsuneResult<AdapterFactory> {
+    if bind_to.starts_with("kitsune-tcp:") {
+        let mut conf = kitsune_p2p_types::tx2::TcpConfig::default();
+        conf.tls = Some(tls);
+        conf.tuning_params = Some(tuning_params);
+        kitsune_p2p_types::tx2::tx2_tcp_adapter(conf).await
+    } else {
+        let mut conf = QuicConfig::default();
+        conf.tls = Some(tls);
+        conf.tuning_params = Some(tuning_params);
+        QuicBackendAdapt::new(conf).await
+    }
+}
@@ -1,11 +1,13 @@
 use futures::stream::StreamExt;
 use kitsune_p2p_proxy::tx2::*;
+use kitsune_p2p_proxy::ProxyUrl;
 use kitsune_p2p_transport_quic::tx2::*;
 use kitsune_p2p_types::metrics::*;
 use kitsune_p2p_types::tls::*;
 use kitsune_p2p_types::tx2::tx2_pool::*;
 use kitsune_p2p_types::tx2::tx2_pool_promote::*;
 use kitsune_p2p_types::tx2::tx2_utils::*;
+use kitsune_p2p_types::tx2::{tx2_tcp_adapter, TcpConfig};
 use kitsune_p2p_types::*;
 use structopt::StructOpt;
 
@@ -29,16 +31,28 @@ async fn main() {
 async fn inner() -> KitsuneResult<()> {
     let opt = Opt::from_args();
 
+    // reach the proxy over the same transport it is bound to
+    let proxy_url = ProxyUrl::from_full(&opt.proxy_url).map_err(KitsuneError::other)?;
+
     let tls_config = TlsConfig::new_ephemeral().await?;
-    let mut conf = QuicConfig::default();
-    conf.tls = Some(tls_config.clone());
-    let f = QuicBackendAdapt::new(conf).await?;
+    let (f, bind_to) = if proxy_url.as_base().scheme() == "kitsune-tcp" {
+        let mut conf = TcpConfig::default();
+        conf.tls = Some(tls_config.clone());
+        (tx2_tcp_adapter(conf).await?, "kitsune-tcp://0.0.0.0:0")
+    } else {
+        let mut conf = QuicConfig::default();
+        conf.tls = Some(tls_config.clone());
+        (
+            QuicBackendAdapt::new(conf).await?,
+            "kitsune-quic://0.0.0.0:0",
+        )
+    };
     let f = tx2_pool_promote(f, Default::default());
     let f = tx2_proxy(f, Default::default())?;
 
     let t = KitsuneTimeout::from_millis(30 * 1000);
 
-    let mut ep = f.bind("kitsune-quic://0.0.0.0:0".into(), t).await?;
+    let mut ep = f.bind(bind_to.into(), t).await?;
 
     let ep_hnd = ep.handle().clone();
 
@@ -52,7 +66,7 @@ async fn inner() -> KitsuneResult<()> {
         KitsuneResult::Ok(())
     });
 
-    let con = ep_hnd.get_connection(opt.proxy_url.into(), t).await?;
+    let con = ep_hnd.get_connection(proxy_url.as_str().into(), t).await?;
 
     con.write(0.into(), PoolBuf::new(), t).await?;
 
@@ -746,19 +746,28 @@ mod tests {
     use kitsune_p2p_types::tx2::tx2_pool_promote::*;
 
     async fn build_node(
+        tcp: bool,
         mut s_done: Option<tokio::sync::oneshot::Sender<()>>,
         expect_err: bool,
     ) -> (tokio::task::JoinHandle<KitsuneResult<()>>, TxUrl, EpHnd) {
         let t = KitsuneTimeout::from_millis(5000);
 
-        let f = tx2_mem_adapter(MemConfig::default()).await.unwrap();
+        let (f, bind_to) = if tcp {
+            let f = kitsune_p2p_types::tx2::tx2_tcp_adapter(Default::default())
+                .await
+                .unwrap();
+            (f, "kitsune-tcp://127.0.0.1:0")
+        } else {
+            let f = tx2_mem_adapter(MemConfig::default()).await.unwrap();
+            (f, "none:")
+        };
         let f = tx2_pool_promote(f, Default::default());
 
         let mut conf = ProxyConfig::default();
         conf.allow_proxy_fwd = true;
         let f = tx2_proxy(f, conf).unwrap();
 
-        let mut ep = f.bind("none:".into(), t).await.unwrap();
+        let mut ep = f.bind(bind_to.into(), t).await.unwrap();
         let ephnd = ep.handle().clone();
         let addr = ephnd.local_addr().unwrap();
 
@@ -819,7 +828,7 @@ mod tests {
         let t = KitsuneTimeout::from_millis(5000);
         let mut all_tasks = Vec::new();
 
-        let (p_join, p_addr, p_ep) = build_node(None, true).await;
+        let (p_join, p_addr, p_ep) = build_node(false, None, true).await;
         all_tasks.push(p_join);
 
         let fake_tgt: Tx2Cert = vec![0xdb; 32].into();
@@ -832,7 +841,7 @@ mod tests {
         println!("Fake Tgt: {:?}", fake_tgt);
 
         let (s_done, r_done) = tokio::sync::oneshot::channel();
-        let (n_join, _n_addr, n_ep) = build_node(Some(s_done), true).await;
+        let (n_join, _n_addr, n_ep) = build_node(false, Some(s_done), true).await;
 
         let mut data = PoolBuf::new();
         data.extend_from_slice(b"hello");
@@ -848,18 +857,27 @@ mod tests {
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_tx2_proxy() {
+        test_proxy(false, 100).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tx2_proxy_over_tcp() {
+        test_proxy(true, 10).await;
+    }
+
+    async fn test_proxy(tcp: bool, count: usize) {
         observability::test_run().ok();
 
         let t = KitsuneTimeout::from_millis(5000);
 
         let mut all_tasks = Vec::new();
 
-        let (p_join, p_addr, p_ep) = build_node(None, false).await;
+        let (p_join, p_addr, p_ep) = build_node(tcp, None, false).await;
         all_tasks.push(p_join);
         //println!("PROXY ADDR = {}", p_addr);
         //println!("PROXY: {:?}", p_ep.local_cert().unwrap());
 
-        let (t_join, t_addr, t_ep) = build_node(None, false).await;
+        let (t_join, t_addr, t_ep) = build_node(tcp, None, false).await;
         all_tasks.push(t_join);
 
         //println!("TGT ADDR = {}", t_addr);
@@ -871,12 +889,10 @@ mod tests {
         let t_addr_proxy = proxify_addr(&p_addr, &t_addr);
         //println!("TGT PROXY ADDR = {}", t_addr_proxy);
 
-        const COUNT: usize = 100;
-
         let mut all_futs = Vec::new();
-        for _ in 0..COUNT {
+        for _ in 0..count {
             let (s_done, r_done) = tokio::sync::oneshot::channel();
-            let (n_join, _n_addr, n_ep) = build_node(Some(s_done), false).await;
+            let (n_join, _n_addr, n_ep) = build_node(tcp, Some(s_done), false).await;
             //println!("N: {:?}", n_ep.local_cert().unwrap());
 
             let t_addr_proxy = t_addr_proxy.clone();
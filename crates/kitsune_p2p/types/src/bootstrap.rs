@@ -1,5 +1,6 @@
 //! Types for the bootstrap server
 use crate::bin_types::{KitsuneBinType, KitsuneSpace};
+use crate::dht_arc::{DhtArc, DhtArcBucket, DhtLocation};
 use std::sync::Arc;
 
 /// The number of random agent infos we want to collect from the bootstrap service when we want to
@@ -36,3 +37,43 @@ impl Default for RandomLimit {
         Self(RANDOM_LIMIT_DEFAULT)
     }
 }
+
+/// Struct to be encoded for the `near_basis` op.
+#[derive(serde::Deserialize, serde::Serialize, Clone)]
+pub struct NearBasisQuery {
+    /// The space to get agents from.
+    pub space: Arc<KitsuneSpace>,
+    /// The location the storage arcs of the agents must cover.
+    pub basis: DhtLocation,
+    /// The maximum number of agents to retrieve for this query.
+    pub limit: RandomLimit,
+}
+
+/// Returned by the `stats` op for a space.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+pub struct SpaceStats {
+    /// The number of agents in the space with unexpired infos listing urls.
+    pub agent_count: u32,
+    /// The average coverage of the storage arcs of those agents.
+    pub average_coverage: f64,
+    /// Estimated number of peers in the space, counting those
+    /// offline at the moment.
+    pub est_total_peers: u32,
+    /// Estimated number of peers holding any one location.
+    pub est_total_redundancy: u32,
+}
+
+impl SpaceStats {
+    /// Estimate the stats of a space from the storage arcs of its agents,
+    /// using the [`PeerDensity`](crate::dht_arc::PeerDensity) of the full arc.
+    pub fn from_arcs<I: IntoIterator<Item = DhtArc>>(arcs: I) -> Self {
+        let density =
+            DhtArcBucket::new_unchecked(DhtArc::full(0u32), arcs.into_iter().collect()).density();
+        Self {
+            agent_count: density.count() as u32,
+            average_coverage: density.average_coverage(),
+            est_total_peers: density.est_total_peers() as u32,
+            est_total_redundancy: density.est_total_redundancy() as u32,
+        }
+    }
+}
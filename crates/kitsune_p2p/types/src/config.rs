@@ -78,6 +78,9 @@ pub mod tuning_params_struct {
 
     mk_tune! {
         /// Gossip strategy to use. [Default: simple-bloom]
+        /// - `sharded-gossip` compares ops with bloom filters.
+        /// - `quantized-gossip` compares ops with fingerprints of regions of
+        ///   dht space and time, so nodes always converge.
         gossip_strategy: String = "simple-bloom".to_string(),
 
         /// Delay between gossip loop iteration. [Default: 1s]
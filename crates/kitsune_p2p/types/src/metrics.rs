@@ -133,6 +133,58 @@ make_avg!(
     metric_pull_api_req_res_elasped_ms,
 );
 
+macro_rules! _make_total {
+    (
+        $doc:literal,
+        $push:ident,
+        $get:ident,
+        $m:ident,
+        $stat:ident,
+    ) => {
+        mod $m {
+            use super::*;
+
+            pub(crate) static $stat: AtomicU64 = AtomicU64::new(0);
+        }
+
+        #[doc = $doc]
+        pub fn $push(v: u64) {
+            $m::$stat.fetch_add(v, Ordering::Relaxed);
+        }
+
+        #[doc = $doc]
+        pub fn $get() -> u64 {
+            $m::$stat.load(Ordering::Relaxed)
+        }
+    };
+}
+
+macro_rules! make_total {
+    ($doc:literal, $push:ident, $get:ident,) => {
+        paste::paste! {
+            _make_total!(
+                $doc,
+                $push,
+                $get,
+                [<__ $push:snake>],
+                [<$push:snake:upper>],
+            );
+        }
+    };
+}
+
+make_total!(
+    "total bytes of gossip sent by this process, never reset",
+    metric_push_gossip_bytes_sent,
+    metric_gossip_bytes_sent_total,
+);
+
+make_total!(
+    "total bytes of gossip received by this process, never reset",
+    metric_push_gossip_bytes_received,
+    metric_gossip_bytes_received_total,
+);
+
 /// Spawns a tokio task with given future/async block.
 /// Captures a new TaskCounter instance to track task count.
 pub fn metric_task<T, E, F>(f: F) -> tokio::task::JoinHandle<Result<T, E>>
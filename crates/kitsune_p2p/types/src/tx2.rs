@@ -6,6 +6,14 @@ pub use framed::*;
 mod mem;
 pub use mem::*;
 
+#[cfg(any(test, feature = "test_utils"))]
+mod sim;
+#[cfg(any(test, feature = "test_utils"))]
+pub use sim::*;
+
+mod tcp;
+pub use tcp::*;
+
 pub mod tx2_adapter;
 
 pub mod tx2_api;
@@ -0,0 +1,1201 @@
+#![allow(clippy::new_ret_no_self)]
+//! kitsune tx2 tcp transport backend, secured with tls 1.3
+//!
+//! All the channels of a connection are multiplexed over a single tls
+//! stream, so this backend works on networks which block the udp quic uses.
+
+use crate::config::*;
+use crate::metrics::metric_task;
+use crate::tls::*;
+use crate::tx2::tx2_adapter::*;
+use crate::tx2::tx2_utils::*;
+use crate::tx2::*;
+use crate::*;
+use futures::future::{BoxFuture, FutureExt};
+use futures::stream::{BoxStream, StreamExt};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::sync::atomic;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Configuration for TcpBackendAdapt
+#[non_exhaustive]
+pub struct TcpConfig {
+    /// Tls config
+    /// Default: None = ephemeral.
+    pub tls: Option<TlsConfig>,
+
+    /// Tuning Params
+    /// Default: None = default.
+    pub tuning_params: Option<KitsuneP2pTuningParams>,
+}
+
+impl Default for TcpConfig {
+    fn default() -> Self {
+        Self {
+            tls: None,
+            tuning_params: None,
+        }
+    }
+}
+
+impl TcpConfig {
+    /// into inner contents with default application
+    pub async fn split(self) -> KitsuneResult<(TlsConfig, KitsuneP2pTuningParams)> {
+        let TcpConfig { tls, tuning_params } = self;
+
+        let tls = match tls {
+            None => TlsConfig::new_ephemeral().await?,
+            Some(tls) => tls,
+        };
+
+        let tuning_params = tuning_params.unwrap_or_else(KitsuneP2pTuningParams::default);
+
+        Ok((tls, tuning_params))
+    }
+}
+
+/// Tcp endpoint bind adapter for kitsune tx2
+pub async fn tx2_tcp_adapter(config: TcpConfig) -> KitsuneResult<AdapterFactory> {
+    TcpBackendAdapt::new(config).await
+}
+
+// -- private -- //
+
+const SCHEME: &str = "kitsune-tcp";
+
+/// Tls ALPN identifier for kitsune tcp handshaking
+const ALPN_KITSUNE_TCP_0: &[u8] = b"kitsune-tcp/0";
+
+/// How many bytes of a channel are buffered before its writer must wait.
+/// Also the largest amount of channel data sent in a single frame.
+const CHAN_BUF_BYTES: usize = 4096;
+
+/// Channel data is sent in frames of `[chan_id: u32 LE][len: u32 LE][data]`.
+/// A frame with no data closes the channel.
+const FRAME_HEADER_BYTES: usize = 8;
+
+/// Set in the length of a frame which carries no data, but gives the writer
+/// of the channel credit for as many bytes as the rest of the length says.
+const CREDIT_FLAG: u32 = 1 << 31;
+
+/// How many bytes of a channel may be sent before its reader has taken them.
+/// The reader gives the writer credit for the bytes it takes, so a slow reader
+/// holds up its own channel's writer, but never the rest of the connection.
+const CHAN_WINDOW_BYTES: usize = 64 * 1024;
+
+/// How many channels each side of a connection may have open at once.
+/// A channel is open until its reader has taken all of its data.
+const MAX_OPEN_CHANS: usize = 128;
+
+/// A frame to send over a connection
+enum OutFrame {
+    /// Data for a channel we opened
+    Data(u32, Vec<u8>),
+    /// The close of a channel we opened
+    Close(u32),
+    /// Credit for the writer of a channel the peer opened
+    Credit(u32, u32),
+}
+
+type ChanWrite = Box<dyn futures::io::AsyncWrite + 'static + Send + Unpin>;
+
+type ChanDataSend = tokio::sync::mpsc::UnboundedSender<Vec<u8>>;
+type ChanDataRecv = tokio::sync::mpsc::UnboundedReceiver<Vec<u8>>;
+
+/// The credit the peer has given each channel we opened, by id.
+/// A channel's id is only free again once the peer has given back
+/// all the credit it took, as by then the peer has closed it too.
+type OutChans = Arc<parking_lot::Mutex<HashMap<u32, Arc<tokio::sync::Semaphore>>>>;
+
+fn blake2b_32(data: &[u8]) -> Vec<u8> {
+    blake2b_simd::Params::new()
+        .hash_length(32)
+        .to_state()
+        .update(data)
+        .finalize()
+        .as_bytes()
+        .to_vec()
+}
+
+/// Resolve a "kitsune-tcp://host:port" url, preferring ipv4 addresses.
+async fn url_to_addr(url: &TxUrl) -> KitsuneResult<std::net::SocketAddr> {
+    let (host, port) = match (url.scheme(), url.host_str(), url.port()) {
+        (SCHEME, Some(host), Some(port)) => (host, port),
+        _ => {
+            return Err(format!(
+                "invalid input. got: '{}', expected: '{}://host:port'",
+                url, SCHEME
+            )
+            .into())
+        }
+    };
+    let rendered = format!("{}:{}", host, port);
+    let mut fallback = None;
+    for addr in tokio::net::lookup_host(rendered.clone())
+        .await
+        .map_err(KitsuneError::other)?
+    {
+        if addr.is_ipv4() {
+            return Ok(addr);
+        }
+        fallback.get_or_insert(addr);
+    }
+    fallback.ok_or_else(|| format!("could not parse '{}', as 'host:port'", rendered).into())
+}
+
+/// The url of a bound listener. If it is bound to all interfaces,
+/// the first non-loopback ipv4 interface stands in for them.
+fn bound_url(addr: std::net::SocketAddr) -> KitsuneResult<TxUrl> {
+    use crate::dependencies::url2;
+    let mut url = url2::url2!("{}://{}", SCHEME, addr);
+    if addr.ip().is_unspecified() {
+        for iface in if_addrs::get_if_addrs().map_err(KitsuneError::other)? {
+            if let std::net::IpAddr::V4(ip) = iface.addr.ip() {
+                if !ip.is_loopback() {
+                    url.set_host(Some(&ip.to_string()))
+                        .map_err(KitsuneError::other)?;
+                    break;
+                }
+            }
+        }
+    }
+    Ok(url.into())
+}
+
+fn read_u32_le(bytes: &[u8]) -> u32 {
+    let mut buf = [0; 4];
+    buf.copy_from_slice(&bytes[..4]);
+    u32::from_le_bytes(buf)
+}
+
+/// hand data read from the tcp stream to a tls session
+fn recv_tls<S: rustls::Session>(session: &mut S, data: &[u8]) -> KitsuneResult<()> {
+    let mut data = std::io::Cursor::new(data);
+    while (data.position() as usize) < data.get_ref().len() {
+        session.read_tls(&mut data).map_err(KitsuneError::other)?;
+        session.process_new_packets().map_err(KitsuneError::other)?;
+    }
+    Ok(())
+}
+
+/// append the plaintext a tls session decrypted to `plain`
+fn recv_plaintext<S: rustls::Session>(session: &mut S, plain: &mut Vec<u8>) -> KitsuneResult<()> {
+    let mut buf = [0; CHAN_BUF_BYTES];
+    loop {
+        match session.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(len) => plain.extend_from_slice(&buf[..len]),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return Ok(()),
+            Err(e) => return Err(KitsuneError::other(e)),
+        }
+    }
+}
+
+/// take everything a tls session has queued for the peer
+fn take_tls<S: rustls::Session>(session: &mut S) -> KitsuneResult<Vec<u8>> {
+    let mut out = Vec::new();
+    while session.wants_write() {
+        session.write_tls(&mut out).map_err(KitsuneError::other)?;
+    }
+    Ok(out)
+}
+
+/// frame channel data and queue it for encryption
+fn send_frame<S: rustls::Session>(session: &mut S, frame: OutFrame) -> KitsuneResult<()> {
+    let (chan_id, len, data) = match frame {
+        OutFrame::Data(chan_id, data) => (chan_id, data.len() as u32, data),
+        OutFrame::Close(chan_id) => (chan_id, 0, Vec::new()),
+        OutFrame::Credit(chan_id, credit) => (chan_id, CREDIT_FLAG | credit, Vec::new()),
+    };
+    let mut out = Vec::with_capacity(FRAME_HEADER_BYTES + data.len());
+    out.extend_from_slice(&chan_id.to_le_bytes());
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(&data);
+    session.write_all(&out).map_err(KitsuneError::other)
+}
+
+/// The id of the `count`th channel opened on a connection in the given
+/// direction. Outgoing connections open odd channel ids, incoming ones even,
+/// so the two sides never pick the same id. Ids wrap after 2^31 channels.
+fn chan_id(count: u32, dir: Tx2ConDir) -> u32 {
+    (count % CREDIT_FLAG).wrapping_mul(2)
+        + match dir {
+            Tx2ConDir::Outgoing => 1,
+            Tx2ConDir::Incoming => 0,
+        }
+}
+
+/// A tls session along with the write half of its tcp stream.
+/// Rustls does no io itself, so the session is driven by hand.
+struct TlsTcp<S: rustls::Session> {
+    session: S,
+    write: OwnedWriteHalf,
+}
+
+impl<S: 'static + rustls::Session> TlsTcp<S> {
+    /// complete the tls handshake, returning the digest of the peer cert
+    async fn handshake(&mut self, read: &mut OwnedReadHalf) -> KitsuneResult<Tx2Cert> {
+        let mut buf = vec![0; CHAN_BUF_BYTES];
+        loop {
+            let out = take_tls(&mut self.session)?;
+            self.write
+                .write_all(&out)
+                .await
+                .map_err(KitsuneError::other)?;
+            if !self.session.is_handshaking() {
+                break;
+            }
+            let len = read.read(&mut buf).await.map_err(KitsuneError::other)?;
+            if len == 0 {
+                return Err(KitsuneErrorKind::Closed.into());
+            }
+            recv_tls(&mut self.session, &buf[..len])?;
+        }
+        match self
+            .session
+            .get_peer_certificates()
+            .and_then(|chain| chain.into_iter().next())
+        {
+            None => Err("invalid peer certificate".into()),
+            Some(cert) => Ok(blake2b_32(cert.as_ref()).into()),
+        }
+    }
+
+    /// Pump the connection until either side closes it.
+    async fn run(
+        self,
+        read: OwnedReadHalf,
+        frame_recv: TReceiver<OutFrame>,
+        in_chans: InChans,
+    ) -> KitsuneResult<()> {
+        let TlsTcp { session, write } = self;
+
+        // The two directions share the session, but only lock it to
+        // encrypt or decrypt, never across io. Otherwise two peers both
+        // blocked writing to each other would never read again.
+        let session = parking_lot::Mutex::new(session);
+        let wants_write = tokio::sync::Notify::new();
+
+        tokio::select! {
+            res = recv_loop(&session, &wants_write, read, in_chans) => res,
+            res = send_loop(&session, &wants_write, write, frame_recv) => res,
+        }
+    }
+}
+
+async fn recv_loop<S: rustls::Session>(
+    session: &parking_lot::Mutex<S>,
+    wants_write: &tokio::sync::Notify,
+    mut read: OwnedReadHalf,
+    mut in_chans: InChans,
+) -> KitsuneResult<()> {
+    let mut plain = Vec::new();
+    let mut buf = vec![0; CHAN_BUF_BYTES];
+    loop {
+        // the first pass picks up any frames which arrived
+        // along with the end of the handshake
+        {
+            let mut session = session.lock();
+            recv_plaintext(&mut *session, &mut plain)?;
+            if session.wants_write() {
+                wants_write.notify_one();
+            }
+        }
+        in_chans.dispatch(&mut plain)?;
+
+        let len = read.read(&mut buf).await.map_err(KitsuneError::other)?;
+        if len == 0 {
+            return Ok(());
+        }
+        recv_tls(&mut *session.lock(), &buf[..len])?;
+    }
+}
+
+async fn send_loop<S: rustls::Session>(
+    session: &parking_lot::Mutex<S>,
+    wants_write: &tokio::sync::Notify,
+    mut write: OwnedWriteHalf,
+    mut frame_recv: TReceiver<OutFrame>,
+) -> KitsuneResult<()> {
+    loop {
+        let out = take_tls(&mut *session.lock())?;
+        if !out.is_empty() {
+            write.write_all(&out).await.map_err(KitsuneError::other)?;
+        }
+        tokio::select! {
+            frame = frame_recv.next() => match frame {
+                None => return Ok(()),
+                Some(frame) => send_frame(&mut *session.lock(), frame)?,
+            },
+            _ = wants_write.notified() => (),
+        }
+    }
+}
+
+/// A channel the peer opened, which it hasn't closed yet
+struct InChanData {
+    data_send: ChanDataSend,
+    /// Bytes handed to the channel which its reader hasn't taken yet
+    queued: Arc<atomic::AtomicUsize>,
+}
+
+/// The channels the peer opened on a connection, by id
+struct InChans {
+    chans: HashMap<u32, InChanData>,
+    in_chan_send: TSender<InChan>,
+    active: Active,
+    /// Where the readers of our channels send their credit
+    frame_send: TSender<OutFrame>,
+    /// Credit from the peer goes to the channels we opened
+    out_chans: OutChans,
+    /// Bytes handed to any channel which their readers haven't taken yet,
+    /// including channels the peer has already closed
+    queued: Arc<atomic::AtomicUsize>,
+}
+
+impl InChans {
+    /// Pass the complete frames at the start of `plain` on to their
+    /// channels, opening the ones we haven't seen yet.
+    /// This never waits on a reader, so one slow channel can't hold up
+    /// the rest of the connection. A peer which sends more than the credit
+    /// it was given is misbehaving, and the connection is closed.
+    fn dispatch(&mut self, plain: &mut Vec<u8>) -> KitsuneResult<()> {
+        let mut offset = 0;
+        while plain.len() - offset >= FRAME_HEADER_BYTES {
+            let chan_id = read_u32_le(&plain[offset..]);
+            let len = read_u32_le(&plain[offset + 4..]);
+            if len & CREDIT_FLAG != 0 {
+                offset += FRAME_HEADER_BYTES;
+                // the channel may have been closed in the meantime
+                if let Some(credit) = self.out_chans.lock().get(&chan_id) {
+                    credit.add_permits((len & !CREDIT_FLAG) as usize);
+                }
+                continue;
+            }
+            let len = len as usize;
+            if len > CHAN_BUF_BYTES {
+                return Err(format!(
+                    "tcp frame of {} bytes exceeds the limit of {}",
+                    len, CHAN_BUF_BYTES
+                )
+                .into());
+            }
+            let start = offset + FRAME_HEADER_BYTES;
+            if plain.len() < start + len {
+                break;
+            }
+            let data = &plain[start..start + len];
+            offset = start + len;
+
+            if data.is_empty() {
+                // dropping the sender closes the channel
+                // once its reader has taken the queued data
+                self.chans.remove(&chan_id);
+                continue;
+            }
+            // An honest peer never has more than this outstanding, as it
+            // holds each channel open until we've given back all its credit.
+            let queued = self.queued.fetch_add(len, atomic::Ordering::Relaxed) + len;
+            if queued > MAX_OPEN_CHANS * CHAN_WINDOW_BYTES {
+                return Err("tcp peer opened too many channels".into());
+            }
+            if !self.chans.contains_key(&chan_id) {
+                self.open(chan_id);
+            }
+            if let Some(chan) = self.chans.get(&chan_id) {
+                let chan_queued = chan.queued.fetch_add(len, atomic::Ordering::Relaxed) + len;
+                if chan_queued > CHAN_WINDOW_BYTES {
+                    return Err("tcp peer sent more than its channel credit".into());
+                }
+                // the pump only stops if the connection is closing
+                let _ = chan.data_send.send(data.to_vec());
+            }
+        }
+        plain.drain(..offset);
+        Ok(())
+    }
+
+    /// Open a channel the peer started, spawning the task which hands it to
+    /// whoever accepts channels and writes its data as the reader makes room.
+    fn open(&mut self, chan_id: u32) {
+        let (send, recv) = bound_async_mem_channel(CHAN_BUF_BYTES, Some(&self.active));
+        let in_chan: InChan = Box::new(FramedReader::new(recv));
+        let (data_send, data_recv) = tokio::sync::mpsc::unbounded_channel();
+        let chan_queued = Arc::new(atomic::AtomicUsize::new(0));
+        let pump = self.active.fut(pump_in_chan(
+            chan_id,
+            in_chan,
+            self.in_chan_send.clone(),
+            send,
+            data_recv,
+            self.frame_send.clone(),
+            [chan_queued.clone(), self.queued.clone()],
+        ));
+        metric_task(async move {
+            let _ = pump.await;
+            KitsuneResult::Ok(())
+        });
+        self.chans.insert(
+            chan_id,
+            InChanData {
+                data_send,
+                queued: chan_queued,
+            },
+        );
+    }
+}
+
+/// Hand a channel to whoever accepts channels on the connection, then
+/// write the data received for it into the channel in order, giving
+/// the writer credit for it as the reader makes room.
+async fn pump_in_chan(
+    chan_id: u32,
+    in_chan: InChan,
+    in_chan_send: TSender<InChan>,
+    mut chan: ChanWrite,
+    mut data_recv: ChanDataRecv,
+    frame_send: TSender<OutFrame>,
+    queued: [Arc<atomic::AtomicUsize>; 2],
+) -> KitsuneResult<()> {
+    // if nobody is accepting channels the data is dropped
+    let mut open = in_chan_send.send(in_chan).await.is_ok();
+    while let Some(data) = data_recv.recv().await {
+        if open
+            && futures::io::AsyncWriteExt::write_all(&mut chan, &data)
+                .await
+                .is_err()
+        {
+            // the reader dropped the channel
+            open = false;
+        }
+        for queued in queued.iter() {
+            queued.fetch_sub(data.len(), atomic::Ordering::Relaxed);
+        }
+        if frame_send
+            .send(OutFrame::Credit(chan_id, data.len() as u32))
+            .await
+            .is_err()
+        {
+            // the connection is closing
+            break;
+        }
+    }
+    Ok(())
+}
+
+struct TcpInChanRecvAdapt(BoxStream<'static, InChanFut>);
+
+impl TcpInChanRecvAdapt {
+    pub fn new(recv: TReceiver<InChan>) -> Self {
+        Self(recv.map(|item| async move { Ok(item) }.boxed()).boxed())
+    }
+}
+
+impl futures::stream::Stream for TcpInChanRecvAdapt {
+    type Item = InChanFut;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let inner = &mut self.0;
+        tokio::pin!(inner);
+        futures::stream::Stream::poll_next(inner, cx)
+    }
+}
+
+impl InChanRecvAdapt for TcpInChanRecvAdapt {}
+
+struct TcpConAdaptInner {
+    uniq: Uniq,
+    dir: Tx2ConDir,
+    peer_addr: TxUrl,
+    peer_cert: Tx2Cert,
+    next_chan_id: Arc<atomic::AtomicU32>,
+    out_chans: OutChans,
+    open_chans: Arc<tokio::sync::Semaphore>,
+    frame_send: TSender<OutFrame>,
+    con_active: Active,
+    mix_active: Active,
+}
+
+struct TcpConAdapt(TcpConAdaptInner);
+
+/// Pick the next channel id which isn't still in use, and give it
+/// the credit of a fresh channel.
+fn reserve_chan_id(
+    out_chans: &OutChans,
+    next_chan_id: &atomic::AtomicU32,
+    dir: Tx2ConDir,
+) -> (u32, Arc<tokio::sync::Semaphore>) {
+    let mut out_chans = out_chans.lock();
+    loop {
+        let count = next_chan_id.fetch_add(1, atomic::Ordering::Relaxed);
+        let chan_id = chan_id(count, dir);
+        if let std::collections::hash_map::Entry::Vacant(e) = out_chans.entry(chan_id) {
+            let credit = Arc::new(tokio::sync::Semaphore::new(CHAN_WINDOW_BYTES));
+            e.insert(credit.clone());
+            return (chan_id, credit);
+        }
+    }
+}
+
+impl ConAdapt for TcpConAdapt {
+    fn uniq(&self) -> Uniq {
+        self.0.uniq
+    }
+
+    fn dir(&self) -> Tx2ConDir {
+        self.0.dir
+    }
+
+    fn peer_addr(&self) -> KitsuneResult<TxUrl> {
+        Ok(self.0.peer_addr.clone())
+    }
+
+    fn peer_cert(&self) -> Tx2Cert {
+        self.0.peer_cert.clone()
+    }
+
+    fn out_chan(&self, timeout: KitsuneTimeout) -> OutChanFut {
+        if self.is_closed() {
+            return async move { Err(KitsuneErrorKind::Closed.into()) }.boxed();
+        }
+
+        let open_chans = self.0.open_chans.clone();
+        let out_chans = self.0.out_chans.clone();
+        let next_chan_id = self.0.next_chan_id.clone();
+        let dir = self.0.dir;
+        let frame_send = self.0.frame_send.clone();
+        let mix_active = self.0.mix_active.clone();
+
+        async move {
+            // wait for the peer to finish reading one of our channels
+            // if we already have as many open as it will take
+            let permit = timeout
+                .mix(async move {
+                    open_chans
+                        .acquire_owned()
+                        .await
+                        .map_err(KitsuneError::other)
+                })
+                .await?;
+            let (chan_id, credit) = reserve_chan_id(&out_chans, &next_chan_id, dir);
+
+            let (send, mut recv) = bound_async_mem_channel(CHAN_BUF_BYTES, Some(&mix_active));
+            let pump = mix_active.fut(async move {
+                let _permit = permit;
+                let mut buf = vec![0; CHAN_BUF_BYTES];
+                loop {
+                    match futures::io::AsyncReadExt::read(&mut recv, &mut buf).await {
+                        Ok(len) if len > 0 => {
+                            // wait for the reader to make room
+                            credit
+                                .acquire_many(len as u32)
+                                .await
+                                .map_err(KitsuneError::other)?
+                                .forget();
+                            if frame_send
+                                .send(OutFrame::Data(chan_id, buf[..len].to_vec()))
+                                .await
+                                .is_err()
+                            {
+                                return Ok(());
+                            }
+                        }
+                        _ => break,
+                    }
+                }
+                frame_send
+                    .send(OutFrame::Close(chan_id))
+                    .await
+                    .map_err(KitsuneError::other)?;
+                // the id is only free again once the reader has everything
+                let _ = credit.acquire_many(CHAN_WINDOW_BYTES as u32).await;
+                out_chans.lock().remove(&chan_id);
+                Ok(())
+            });
+            metric_task(async move {
+                let _ = pump.await;
+                KitsuneResult::Ok(())
+            });
+
+            let send: OutChan = Box::new(FramedWriter::new(send));
+            Ok(send)
+        }
+        .boxed()
+    }
+
+    fn is_closed(&self) -> bool {
+        !self.0.mix_active.is_active()
+    }
+
+    fn close(&self, code: u32, reason: &str) -> BoxFuture<'static, ()> {
+        tracing::info!(
+            peer_cert=?self.0.peer_cert,
+            %code,
+            %reason,
+            "close connection (tcp)",
+        );
+        self.0.con_active.kill();
+        self.0.frame_send.close_channel();
+        async move {}.boxed()
+    }
+}
+
+/// Run the tls handshake over a fresh tcp stream, then spawn the task
+/// pumping the connection.
+async fn establish<S: 'static + rustls::Session>(
+    session: S,
+    stream: TcpStream,
+    local_cert: Tx2Cert,
+    dir: Tx2ConDir,
+    ep_active: Active,
+) -> KitsuneResult<Con> {
+    stream.set_nodelay(true).map_err(KitsuneError::other)?;
+
+    use crate::dependencies::url2;
+    let addr = stream.peer_addr().map_err(KitsuneError::other)?;
+    let peer_addr: TxUrl = url2::url2!("{}://{}", SCHEME, addr).into();
+
+    let (mut read, write) = stream.into_split();
+    let mut tls = TlsTcp { session, write };
+    let peer_cert = tls.handshake(&mut read).await?;
+
+    let con_active = Active::new();
+    let mix_active = con_active.mix(&ep_active);
+
+    let (frame_send, frame_recv) = t_chan(32);
+    let (in_chan_send, in_chan_recv) = t_chan(32);
+    let out_chans: OutChans = Arc::new(parking_lot::Mutex::new(HashMap::new()));
+
+    {
+        let con_active = con_active.clone();
+        let mix_active = mix_active.clone();
+        let frame_send = frame_send.clone();
+        let out_chans = out_chans.clone();
+        metric_task(async move {
+            let in_chans = InChans {
+                chans: HashMap::new(),
+                in_chan_send,
+                active: mix_active.clone(),
+                frame_send,
+                out_chans,
+                queued: Arc::new(atomic::AtomicUsize::new(0)),
+            };
+            let res = mix_active.fut(tls.run(read, frame_recv, in_chans)).await;
+            if let Err(err) = res {
+                tracing::debug!(?err, "tcp connection closed");
+            }
+            con_active.kill();
+            KitsuneResult::Ok(())
+        });
+    }
+
+    match dir {
+        Tx2ConDir::Outgoing => {
+            tracing::info!(?local_cert, ?peer_cert, url=%peer_addr, "established outgoing connection (tcp)");
+        }
+        Tx2ConDir::Incoming => {
+            tracing::info!(?local_cert, ?peer_cert, url=%peer_addr, "established incoming connection (tcp)");
+        }
+    }
+
+    let con: Arc<dyn ConAdapt> = Arc::new(TcpConAdapt(TcpConAdaptInner {
+        uniq: Uniq::default(),
+        dir,
+        peer_addr,
+        peer_cert,
+        next_chan_id: Arc::new(atomic::AtomicU32::new(0)),
+        out_chans,
+        open_chans: Arc::new(tokio::sync::Semaphore::new(MAX_OPEN_CHANS)),
+        frame_send,
+        con_active,
+        mix_active,
+    }));
+    let chan_recv: Box<dyn InChanRecvAdapt> = Box::new(TcpInChanRecvAdapt::new(in_chan_recv));
+
+    Ok((con, chan_recv))
+}
+
+struct TcpConRecvAdapt(BoxStream<'static, ConFut>);
+
+impl TcpConRecvAdapt {
+    pub fn new(
+        listener: TcpListener,
+        local_cert: Tx2Cert,
+        tls_srv: Arc<rustls::ServerConfig>,
+        tuning_params: KitsuneP2pTuningParams,
+        ep_active: Active,
+    ) -> Self {
+        let listener = Arc::new(listener);
+        Self(
+            futures::stream::unfold(listener, move |listener| {
+                let local_cert = local_cert.clone();
+                let tls_srv = tls_srv.clone();
+                let tuning_params = tuning_params.clone();
+                let ep_active = ep_active.clone();
+                async move {
+                    let accepted = {
+                        let listener = listener.clone();
+                        ep_active
+                            .fut(async move {
+                                Ok(listener.accept().await.map_err(KitsuneError::other))
+                            })
+                            .await
+                    };
+                    let stream = match accepted {
+                        // the endpoint was closed
+                        Err(_) => return None,
+                        Ok(stream) => stream,
+                    };
+                    let con_fut = async move {
+                        let (stream, _) = stream?;
+                        let session = rustls::ServerSession::new(&tls_srv);
+                        tuning_params
+                            .implicit_timeout()
+                            .mix(establish(
+                                session,
+                                stream,
+                                local_cert,
+                                Tx2ConDir::Incoming,
+                                ep_active,
+                            ))
+                            .await
+                    }
+                    .boxed();
+                    Some((con_fut, listener))
+                }
+            })
+            .boxed(),
+        )
+    }
+}
+
+impl futures::stream::Stream for TcpConRecvAdapt {
+    type Item = ConFut;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let inner = &mut self.0;
+        tokio::pin!(inner);
+        futures::stream::Stream::poll_next(inner, cx)
+    }
+}
+
+impl ConRecvAdapt for TcpConRecvAdapt {}
+
+struct TcpEndpointAdapt {
+    uniq: Uniq,
+    local_cert: Tx2Cert,
+    url: TxUrl,
+    tls_cli: Arc<rustls::ClientConfig>,
+    ep_active: Active,
+}
+
+impl EndpointAdapt for TcpEndpointAdapt {
+    fn debug(&self) -> serde_json::Value {
+        match self.local_addr() {
+            Ok(addr) => serde_json::json!({
+                "type": "tx2_tcp",
+                "state": "open",
+                "addr": addr,
+            }),
+            Err(_) => serde_json::json!({
+                "type": "tx2_tcp",
+                "state": "closed",
+            }),
+        }
+    }
+
+    fn uniq(&self) -> Uniq {
+        self.uniq
+    }
+
+    fn local_addr(&self) -> KitsuneResult<TxUrl> {
+        if self.is_closed() {
+            return Err(KitsuneErrorKind::Closed.into());
+        }
+        Ok(self.url.clone())
+    }
+
+    fn local_cert(&self) -> Tx2Cert {
+        self.local_cert.clone()
+    }
+
+    fn connect(&self, url: TxUrl, timeout: KitsuneTimeout) -> ConFut {
+        let local_cert = self.local_cert.clone();
+        let tls_cli = self.tls_cli.clone();
+        let ep_active = self.ep_active.clone();
+        timeout
+            .mix(async move {
+                if !ep_active.is_active() {
+                    return Err(KitsuneErrorKind::Closed.into());
+                }
+                let addr = url_to_addr(&url).await?;
+                let stream = TcpStream::connect(addr)
+                    .await
+                    .map_err(KitsuneError::other)?;
+                let name = webpki::DNSNameRef::try_from_ascii_str("stub.stub")
+                    .map_err(KitsuneError::other)?;
+                let session = rustls::ClientSession::new(&tls_cli, name);
+                match establish(session, stream, local_cert, Tx2ConDir::Outgoing, ep_active).await {
+                    Ok(con) => Ok(con),
+                    Err(err) => {
+                        tracing::warn!(?err, "failed to establish outgoing connection (tcp)");
+                        Err(err)
+                    }
+                }
+            })
+            .boxed()
+    }
+
+    fn is_closed(&self) -> bool {
+        !self.ep_active.is_active()
+    }
+
+    fn close(&self, _code: u32, _reason: &str) -> BoxFuture<'static, ()> {
+        tracing::warn!(
+            local_cert=?self.local_cert,
+            "CLOSING ENDPOINT"
+        );
+        self.ep_active.kill();
+        async move {}.boxed()
+    }
+}
+
+/// Tcp endpoint backend bind adapter for kitsune tx2
+pub struct TcpBackendAdapt {
+    local_cert: Tx2Cert,
+    tls_srv: Arc<rustls::ServerConfig>,
+    tls_cli: Arc<rustls::ClientConfig>,
+    tuning_params: KitsuneP2pTuningParams,
+}
+
+impl TcpBackendAdapt {
+    /// Construct a new tcp tx2 backend bind adapter
+    pub async fn new(config: TcpConfig) -> KitsuneResult<AdapterFactory> {
+        let (tls, tuning_params) = config.split().await?;
+
+        let local_cert = tls.cert_digest.clone().into();
+
+        let (tls_srv, tls_cli) = gen_tls_configs(ALPN_KITSUNE_TCP_0, &tls, tuning_params.clone())?;
+
+        let out: AdapterFactory = Arc::new(Self {
+            local_cert,
+            tls_srv,
+            tls_cli,
+            tuning_params,
+        });
+
+        Ok(out)
+    }
+}
+
+impl BindAdapt for TcpBackendAdapt {
+    fn bind(&self, url: TxUrl, timeout: KitsuneTimeout) -> EndpointFut {
+        let local_cert = self.local_cert.clone();
+        let tls_srv = self.tls_srv.clone();
+        let tls_cli = self.tls_cli.clone();
+        let tuning_params = self.tuning_params.clone();
+        timeout
+            .mix(async move {
+                let addr = url_to_addr(&url).await?;
+                let listener = TcpListener::bind(addr).await.map_err(KitsuneError::other)?;
+                let url = bound_url(listener.local_addr().map_err(KitsuneError::other)?)?;
+
+                let ep_active = Active::new();
+                let ep: Arc<dyn EndpointAdapt> = Arc::new(TcpEndpointAdapt {
+                    uniq: Uniq::default(),
+                    local_cert: local_cert.clone(),
+                    url: url.clone(),
+                    tls_cli,
+                    ep_active: ep_active.clone(),
+                });
+                let con_recv: Box<dyn ConRecvAdapt> = Box::new(TcpConRecvAdapt::new(
+                    listener,
+                    local_cert.clone(),
+                    tls_srv,
+                    tuning_params,
+                    ep_active,
+                ));
+
+                tracing::info!(?local_cert, %url, "bound local endpoint (tcp)");
+
+                Ok((ep, con_recv))
+            })
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tcp_tx2() {
+        observability::test_run().ok();
+
+        let t = KitsuneTimeout::from_millis(5000);
+
+        let (s_done, r_done) = tokio::sync::oneshot::channel();
+
+        let factory = tx2_tcp_adapter(TcpConfig::default()).await.unwrap();
+        let (ep1, _con_recv1) = factory
+            .bind("kitsune-tcp://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+
+        let factory = tx2_tcp_adapter(TcpConfig::default()).await.unwrap();
+        let (ep2, mut con_recv2) = factory
+            .bind("kitsune-tcp://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+
+        let addr2 = ep2.local_addr().unwrap();
+        println!("addr2: {}", addr2);
+
+        let rt = metric_task(async move {
+            if let Some(mc) = con_recv2.next().await {
+                let (con, mut recv) = mc.await.unwrap();
+                assert!(matches!(con.dir(), Tx2ConDir::Incoming));
+                if let Some(mc) = recv.next().await {
+                    let mut c = mc.await.unwrap();
+                    let t = KitsuneTimeout::from_millis(5000);
+                    let (_, data) = c.read(t).await.unwrap();
+                    assert_eq!(b"hello", data.as_ref());
+                    s_done.send(()).unwrap();
+                }
+            }
+            KitsuneResult::Ok(())
+        });
+
+        let (c, _recv) = ep1.connect(addr2, t).await.unwrap();
+        assert_eq!(ep2.local_cert(), c.peer_cert());
+        let mut c = c.out_chan(t).await.unwrap();
+
+        let mut data = PoolBuf::new();
+        data.extend_from_slice(b"hello");
+        c.write(0.into(), data, t).await.unwrap();
+
+        r_done.await.unwrap();
+
+        ep1.close(0, "").await;
+        ep2.close(0, "").await;
+
+        rt.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tcp_tx2_concurrent_chans() {
+        observability::test_run().ok();
+
+        const CHAN_COUNT: usize = 8;
+        const MSG_BYTES: usize = CHAN_BUF_BYTES * 5;
+
+        let t = KitsuneTimeout::from_millis(5000);
+
+        let factory = tx2_tcp_adapter(TcpConfig::default()).await.unwrap();
+        let (ep1, _con_recv1) = factory
+            .bind("kitsune-tcp://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+
+        let factory = tx2_tcp_adapter(TcpConfig::default()).await.unwrap();
+        let (ep2, mut con_recv2) = factory
+            .bind("kitsune-tcp://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+
+        let addr2 = ep2.local_addr().unwrap();
+
+        let (s_written, r_written) = tokio::sync::oneshot::channel();
+
+        // Accept every channel but don't read any of them until all the
+        // writes are done, so each write must fit in its channel's window.
+        let rt = metric_task(async move {
+            let mc = con_recv2.next().await.unwrap();
+            let (_con, mut recv) = mc.await.unwrap();
+            let mut chans = Vec::new();
+            while chans.len() < CHAN_COUNT {
+                let mc = recv.next().await.unwrap();
+                chans.push(mc.await.unwrap());
+            }
+            r_written.await.unwrap();
+            let mut received = Vec::new();
+            for mut c in chans {
+                let t = KitsuneTimeout::from_millis(5000);
+                let (_, data) = c.read(t).await.unwrap();
+                received.push(data.to_vec());
+            }
+            KitsuneResult::Ok(received)
+        });
+
+        let (c, _recv) = ep1.connect(addr2, t).await.unwrap();
+        let expected: Vec<Vec<u8>> = (0..CHAN_COUNT as u8).map(|i| vec![i; MSG_BYTES]).collect();
+        futures::future::join_all(expected.iter().map(|msg| {
+            let out_chan = c.out_chan(t);
+            async move {
+                let mut out_chan = out_chan.await.unwrap();
+                let mut data = PoolBuf::new();
+                data.extend_from_slice(msg);
+                out_chan.write(0.into(), data, t).await.unwrap();
+            }
+        }))
+        .await;
+        s_written.send(()).unwrap();
+
+        let mut received = rt.await.unwrap().unwrap();
+        received.sort();
+        assert_eq!(expected, received);
+
+        ep1.close(0, "").await;
+        ep2.close(0, "").await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tcp_tx2_unread_chan_only_blocks_its_writer() {
+        observability::test_run().ok();
+
+        let t = KitsuneTimeout::from_millis(5000);
+
+        let factory = tx2_tcp_adapter(TcpConfig::default()).await.unwrap();
+        let (ep1, _con_recv1) = factory
+            .bind("kitsune-tcp://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+
+        let factory = tx2_tcp_adapter(TcpConfig::default()).await.unwrap();
+        let (ep2, mut con_recv2) = factory
+            .bind("kitsune-tcp://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+
+        let addr2 = ep2.local_addr().unwrap();
+
+        // Hold on to the first channel without ever reading it,
+        // and read the second.
+        let rt = metric_task(async move {
+            let mc = con_recv2.next().await.unwrap();
+            let (_con, mut recv) = mc.await.unwrap();
+            let _unread = recv.next().await.unwrap().await.unwrap();
+            let mut c = recv.next().await.unwrap().await.unwrap();
+            let (_, data) = c.read(t).await.unwrap();
+            KitsuneResult::Ok(data.to_vec())
+        });
+
+        let (c, _recv) = ep1.connect(addr2, t).await.unwrap();
+
+        let mut unread = c.out_chan(t).await.unwrap();
+        let mut data = PoolBuf::new();
+        data.extend_from_slice(&vec![0; CHAN_WINDOW_BYTES * 2]);
+        let res = unread
+            .write(0.into(), data, KitsuneTimeout::from_millis(500))
+            .await;
+        assert!(res.is_err());
+
+        let mut read = c.out_chan(t).await.unwrap();
+        let mut data = PoolBuf::new();
+        data.extend_from_slice(b"hello");
+        read.write(0.into(), data, t).await.unwrap();
+
+        assert_eq!(b"hello".to_vec(), rt.await.unwrap().unwrap());
+
+        ep1.close(0, "").await;
+        ep2.close(0, "").await;
+    }
+
+    #[test]
+    fn test_tcp_chan_ids_wrap_and_skip_ids_in_use() {
+        let out_chans: OutChans = Arc::new(parking_lot::Mutex::new(HashMap::new()));
+        let next_chan_id = atomic::AtomicU32::new(CREDIT_FLAG - 1);
+
+        let (last, _) = reserve_chan_id(&out_chans, &next_chan_id, Tx2ConDir::Outgoing);
+        assert_eq!(u32::MAX, last);
+
+        // the first id is still open, so the wrapped count skips it
+        out_chans
+            .lock()
+            .insert(1, Arc::new(tokio::sync::Semaphore::new(0)));
+        let (wrapped, _) = reserve_chan_id(&out_chans, &next_chan_id, Tx2ConDir::Outgoing);
+        assert_eq!(3, wrapped);
+
+        let (incoming, _) = reserve_chan_id(&out_chans, &next_chan_id, Tx2ConDir::Incoming);
+        assert_eq!(4, incoming);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tcp_tx2_pool_promote() {
+        use crate::tx2::tx2_api::*;
+        use crate::tx2::tx2_pool_promote::*;
+
+        observability::test_run().ok();
+
+        const REQ_COUNT: usize = 20;
+
+        let t = KitsuneTimeout::from_millis(5000);
+
+        crate::write_codec_enum! {
+            codec Test {
+                Echo(0x01) {
+                    data.0: Vec<u8>,
+                },
+            }
+        }
+
+        fn handle(mut ep: Tx2Ep<Test>) -> tokio::task::JoinHandle<KitsuneResult<()>> {
+            metric_task(async move {
+                while let Some(evt) = ep.next().await {
+                    if let Tx2EpEvent::IncomingRequest(Tx2EpIncomingRequest {
+                        data, respond, ..
+                    }) = evt
+                    {
+                        let t = KitsuneTimeout::from_millis(5000);
+                        respond.respond(data, t).await.unwrap();
+                    }
+                }
+                Ok(())
+            })
+        }
+
+        let mk_ep = || async {
+            let f = tx2_tcp_adapter(TcpConfig::default()).await.unwrap();
+            let f = tx2_pool_promote(f, Default::default());
+            let f = tx2_api(f, Default::default());
+
+            f.bind("kitsune-tcp://127.0.0.1:0", t).await.unwrap()
+        };
+
+        let ep1 = mk_ep().await;
+        let ep1_hnd = ep1.handle().clone();
+        let ep1_task = handle(ep1);
+
+        let ep2 = mk_ep().await;
+        let ep2_hnd = ep2.handle().clone();
+        let ep2_task = handle(ep2);
+
+        let addr2 = ep2_hnd.local_addr().unwrap();
+
+        let con = ep1_hnd.get_connection(addr2, t).await.unwrap();
+        let requests = (0..REQ_COUNT).map(|i| {
+            let con = con.clone();
+            async move {
+                let req = Test::echo(vec![i as u8; CHAN_BUF_BYTES * 3]);
+                let res = con.request(&req, t).await.unwrap();
+                assert_eq!(req, res);
+            }
+        });
+        futures::future::join_all(requests).await;
+
+        ep1_hnd.close(0, "").await;
+        ep2_hnd.close(0, "").await;
+
+        ep1_task.await.unwrap().unwrap();
+        ep2_task.await.unwrap().unwrap();
+    }
+}
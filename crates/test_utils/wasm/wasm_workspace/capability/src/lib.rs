@@ -111,3 +111,8 @@ fn send_assigned_cap_claim(agent: AgentPubKey) -> ExternResult<()> {
     )?;
     Ok(())
 }
+
+#[hdk_extern]
+fn capability_claims(query: CapClaimQuery) -> ExternResult<Vec<CapClaim>> {
+    hdk::prelude::capability_claims(query)
+}
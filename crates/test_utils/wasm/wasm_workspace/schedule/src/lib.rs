@@ -0,0 +1,44 @@
+use hdk::prelude::*;
+
+/// The number of times the scheduled function runs before it stops.
+const TICKS: usize = 5;
+
+#[hdk_entry(id = "tick")]
+struct Tick(u32);
+
+entry_defs![Tick::entry_def()];
+
+/// All the ticks on the chain, skipping the agent key created at genesis.
+fn query_tick_headers() -> ExternResult<Vec<Element>> {
+    Ok(hdk::prelude::query(
+        ChainQueryFilter::new()
+            .header_type(HeaderType::Create)
+            .include_entries(true),
+    )?
+    .into_iter()
+    .filter(|element| matches!(element.header().entry_type(), Some(EntryType::App(_))))
+    .collect())
+}
+
+#[hdk_extern]
+fn schedule(_: ()) -> ExternResult<()> {
+    hdk::prelude::schedule("scheduled_tick", std::time::Duration::from_millis(1))
+}
+
+#[hdk_extern]
+fn scheduled_tick(_: ()) -> ExternResult<ScheduledCallbackResult> {
+    let ticks = query_tick_headers()?.len();
+    if ticks < TICKS {
+        create_entry(&Tick(ticks as u32))?;
+        Ok(ScheduledCallbackResult::Reschedule(
+            std::time::Duration::from_millis(1),
+        ))
+    } else {
+        Ok(ScheduledCallbackResult::Done)
+    }
+}
+
+#[hdk_extern]
+fn query_tick(_: ()) -> ExternResult<Vec<Element>> {
+    query_tick_headers()
+}